@@ -0,0 +1,705 @@
+# English
+
+## 错误
+error-scan = Hardware scan error: { $detail }
+error-backup = Driver backup error: { $detail }
+error-restore = Driver restore error: { $detail }
+error-update = Driver update error: { $detail }
+error-install = Driver installation error: { $detail }
+error-signature = Driver signature error: { $detail }
+error-network = Network error: { $detail }
+error-io = I/O error: { $detail }
+error-database = Database error: { $detail }
+error-config = Configuration error: { $detail }
+error-parse = Parse error: { $detail }
+error-permission = Permission error: { $detail }
+error-init = Initialization error: { $detail }
+error-download = Download error: { $detail }
+error-validation = Validation error: { $detail }
+error-timeout = Timeout: { $detail }
+error-cancelled = Operation cancelled: { $detail }
+error-unknown = Unknown error: { $detail }
+
+## 后台操作
+op-initialize = Initializing...
+op-scan = Scanning hardware...
+op-find-updates = Looking for driver updates...
+op-update-all = Updating drivers...
+op-preferences = Saving update preferences...
+op-apply-settings = Applying settings...
+op-downloads = Downloading driver files...
+op-backup = Backing up drivers...
+op-list-snapshots = Loading backups...
+op-open-restore-plan = Reading backup...
+op-restore = Restoring drivers...
+op-recovery = Handling unfinished installation...
+op-history = Loading installation history...
+op-revert-change = Reverting change...
+
+## 安装步骤
+step-restore-point = Create restore point
+step-backup = Back up current driver
+step-download = Download driver
+step-verify = Verify driver
+step-install = Install driver
+step-verify-after = Verify after install
+
+## 主界面
+nav-system-info = System
+nav-hardware-scan = Hardware
+nav-driver-updates = Driver updates
+nav-downloads = Downloads
+nav-restore = Restore
+nav-history = History
+nav-settings = Settings
+nav-about = About
+status-ready = Ready
+action-cancel = Cancel
+init-failed = Failed to initialize: { $error }
+dashboard-system-summary = System summary
+dashboard-title = Dashboard
+dashboard-feature = Feature:
+dashboard-status = Status:
+action-scan-hardware = Scan hardware
+action-find-driver-updates = Find driver updates
+action-update-all = Update all drivers
+dashboard-hardware-summary = Hardware summary
+dashboard-device-count = { $count } hardware devices found
+action-start-scan = Start scan
+action-find-updates = Find updates
+updates-reboot-required = Some drivers take effect after restarting the computer
+downloads-action-failed = Operation failed: { $error }
+restore-backup-now = Back up current drivers
+restore-refresh-snapshots = Refresh backups
+restore-snapshot-item = { $created_at } - { $description } ({ $count } drivers, { $id })
+action-open = Open
+restore-package-path = Backup package:
+restore-open-package = Open backup
+restore-description = Description: { $description }
+restore-created-at = Created: { $created_at }
+restore-verify-failed = Verification failed: { $problems }
+restore-device-missing = Device not present
+restore-matched-device = Matches: { $device }
+restore-selected = Restore selected
+restore-selected-count = { $count } drivers selected
+settings-config-file = Configuration file: { $path }
+about-title = HamsterDrivers - Windows driver manager
+about-version = Version: { $version }
+about-author = Author: { $author }
+about-license = License: { $license }
+about-description = A Windows driver manager that scans your hardware, compares installed drivers with the versions on vendor servers, and downloads and installs the latest ones.
+scan-starting = Starting scan...
+downloads-queue-failed = Failed to add to download queue: { $error }
+downloads-queued = Added { $count } drivers to the download queue
+preferences-save-failed = Failed to save update preferences: { $error }
+restore-manual-backup = Manual backup
+recovery-title = Unfinished driver installation
+recovery-intro = The program exited unexpectedly while installing drivers. These operations did not finish:
+recovery-kind-update = update
+recovery-kind-install = install
+recovery-kind-restore = restore
+recovery-transaction = { $description } ({ $kind }) started at { $started_at }
+recovery-interrupted-at = Interrupted at: { $step }
+recovery-completed-step = Completed: { $step }
+recovery-not-started = Not started
+recovery-resume = Resume
+recovery-rollback = Roll back
+recovery-discard = Dismiss
+action-close = Close
+recovery-resumed = Resumed installation: { $succeeded } succeeded, { $failed } failed
+recovery-rolled-back = Rolled back to the drivers from before the installation
+recovery-rollback-partial = Some drivers could not be rolled back, see the log
+recovery-discarded = Dismissed the unfinished installation
+recovery-failed = Failed to handle the unfinished installation: { $error }
+config-load-failed = Failed to read configuration: { $error }
+schedule-stopped = Scheduled updates stopped: { $error }
+schedule-agent-running = The background agent is running and handles scheduled updates
+downloads-load-failed = Failed to read the download queue: { $error }
+scan-finished = Hardware scan finished, { $count } devices found
+scan-done = Scan complete
+scan-failed = Hardware scan failed: { $error }
+updates-found = Found { $count } driver update candidates
+updates-none-found = No driver updates found
+updates-find-failed = Failed to look for driver updates: { $error }
+updates-nothing-to-update = No drivers need updating
+updates-finished = Update finished
+updates-failed = Failed to update drivers: { $error }
+restore-backup-finished = Backup finished: { $id } ({ $count } drivers)
+restore-backup-failed = Failed to back up drivers: { $error }
+restore-list-failed = Failed to list backups: { $error }
+restore-plan-count = { $count } backed up drivers
+restore-open-failed = Failed to open backup: { $error }
+restore-finished = Restore finished
+restore-failed = Failed to restore drivers: { $error }
+history-load-failed = Failed to read installation history: { $error }
+history-reverted-reboot = Change reverted, restart to take effect: { $message }
+history-reverted = Change reverted: { $message }
+history-revert-failed = Revert failed: { $error }
+settings-saved = Settings saved
+settings-saved-deferred = Settings saved. They take effect when the running operation finishes.
+settings-save-failed = Failed to save settings: { $error }
+journal-read-failed = Failed to read the installation journal: { $error }
+journal-unreadable = Failed to read install journal { $id }: { $error }
+journal-truncated-record = The last record of install journal { $id } is incomplete and was ignored
+results-summary = { $title }: { $succeeded } succeeded, { $failed } failed
+
+## 设置
+settings-apply = Apply
+settings-revert = Revert
+settings-defaults = Restore defaults
+settings-unapplied = You have unapplied changes
+settings-section-downloads = Downloads
+settings-section-network = Network
+settings-section-scanner = Hardware scan
+settings-section-channels = Update channels
+settings-section-schedule = Scheduled updates
+settings-section-backup = Backup retention
+settings-section-timeouts = Timeouts (0 means no limit)
+settings-section-logging = Logging
+settings-download-dir = Download folder:
+settings-concurrency = Parallel downloads:
+settings-per-host-concurrency = Parallel downloads per host:
+settings-download-rate = Total download speed limit:
+settings-task-rate = Per-download speed limit:
+settings-rate-unlimited-hint = 0 means unlimited
+settings-large-download-threshold = Large download size:
+settings-large-download-threshold-hint = Files at least this large only download within the windows below; 0 treats all files alike
+settings-large-download-windows = Large download windows:
+settings-peer-cache = LAN cache:
+settings-peer-cache-enabled = Look on the LAN before downloading
+settings-peer-cache-hint = Drivers with a checksum are looked up in the local cache, the shared folder and other computers first
+settings-peer-cache-serve = Serve to other computers:
+settings-peers = Peers:
+settings-peers-hint = Separate multiple addresses with commas
+settings-shared-folder = Shared folder:
+settings-peer-timeout = Peer timeout:
+settings-proxy = Proxy:
+settings-aria2 = aria2 address:
+settings-connect-timeout = Connection timeout:
+settings-scan-methods = Scan methods:
+settings-hidden-devices = Hidden devices:
+settings-device-classes = Device classes:
+settings-beta = Beta:
+settings-ignored-pinned = Ignored and pinned:
+settings-schedule-enabled = Enabled:
+settings-schedule-action = Action:
+settings-schedule-plan = Schedule:
+settings-schedule-every = Every
+settings-jitter = Random delay:
+settings-maintenance-windows = Maintenance windows:
+settings-window-to = to
+settings-log-level = Log level:
+settings-use-proxy = Use a proxy
+settings-proxy-hint = Supports http://, https://, socks5:// and socks5h://; system proxy settings apply when unset
+settings-proxy-auth = Proxy authentication:
+settings-proxy-username = Username
+settings-proxy-password = Password
+settings-no-proxy = Bypass proxy for:
+settings-no-proxy-hint = Host names, domains starting with a dot or IP ranges, separated by commas
+settings-ca-certificates = Trusted CA certificates:
+settings-ca-certificate-hint = Path to a PEM certificate file
+settings-add-certificate = Add certificate
+settings-network-timeouts = Network timeouts:
+settings-connect-prefix = Connect 
+settings-request-prefix = Request 
+settings-retries = Retries on failure:
+settings-retry-backoff-prefix = First wait 
+settings-repository = Driver repository:
+settings-repository-hint = When set, drivers are looked up in this self-hosted repository first
+settings-repository-api-key = API key
+settings-vendor-fallback = Fall back to vendor sites
+settings-port-prefix = Port 
+unit-seconds-suffix =  s
+settings-wmi-hint = Finds devices SetupAPI misses, but scans more slowly
+settings-include-hidden = Show disconnected devices in the device list by default
+action-remove = Remove
+settings-all-classes = All classes
+settings-add-class = Add class
+settings-include-beta = Offer beta drivers
+settings-ignored-pinned-summary = { $ignored } versions ignored and { $pinned } devices pinned, change them on the driver updates page
+settings-schedule-enable = Check for updates on a schedule (takes effect after restart)
+settings-use-cron = Cron expression
+settings-cron-hint = min hour day month weekday
+unit-hours-suffix =  h
+unit-minutes-suffix =  min
+settings-every-day = Every day
+action-delete = Delete
+settings-add-window = Add window
+settings-window-hint = Weekdays as mon..sun separated by commas, times as HH:MM
+settings-keep-last = Most recent:
+settings-keep-daily = Daily:
+settings-keep-weekly = Weekly:
+settings-keep-monthly = Monthly:
+settings-max-rollback-points = Rollback points:
+settings-timeout-scan = Hardware scan:
+settings-timeout-match = Driver matching:
+settings-timeout-download = Driver download:
+settings-timeout-install = Driver installation:
+unit-snapshots-suffix =  snapshots
+settings-log-level-hint = Takes effect after restart. The RUST_LOG environment variable takes precedence when set
+settings-section-interface = Interface
+settings-language = Language:
+
+## 配置验证
+config-invalid-log-level = Unknown log level: { $level }
+config-invalid-language = Unsupported language: { $language }
+config-empty-retention = The retention policy must keep at least one snapshot
+config-no-rollback-points = At least one rollback point must be kept
+config-zero-aria2-port = The aria2 port cannot be 0
+config-invalid-concurrency = Parallel downloads must be between 1 and 16
+config-invalid-per-host-concurrency = Parallel downloads per host must be between 1 and 16
+config-zero-download-timeout = The download timeout cannot be 0
+config-invalid-proxy = The proxy address must start with http://, https://, socks5:// or socks5h://
+config-zero-network-timeout = Network connection and request timeouts must be greater than 0
+config-invalid-retries = Retries must be between 0 and 10
+config-invalid-listen-address = Invalid listen address: { $address }
+config-invalid-peer = Peer address must start with http:// or https://: { $peer }
+config-empty-shared-folder = Shared folder cannot be empty
+config-zero-peer-timeout = Peer timeout cannot be 0
+config-invalid-repository-url = Driver repository URL must start with http:// or https://: { $url }
+config-empty-ca-path = Certificate file paths must not be empty
+config-no-scan-method = Enable at least one scan method
+config-empty-device-class = Device classes cannot be empty
+config-zero-install-timeout = The install timeout cannot be 0, otherwise a stuck installer cannot be stopped
+config-zero-interval = The check interval cannot be 0
+config-invalid-time = Times must be HH:MM: { $value }
+config-invalid-weekday = Invalid weekday: { $value }
+schedule-action-check-only = Check only
+schedule-action-download = Check and download
+schedule-action-download-and-install = Check, download and install
+cron-field-count = A cron expression needs 5 fields: { $expression }
+cron-invalid-step = Invalid step: { $part }
+cron-zero-step = Step cannot be 0: { $part }
+cron-out-of-range = Value out of range { $min }-{ $max }: { $part }
+cron-invalid-value = Invalid value: { $value }
+config-read-failed = Failed to read the configuration file: { $error }
+config-parse-failed = Failed to parse the configuration file: { $error }
+config-serialize-failed = Failed to serialize the configuration: { $error }
+config-create-dir-failed = Failed to create the configuration folder: { $error }
+config-write-failed = Failed to write the configuration file: { $error }
+config-invalid-app = Invalid application settings: { $error }
+config-invalid-download = Invalid download settings: { $error }
+config-invalid-scanner = Invalid scan settings: { $error }
+config-invalid-schedule = Invalid schedule settings: { $error }
+config-invalid-timeouts = Invalid timeout settings: { $error }
+config-invalid-backup = Invalid backup settings: { $error }
+
+## 下载
+download-status-queued = Queued
+download-status-downloading = Downloading
+download-status-waiting = Waiting for download window
+peer-cache-open-failed = Failed to open the package cache: { $error }
+peer-cache-serve-failed = Failed to serve packages on { $address }: { $error }
+download-status-paused = Paused
+download-status-completed = Completed
+download-status-failed = Failed
+download-status-cancelled = Cancelled
+download-priority-user = Manual
+download-priority-scheduled = Scheduled
+download-partial-removed = Removed incomplete download: { $path }
+
+## 设备
+device-status-ok = OK
+device-status-error = Error
+device-status-degraded = Degraded
+device-status-unknown = Unknown
+
+## 驱动更新
+channel-stable = Stable
+channel-beta = Beta
+
+## 设备
+device-column-name = Name
+device-column-class = Type
+device-column-vendor = Vendor
+device-column-driver-version = Driver version
+device-column-driver-date = Driver date
+device-column-status = Status
+device-not-present = Not connected
+device-problem-code = Problem code { $code }
+device-not-checked = Not checked
+device-up-to-date = Up to date
+device-update-to = Update to { $version }
+device-detail-class = Type:
+device-detail-vendor = Vendor:
+device-detail-status = Status:
+device-detail-problem-code = Problem code:
+device-detail-present = Connected:
+common-yes = Yes
+common-no = No
+device-detail-instance-id = Instance ID:
+device-detail-hardware-id = Hardware ID:
+device-detail-driver-version = Driver version:
+device-detail-driver-date = Driver date:
+device-detail-driver-provider = Driver provider:
+device-detail-inf = INF file:
+device-detail-update-status = Update status:
+device-detail-all-hardware-ids = All hardware IDs
+device-detail-compatible-ids = Compatible IDs
+
+## 驱动更新
+risk-beta = Beta driver
+risk-no-checksum = File cannot be verified
+risk-unknown-size = Unknown size
+risk-critical-device = Critical device, a failure may prevent booting
+risk-unknown-version-order = Cannot confirm the version is newer
+risk-major-version = Major version change
+update-item-unfinished = Not finished
+update-item-pinned = [Pinned]
+update-item-ignored = [Ignored]
+update-item-no-update = No update found
+update-item-current-version = Current version:
+update-item-new-version = New version:
+update-item-release-date = Release date:
+common-unknown = Unknown
+update-item-size = Size:
+update-item-source = Source:
+update-item-channel = Channel:
+update-item-risks = Warning: { $risks }
+list-separator = { ", " }
+update-item-queued = Waiting
+update-item-downloading = Downloading { $percent }%
+update-item-installed-reboot = Installed, takes effect after restart
+update-item-installed = Installed
+update-item-failed = Failed: { $error }
+update-item-unignore = Unignore
+update-item-ignore = Ignore this version
+update-item-unpin = Unpin
+update-item-pin = Pin current driver
+update-item-release-notes = Release notes
+
+## 下载
+downloads-summary = { $total } tasks, { $active } downloading
+downloads-clear-finished = Clear finished
+downloads-empty = The download queue is empty. Select drivers on the Updates page and click "Download selected only"
+downloads-remaining = { $time } left
+downloads-pause = Pause
+downloads-resume = Resume
+downloads-retry = Retry
+downloads-cancel = Cancel
+downloads-remove = Remove
+duration-seconds = { $seconds } s
+duration-minutes-seconds = { $minutes } min { $seconds } s
+duration-hours-minutes = { $hours } h { $minutes } min
+
+## 安装历史
+history-action-install = Install
+history-action-update = Update
+history-action-uninstall = Uninstall
+history-action-backup = Backup
+history-action-restore = Restore
+history-action-rollback = Rollback
+history-action-started = { $action } started
+history-status-success = Succeeded
+history-status-failed = Failed
+history-status-cancelled = Cancelled
+history-status-in-progress = In progress
+history-period-all = All time
+history-period-day = Last day
+history-period-week = Last week
+history-period-month = Last month
+common-search = Search:
+history-all-actions = All actions
+history-all-results = All results
+history-refresh = Refresh
+history-confirm-revert = Revert the change to { $device } and restore the previous driver?
+history-confirm-revert-button = Revert
+common-cancel = Cancel
+history-empty = No installation history yet
+history-no-match = No matching records
+history-column-time = Time
+history-column-device = Device
+history-column-action = Action
+history-column-result = Result
+history-column-version = Version
+history-column-duration = Duration
+history-column-message = Details
+history-revert = Revert
+history-revert-hint = Roll back this change using the driver backed up before installation
+
+## 设备
+devices-not-scanned = No scan yet
+common-close = Close
+devices-visible = Showing { $visible } of { $total } devices
+devices-problems-only = Problem devices only
+devices-outdated-only = Updates only
+devices-show-hidden = Show hidden devices
+devices-group-by-class = Group by type
+common-select-all = Select all
+devices-clear-selection = Clear selection
+devices-copy-selected = Copy selected
+devices-selected = { $count } devices selected
+
+## 驱动更新
+common-select-none = Select none
+updates-update-selected = Update selected ({ $count })
+updates-download-selected = Download selected only
+updates-show-all = Show ignored and up-to-date devices
+updates-available = { $count } drivers can be updated
+
+## 格式
+size-bytes = { $count } bytes
+
+## 核心
+core-initializing = Initializing the driver update core...
+core-system-info-ready = System information collected
+core-aria2-started = Aria2 RPC server started
+core-aria2-failed = Failed to start the Aria2 RPC server: { $error }. Downloads may be limited
+core-scan-started = Scanning system hardware...
+scan-step-started = Starting hardware scan
+scan-step-scanning = Hardware scan
+scan-step-scanned = Hardware scan finished
+scan-step-stored = Hardware information saved
+scan-step-finished = Scan finished
+core-scan-finished = Hardware scan finished, found { $count } devices
+core-search-started = Searching for driver updates...
+core-search-operation = Driver update search
+core-matching-operation = Driver matching
+core-search-finished = Update search finished, { $count } candidates found
+core-no-scan-for-search = Scan the hardware before searching for driver updates
+transaction-install = Install driver { $hardware_id }
+core-install-started = Installing driver: { $file } ({ $hardware_id })
+core-install-succeeded = Driver installed: { $file }
+install-verify-failed = ; verification after install failed: { $error }
+core-install-failed-rolling-back = Driver installation failed, rolling back: { $message }
+install-rolled-back = ; rolled back to the previous driver
+install-rollback-failed = ; rollback failed: { $error }
+core-install-failed-no-rollback = Driver installation failed and no rollback point is available: { $message }
+core-device-missing-after-install = Device { $hardware_id } was not found after installation
+core-current-driver-version = Current driver version { $version }
+transaction-update = Update driver { $device }
+core-download-progress = Download progress: { $percent }% - { $file }
+core-download-waiting = { $file } is large and will be downloaded during the allowed time window
+core-no-matching-driver = No matching driver found
+transaction-batch-update = Batch driver update
+core-update-cancelled = Update cancelled
+core-update-failed = Update failed: { $error }
+app-name = Hamster Drivers
+core-restore-point-failed = Failed to create a system restore point: { $error }. Continuing
+core-no-rollback-point = Could not create a rollback point
+core-rollback-point-created = Created rollback point { $id }: { $count } drivers
+core-nothing-exported = No current drivers could be exported, this installation cannot be rolled back automatically
+core-rollback-point-failed = Failed to create a rollback point: { $error }. This installation cannot be rolled back automatically
+core-export-failed = Failed to export the driver of { $device }: { $error }
+core-register-backup-failed = Failed to record the driver backup of { $device }: { $error }
+core-rollback-point-missing = Rollback point not found: { $id }
+core-rollback-message = Rollback point { $id }: { $message }
+core-log-missing = Installation record not found: { $id }
+core-log-not-revertible = This record cannot be reverted: { $id }
+core-rollback-point-no-device = Rollback point { $id } has no driver for this device
+core-no-scan-for-restore = Scan the hardware before matching backed up drivers
+core-restore-plan = Backup { $package } contains { $total } drivers, { $restorable } can be restored to this computer
+core-no-scan-for-backup = Scan the hardware before backing up drivers
+core-backup-finished = Driver backup finished: { $drivers } drivers, { $new_files } new files ({ $size }), { $reused } existing files reused
+core-entry-not-in-package = The selected drivers are not in the backup
+transaction-restore = Restore backed up drivers
+core-restore-cancelled = Restore cancelled: { $device }
+core-backup-entry = Backup entry { $id }
+core-restoring = Restoring driver: { $device } -> { $version }
+core-restore-failed = Restore failed: { $error }
+core-log-write-failed = Failed to write the installation log: { $error }
+core-no-matching-driver-for = No matching driver found: { $hardware_id }
+core-transaction-no-rollback = This transaction has no rollback point: { $id }
+system-summary =
+    Windows edition: { $edition }
+    Version: { $version }
+    Build: { $build }
+    Activation: { $activation }
+    DirectX: { $directx }
+    Manufacturer: { $manufacturer }
+    Model: { $model }
+    CPU: { $cpu }
+    Memory: { $memory }
+    Graphics: { $gpu }
+hardware-summary = { $count } hardware devices found
+update-summary = { $total } devices, { $outdated } need updates
+verify-empty-file = The driver file is empty: { $path }
+verify-size-mismatch = Driver file size mismatch: expected { $expected } bytes, got { $actual } bytes
+verify-checksum-mismatch = Driver file SHA-256 mismatch: { $path }
+verify-passed-checksum = { $size } bytes, SHA-256 { $checksum }
+core-device-missing = Device not found: { $device }
+core-backup-verify-failed = Backup verification failed for { $device }: { $problems }
+
+## 命令行
+cli-usage =
+    Usage:
+      hamster-drivers backup list
+      hamster-drivers backup fsck
+      hamster-drivers backup gc
+      hamster-drivers backup prune [--keep-last N] [--keep-daily N] [--keep-weekly N] [--keep-monthly N] [--dry-run]
+      hamster-drivers history [list|stats] [--hardware-id ID] [--action ACTION] [--status STATUS] [--since YYYY-MM-DD] [--until YYYY-MM-DD] [--limit N] [--csv FILE]
+      hamster-drivers history prune --days N
+      hamster-drivers schedule status
+      hamster-drivers schedule run
+      hamster-drivers agent run
+      hamster-drivers agent status
+      hamster-drivers agent send COMMAND [--wait]
+
+      COMMAND: scan, check, download, install, backup, restore PATH, refresh, cancel, shutdown
+
+      ACTION: install, update, uninstall, backup, restore, rollback
+      STATUS: success, failed, cancelled, in-progress
+cli-unknown-command = Unknown command
+cli-driver-count = { $count } drivers
+cli-fsck-checked = Checked { $snapshots } snapshots, { $files } files
+cli-fsck-unreadable = Unreadable snapshot: { $id }
+cli-fsck-missing = Snapshot { $id } references a missing file: { $hash }
+cli-fsck-corrupt = Corrupt file: { $hash }
+cli-fsck-orphaned = { $count } files are unreferenced, run backup gc to remove them
+cli-fsck-healthy = The backup store is healthy
+cli-gc-finished = Removed { $count } unreferenced files, freed { $size }
+cli-prune-would-remove = Would remove: { $id }
+cli-prune-removed = Removed: { $id }
+cli-prune-summary = Kept { $kept } snapshots, removed { $removed }
+cli-unknown-backup-command = Unknown backup command
+cli-schedule-enabled = Scheduled updates: { $state }
+cli-enabled = enabled
+cli-disabled = disabled
+cli-schedule-cron = Schedule: cron { $cron }
+cli-schedule-interval = Schedule: every { $hours } hours
+cli-schedule-action = Action: { $action }
+cli-schedule-last-run = Last run: { $time }
+cli-schedule-last-result = Last result: { $result }
+cli-schedule-next-run = Next run: { $time }
+cli-schedule-succeeded = Succeeded
+cli-schedule-failed = Scheduled update failed: { $error }
+cli-schedule-finished = Scheduled update finished
+cli-unknown-schedule-command = Unknown schedule command
+cli-update-available = Update available: { $hardware_id } { $current } -> { $latest }
+cli-agent-listening = Agent listening on { $endpoint }
+cli-agent-not-running = The agent is not running: { $error }
+cli-agent-running = The agent is running: { $endpoint }
+cli-agent-devices = Devices: { $devices }, updates: { $updates }
+cli-agent-tasks = Downloading: { $downloads }, installing: { $installs }
+cli-agent-last-error = Last error: { $error }
+cli-unknown-agent-command = Unknown agent command
+cli-command-sent = Command sent
+cli-command-completed = Command completed
+cli-command-failed = Command failed: { $error }
+cli-agent-disconnected = The agent connection was closed
+cli-missing-value = Missing value for { $arg }
+cli-invalid-number = Invalid number for { $arg }
+cli-unknown-argument = Unknown argument: { $arg }
+cli-prune-needs-days = history prune requires --days N
+cli-history-pruned = Removed { $count } installation records older than { $days } days
+cli-history-stats = { $total } records: { $succeeded } succeeded, { $failed } failed, { $cancelled } cancelled, { $in_progress } unfinished
+cli-history-durations = Duration: average { $average } ms, min { $min } ms, max { $max } ms
+cli-history-exported = Exported { $count } records to { $path }
+cli-unknown-action = Unknown action: { $value }
+cli-unknown-status = Unknown status: { $value }
+cli-invalid-date = Dates must be YYYY-MM-DD: { $value }
+cli-invalid-local-date = Invalid local date: { $value }
+
+## 主界面
+gui-start-failed = Failed to start the GUI
+
+## 托盘
+tray-updates-found = { $count } driver updates found
+tray-scan = Scan hardware
+tray-check-updates = Check for updates
+tray-open = Open main window
+tray-settings = Settings
+tray-exit = Exit
+tray-install-all = Install all updates
+tray-ignore-updates = Ignore updates
+tray-scanning = Scanning...
+tray-cancel-scan = Cancel scan
+tray-tooltip = { $app } - { $status }
+tray-status-idle = Drivers are up to date
+tray-installing = Installing updates...
+tray-cancel-install = Cancel installation
+tray-reboot-pending = Restart required to finish installation
+tray-create-failed = Failed to create the system tray: { $error }
+tray-update-failed = Failed to update the system tray: { $error }
+
+## 核心
+timeout-exceeded = { $operation } did not finish within { $seconds } seconds
+core-no-update-for = No update for this device: { $hardware_id }
+backup-automatic = Automatic backup
+event-loop-closed = The event loop has stopped
+schedule-submitted = Submitted: { $action }
+
+## 配置验证
+config-invalid-appearance = Invalid appearance settings: { $error }
+config-invalid-ui-scale = The interface scale must be between { $min } and { $max }
+config-empty-font-name = Fallback font names cannot be empty
+theme-system = Follow system
+theme-light = Light
+theme-dark = Dark
+config-invalid-notifications = Invalid notification settings: { $error }
+config-invalid-network = Invalid network settings: { $error }
+config-invalid-repository = Invalid driver repository settings: { $error }
+config-invalid-webhook-url = Webhook URL must start with http:// or https://: { $url }
+config-empty-header-name = Webhook header names cannot be empty
+config-empty-notify-directory = Notification file directory cannot be empty
+
+## 设置
+settings-missing-fonts = These fonts were not found and were replaced: { $fonts }
+settings-section-appearance = Appearance
+settings-theme = Theme
+settings-ui-scale = Interface scale
+settings-font-regular = Body font
+settings-font-bold = Heading font (bold)
+settings-font-fallbacks = Fallback fonts
+settings-font-fallbacks-hint = Tried in order when the body font lacks a character, separate fonts with commas
+settings-font-auto = Automatic
+settings-font-path-hint = or enter a font path
+settings-minimize-to-tray = System tray
+settings-minimize-to-tray-hint = Hide to tray when minimized or closed
+settings-section-notifications = Notifications
+settings-notifications-enabled = Background notifications
+settings-notifications-hint = Notify about updates, pending restarts and failures
+settings-notification-channels = Channels
+settings-add-channel = Add { $channel }
+settings-notify-directory-hint = Directory for notification files
+
+## 通知
+notify-kind-updates-found = Updates found
+notify-kind-reboot-required = Restart required
+notify-kind-error = Operation failed
+notify-channel-desktop = Desktop notification
+notify-channel-webhook = Webhook
+notify-channel-file = File drop
+notify-template-generic = Generic JSON
+notify-template-dingtalk = DingTalk
+notify-template-wecom = WeCom
+notify-template-feishu = Feishu
+notify-updates-found-title = { $count } driver updates available
+notify-updates-found = Devices with updates: { $devices }
+notify-updates-found-more = Devices with updates: { $devices } and { $count } more
+notify-reboot-required-title = Restart required
+notify-reboot-required = A driver was installed and takes effect after a restart ({ $hardware_id })
+notify-error-title = Driver update failed
+
+## 驱动仓库
+repository-usage =
+    Usage:
+      hamster-repository --catalog DIR [--listen ADDR] [--public-url URL] [--api-key KEY] [--help]
+
+      DIR: directory containing driver manifests (*.toml) and packages
+      ADDR: listen address, default 0.0.0.0:8080
+      URL: address clients use to reach this server, used in download links
+      KEY: API key required by the query and package endpoints, can also be set with HAMSTER_REPOSITORY_API_KEY
+repository-catalog-loaded = Loaded { $count } drivers from { $path }
+repository-listening = Driver repository running at { $url }, press Ctrl+C to stop
+repository-public-url-missing = No public URL set while listening on { $address }; download links use that address and may not be reachable from other computers
+repository-listen-failed = Cannot listen on { $address }: { $error }
+repository-catalog-missing = Driver catalog directory not found: { $path }
+repository-manifest-invalid = Invalid driver manifest { $path }: { $error }
+repository-duplicate-id = Duplicate driver ID: { $id }
+repository-missing-hardware-id = No hardware ID specified
+repository-package-unreadable = Cannot read driver package { $path }: { $error }
+repository-checksum-mismatch = Checksum mismatch: expected { $expected }, got { $actual }
+repository-busy = Server busy
+repository-request-timeout = Request timed out
+repository-headers-too-large = Request headers too large
+repository-invalid-content-length = Invalid Content-Length
+repository-payload-too-large = Request body too large
+repository-method-not-allowed = Method not allowed
+repository-package-not-found = Driver package not found
+repository-invalid-api-key = Invalid API key
+repository-endpoint-not-found = Endpoint not found
+repository-invalid-request = Invalid request: { $error }
+repository-invalid-hardware-id = Invalid hardware ID
+repository-no-driver = No driver available for { $hardware_id }
+repository-batch-too-large = At most { $max } devices can be queried at once
@@ -0,0 +1,705 @@
+# 简体中文
+
+## 错误
+error-scan = 硬件扫描错误: { $detail }
+error-backup = 驱动备份错误: { $detail }
+error-restore = 驱动恢复错误: { $detail }
+error-update = 驱动更新错误: { $detail }
+error-install = 驱动安装错误: { $detail }
+error-signature = 驱动签名错误: { $detail }
+error-network = 网络错误: { $detail }
+error-io = IO错误: { $detail }
+error-database = 数据库错误: { $detail }
+error-config = 配置错误: { $detail }
+error-parse = 解析错误: { $detail }
+error-permission = 权限错误: { $detail }
+error-init = 初始化错误: { $detail }
+error-download = 下载错误: { $detail }
+error-validation = 验证错误: { $detail }
+error-timeout = 超时错误: { $detail }
+error-cancelled = 操作已取消: { $detail }
+error-unknown = 未知错误: { $detail }
+
+## 后台操作
+op-initialize = 正在初始化...
+op-scan = 正在扫描硬件...
+op-find-updates = 正在查找驱动更新...
+op-update-all = 正在更新驱动...
+op-preferences = 正在保存更新偏好...
+op-apply-settings = 正在应用设置...
+op-downloads = 正在下载驱动文件...
+op-backup = 正在备份驱动...
+op-list-snapshots = 正在读取备份列表...
+op-open-restore-plan = 正在读取备份...
+op-restore = 正在恢复驱动...
+op-recovery = 正在处理未完成的安装...
+op-history = 正在读取安装历史...
+op-revert-change = 正在撤销更改...
+
+## 安装步骤
+step-restore-point = 创建还原点
+step-backup = 备份当前驱动
+step-download = 下载驱动
+step-verify = 校验驱动
+step-install = 安装驱动
+step-verify-after = 安装后验证
+
+## 主界面
+nav-system-info = 系统信息
+nav-hardware-scan = 硬件扫描
+nav-driver-updates = 驱动更新
+nav-downloads = 下载管理
+nav-restore = 驱动恢复
+nav-history = 安装历史
+nav-settings = 设置
+nav-about = 关于
+status-ready = 就绪
+action-cancel = 取消
+init-failed = 初始化核心失败: { $error }
+dashboard-system-summary = 系统摘要
+dashboard-title = 仪表盘
+dashboard-feature = 功能:
+dashboard-status = 状态:
+action-scan-hardware = 扫描硬件
+action-find-driver-updates = 查找驱动更新
+action-update-all = 更新所有驱动
+dashboard-hardware-summary = 硬件摘要
+dashboard-device-count = 扫描到 { $count } 个硬件设备
+action-start-scan = 开始扫描
+action-find-updates = 查找更新
+updates-reboot-required = 部分驱动需要重启计算机后才能生效
+downloads-action-failed = 操作失败: { $error }
+restore-backup-now = 备份当前驱动
+restore-refresh-snapshots = 刷新备份列表
+restore-snapshot-item = { $created_at } - { $description } ({ $count } 个驱动, { $id })
+action-open = 打开
+restore-package-path = 备份包路径:
+restore-open-package = 打开备份
+restore-description = 备份说明: { $description }
+restore-created-at = 创建时间: { $created_at }
+restore-verify-failed = 校验失败: { $problems }
+restore-device-missing = 当前设备不存在
+restore-matched-device = 匹配设备: { $device }
+restore-selected = 恢复所选
+restore-selected-count = 已选择 { $count } 个驱动
+settings-config-file = 配置文件: { $path }
+about-title = HamsterDrivers - Windows驱动管理工具
+about-version = 版本: { $version }
+about-author = 作者: { $author }
+about-license = 许可证: { $license }
+about-description = 这是一个功能强大的Windows驱动管理工具，旨在帮助用户自动扫描、识别、比较厂商服务器上的驱动版本，并下载安装最新驱动。
+scan-starting = 开始扫描...
+downloads-queue-failed = 加入下载队列失败: { $error }
+downloads-queued = 已将 { $count } 个驱动加入下载队列
+preferences-save-failed = 保存更新偏好失败: { $error }
+restore-manual-backup = 手动备份
+recovery-title = 未完成的驱动安装
+recovery-intro = 上次安装驱动时程序意外退出，以下操作没有完成：
+recovery-kind-update = 更新
+recovery-kind-install = 安装
+recovery-kind-restore = 恢复
+recovery-transaction = { $description } ({ $kind }) 开始于 { $started_at }
+recovery-interrupted-at = 中断于: { $step }
+recovery-completed-step = 已完成: { $step }
+recovery-not-started = 尚未开始
+recovery-resume = 继续安装
+recovery-rollback = 回滚
+recovery-discard = 忽略
+action-close = 关闭
+recovery-resumed = 继续安装完成: 成功 { $succeeded } 个，失败 { $failed } 个
+recovery-rolled-back = 已回滚到安装前的驱动
+recovery-rollback-partial = 部分驱动回滚失败，请查看日志
+recovery-discarded = 已忽略未完成的安装
+recovery-failed = 处理未完成的安装失败: { $error }
+config-load-failed = 读取配置失败: { $error }
+schedule-stopped = 定时更新已停止: { $error }
+schedule-agent-running = 后台代理正在运行，定时更新由代理执行
+downloads-load-failed = 读取下载队列失败: { $error }
+scan-finished = 硬件扫描完成，发现 { $count } 个设备
+scan-done = 扫描完成
+scan-failed = 硬件扫描失败: { $error }
+updates-found = 找到 { $count } 个驱动更新候选
+updates-none-found = 没有找到驱动更新
+updates-find-failed = 查找驱动更新失败: { $error }
+updates-nothing-to-update = 没有需要更新的驱动
+updates-finished = 更新完成
+updates-failed = 更新驱动失败: { $error }
+restore-backup-finished = 备份完成: { $id } ({ $count } 个驱动)
+restore-backup-failed = 备份驱动失败: { $error }
+restore-list-failed = 读取备份列表失败: { $error }
+restore-plan-count = 共 { $count } 个备份驱动
+restore-open-failed = 打开备份失败: { $error }
+restore-finished = 恢复完成
+restore-failed = 恢复驱动失败: { $error }
+history-load-failed = 读取安装历史失败: { $error }
+history-reverted-reboot = 已撤销更改，重启后生效: { $message }
+history-reverted = 已撤销更改: { $message }
+history-revert-failed = 撤销失败: { $error }
+settings-saved = 设置已保存
+settings-saved-deferred = 设置已保存，正在进行的操作结束后生效
+settings-save-failed = 保存设置失败: { $error }
+journal-read-failed = 读取安装日志失败: { $error }
+journal-unreadable = 读取安装日志 { $id } 失败: { $error }
+journal-truncated-record = 安装日志 { $id } 的最后一条记录不完整，已忽略
+results-summary = { $title }: 成功 { $succeeded } 个，失败 { $failed } 个
+
+## 设置
+settings-apply = 应用
+settings-revert = 还原
+settings-defaults = 恢复默认
+settings-unapplied = 有未应用的修改
+settings-section-downloads = 下载
+settings-section-network = 网络
+settings-section-scanner = 硬件扫描
+settings-section-channels = 更新渠道
+settings-section-schedule = 定时更新
+settings-section-backup = 备份保留
+settings-section-timeouts = 超时（0 表示不限时）
+settings-section-logging = 日志
+settings-download-dir = 下载目录:
+settings-concurrency = 同时下载数:
+settings-per-host-concurrency = 同一主机同时下载数:
+settings-download-rate = 总下载速度上限:
+settings-task-rate = 单个任务速度上限:
+settings-rate-unlimited-hint = 0 表示不限速
+settings-large-download-threshold = 大文件大小:
+settings-large-download-threshold-hint = 不小于该大小的文件只在下方的时间段内下载，0 表示不区分大小
+settings-large-download-windows = 大文件下载时段:
+settings-peer-cache = 局域网缓存:
+settings-peer-cache-enabled = 下载前先从局域网查找
+settings-peer-cache-hint = 有校验和的驱动会先在本机缓存、共享文件夹和其他计算机中查找
+settings-peer-cache-serve = 向其他计算机提供:
+settings-peers = 其他计算机:
+settings-peers-hint = 多个地址用逗号分隔
+settings-shared-folder = 共享文件夹:
+settings-peer-timeout = 询问超时:
+settings-proxy = 代理:
+settings-aria2 = aria2 地址:
+settings-connect-timeout = 连接超时:
+settings-scan-methods = 扫描方式:
+settings-hidden-devices = 隐藏设备:
+settings-device-classes = 设备类别:
+settings-beta = 测试版:
+settings-ignored-pinned = 忽略和固定:
+settings-schedule-enabled = 启用:
+settings-schedule-action = 执行操作:
+settings-schedule-plan = 计划:
+settings-schedule-every = 每隔
+settings-jitter = 随机推迟:
+settings-maintenance-windows = 维护时间段:
+settings-window-to = 至
+settings-log-level = 日志级别:
+settings-use-proxy = 使用代理
+settings-proxy-hint = 支持 http://、https://、socks5:// 和 socks5h://，不使用时按系统代理设置
+settings-proxy-auth = 代理认证:
+settings-proxy-username = 用户名
+settings-proxy-password = 密码
+settings-no-proxy = 不使用代理的地址:
+settings-no-proxy-hint = 主机名、以点开头的域名或 IP 网段，逗号分隔
+settings-ca-certificates = 信任的 CA 证书:
+settings-ca-certificate-hint = PEM 证书文件路径
+settings-add-certificate = 添加证书
+settings-network-timeouts = 网络超时:
+settings-connect-prefix = 连接 
+settings-request-prefix = 请求 
+settings-retries = 失败重试次数:
+settings-retry-backoff-prefix = 首次等待 
+settings-repository = 驱动仓库:
+settings-repository-hint = 填写自建驱动仓库的地址后先从仓库查找驱动
+settings-repository-api-key = API 密钥
+settings-vendor-fallback = 仓库中没有时从厂商网站查找
+settings-port-prefix = 端口 
+unit-seconds-suffix =  秒
+settings-wmi-hint = 能补充 SetupAPI 漏掉的设备，但扫描较慢
+settings-include-hidden = 设备列表默认显示未连接的设备
+action-remove = 移除
+settings-all-classes = 全部类别
+settings-add-class = 添加类别
+settings-include-beta = 提示测试版（Beta）驱动
+settings-ignored-pinned-summary = 忽略了 { $ignored } 个版本，固定了 { $pinned } 个设备，可在驱动更新页面修改
+settings-schedule-enable = 定时检查更新（重启程序后生效）
+settings-use-cron = Cron 表达式
+settings-cron-hint = 分 时 日 月 周
+unit-hours-suffix =  小时
+unit-minutes-suffix =  分钟
+settings-every-day = 每天
+action-delete = 删除
+settings-add-window = 添加时间段
+settings-window-hint = 星期用 mon..sun 逗号分隔，时间为 HH:MM
+settings-keep-last = 最近的快照:
+settings-keep-daily = 每天保留:
+settings-keep-weekly = 每周保留:
+settings-keep-monthly = 每月保留:
+settings-max-rollback-points = 回滚点数量:
+settings-timeout-scan = 硬件扫描:
+settings-timeout-match = 驱动匹配:
+settings-timeout-download = 驱动下载:
+settings-timeout-install = 驱动安装:
+unit-snapshots-suffix =  个
+settings-log-level-hint = 重启程序后生效，设置了 RUST_LOG 环境变量时以环境变量为准
+settings-section-interface = 界面
+settings-language = 语言:
+
+## 配置验证
+config-invalid-log-level = 未知的日志级别: { $level }
+config-invalid-language = 不支持的界面语言: { $language }
+config-empty-retention = 保留策略至少需要保留一个快照
+config-no-rollback-points = 至少需要保留一个回滚点
+config-zero-aria2-port = aria2端口不能为0
+config-invalid-concurrency = 同时下载数量应在1到16之间
+config-invalid-per-host-concurrency = 同一主机同时下载数量应在1到16之间
+config-zero-download-timeout = 下载超时时间不能为0
+config-invalid-proxy = 代理地址应以 http://、https://、socks5:// 或 socks5h:// 开头
+config-zero-network-timeout = 网络连接和请求的超时时间不能为0
+config-invalid-retries = 重试次数应在0到10之间
+config-invalid-listen-address = 监听地址无效: { $address }
+config-invalid-peer = 其他计算机的地址应以 http:// 或 https:// 开头: { $peer }
+config-empty-shared-folder = 共享文件夹不能为空
+config-zero-peer-timeout = 询问其他计算机的超时时间不能为0
+config-invalid-repository-url = 驱动仓库地址应以 http:// 或 https:// 开头: { $url }
+config-empty-ca-path = 证书文件路径不能为空
+config-no-scan-method = 至少需要启用一种扫描方式
+config-empty-device-class = 设备类别不能为空
+config-zero-install-timeout = 安装超时不能为0，否则卡住的安装程序无法被终止
+config-zero-interval = 检查间隔不能为0
+config-invalid-time = 时间格式应为 HH:MM: { $value }
+config-invalid-weekday = 无效的星期: { $value }
+schedule-action-check-only = 只检查更新
+schedule-action-download = 检查并下载
+schedule-action-download-and-install = 检查、下载并安装
+cron-field-count = Cron表达式需要5个字段: { $expression }
+cron-invalid-step = 无效的步长: { $part }
+cron-zero-step = 步长不能为0: { $part }
+cron-out-of-range = 取值超出范围 { $min }-{ $max }: { $part }
+cron-invalid-value = 无效的取值: { $value }
+config-read-failed = 读取配置文件失败: { $error }
+config-parse-failed = 解析配置文件失败: { $error }
+config-serialize-failed = 序列化配置失败: { $error }
+config-create-dir-failed = 创建配置目录失败: { $error }
+config-write-failed = 写入配置文件失败: { $error }
+config-invalid-app = 应用程序配置验证失败: { $error }
+config-invalid-download = 下载配置验证失败: { $error }
+config-invalid-scanner = 扫描配置验证失败: { $error }
+config-invalid-schedule = 定时更新配置验证失败: { $error }
+config-invalid-timeouts = 超时配置验证失败: { $error }
+config-invalid-backup = 备份配置验证失败: { $error }
+
+## 下载
+download-status-queued = 排队中
+download-status-downloading = 下载中
+download-status-waiting = 等待下载时段
+peer-cache-open-failed = 无法打开驱动包缓存: { $error }
+peer-cache-serve-failed = 无法在 { $address } 提供驱动包: { $error }
+download-status-paused = 已暂停
+download-status-completed = 已完成
+download-status-failed = 失败
+download-status-cancelled = 已取消
+download-priority-user = 手动
+download-priority-scheduled = 定时
+download-partial-removed = 已删除未完成的下载: { $path }
+
+## 设备
+device-status-ok = 正常
+device-status-error = 错误
+device-status-degraded = 降级
+device-status-unknown = 未知
+
+## 驱动更新
+channel-stable = 正式版
+channel-beta = 测试版
+
+## 设备
+device-column-name = 名称
+device-column-class = 类型
+device-column-vendor = 厂商
+device-column-driver-version = 驱动版本
+device-column-driver-date = 驱动日期
+device-column-status = 状态
+device-not-present = 未连接
+device-problem-code = 问题代码 { $code }
+device-not-checked = 未检查
+device-up-to-date = 已是最新
+device-update-to = 可更新到 { $version }
+device-detail-class = 设备类型:
+device-detail-vendor = 厂商:
+device-detail-status = 状态:
+device-detail-problem-code = 问题代码:
+device-detail-present = 已连接:
+common-yes = 是
+common-no = 否
+device-detail-instance-id = 实例ID:
+device-detail-hardware-id = 硬件ID:
+device-detail-driver-version = 驱动版本:
+device-detail-driver-date = 驱动日期:
+device-detail-driver-provider = 驱动提供商:
+device-detail-inf = INF 文件:
+device-detail-update-status = 更新状态:
+device-detail-all-hardware-ids = 全部硬件ID
+device-detail-compatible-ids = 兼容ID
+
+## 驱动更新
+risk-beta = 测试版驱动
+risk-no-checksum = 无法校验文件
+risk-unknown-size = 大小未知
+risk-critical-device = 关键设备，失败可能影响启动
+risk-unknown-version-order = 无法确认版本较新
+risk-major-version = 主版本变化
+update-item-unfinished = 未完成
+update-item-pinned = [已固定]
+update-item-ignored = [已忽略]
+update-item-no-update = 未找到更新
+update-item-current-version = 当前版本:
+update-item-new-version = 新版本:
+update-item-release-date = 发布日期:
+common-unknown = 未知
+update-item-size = 大小:
+update-item-source = 来源:
+update-item-channel = 渠道:
+update-item-risks = 注意: { $risks }
+list-separator = ，
+update-item-queued = 等待更新
+update-item-downloading = 下载中 { $percent }%
+update-item-installed-reboot = 已安装，重启后生效
+update-item-installed = 已安装
+update-item-failed = 失败: { $error }
+update-item-unignore = 取消忽略
+update-item-ignore = 忽略此版本
+update-item-unpin = 取消固定
+update-item-pin = 固定当前驱动
+update-item-release-notes = 发布说明
+
+## 下载
+downloads-summary = 共 { $total } 个任务，{ $active } 个正在下载
+downloads-clear-finished = 清除已结束
+downloads-empty = 下载队列为空，可在驱动更新页面选择驱动后点击“仅下载所选”
+downloads-remaining = 剩余 { $time }
+downloads-pause = 暂停
+downloads-resume = 继续
+downloads-retry = 重试
+downloads-cancel = 取消
+downloads-remove = 移除
+duration-seconds = { $seconds }秒
+duration-minutes-seconds = { $minutes }分{ $seconds }秒
+duration-hours-minutes = { $hours }小时{ $minutes }分
+
+## 安装历史
+history-action-install = 安装
+history-action-update = 更新
+history-action-uninstall = 卸载
+history-action-backup = 备份
+history-action-restore = 还原
+history-action-rollback = 回滚
+history-action-started = 开始执行{ $action }操作
+history-status-success = 成功
+history-status-failed = 失败
+history-status-cancelled = 已取消
+history-status-in-progress = 进行中
+history-period-all = 全部时间
+history-period-day = 最近一天
+history-period-week = 最近一周
+history-period-month = 最近一个月
+common-search = 搜索:
+history-all-actions = 全部操作
+history-all-results = 全部结果
+history-refresh = 刷新
+history-confirm-revert = 确定要撤销对 { $device } 的更改并恢复之前的驱动吗？
+history-confirm-revert-button = 确定撤销
+common-cancel = 取消
+history-empty = 还没有安装记录
+history-no-match = 没有符合条件的记录
+history-column-time = 时间
+history-column-device = 设备
+history-column-action = 操作
+history-column-result = 结果
+history-column-version = 版本
+history-column-duration = 耗时
+history-column-message = 说明
+history-revert = 撤销
+history-revert-hint = 用安装前备份的驱动回滚这次更改
+
+## 设备
+devices-not-scanned = 未进行扫描
+common-close = 关闭
+devices-visible = 显示 { $visible } / { $total } 个设备
+devices-problems-only = 仅问题设备
+devices-outdated-only = 仅可更新
+devices-show-hidden = 显示隐藏设备
+devices-group-by-class = 按类型分组
+common-select-all = 全选
+devices-clear-selection = 取消选择
+devices-copy-selected = 复制所选信息
+devices-selected = 已选择 { $count } 个设备
+
+## 驱动更新
+common-select-none = 全不选
+updates-update-selected = 更新所选 ({ $count })
+updates-download-selected = 仅下载所选
+updates-show-all = 显示已忽略和无需更新的设备
+updates-available = { $count } 个驱动可以更新
+
+## 格式
+size-bytes = { $count } 字节
+
+## 核心
+core-initializing = 正在初始化驱动更新核心...
+core-system-info-ready = 系统信息获取完成
+core-aria2-started = Aria2 RPC服务器启动完成
+core-aria2-failed = Aria2 RPC服务器启动失败: { $error }，将继续运行但下载功能可能受限
+core-scan-started = 开始扫描系统硬件...
+scan-step-started = 开始硬件扫描
+scan-step-scanning = 硬件扫描
+scan-step-scanned = 硬件扫描完成
+scan-step-stored = 硬件信息入库完成
+scan-step-finished = 扫描完成
+core-scan-finished = 系统硬件扫描完成，共发现 { $count } 个设备
+core-search-started = 开始查找驱动更新...
+core-search-operation = 查找驱动更新
+core-matching-operation = 驱动匹配
+core-search-finished = 驱动更新查找完成，找到 { $count } 个更新候选
+core-no-scan-for-search = 未进行硬件扫描，无法查找驱动更新
+transaction-install = 安装驱动 { $hardware_id }
+core-install-started = 开始安装驱动: { $file } ({ $hardware_id })
+core-install-succeeded = 驱动安装成功: { $file }
+install-verify-failed = ；安装后验证失败: { $error }
+core-install-failed-rolling-back = 驱动安装失败，正在回滚: { $message }
+install-rolled-back = ；已回滚到安装前的驱动
+install-rollback-failed = ；回滚失败: { $error }
+core-install-failed-no-rollback = 驱动安装失败，且没有可用的回滚点: { $message }
+core-device-missing-after-install = 安装后未检测到设备 { $hardware_id }
+core-current-driver-version = 当前驱动版本 { $version }
+transaction-update = 更新驱动 { $device }
+core-download-progress = 下载进度: { $percent }% - { $file }
+core-download-waiting = { $file } 较大，将在允许下载大文件的时间段内下载
+core-no-matching-driver = 没有找到匹配的驱动
+transaction-batch-update = 批量更新驱动
+core-update-cancelled = 更新已取消
+core-update-failed = 更新失败: { $error }
+app-name = 仓鼠驱动管家
+core-restore-point-failed = 创建系统还原点失败: { $error }，将继续执行
+core-no-rollback-point = 未能创建回滚点
+core-rollback-point-created = 已创建回滚点 { $id }: { $count } 个驱动
+core-nothing-exported = 未能导出任何当前驱动，本次安装将无法自动回滚
+core-rollback-point-failed = 创建回滚点失败: { $error }，本次安装将无法自动回滚
+core-export-failed = 导出驱动失败 { $device }: { $error }
+core-register-backup-failed = 登记驱动备份失败 { $device }: { $error }
+core-rollback-point-missing = 回滚点不存在: { $id }
+core-rollback-message = 回滚点 { $id }: { $message }
+core-log-missing = 安装记录不存在: { $id }
+core-log-not-revertible = 该记录无法撤销: { $id }
+core-rollback-point-no-device = 回滚点 { $id } 中没有该设备的驱动
+core-no-scan-for-restore = 未进行硬件扫描，无法匹配备份驱动
+core-restore-plan = 备份包 { $package } 中共 { $total } 个驱动，其中 { $restorable } 个可恢复到当前设备
+core-no-scan-for-backup = 未进行硬件扫描，无法备份驱动
+core-backup-finished = 驱动备份完成: { $drivers } 个驱动，新增 { $new_files } 个文件 ({ $size })，复用 { $reused } 个已有文件
+core-entry-not-in-package = 选择的驱动不在备份包中
+transaction-restore = 恢复备份驱动
+core-restore-cancelled = 恢复已取消: { $device }
+core-backup-entry = 备份条目 { $id }
+core-restoring = 恢复驱动: { $device } -> { $version }
+core-restore-failed = 恢复失败: { $error }
+core-log-write-failed = 写入安装日志失败: { $error }
+core-no-matching-driver-for = 没有找到匹配的驱动: { $hardware_id }
+core-transaction-no-rollback = 该事务没有可用的回滚点: { $id }
+system-summary =
+    Windows版本: { $edition }
+    版本: { $version }
+    内部版本号: { $build }
+    激活状态: { $activation }
+    DirectX: { $directx }
+    制造商: { $manufacturer }
+    型号: { $model }
+    CPU: { $cpu }
+    内存: { $memory }
+    显卡: { $gpu }
+hardware-summary = 扫描到 { $count } 个硬件设备
+update-summary = 总共 { $total } 个设备，其中 { $outdated } 个需要更新
+verify-empty-file = 驱动文件为空: { $path }
+verify-size-mismatch = 驱动文件大小不符: 期望 { $expected } 字节，实际 { $actual } 字节
+verify-checksum-mismatch = 驱动文件SHA-256不符: { $path }
+verify-passed-checksum = { $size } 字节，SHA-256 { $checksum }
+core-device-missing = 设备不存在: { $device }
+core-backup-verify-failed = 备份校验失败 { $device }: { $problems }
+
+## 命令行
+cli-usage =
+    用法:
+      hamster-drivers backup list
+      hamster-drivers backup fsck
+      hamster-drivers backup gc
+      hamster-drivers backup prune [--keep-last N] [--keep-daily N] [--keep-weekly N] [--keep-monthly N] [--dry-run]
+      hamster-drivers history [list|stats] [--hardware-id ID] [--action ACTION] [--status STATUS] [--since YYYY-MM-DD] [--until YYYY-MM-DD] [--limit N] [--csv FILE]
+      hamster-drivers history prune --days N
+      hamster-drivers schedule status
+      hamster-drivers schedule run
+      hamster-drivers agent run
+      hamster-drivers agent status
+      hamster-drivers agent send COMMAND [--wait]
+
+      COMMAND: scan, check, download, install, backup, restore PATH, refresh, cancel, shutdown
+
+      ACTION: install, update, uninstall, backup, restore, rollback
+      STATUS: success, failed, cancelled, in-progress
+cli-unknown-command = 未知命令
+cli-driver-count = { $count } 个驱动
+cli-fsck-checked = 已检查 { $snapshots } 个快照，{ $files } 个文件
+cli-fsck-unreadable = 快照无法读取: { $id }
+cli-fsck-missing = 快照 { $id } 引用的文件缺失: { $hash }
+cli-fsck-corrupt = 文件已损坏: { $hash }
+cli-fsck-orphaned = { $count } 个文件未被引用，可运行 backup gc 清理
+cli-fsck-healthy = 备份存储完整
+cli-gc-finished = 已删除 { $count } 个未引用文件，释放 { $size }
+cli-prune-would-remove = 将删除: { $id }
+cli-prune-removed = 已删除: { $id }
+cli-prune-summary = 保留 { $kept } 个快照，删除 { $removed } 个快照
+cli-unknown-backup-command = 未知的备份命令
+cli-schedule-enabled = 定时更新: { $state }
+cli-enabled = 已启用
+cli-disabled = 未启用
+cli-schedule-cron = 计划: cron { $cron }
+cli-schedule-interval = 计划: 每 { $hours } 小时
+cli-schedule-action = 动作: { $action }
+cli-schedule-last-run = 上次执行: { $time }
+cli-schedule-last-result = 上次结果: { $result }
+cli-schedule-next-run = 下次执行: { $time }
+cli-schedule-succeeded = 成功
+cli-schedule-failed = 定时更新失败: { $error }
+cli-schedule-finished = 定时更新完成
+cli-unknown-schedule-command = 未知的定时命令
+cli-update-available = 可更新: { $hardware_id } { $current } -> { $latest }
+cli-agent-listening = 代理正在监听: { $endpoint }
+cli-agent-not-running = 代理未运行: { $error }
+cli-agent-running = 代理正在运行: { $endpoint }
+cli-agent-devices = 设备: { $devices }，可更新: { $updates }
+cli-agent-tasks = 下载中: { $downloads }，安装中: { $installs }
+cli-agent-last-error = 最近的错误: { $error }
+cli-unknown-agent-command = 未知的代理命令
+cli-command-sent = 命令已提交
+cli-command-completed = 命令已完成
+cli-command-failed = 命令失败: { $error }
+cli-agent-disconnected = 代理连接已关闭
+cli-missing-value = 参数 { $arg } 缺少取值
+cli-invalid-number = 参数 { $arg } 的数值无效
+cli-unknown-argument = 未知参数: { $arg }
+cli-prune-needs-days = history prune 需要 --days N
+cli-history-pruned = 已删除 { $count } 条 { $days } 天前的安装记录
+cli-history-stats = 共 { $total } 条记录: 成功 { $succeeded }，失败 { $failed }，取消 { $cancelled }，未完成 { $in_progress }
+cli-history-durations = 耗时: 平均 { $average } 毫秒，最短 { $min } 毫秒，最长 { $max } 毫秒
+cli-history-exported = 已导出 { $count } 条记录到 { $path }
+cli-unknown-action = 未知的操作类型: { $value }
+cli-unknown-status = 未知的状态: { $value }
+cli-invalid-date = 日期格式应为 YYYY-MM-DD: { $value }
+cli-invalid-local-date = 无效的本地日期: { $value }
+
+## 主界面
+gui-start-failed = 启动GUI失败
+
+## 托盘
+tray-updates-found = 发现 { $count } 个驱动更新
+tray-scan = 扫描硬件
+tray-check-updates = 检查更新
+tray-open = 打开主界面
+tray-settings = 设置
+tray-exit = 退出
+tray-install-all = 安装所有更新
+tray-ignore-updates = 忽略更新
+tray-scanning = 正在扫描...
+tray-cancel-scan = 取消扫描
+tray-tooltip = { $app } - { $status }
+tray-status-idle = 驱动均为最新
+tray-installing = 正在安装更新...
+tray-cancel-install = 取消安装
+tray-reboot-pending = 需要重启以完成安装
+tray-create-failed = 创建系统托盘失败: { $error }
+tray-update-failed = 更新系统托盘失败: { $error }
+
+## 核心
+timeout-exceeded = { $operation }超过 { $seconds } 秒未完成
+core-no-update-for = 没有该设备的更新: { $hardware_id }
+backup-automatic = 自动备份
+event-loop-closed = 事件循环已关闭
+schedule-submitted = 已提交: { $action }
+
+## 配置验证
+config-invalid-appearance = 界面外观配置验证失败: { $error }
+config-invalid-ui-scale = 界面缩放应在 { $min } 到 { $max } 之间
+config-empty-font-name = 后备字体不能为空
+theme-system = 跟随系统
+theme-light = 浅色
+theme-dark = 深色
+config-invalid-notifications = 通知配置验证失败: { $error }
+config-invalid-network = 网络配置验证失败: { $error }
+config-invalid-repository = 驱动仓库配置验证失败: { $error }
+config-invalid-webhook-url = Webhook 地址必须以 http:// 或 https:// 开头: { $url }
+config-empty-header-name = Webhook 请求头名称不能为空
+config-empty-notify-directory = 通知文件目录不能为空
+
+## 设置
+settings-missing-fonts = 找不到以下字体，已使用其他字体代替: { $fonts }
+settings-section-appearance = 外观
+settings-theme = 主题
+settings-ui-scale = 界面缩放
+settings-font-regular = 正文字体
+settings-font-bold = 标题字体（粗体）
+settings-font-fallbacks = 后备字体
+settings-font-fallbacks-hint = 正文字体缺字时按顺序尝试，多个字体用逗号分隔
+settings-font-auto = 自动
+settings-font-path-hint = 或输入字体路径
+settings-minimize-to-tray = 系统托盘
+settings-minimize-to-tray-hint = 最小化或关闭时隐藏到托盘
+settings-section-notifications = 通知
+settings-notifications-enabled = 后台通知
+settings-notifications-hint = 发现更新、需要重启或操作失败时通知
+settings-notification-channels = 通知渠道
+settings-add-channel = 添加{ $channel }
+settings-notify-directory-hint = 通知文件保存目录
+
+## 通知
+notify-kind-updates-found = 发现更新
+notify-kind-reboot-required = 需要重启
+notify-kind-error = 操作失败
+notify-channel-desktop = 桌面通知
+notify-channel-webhook = Webhook
+notify-channel-file = 文件投递
+notify-template-generic = 通用 JSON
+notify-template-dingtalk = 钉钉
+notify-template-wecom = 企业微信
+notify-template-feishu = 飞书
+notify-updates-found-title = 发现 { $count } 个驱动更新
+notify-updates-found = 可更新的设备: { $devices }
+notify-updates-found-more = 可更新的设备: { $devices } 等 { $count } 个
+notify-reboot-required-title = 需要重启
+notify-reboot-required = 驱动已安装，重启计算机后生效（{ $hardware_id }）
+notify-error-title = 驱动更新失败
+
+## 驱动仓库
+repository-usage =
+    用法:
+      hamster-repository --catalog DIR [--listen ADDR] [--public-url URL] [--api-key KEY] [--help]
+
+      DIR: 驱动清单（*.toml）和驱动包所在的目录
+      ADDR: 监听地址，默认 0.0.0.0:8080
+      URL: 客户端访问本服务的地址，用于生成下载链接
+      KEY: 查询接口和驱动包下载要求的 API 密钥，也可以用环境变量 HAMSTER_REPOSITORY_API_KEY 设置
+repository-catalog-loaded = 已从 { $path } 加载 { $count } 个驱动
+repository-listening = 驱动仓库正在 { $url } 上运行，按 Ctrl+C 停止
+repository-public-url-missing = 监听 { $address } 时未设置公开地址，下载链接将使用该地址，其他计算机可能无法访问
+repository-listen-failed = 无法监听 { $address }: { $error }
+repository-catalog-missing = 驱动目录不存在: { $path }
+repository-manifest-invalid = 驱动清单无效 { $path }: { $error }
+repository-duplicate-id = 驱动标识重复: { $id }
+repository-missing-hardware-id = 未填写硬件ID
+repository-package-unreadable = 无法读取驱动包 { $path }: { $error }
+repository-checksum-mismatch = 文件校验和不匹配: 期望 { $expected }，实际 { $actual }
+repository-busy = 服务繁忙
+repository-request-timeout = 请求超时
+repository-headers-too-large = 请求头过长
+repository-invalid-content-length = Content-Length 无效
+repository-payload-too-large = 请求体过大
+repository-method-not-allowed = 不支持的请求方法
+repository-package-not-found = 驱动包不存在
+repository-invalid-api-key = API密钥无效
+repository-endpoint-not-found = 接口不存在
+repository-invalid-request = 请求格式无效: { $error }
+repository-invalid-hardware-id = 硬件ID无效
+repository-no-driver = 没有适用于 { $hardware_id } 的驱动
+repository-batch-too-large = 一次最多查询 { $max } 个设备
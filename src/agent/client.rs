@@ -0,0 +1,95 @@
+//! 代理客户端
+
+use tokio::io::{BufReader, ReadHalf, WriteHalf};
+use crate::agent::{read_message, write_message, AgentRequest, AgentResponse, AgentStream, PROTOCOL_VERSION};
+use crate::core::{AppCommand, AppState, StateEvent};
+use crate::utils::{HamsterError, Result};
+
+/// 连接代理的客户端，请求按顺序应答
+pub struct AgentClient {
+    reader: BufReader<ReadHalf<AgentStream>>,
+    writer: WriteHalf<AgentStream>,
+}
+
+impl AgentClient {
+    /// 连接代理并检查协议版本
+    pub async fn connect(endpoint: &str) -> Result<Self> {
+        let mut client = Self::from_stream(crate::agent::connect(endpoint).await?);
+        let version = client.ping().await?;
+        if version != PROTOCOL_VERSION {
+            return Err(HamsterError::ValidationError(format!(
+                "代理协议版本不兼容: 代理 {}，客户端 {}", version, PROTOCOL_VERSION
+            )));
+        }
+        Ok(client)
+    }
+
+    /// 连接默认地址的代理
+    pub async fn connect_default() -> Result<Self> {
+        Self::connect(&crate::agent::default_endpoint()?).await
+    }
+
+    pub fn from_stream(stream: AgentStream) -> Self {
+        let (reader, writer) = tokio::io::split(stream);
+        Self { reader: BufReader::new(reader), writer }
+    }
+
+    /// 返回代理的协议版本
+    pub async fn ping(&mut self) -> Result<u32> {
+        match self.request(AgentRequest::Ping).await? {
+            AgentResponse::Pong { version } => Ok(version),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// 发送命令，命令进入事件循环队列后返回
+    pub async fn send_command(&mut self, command: AppCommand) -> Result<()> {
+        match self.request(AgentRequest::Command(command)).await? {
+            AgentResponse::Accepted => Ok(()),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub async fn get_state(&mut self) -> Result<AppState> {
+        match self.request(AgentRequest::GetState).await? {
+            AgentResponse::State(state) => Ok(*state),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// 订阅状态事件，之后用 next_event 读取
+    pub async fn subscribe(&mut self) -> Result<()> {
+        match self.request(AgentRequest::Subscribe).await? {
+            AgentResponse::Subscribed => Ok(()),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// 读取下一个状态事件，连接关闭时返回 None
+    pub async fn next_event(&mut self) -> Result<Option<StateEvent>> {
+        match read_message(&mut self.reader).await? {
+            Some(AgentResponse::Event(event)) => Ok(Some(event)),
+            Some(other) => Err(unexpected(other)),
+            None => Ok(None),
+        }
+    }
+
+    /// 订阅后的连接上可能先收到事件，跳过它们直到拿到应答
+    async fn request(&mut self, request: AgentRequest) -> Result<AgentResponse> {
+        write_message(&mut self.writer, &request).await?;
+        loop {
+            match read_message(&mut self.reader).await? {
+                Some(AgentResponse::Event(_)) => continue,
+                Some(response) => return Ok(response),
+                None => return Err(HamsterError::NetworkError("代理连接已关闭".to_string())),
+            }
+        }
+    }
+}
+
+fn unexpected(response: AgentResponse) -> HamsterError {
+    match response {
+        AgentResponse::Error(message) => HamsterError::Unknown(message),
+        other => HamsterError::ParseError(format!("代理返回了意外的应答: {:?}", other)),
+    }
+}
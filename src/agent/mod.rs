@@ -0,0 +1,17 @@
+//! 后台代理
+//!
+//! 代理进程持有核心控制器和事件循环，命令行通过本地 IPC
+//! （Linux 上为 Unix 套接字，Windows 上为命名管道）发送命令并订阅状态事件。
+//!
+//! 协议只包含 AppCommand 和 StateEvent，没有安装历史、恢复计划、下载队列和设置，
+//! 桌面界面和托盘因此仍在进程内持有核心。代理运行时界面不再执行定时更新，由代理负责。
+
+mod client;
+mod protocol;
+mod server;
+mod transport;
+
+pub use client::*;
+pub use protocol::*;
+pub use server::*;
+pub use transport::*;
@@ -0,0 +1,71 @@
+//! 代理通信协议
+//!
+//! 每条消息是一行 JSON，客户端发送 AgentRequest，代理回复 AgentResponse。
+//! 订阅后代理会在同一连接上持续推送 AgentResponse::Event。
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use crate::core::{AppCommand, AppState, StateEvent};
+use crate::utils::{HamsterError, Result};
+
+/// 协议版本，不兼容的修改需要递增
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// 单条消息的长度上限，防止异常客户端占满内存
+const MAX_MESSAGE_LEN: u64 = 16 * 1024 * 1024;
+
+/// 客户端请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum AgentRequest {
+    Ping,
+    /// 交给事件循环执行的命令
+    Command(AppCommand),
+    /// 获取当前状态快照
+    GetState,
+    /// 订阅之后的状态事件
+    Subscribe,
+}
+
+/// 代理回复
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum AgentResponse {
+    Pong { version: u32 },
+    /// 命令已进入队列
+    Accepted,
+    State(Box<AppState>),
+    Subscribed,
+    Event(StateEvent),
+    Error(String),
+}
+
+/// 写入一条消息
+pub async fn write_message<W, T>(writer: &mut W, message: &T) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// 读取一条消息，连接关闭时返回 None
+pub async fn read_message<R, T>(reader: &mut R) -> Result<Option<T>>
+where
+    R: AsyncBufRead + Unpin,
+    T: DeserializeOwned,
+{
+    let mut line = String::new();
+    let len = (&mut *reader).take(MAX_MESSAGE_LEN).read_line(&mut line).await?;
+    if len == 0 {
+        return Ok(None);
+    }
+    if !line.ends_with('\n') && len as u64 == MAX_MESSAGE_LEN {
+        return Err(HamsterError::ParseError(format!("消息超过 {} 字节", MAX_MESSAGE_LEN)));
+    }
+    Ok(Some(serde_json::from_str(line.trim_end())?))
+}
@@ -0,0 +1,243 @@
+//! 代理服务端
+
+use std::sync::Arc;
+use tokio::io::BufReader;
+use tokio::sync::{broadcast, mpsc, RwLock};
+use crate::agent::{read_message, write_message, AgentListener, AgentRequest, AgentResponse, AgentStream, PROTOCOL_VERSION};
+use crate::config::ConfigManager;
+use crate::core::{AppCommand, AppState, DriverUpdaterCore, EventLoop, StateEvent, StateEventHandler, UpdateScheduler};
+use crate::notify::NotificationDispatcher;
+use crate::utils::{CancelHandle, HamsterError, Result};
+
+// 订阅者跟不上时最多缓存的事件数，超出后丢弃最旧的事件
+const EVENT_BUFFER: usize = 256;
+
+/// 代理服务端，把客户端命令转发给事件循环，把事件广播给订阅者
+#[derive(Clone)]
+pub struct AgentServer {
+    command_tx: mpsc::Sender<AppCommand>,
+    events: broadcast::Sender<StateEvent>,
+    state: Arc<RwLock<AppState>>,
+    cancel: Option<CancelHandle>,
+    notifications: Option<Arc<NotificationDispatcher>>,
+}
+
+impl AgentServer {
+    /// 使用已有的命令通道和状态创建服务端
+    pub fn new(command_tx: mpsc::Sender<AppCommand>, state: Arc<RwLock<AppState>>) -> Self {
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        Self { command_tx, events, state, cancel: None, notifications: None }
+    }
+
+    /// Cancel 命令直接作用于该句柄，不必排在正在执行的命令之后
+    pub fn with_cancel_handle(mut self, cancel: CancelHandle) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// 在后台发现更新或需要重启时发送通知
+    pub fn with_notifications(mut self, notifications: NotificationDispatcher) -> Self {
+        self.notifications = Some(Arc::new(notifications));
+        self
+    }
+
+    /// 创建核心控制器和事件循环，启用了定时更新时同时启动调度器
+    pub async fn start(config: &ConfigManager) -> Result<Self> {
+        let mut core = DriverUpdaterCore::new(":memory:", config).await
+            .map_err(|e| HamsterError::InitError(e.to_string()))?;
+        core.initialize().await
+            .map_err(|e| HamsterError::InitError(e.to_string()))?;
+
+        let (command_tx, command_rx, event_tx, event_rx) = crate::core::create_channels();
+        let server = Self::new(command_tx.clone(), Arc::new(RwLock::new(AppState::new())))
+            .with_cancel_handle(core.cancel_handle())
+            .with_notifications(NotificationDispatcher::new(config.notification_config.clone()));
+
+        let mut event_loop = EventLoop::new(command_rx, event_tx, server.state.clone(), Arc::new(RwLock::new(core)));
+        tokio::spawn(async move {
+            if let Err(e) = event_loop.run().await {
+                tracing::error!("事件循环异常退出: {}", e);
+            }
+        });
+        tokio::spawn(server.clone().dispatch_events(event_rx));
+
+        if config.schedule_config.enabled {
+            let scheduler = UpdateScheduler::new(config.schedule_config.clone(), command_tx)?;
+            tokio::spawn(async move {
+                if let Err(e) = scheduler.run().await {
+                    tracing::error!("定时更新已停止: {}", e);
+                }
+            });
+        }
+        Ok(server)
+    }
+
+    /// 更新共享状态、广播事件并发送通知
+    pub async fn dispatch_events(self, mut event_rx: mpsc::Receiver<StateEvent>) {
+        while let Some(event) = event_rx.recv().await {
+            self.state.write().await.handle_event(event.clone());
+            // 没有订阅者时发送失败，忽略即可
+            let _ = self.events.send(event.clone());
+            if let Some(ref notifications) = self.notifications {
+                notifications.handle_event(&event).await;
+            }
+        }
+    }
+
+    /// 接受连接直到收到 Shutdown 命令
+    pub async fn serve(&self, mut listener: AgentListener) -> Result<()> {
+        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
+        tracing::info!("代理已启动");
+
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let stream = match accepted {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            tracing::warn!("接受代理连接失败: {}", e);
+                            continue;
+                        }
+                    };
+                    let server = self.clone();
+                    let shutdown_tx = shutdown_tx.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = server.handle_connection(stream, shutdown_tx).await {
+                            tracing::warn!("代理连接异常断开: {}", e);
+                        }
+                    });
+                }
+                _ = shutdown_rx.recv() => break,
+            }
+        }
+
+        tracing::info!("代理已停止");
+        Ok(())
+    }
+
+    async fn handle_connection(&self, stream: AgentStream, shutdown_tx: mpsc::Sender<()>) -> Result<()> {
+        let (reader, mut writer) = tokio::io::split(stream);
+        let mut subscription: Option<broadcast::Receiver<StateEvent>> = None;
+
+        // read_line 不能在 select! 中被取消，由单独的任务读取请求
+        let (request_tx, mut request_rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(reader);
+            loop {
+                let request = read_message::<_, AgentRequest>(&mut reader).await;
+                let finished = !matches!(request, Ok(Some(_)));
+                if request_tx.send(request).await.is_err() || finished {
+                    break;
+                }
+            }
+        });
+
+        loop {
+            tokio::select! {
+                Some(request) = request_rx.recv() => {
+                    let request = match request {
+                        Ok(Some(request)) => request,
+                        Ok(None) => return Ok(()),
+                        Err(e) => {
+                            // 请求格式错误后无法再对齐消息边界，回复错误并断开
+                            write_message(&mut writer, &AgentResponse::Error(e.to_string())).await?;
+                            return Ok(());
+                        }
+                    };
+                    let response = match request {
+                        AgentRequest::Ping => AgentResponse::Pong { version: PROTOCOL_VERSION },
+                        AgentRequest::GetState => AgentResponse::State(Box::new(self.state.read().await.clone())),
+                        AgentRequest::Subscribe => {
+                            subscription = Some(self.events.subscribe());
+                            AgentResponse::Subscribed
+                        }
+                        AgentRequest::Command(AppCommand::Cancel) if self.cancel.is_some() => {
+                            if let Some(ref cancel) = self.cancel {
+                                cancel.cancel();
+                            }
+                            AgentResponse::Accepted
+                        }
+                        AgentRequest::Command(command) => {
+                            let shutdown = command == AppCommand::Shutdown;
+                            match self.command_tx.send(command).await {
+                                Ok(()) => {
+                                    if shutdown {
+                                        let _ = shutdown_tx.try_send(());
+                                    }
+                                    AgentResponse::Accepted
+                                }
+                                Err(_) => AgentResponse::Error("事件循环已关闭".to_string()),
+                            }
+                        }
+                    };
+                    write_message(&mut writer, &response).await?;
+                }
+                event = next_event(&mut subscription) => {
+                    match event {
+                        Ok(event) => write_message(&mut writer, &AgentResponse::Event(event)).await?,
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            tracing::warn!("订阅者处理过慢，丢弃了 {} 个事件", skipped);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => subscription = None,
+                    }
+                }
+                else => return Ok(()),
+            }
+        }
+    }
+}
+
+/// 未订阅时永远等待
+async fn next_event(
+    subscription: &mut Option<broadcast::Receiver<StateEvent>>,
+) -> std::result::Result<StateEvent, broadcast::error::RecvError> {
+    match subscription {
+        Some(receiver) => receiver.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AgentClient;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_commands_and_events_over_socket() {
+        let endpoint = std::env::temp_dir()
+            .join(format!("hamster_agent_{}.sock", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        let listener = AgentListener::bind(&endpoint).await.unwrap();
+        // 套接字只允许当前用户连接，绑定用的临时目录已删除
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata(&endpoint).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(!std::env::temp_dir().join(format!(".agent-{}", std::process::id())).exists());
+        // 同一地址不能再启动第二个代理
+        assert!(AgentListener::bind(&endpoint).await.is_err());
+
+        let (command_tx, mut command_rx) = mpsc::channel(8);
+        let (event_tx, event_rx) = mpsc::channel(8);
+        let server = AgentServer::new(command_tx, Arc::new(RwLock::new(AppState::new())));
+        tokio::spawn(server.clone().dispatch_events(event_rx));
+        let serving = tokio::spawn(async move { server.serve(listener).await });
+
+        let mut subscriber = AgentClient::connect(&endpoint).await.unwrap();
+        subscriber.subscribe().await.unwrap();
+        let mut client = AgentClient::connect(&endpoint).await.unwrap();
+        client.send_command(AppCommand::BackupDrivers).await.unwrap();
+        assert_eq!(command_rx.recv().await, Some(AppCommand::BackupDrivers));
+
+        event_tx.send(StateEvent::BackupCompleted("snap_1".to_string())).await.unwrap();
+        match subscriber.next_event().await.unwrap() {
+            Some(StateEvent::BackupCompleted(id)) => assert_eq!(id, "snap_1"),
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert_eq!(client.get_state().await.unwrap().last_backup.as_deref(), Some("snap_1"));
+
+        client.send_command(AppCommand::Shutdown).await.unwrap();
+        serving.await.unwrap().unwrap();
+        assert!(!std::path::Path::new(&endpoint).exists());
+    }
+}
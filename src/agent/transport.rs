@@ -0,0 +1,138 @@
+//! 本地 IPC 传输层
+//!
+//! Unix 上使用应用数据目录下的套接字文件，Windows 上使用命名管道。
+
+use tokio::io::{AsyncRead, AsyncWrite};
+use crate::utils::{HamsterError, Result};
+
+/// 双向字节流
+pub trait AgentIo: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> AgentIo for T {}
+
+pub type AgentStream = Box<dyn AgentIo>;
+
+#[cfg(unix)]
+mod platform {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    use std::path::{Path, PathBuf};
+    use tokio::net::{UnixListener, UnixStream};
+    use super::*;
+
+    /// 默认的代理地址
+    pub fn default_endpoint() -> Result<String> {
+        let path = crate::utils::get_app_data_dir()?.join("agent.sock");
+        Ok(path.to_string_lossy().to_string())
+    }
+
+    /// 代理监听端，释放时删除套接字文件
+    pub struct AgentListener {
+        listener: UnixListener,
+        path: PathBuf,
+    }
+
+    impl AgentListener {
+        /// 已有代理在监听时返回错误，残留的套接字文件会被清理
+        pub async fn bind(endpoint: &str) -> Result<Self> {
+            let path = PathBuf::from(endpoint);
+            if path.exists() {
+                if UnixStream::connect(&path).await.is_ok() {
+                    return Err(HamsterError::InitError(format!("代理已在运行: {}", endpoint)));
+                }
+                std::fs::remove_file(&path)?;
+            }
+            let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+            std::fs::create_dir_all(parent)?;
+
+            // 先在只有当前用户能进入的目录中创建套接字并设置权限，再移到 endpoint，
+            // 套接字出现在 endpoint 时已经只允许当前用户连接
+            let staging = parent.join(format!(".agent-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&staging);
+            std::fs::DirBuilder::new().mode(0o700).create(&staging)?;
+            let staged = staging.join("agent.sock");
+            let bound = UnixListener::bind(&staged).and_then(|listener| {
+                std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+                std::fs::rename(&staged, &path)?;
+                Ok(listener)
+            });
+            let _ = std::fs::remove_dir_all(&staging);
+            Ok(Self { listener: bound?, path })
+        }
+
+        pub async fn accept(&mut self) -> Result<AgentStream> {
+            let (stream, _) = self.listener.accept().await?;
+            Ok(Box::new(stream))
+        }
+    }
+
+    impl Drop for AgentListener {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    pub async fn connect(endpoint: &str) -> Result<AgentStream> {
+        let stream = UnixStream::connect(endpoint).await
+            .map_err(|e| HamsterError::NetworkError(format!("无法连接代理 {}: {}", endpoint, e)))?;
+        Ok(Box::new(stream))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::time::Duration;
+    use tokio::net::windows::named_pipe::{ClientOptions, NamedPipeServer, ServerOptions};
+    use super::*;
+
+    // 所有管道实例都忙
+    const ERROR_PIPE_BUSY: i32 = 231;
+
+    /// 默认的代理地址
+    pub fn default_endpoint() -> Result<String> {
+        Ok(r"\\.\pipe\hamster-drivers-agent".to_string())
+    }
+
+    /// 代理监听端，总是预先创建好下一个管道实例
+    pub struct AgentListener {
+        name: String,
+        next: NamedPipeServer,
+    }
+
+    impl AgentListener {
+        /// 已有代理在监听时返回错误
+        pub async fn bind(endpoint: &str) -> Result<Self> {
+            let next = ServerOptions::new()
+                .first_pipe_instance(true)
+                .reject_remote_clients(true)
+                .create(endpoint)
+                .map_err(|e| HamsterError::InitError(format!("代理已在运行或无法创建管道 {}: {}", endpoint, e)))?;
+            Ok(Self { name: endpoint.to_string(), next })
+        }
+
+        pub async fn accept(&mut self) -> Result<AgentStream> {
+            self.next.connect().await?;
+            let following = ServerOptions::new()
+                .reject_remote_clients(true)
+                .create(&self.name)?;
+            let connected = std::mem::replace(&mut self.next, following);
+            Ok(Box::new(connected))
+        }
+    }
+
+    pub async fn connect(endpoint: &str) -> Result<AgentStream> {
+        for _ in 0..20 {
+            match ClientOptions::new().open(endpoint) {
+                Ok(client) => return Ok(Box::new(client)),
+                Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY) => {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+                Err(e) => {
+                    return Err(HamsterError::NetworkError(format!("无法连接代理 {}: {}", endpoint, e)));
+                }
+            }
+        }
+        Err(HamsterError::TimeoutError(format!("代理管道一直忙: {}", endpoint)))
+    }
+}
+
+pub use platform::*;
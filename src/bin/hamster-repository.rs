@@ -0,0 +1,72 @@
+//! 自建驱动仓库服务
+//!
+//! hamster-repository --catalog DIR [--listen ADDR] [--public-url URL] [--api-key KEY] [--help]
+
+use std::path::PathBuf;
+use hamster_drivers::repository::{Catalog, RepositoryServer, ServerOptions};
+use hamster_drivers::t;
+use hamster_drivers::utils::{init_logging, LogConfig};
+
+const DEFAULT_LISTEN: &str = "0.0.0.0:8080";
+// 不想在命令行中暴露密钥时可以用环境变量设置
+const API_KEY_ENV: &str = "HAMSTER_REPOSITORY_API_KEY";
+
+struct Options {
+    catalog: PathBuf,
+    listen: String,
+    server: ServerOptions,
+}
+
+#[tokio::main]
+async fn main() {
+    // 库中的日志使用 tracing，未设置 RUST_LOG 时输出 info 及以上
+    if let Err(e) = init_logging(&LogConfig::default()) {
+        eprintln!("{}", e);
+    }
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = run(&args).await {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    }
+}
+
+async fn run(args: &[String]) -> anyhow::Result<()> {
+    let Some(options) = parse_args(args)? else {
+        println!("{}", t!("repository-usage"));
+        return Ok(());
+    };
+    let catalog = Catalog::load(&options.catalog)?;
+    println!("{}", t!("repository-catalog-loaded", count = catalog.entries().len(), path = options.catalog.display()));
+    let server = RepositoryServer::start(catalog, &options.listen, options.server)?;
+    println!("{}", t!("repository-listening", url = server.url()));
+    tokio::signal::ctrl_c().await?;
+    server.stop();
+    Ok(())
+}
+
+// 请求帮助时返回 None
+fn parse_args(args: &[String]) -> anyhow::Result<Option<Options>> {
+    let mut catalog = None;
+    let mut listen = DEFAULT_LISTEN.to_string();
+    let mut server = ServerOptions { api_key: std::env::var(API_KEY_ENV).ok(), ..ServerOptions::default() };
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if matches!(arg.as_str(), "--help" | "-h") {
+            return Ok(None);
+        }
+        let value = iter.next()
+            .ok_or_else(|| anyhow::anyhow!(t!("cli-missing-value", arg = arg)))?
+            .clone();
+        match arg.as_str() {
+            "--catalog" => catalog = Some(PathBuf::from(value)),
+            "--listen" => listen = value,
+            "--public-url" => server.public_url = Some(value),
+            "--api-key" => server.api_key = Some(value),
+            _ => return Err(anyhow::anyhow!("{}\n{}", t!("cli-unknown-argument", arg = arg), t!("repository-usage"))),
+        }
+    }
+
+    let catalog = catalog.ok_or_else(|| anyhow::anyhow!(t!("repository-usage")))?;
+    Ok(Some(Options { catalog, listen, server }))
+}
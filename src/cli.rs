@@ -0,0 +1,417 @@
+//! 命令行入口
+//!
+//! 无参数启动时进入图形界面，带参数时执行对应的维护命令
+
+use std::sync::Arc;
+use crate::agent::{AgentClient, AgentListener, AgentServer};
+use crate::config::ConfigManager;
+use crate::core::{create_channels, event_handler_task, plan_next_run, scheduled_commands, AppCommand, AppState, CoreEvent, DriverUpdaterCore, EventLoop, SchedulerState, StateEvent};
+use crate::driver::fetcher::{DownloadPriority, PeerCacheServer};
+use crate::driver::installer::{action_label, InstallationAction, InstallationLogger, InstallationStatus, LogFilter};
+use crate::i18n::{format_datetime, format_size};
+use crate::installer::{BackupStore, RetentionPolicy};
+use crate::notify::NotificationDispatcher;
+use crate::t;
+
+fn usage() -> String {
+    t!("cli-usage")
+}
+
+/// 执行命令行命令，返回进程退出码
+pub fn run(args: &[String]) -> i32 {
+    let result = match args.first().map(String::as_str) {
+        Some("backup") => run_backup_command(&args[1..]),
+        Some("history") => run_history_command(&args[1..]),
+        Some("schedule") => run_schedule_command(&args[1..]),
+        Some("agent") => run_agent_command(&args[1..]),
+        _ => Err(anyhow::anyhow!("{}\n{}", t!("cli-unknown-command"), usage())),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}", e);
+            2
+        }
+    }
+}
+
+/// main 运行在 tokio 运行时中，命令行在当前线程上阻塞等待异步操作
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))
+}
+
+fn run_backup_command(args: &[String]) -> anyhow::Result<i32> {
+    let store = BackupStore::open_default()?;
+
+    match args.first().map(String::as_str) {
+        Some("list") => {
+            for snapshot in store.list_snapshots()? {
+                println!(
+                    "{}  {}  {}  {}  {}",
+                    snapshot.id, snapshot.created_at, t!("cli-driver-count", count = snapshot.driver_count),
+                    format_size(snapshot.total_size), snapshot.description
+                );
+            }
+            Ok(0)
+        }
+        Some("fsck") => {
+            let report = store.fsck()?;
+            println!("{}", t!("cli-fsck-checked", snapshots = report.snapshots_checked, files = report.blobs_checked));
+            for id in &report.unreadable_snapshots {
+                println!("{}", t!("cli-fsck-unreadable", id = id));
+            }
+            for (id, hash) in &report.missing_blobs {
+                println!("{}", t!("cli-fsck-missing", id = id, hash = hash));
+            }
+            for hash in &report.corrupt_blobs {
+                println!("{}", t!("cli-fsck-corrupt", hash = hash));
+            }
+            if !report.orphaned_blobs.is_empty() {
+                println!("{}", t!("cli-fsck-orphaned", count = report.orphaned_blobs.len()));
+            }
+
+            if report.is_healthy() {
+                println!("{}", t!("cli-fsck-healthy"));
+                Ok(0)
+            } else {
+                Ok(1)
+            }
+        }
+        Some("gc") => {
+            let report = store.collect_garbage()?;
+            println!("{}", t!("cli-gc-finished", count = report.removed_blobs, size = format_size(report.freed_bytes)));
+            Ok(0)
+        }
+        Some("prune") => {
+            let (policy, dry_run) = parse_retention_args(&args[1..])?;
+            let report = store.apply_retention(&policy, dry_run)?;
+            for id in &report.removed {
+                println!("{}", t!(if dry_run { "cli-prune-would-remove" } else { "cli-prune-removed" }, id = id));
+            }
+            println!("{}", t!("cli-prune-summary", kept = report.kept.len(), removed = report.removed.len()));
+
+            if !dry_run {
+                let gc = store.collect_garbage()?;
+                println!("{}", t!("cli-gc-finished", count = gc.removed_blobs, size = format_size(gc.freed_bytes)));
+            }
+            Ok(0)
+        }
+        _ => Err(anyhow::anyhow!("{}\n{}", t!("cli-unknown-backup-command"), usage())),
+    }
+}
+
+fn run_schedule_command(args: &[String]) -> anyhow::Result<i32> {
+    let config = ConfigManager::load_or_default()?;
+    let schedule = &config.schedule_config;
+    let state_path = SchedulerState::default_path()?;
+    let mut state = SchedulerState::load(&state_path)?;
+    let format_time = |time: Option<chrono::DateTime<chrono::Local>>| {
+        time.map(|time| format_datetime(&time)).unwrap_or_else(|| "-".to_string())
+    };
+
+    match args.first().map(String::as_str) {
+        Some("status") => {
+            println!("{}", t!("cli-schedule-enabled", state = t!(if schedule.enabled { "cli-enabled" } else { "cli-disabled" })));
+            match schedule.cron {
+                Some(ref cron) => println!("{}", t!("cli-schedule-cron", cron = cron)),
+                None => println!("{}", t!("cli-schedule-interval", hours = schedule.interval_hours)),
+            }
+            println!("{}", t!("cli-schedule-action", action = schedule.action.label()));
+            println!("{}", t!("cli-schedule-last-run", time = format_time(state.last_run)));
+            println!("{}", t!("cli-schedule-last-result", result = state.last_result.as_deref().unwrap_or("-")));
+            println!("{}", t!("cli-schedule-next-run", time = format_time(plan_next_run(schedule, &state, chrono::Local::now())?)));
+            Ok(0)
+        }
+        Some("run") => {
+            let last_error = block_on(run_scheduled_once(&config))?;
+            state.last_run = Some(chrono::Local::now());
+            state.next_run = None;
+            state.last_result = Some(last_error.clone().unwrap_or_else(|| t!("cli-schedule-succeeded")));
+            state.save(&state_path)?;
+
+            match last_error {
+                Some(e) => {
+                    eprintln!("{}", t!("cli-schedule-failed", error = e));
+                    Ok(1)
+                }
+                None => {
+                    println!("{}", t!("cli-schedule-finished"));
+                    Ok(0)
+                }
+            }
+        }
+        _ => Err(anyhow::anyhow!("{}\n{}", t!("cli-unknown-schedule-command"), usage())),
+    }
+}
+
+/// 不启动界面执行一次定时动作，返回最后一个错误
+async fn run_scheduled_once(config: &ConfigManager) -> anyhow::Result<Option<String>> {
+    let mut core = DriverUpdaterCore::new(":memory:", config).await?;
+    core.initialize().await?;
+
+    let (command_tx, command_rx, event_tx, event_rx) = create_channels();
+    let state = Arc::new(tokio::sync::RwLock::new(AppState::new()));
+    for command in scheduled_commands(config.schedule_config.action) {
+        command_tx.send(command).await?;
+    }
+    command_tx.send(AppCommand::Shutdown).await?;
+
+    let notifications = Arc::new(NotificationDispatcher::new(config.notification_config.clone()));
+    let handler = tokio::spawn(event_handler_task(event_rx, state.clone(), Some(notifications)));
+    let mut event_loop = EventLoop::new(command_rx, event_tx, state.clone(), Arc::new(tokio::sync::RwLock::new(core)));
+    event_loop.run().await?;
+    // 释放事件发送端，等待剩余事件处理完
+    drop(event_loop);
+    handler.await?;
+
+    let state = state.read().await;
+    for candidate in state.update_candidates.iter().filter(|candidate| candidate.needs_update) {
+        println!("{}", t!("cli-update-available",
+            hardware_id = candidate.hardware_info.hardware_id,
+            current = candidate.current_version,
+            latest = candidate.matched_driver.as_ref().map(|d| d.driver_version.as_str()).unwrap_or("-")));
+    }
+    Ok(state.last_error.clone())
+}
+
+fn run_agent_command(args: &[String]) -> anyhow::Result<i32> {
+    let endpoint = crate::agent::default_endpoint()?;
+    match args.first().map(String::as_str) {
+        Some("run") => block_on(async {
+            let listener = AgentListener::bind(&endpoint).await?;
+            let config = ConfigManager::load_or_default()?;
+            let server = AgentServer::start(&config).await?;
+            // 后台服务运行期间同时向局域网提供已下载的驱动包
+            let _peer_cache = PeerCacheServer::start_from_config(&config.download_config.peer_cache)?;
+            println!("{}", t!("cli-agent-listening", endpoint = endpoint));
+            server.serve(listener).await?;
+            Ok(0)
+        }),
+        Some("status") => block_on(async {
+            let mut client = match AgentClient::connect(&endpoint).await {
+                Ok(client) => client,
+                Err(e) => {
+                    println!("{}", t!("cli-agent-not-running", error = e));
+                    return Ok(1);
+                }
+            };
+            let state = client.get_state().await?;
+            println!("{}", t!("cli-agent-running", endpoint = endpoint));
+            println!("{}", t!("cli-agent-devices", devices = state.devices.len(),
+                updates = state.update_candidates.iter().filter(|candidate| candidate.needs_update).count()));
+            println!("{}", t!("cli-agent-tasks", downloads = state.downloads.len(), installs = state.installs.len()));
+            if let Some(error) = state.last_error {
+                println!("{}", t!("cli-agent-last-error", error = error));
+            }
+            Ok(0)
+        }),
+        Some("send") => {
+            let wait = args.iter().any(|arg| arg == "--wait");
+            let rest: Vec<&String> = args[1..].iter().filter(|arg| *arg != "--wait").collect();
+            let command = match rest.as_slice() {
+                [name] if name.as_str() == "scan" => AppCommand::ScanHardware,
+                [name] if name.as_str() == "check" => AppCommand::CheckUpdates,
+                [name] if name.as_str() == "download" => AppCommand::DownloadUpdates(DownloadPriority::User),
+                [name] if name.as_str() == "install" => AppCommand::InstallUpdates(DownloadPriority::User),
+                [name] if name.as_str() == "backup" => AppCommand::BackupDrivers,
+                [name, path] if name.as_str() == "restore" => AppCommand::RestoreDrivers(path.to_string()),
+                [name] if name.as_str() == "refresh" => AppCommand::RefreshSystemInfo,
+                [name] if name.as_str() == "cancel" => AppCommand::Cancel,
+                [name] if name.as_str() == "shutdown" => AppCommand::Shutdown,
+                _ => return Err(anyhow::anyhow!("{}\n{}", t!("cli-unknown-agent-command"), usage())),
+            };
+            block_on(send_agent_command(&endpoint, command, wait))
+        }
+        _ => Err(anyhow::anyhow!("{}\n{}", t!("cli-unknown-agent-command"), usage())),
+    }
+}
+
+/// 发送命令，wait 时打印事件直到命令完成
+async fn send_agent_command(endpoint: &str, command: AppCommand, wait: bool) -> anyhow::Result<i32> {
+    let mut client = AgentClient::connect(endpoint).await?;
+    if wait {
+        client.subscribe().await?;
+    }
+    client.send_command(command.clone()).await?;
+    if !wait {
+        println!("{}", t!("cli-command-sent"));
+        return Ok(0);
+    }
+
+    while let Some(event) = client.next_event().await? {
+        match event {
+            StateEvent::CommandCompleted(completed) if completed == command => {
+                println!("{}", t!("cli-command-completed"));
+                return Ok(0);
+            }
+            StateEvent::CommandFailed(message) => {
+                eprintln!("{}", t!("cli-command-failed", error = message));
+                return Ok(1);
+            }
+            StateEvent::Core(event) if matches!(*event, CoreEvent::DownloadProgress { .. } | CoreEvent::ScanProgress { .. }) => {}
+            other => println!("{:?}", other),
+        }
+    }
+    Err(anyhow::anyhow!(t!("cli-agent-disconnected")))
+}
+
+fn parse_retention_args(args: &[String]) -> anyhow::Result<(RetentionPolicy, bool)> {
+    // 以配置中的保留策略为基础，--keep-* 覆盖对应的项
+    let mut policy = ConfigManager::load_or_default()
+        .map(|config| config.backup_config.retention)
+        .unwrap_or_default();
+    let mut dry_run = false;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if arg == "--dry-run" {
+            dry_run = true;
+            continue;
+        }
+
+        let value = iter.next()
+            .ok_or_else(|| anyhow::anyhow!(t!("cli-missing-value", arg = arg)))?
+            .parse::<usize>()
+            .map_err(|_| anyhow::anyhow!(t!("cli-invalid-number", arg = arg)))?;
+
+        match arg.as_str() {
+            "--keep-last" => policy.keep_last = value,
+            "--keep-daily" => policy.keep_daily = value,
+            "--keep-weekly" => policy.keep_weekly = value,
+            "--keep-monthly" => policy.keep_monthly = value,
+            _ => return Err(anyhow::anyhow!("{}\n{}", t!("cli-unknown-argument", arg = arg), usage())),
+        }
+    }
+
+    Ok((policy, dry_run))
+}
+
+fn run_history_command(args: &[String]) -> anyhow::Result<i32> {
+    let logger = InstallationLogger::open_default()?;
+    let (command, options) = match args.first().map(String::as_str) {
+        Some(command @ ("list" | "stats" | "prune")) => (command, &args[1..]),
+        _ => ("list", args),
+    };
+
+    if command == "prune" {
+        let days = match options {
+            [flag, value] if flag == "--days" => value.parse::<u32>()
+                .map_err(|_| anyhow::anyhow!(t!("cli-invalid-number", arg = "--days")))?,
+            _ => return Err(anyhow::anyhow!("{}\n{}", t!("cli-prune-needs-days"), usage())),
+        };
+        let removed = logger.cleanup_old_logs(days)?;
+        println!("{}", t!("cli-history-pruned", count = removed, days = days));
+        return Ok(0);
+    }
+
+    let query = parse_history_args(options)?;
+    if command == "stats" {
+        let stats = logger.statistics(&query.filter)?;
+        println!("{}", t!(
+            "cli-history-stats",
+            total = stats.total,
+            succeeded = stats.succeeded,
+            failed = stats.failed,
+            cancelled = stats.cancelled,
+            in_progress = stats.in_progress,
+        ));
+        if stats.durations.count > 0 {
+            println!("{}", t!(
+                "cli-history-durations",
+                average = stats.durations.average,
+                min = stats.durations.min,
+                max = stats.durations.max,
+            ));
+        }
+        return Ok(0);
+    }
+
+    if let Some(ref path) = query.csv {
+        let count = logger.export_csv(&query.filter, std::path::Path::new(path))?;
+        println!("{}", t!("cli-history-exported", count = count, path = path));
+        return Ok(0);
+    }
+
+    let mut entries = logger.query(&query.filter)?;
+    if let Some(limit) = query.limit {
+        entries.truncate(limit);
+    }
+    for entry in &entries {
+        println!(
+            "{}  {}  {:?}  {}  {} {}  {}",
+            entry.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+            action_label(&entry.action),
+            entry.status,
+            entry.hardware_id,
+            entry.driver_info.file_name,
+            entry.driver_info.driver_version,
+            entry.message
+        );
+    }
+    Ok(0)
+}
+
+struct HistoryQuery {
+    filter: LogFilter,
+    limit: Option<usize>,
+    csv: Option<String>,
+}
+
+fn parse_history_args(args: &[String]) -> anyhow::Result<HistoryQuery> {
+    let mut query = HistoryQuery {
+        filter: LogFilter::default(),
+        limit: None,
+        csv: None,
+    };
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let value = iter.next().ok_or_else(|| anyhow::anyhow!(t!("cli-missing-value", arg = arg)))?;
+        match arg.as_str() {
+            "--hardware-id" => query.filter.hardware_id = Some(value.clone()),
+            "--action" => query.filter.action = Some(parse_action(value)?),
+            "--status" => query.filter.status = Some(parse_status(value)?),
+            "--since" => query.filter.since = Some(parse_date(value)?),
+            // 截止日期包含当天
+            "--until" => query.filter.until = Some(parse_date(value)? + chrono::Duration::days(1)),
+            "--limit" => query.limit = Some(value.parse().map_err(|_| anyhow::anyhow!(t!("cli-invalid-number", arg = "--limit")))?),
+            "--csv" => query.csv = Some(value.clone()),
+            _ => return Err(anyhow::anyhow!("{}\n{}", t!("cli-unknown-argument", arg = arg), usage())),
+        }
+    }
+
+    Ok(query)
+}
+
+fn parse_action(value: &str) -> anyhow::Result<InstallationAction> {
+    match value.to_ascii_lowercase().as_str() {
+        "install" => Ok(InstallationAction::Install),
+        "update" => Ok(InstallationAction::Update),
+        "uninstall" => Ok(InstallationAction::Uninstall),
+        "backup" => Ok(InstallationAction::Backup),
+        "restore" => Ok(InstallationAction::Restore),
+        "rollback" => Ok(InstallationAction::Rollback),
+        _ => Err(anyhow::anyhow!(t!("cli-unknown-action", value = value))),
+    }
+}
+
+fn parse_status(value: &str) -> anyhow::Result<InstallationStatus> {
+    match value.to_ascii_lowercase().as_str() {
+        "success" => Ok(InstallationStatus::Success),
+        "failed" => Ok(InstallationStatus::Failed),
+        "cancelled" => Ok(InstallationStatus::Cancelled),
+        "in-progress" => Ok(InstallationStatus::InProgress),
+        _ => Err(anyhow::anyhow!(t!("cli-unknown-status", value = value))),
+    }
+}
+
+// 按本地时区的当天零点解析日期
+fn parse_date(value: &str) -> anyhow::Result<chrono::DateTime<chrono::Utc>> {
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!(t!("cli-invalid-date", value = value)))?;
+    let local = date.and_hms_opt(0, 0, 0)
+        .and_then(|time| time.and_local_timezone(chrono::Local).earliest())
+        .ok_or_else(|| anyhow::anyhow!(t!("cli-invalid-local-date", value = value)))?;
+    Ok(local.with_timezone(&chrono::Utc))
+}
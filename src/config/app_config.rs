@@ -1,16 +1,35 @@
 //! 应用程序配置
-use crate::utils::error::Result;
+use crate::i18n::Locale;
+use crate::utils::{HamsterError, Result};
+use crate::t;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use toml;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 支持的日志级别，从详细到简略
+pub const LOG_LEVELS: [&str; 5] = ["trace", "debug", "info", "warn", "error"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AppConfig {
     pub app_name: String,
     pub version: String,
     pub data_dir: String,
     pub temp_dir: String,
     pub log_level: String,
+    /// 界面语言，如 zh-CN、en-US
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// 最小化或关闭主窗口时隐藏到系统托盘
+    #[serde(default = "default_minimize_to_tray")]
+    pub minimize_to_tray: bool,
+}
+
+fn default_language() -> String {
+    Locale::default().code().to_string()
+}
+
+fn default_minimize_to_tray() -> bool {
+    true
 }
 
 impl AppConfig {
@@ -21,17 +40,36 @@ impl AppConfig {
             data_dir: "".to_string(),
             temp_dir: "".to_string(),
             log_level: "info".to_string(),
+            language: default_language(),
+            minimize_to_tray: default_minimize_to_tray(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !LOG_LEVELS.contains(&self.log_level.as_str()) {
+            return Err(HamsterError::ValidationError(t!("config-invalid-log-level", level = self.log_level)));
+        }
+        if Locale::from_code(&self.language).is_none() {
+            return Err(HamsterError::ValidationError(t!("config-invalid-language", language = self.language)));
         }
+        Ok(())
+    }
+
+    /// 配置的界面语言，无法识别时使用默认语言
+    pub fn locale(&self) -> Locale {
+        Locale::from_code(&self.language).unwrap_or_default()
     }
 
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = std::fs::read_to_string(path)?;
-        let config: AppConfig = toml::from_str(&content)?;
+        let config: AppConfig = toml::from_str(&content)
+            .map_err(|e| HamsterError::ConfigError(e.to_string()))?;
         Ok(config)
     }
 
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let content = toml::to_string(self)?;
+        let content = toml::to_string(self)
+            .map_err(|e| HamsterError::ConfigError(e.to_string()))?;
         std::fs::write(path, content)?;
         Ok(())
     }
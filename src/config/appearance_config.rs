@@ -0,0 +1,74 @@
+//! 界面外观配置
+
+use serde::{Deserialize, Serialize};
+use crate::utils::{HamsterError, Result};
+use crate::t;
+
+/// 界面缩放的允许范围
+pub const UI_SCALE_MIN: f32 = 0.5;
+pub const UI_SCALE_MAX: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemePreference {
+    /// 跟随系统的浅色或深色模式
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl ThemePreference {
+    pub const ALL: [ThemePreference; 3] = [ThemePreference::System, ThemePreference::Light, ThemePreference::Dark];
+
+    pub fn label(&self) -> String {
+        match self {
+            ThemePreference::System => t!("theme-system"),
+            ThemePreference::Light => t!("theme-light"),
+            ThemePreference::Dark => t!("theme-dark"),
+        }
+    }
+}
+
+/// 字体文件可以写文件名（在系统字体目录中查找）或完整路径
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FontConfig {
+    /// 正文字体，空时按平台自动选择
+    pub regular: String,
+    /// 标题使用的粗体，空时使用正文字体
+    pub bold: String,
+    /// 正文字体缺字时依次尝试的字体
+    pub fallbacks: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppearanceConfig {
+    pub theme: ThemePreference,
+    /// 在系统 DPI 缩放基础上的额外缩放
+    pub ui_scale: f32,
+    pub fonts: FontConfig,
+}
+
+impl Default for AppearanceConfig {
+    fn default() -> Self {
+        Self {
+            theme: ThemePreference::System,
+            ui_scale: 1.0,
+            fonts: FontConfig::default(),
+        }
+    }
+}
+
+impl AppearanceConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !(UI_SCALE_MIN..=UI_SCALE_MAX).contains(&self.ui_scale) {
+            return Err(HamsterError::ValidationError(t!("config-invalid-ui-scale", min = UI_SCALE_MIN, max = UI_SCALE_MAX)));
+        }
+        if self.fonts.fallbacks.iter().any(|font| font.trim().is_empty()) {
+            return Err(HamsterError::ValidationError(t!("config-empty-font-name")));
+        }
+        Ok(())
+    }
+}
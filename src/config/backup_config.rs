@@ -0,0 +1,36 @@
+//! 驱动备份配置
+
+use serde::{Deserialize, Serialize};
+use crate::installer::RetentionPolicy;
+use crate::utils::{HamsterError, Result};
+use crate::t;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// 清理旧备份时保留的快照，命令行未指定 --keep-* 时使用
+    pub retention: RetentionPolicy,
+    /// 最多保留的回滚点，超出时删除最旧的回滚点
+    pub max_rollback_points: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            retention: RetentionPolicy::default(),
+            max_rollback_points: 5,
+        }
+    }
+}
+
+impl BackupConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.retention.is_empty() {
+            return Err(HamsterError::ValidationError(t!("config-empty-retention")));
+        }
+        if self.max_rollback_points == 0 {
+            return Err(HamsterError::ValidationError(t!("config-no-rollback-points")));
+        }
+        Ok(())
+    }
+}
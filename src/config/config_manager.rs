@@ -6,16 +6,41 @@ use std::fs;
 use std::path::Path;
 use serde::{Deserialize, Serialize};
 use toml;
-use crate::utils::error::{HamsterError, Result};
+use crate::utils::{HamsterError, Result};
+use crate::t;
 use crate::config::app_config::AppConfig;
+use crate::config::appearance_config::AppearanceConfig;
+use crate::config::backup_config::BackupConfig;
 use crate::config::download_config::DownloadConfig;
+use crate::config::network_config::NetworkConfig;
+use crate::config::notification_config::NotificationConfig;
+use crate::config::repository_config::RepositoryConfig;
 use crate::config::scanner_config::ScannerConfig;
+use crate::config::schedule_config::ScheduleConfig;
+use crate::config::timeout_config::TimeoutConfig;
+use crate::config::update_preferences::UpdatePreferences;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConfigManager {
     pub app_config: AppConfig,
     pub download_config: DownloadConfig,
     pub scanner_config: ScannerConfig,
+    #[serde(default)]
+    pub schedule_config: ScheduleConfig,
+    #[serde(default)]
+    pub timeout_config: TimeoutConfig,
+    #[serde(default)]
+    pub update_preferences: UpdatePreferences,
+    #[serde(default)]
+    pub backup_config: BackupConfig,
+    #[serde(default)]
+    pub appearance_config: AppearanceConfig,
+    #[serde(default)]
+    pub notification_config: NotificationConfig,
+    #[serde(default)]
+    pub network_config: NetworkConfig,
+    #[serde(default)]
+    pub repository_config: RepositoryConfig,
 }
 
 impl ConfigManager {
@@ -24,16 +49,29 @@ impl ConfigManager {
             app_config: AppConfig::default(),
             download_config: DownloadConfig::default(),
             scanner_config: ScannerConfig::default(),
+            schedule_config: ScheduleConfig::default(),
+            timeout_config: TimeoutConfig::default(),
+            update_preferences: UpdatePreferences::default(),
+            backup_config: BackupConfig::default(),
+            appearance_config: AppearanceConfig::default(),
+            notification_config: NotificationConfig::default(),
+            network_config: NetworkConfig::default(),
+            repository_config: RepositoryConfig::default(),
         }
     }
 
     /// 从文件加载配置
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = fs::read_to_string(path)
-            .map_err(|e| HamsterError::ConfigError(format!("读取配置文件失败: {}", e)))?;
+            .map_err(|e| HamsterError::ConfigError(t!("config-read-failed", error = e)))?;
         
-        let config: ConfigManager = toml::from_str(&content)
-            .map_err(|e| HamsterError::ConfigError(format!("解析配置文件失败: {}", e)))?;
+        let mut config: ConfigManager = toml::from_str(&content)
+            .map_err(|e| HamsterError::ConfigError(t!("config-parse-failed", error = e)))?;
+
+        // 旧版本的代理设置在下载配置中
+        if let Some(proxy) = config.download_config.proxy.take() {
+            config.network_config.proxy.get_or_insert(proxy);
+        }
         
         Ok(config)
     }
@@ -41,10 +79,14 @@ impl ConfigManager {
     /// 保存配置到文件
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let content = toml::to_string_pretty(self)
-            .map_err(|e| HamsterError::ConfigError(format!("序列化配置失败: {}", e)))?;
+            .map_err(|e| HamsterError::ConfigError(t!("config-serialize-failed", error = e)))?;
         
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| HamsterError::ConfigError(t!("config-create-dir-failed", error = e)))?;
+        }
         fs::write(path, content)
-            .map_err(|e| HamsterError::ConfigError(format!("写入配置文件失败: {}", e)))?;
+            .map_err(|e| HamsterError::ConfigError(t!("config-write-failed", error = e)))?;
         
         Ok(())
     }
@@ -72,13 +114,85 @@ impl ConfigManager {
         self
     }
 
+    /// 设置定时更新配置
+    pub fn with_schedule_config(mut self, config: ScheduleConfig) -> Self {
+        self.schedule_config = config;
+        self
+    }
+
+    /// 设置超时配置
+    pub fn with_timeout_config(mut self, config: TimeoutConfig) -> Self {
+        self.timeout_config = config;
+        self
+    }
+
+    /// 设置更新偏好
+    pub fn with_update_preferences(mut self, preferences: UpdatePreferences) -> Self {
+        self.update_preferences = preferences;
+        self
+    }
+
+    /// 设置备份配置
+    pub fn with_backup_config(mut self, config: BackupConfig) -> Self {
+        self.backup_config = config;
+        self
+    }
+
+    /// 设置界面外观配置
+    pub fn with_appearance_config(mut self, config: AppearanceConfig) -> Self {
+        self.appearance_config = config;
+        self
+    }
+
+    /// 设置通知配置
+    pub fn with_notification_config(mut self, config: NotificationConfig) -> Self {
+        self.notification_config = config;
+        self
+    }
+
+    /// 设置网络配置
+    pub fn with_network_config(mut self, config: NetworkConfig) -> Self {
+        self.network_config = config;
+        self
+    }
+
+    /// 设置驱动仓库配置
+    pub fn with_repository_config(mut self, config: RepositoryConfig) -> Self {
+        self.repository_config = config;
+        self
+    }
+
     /// 验证所有配置的有效性
     pub fn validate(&self) -> Result<()> {
+        self.app_config.validate()
+            .map_err(|e| HamsterError::ConfigError(t!("config-invalid-app", error = e)))?;
+
         self.download_config.validate()
-            .map_err(|e| HamsterError::ConfigError(format!("下载配置验证失败: {}", e)))?;
+            .map_err(|e| HamsterError::ConfigError(t!("config-invalid-download", error = e)))?;
         
         self.scanner_config.validate()
-            .map_err(|e| HamsterError::ConfigError(format!("扫描配置验证失败: {}", e)))?;
+            .map_err(|e| HamsterError::ConfigError(t!("config-invalid-scanner", error = e)))?;
+        
+        self.schedule_config.validate()
+            .map_err(|e| HamsterError::ConfigError(t!("config-invalid-schedule", error = e)))?;
+        
+        self.timeout_config.validate()
+            .map_err(|e| HamsterError::ConfigError(t!("config-invalid-timeouts", error = e)))?;
+
+        self.backup_config.validate()
+            .map_err(|e| HamsterError::ConfigError(t!("config-invalid-backup", error = e)))?;
+
+        self.appearance_config.validate()
+            .map_err(|e| HamsterError::ConfigError(t!("config-invalid-appearance", error = e)))?;
+
+        self.notification_config.validate()
+            .map_err(|e| HamsterError::ConfigError(t!("config-invalid-notifications", error = e)))?;
+
+        self.network_config.validate()
+            .map_err(|e| HamsterError::ConfigError(t!("config-invalid-network", error = e)))?;
+
+        self.repository_config.validate()
+            .map_err(|e| HamsterError::ConfigError(t!("config-invalid-repository", error = e)))?;
         
         Ok(())
     }
@@ -91,6 +205,17 @@ impl ConfigManager {
         path
     }
 
+    /// 从默认路径加载配置，文件不存在时使用默认值
+    pub fn load_or_default() -> Result<Self> {
+        let path = Self::default_config_path();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let config = Self::load_from_file(&path)?;
+        config.validate()?;
+        Ok(config)
+    }
+
     /// 初始化默认配置文件（如果不存在）
     pub fn init_default_config<P: AsRef<Path>>(path: P) -> Result<()> {
         let config = Self::new();
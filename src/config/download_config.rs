@@ -0,0 +1,88 @@
+//! 下载配置
+
+use serde::{Deserialize, Serialize};
+use crate::config::{MaintenanceWindow, PeerCacheConfig};
+use crate::utils::{HamsterError, Result};
+use crate::t;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadConfig {
+    /// 下载目录，为空时使用程序目录下的 downloads
+    pub download_dir: String,
+    pub aria2_host: String,
+    pub aria2_port: u16,
+    pub max_concurrent_downloads: usize,
+    /// 同一主机同时下载的数量，避免厂商服务器限流
+    pub max_downloads_per_host: usize,
+    /// aria2 单次连接的超时时间（秒），整个文件的下载时限见 TimeoutConfig
+    pub timeout_secs: u64,
+    /// 旧版本的下载代理，加载时移到 NetworkConfig，不再保存
+    #[serde(skip_serializing)]
+    pub proxy: Option<String>,
+    /// 所有下载合计的速度上限（KB/s），0 表示不限速
+    pub max_download_rate_kbps: u64,
+    /// 单个下载任务的速度上限（KB/s），0 表示不限速
+    pub max_task_rate_kbps: u64,
+    /// 不小于该大小（MB）的文件只在允许的时间段内下载，0 表示不区分大小
+    pub large_download_threshold_mb: u64,
+    /// 允许下载大文件的时间段，如 19:00-07:00，为空时随时可以下载
+    pub large_download_windows: Vec<MaintenanceWindow>,
+    /// 局域网驱动包缓存
+    pub peer_cache: PeerCacheConfig,
+}
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self {
+            download_dir: "./downloads".to_string(),
+            aria2_host: "localhost".to_string(),
+            aria2_port: 6800,
+            max_concurrent_downloads: 3,
+            max_downloads_per_host: 2,
+            timeout_secs: 60,
+            proxy: None,
+            max_download_rate_kbps: 0,
+            max_task_rate_kbps: 0,
+            large_download_threshold_mb: 100,
+            large_download_windows: Vec::new(),
+            peer_cache: PeerCacheConfig::default(),
+        }
+    }
+}
+
+impl DownloadConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.aria2_port == 0 {
+            return Err(HamsterError::ValidationError(t!("config-zero-aria2-port")));
+        }
+        if !(1..=16).contains(&self.max_concurrent_downloads) {
+            return Err(HamsterError::ValidationError(t!("config-invalid-concurrency")));
+        }
+        if !(1..=16).contains(&self.max_downloads_per_host) {
+            return Err(HamsterError::ValidationError(t!("config-invalid-per-host-concurrency")));
+        }
+        if self.timeout_secs == 0 {
+            return Err(HamsterError::ValidationError(t!("config-zero-download-timeout")));
+        }
+        for window in &self.large_download_windows {
+            window.validate()?;
+        }
+        self.peer_cache.validate()?;
+        Ok(())
+    }
+
+    /// 全局速度上限，字节/秒
+    pub fn max_download_rate(&self) -> u64 {
+        self.max_download_rate_kbps.saturating_mul(1024)
+    }
+
+    /// 单个任务的速度上限，字节/秒
+    pub fn max_task_rate(&self) -> u64 {
+        self.max_task_rate_kbps.saturating_mul(1024)
+    }
+
+    pub fn large_download_threshold(&self) -> u64 {
+        self.large_download_threshold_mb.saturating_mul(1024 * 1024)
+    }
+}
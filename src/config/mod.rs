@@ -0,0 +1,26 @@
+mod app_config;
+mod appearance_config;
+mod backup_config;
+mod config_manager;
+mod download_config;
+mod network_config;
+mod notification_config;
+mod peer_cache_config;
+mod repository_config;
+mod scanner_config;
+mod schedule_config;
+mod timeout_config;
+mod update_preferences;
+pub use app_config::*;
+pub use appearance_config::*;
+pub use backup_config::*;
+pub use config_manager::*;
+pub use download_config::*;
+pub use network_config::*;
+pub use notification_config::*;
+pub use peer_cache_config::*;
+pub use repository_config::*;
+pub use scanner_config::*;
+pub use schedule_config::*;
+pub use timeout_config::*;
+pub use update_preferences::*;
@@ -0,0 +1,86 @@
+//! 网络配置
+//!
+//! 所有联网组件共用的代理、证书、超时、重试和 User-Agent 设置。
+
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::utils::{HamsterError, Result};
+use crate::t;
+
+// 支持的代理协议
+const PROXY_SCHEMES: [&str; 4] = ["http://", "https://", "socks5://", "socks5h://"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// 代理地址，如 http://127.0.0.1:7890 或 socks5://127.0.0.1:1080，为空时使用系统代理设置
+    pub proxy: Option<String>,
+    pub proxy_username: Option<String>,
+    pub proxy_password: Option<String>,
+    /// 不经过代理的主机，如 localhost、.corp.example.com、10.0.0.0/8
+    pub no_proxy: Vec<String>,
+    /// 额外信任的 CA 证书文件（PEM），用于企业代理或内部镜像
+    pub ca_certificates: Vec<String>,
+    pub connect_timeout_secs: u64,
+    /// 单个请求的超时时间，不用于驱动文件下载
+    pub request_timeout_secs: u64,
+    /// 连接失败、超时或服务器暂时不可用时的重试次数
+    pub max_retries: u32,
+    /// 第一次重试前等待的毫秒数，之后每次翻倍
+    pub retry_backoff_ms: u64,
+    /// 为空时使用 HamsterDrivers/<版本>
+    pub user_agent: String,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            proxy_username: None,
+            proxy_password: None,
+            no_proxy: vec!["localhost".to_string(), "127.0.0.1".to_string()],
+            ca_certificates: Vec::new(),
+            connect_timeout_secs: 10,
+            request_timeout_secs: 30,
+            max_retries: 2,
+            retry_backoff_ms: 500,
+            user_agent: String::new(),
+        }
+    }
+}
+
+impl NetworkConfig {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(ref proxy) = self.proxy {
+            if !PROXY_SCHEMES.iter().any(|scheme| proxy.starts_with(scheme)) {
+                return Err(HamsterError::ValidationError(t!("config-invalid-proxy")));
+            }
+        }
+        if self.connect_timeout_secs == 0 || self.request_timeout_secs == 0 {
+            return Err(HamsterError::ValidationError(t!("config-zero-network-timeout")));
+        }
+        if self.max_retries > 10 {
+            return Err(HamsterError::ValidationError(t!("config-invalid-retries")));
+        }
+        if self.ca_certificates.iter().any(|path| path.trim().is_empty()) {
+            return Err(HamsterError::ValidationError(t!("config-empty-ca-path")));
+        }
+        Ok(())
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout_secs.max(1))
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout_secs.max(1))
+    }
+
+    pub fn user_agent(&self) -> String {
+        if self.user_agent.trim().is_empty() {
+            format!("HamsterDrivers/{}", env!("CARGO_PKG_VERSION"))
+        } else {
+            self.user_agent.trim().to_string()
+        }
+    }
+}
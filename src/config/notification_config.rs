@@ -0,0 +1,162 @@
+//! 通知配置
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::utils::{HamsterError, Result};
+use crate::t;
+
+/// 可以触发通知的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// 检查发现可用的驱动更新
+    UpdatesFound,
+    /// 安装完成后需要重启
+    RebootRequired,
+    /// 后台操作失败
+    Error,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 3] = [NotificationKind::UpdatesFound, NotificationKind::RebootRequired, NotificationKind::Error];
+
+    pub fn label(&self) -> String {
+        match self {
+            NotificationKind::UpdatesFound => t!("notify-kind-updates-found"),
+            NotificationKind::RebootRequired => t!("notify-kind-reboot-required"),
+            NotificationKind::Error => t!("notify-kind-error"),
+        }
+    }
+}
+
+/// Webhook 请求体格式，通用格式发送完整的通知 JSON，其余为聊天工具机器人的消息格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookTemplate {
+    #[default]
+    Generic,
+    Slack,
+    Discord,
+    #[serde(rename = "dingtalk")]
+    DingTalk,
+    #[serde(rename = "wecom")]
+    WeCom,
+    Feishu,
+}
+
+impl WebhookTemplate {
+    pub const ALL: [WebhookTemplate; 6] = [
+        WebhookTemplate::Generic,
+        WebhookTemplate::Slack,
+        WebhookTemplate::Discord,
+        WebhookTemplate::DingTalk,
+        WebhookTemplate::WeCom,
+        WebhookTemplate::Feishu,
+    ];
+
+    pub fn label(&self) -> String {
+        match self {
+            WebhookTemplate::Generic => t!("notify-template-generic"),
+            WebhookTemplate::Slack => "Slack".to_string(),
+            WebhookTemplate::Discord => "Discord".to_string(),
+            WebhookTemplate::DingTalk => t!("notify-template-dingtalk"),
+            WebhookTemplate::WeCom => t!("notify-template-wecom"),
+            WebhookTemplate::Feishu => t!("notify-template-feishu"),
+        }
+    }
+}
+
+/// 通知发送的位置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChannelTarget {
+    /// 系统桌面通知
+    Desktop,
+    /// 向 URL 发送 JSON POST 请求
+    Webhook {
+        url: String,
+        #[serde(default)]
+        template: WebhookTemplate,
+        /// 附加的请求头，如认证令牌
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// 在目录中为每条通知写入一个 JSON 文件，供其他程序收取
+    File { directory: String },
+}
+
+impl ChannelTarget {
+    pub fn label(&self) -> String {
+        match self {
+            ChannelTarget::Desktop => t!("notify-channel-desktop"),
+            ChannelTarget::Webhook { .. } => t!("notify-channel-webhook"),
+            ChannelTarget::File { .. } => t!("notify-channel-file"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationChannel {
+    #[serde(flatten)]
+    pub target: ChannelTarget,
+    /// 触发该渠道的事件，为空时所有事件都发送
+    #[serde(default)]
+    pub events: Vec<NotificationKind>,
+}
+
+impl NotificationChannel {
+    pub fn new(target: ChannelTarget) -> Self {
+        Self { target, events: Vec::new() }
+    }
+
+    pub fn accepts(&self, kind: NotificationKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match &self.target {
+            ChannelTarget::Desktop => Ok(()),
+            ChannelTarget::Webhook { url, headers, .. } => {
+                if !(url.starts_with("http://") || url.starts_with("https://")) {
+                    return Err(HamsterError::ValidationError(t!("config-invalid-webhook-url", url = url)));
+                }
+                if headers.keys().any(|name| name.trim().is_empty()) {
+                    return Err(HamsterError::ValidationError(t!("config-empty-header-name")));
+                }
+                Ok(())
+            }
+            ChannelTarget::File { directory } if directory.trim().is_empty() => {
+                Err(HamsterError::ValidationError(t!("config-empty-notify-directory")))
+            }
+            ChannelTarget::File { .. } => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub enabled: bool,
+    pub channels: Vec<NotificationChannel>,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            channels: vec![NotificationChannel {
+                target: ChannelTarget::Desktop,
+                events: vec![NotificationKind::UpdatesFound, NotificationKind::RebootRequired],
+            }],
+        }
+    }
+}
+
+impl NotificationConfig {
+    pub fn validate(&self) -> Result<()> {
+        for channel in &self.channels {
+            channel.validate()?;
+        }
+        Ok(())
+    }
+}
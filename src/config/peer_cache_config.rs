@@ -0,0 +1,64 @@
+//! 局域网驱动包缓存配置
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::utils::{HamsterError, Result};
+use crate::t;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PeerCacheConfig {
+    /// 下载有校验和的驱动前先在本机缓存、共享文件夹和其他计算机中查找
+    pub enabled: bool,
+    /// 向局域网中的其他计算机提供本机缓存的驱动包
+    pub serve: bool,
+    /// 提供驱动包的监听地址
+    pub listen_address: String,
+    /// 其他计算机的地址，如 http://192.168.1.20:47800
+    pub peers: Vec<String>,
+    /// 共享文件夹，驱动包按 SHA-256 命名存放
+    pub shared_folder: Option<String>,
+    /// 询问其他计算机是否有驱动包的超时时间（秒）
+    pub peer_timeout_secs: u64,
+}
+
+impl Default for PeerCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            serve: false,
+            listen_address: "0.0.0.0:47800".to_string(),
+            peers: Vec::new(),
+            shared_folder: None,
+            peer_timeout_secs: 3,
+        }
+    }
+}
+
+impl PeerCacheConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.serve && self.listen_address.parse::<SocketAddr>().is_err() {
+            return Err(HamsterError::ValidationError(t!("config-invalid-listen-address", address = self.listen_address)));
+        }
+        if let Some(peer) = self.peers.iter().find(|peer| !(peer.starts_with("http://") || peer.starts_with("https://"))) {
+            return Err(HamsterError::ValidationError(t!("config-invalid-peer", peer = peer)));
+        }
+        if self.shared_folder.as_ref().is_some_and(|folder| folder.trim().is_empty()) {
+            return Err(HamsterError::ValidationError(t!("config-empty-shared-folder")));
+        }
+        if self.peer_timeout_secs == 0 {
+            return Err(HamsterError::ValidationError(t!("config-zero-peer-timeout")));
+        }
+        Ok(())
+    }
+
+    /// 需要本机缓存：从缓存查找或向其他计算机提供
+    pub fn uses_store(&self) -> bool {
+        self.enabled || self.serve
+    }
+
+    pub fn peer_timeout(&self) -> Duration {
+        Duration::from_secs(self.peer_timeout_secs.max(1))
+    }
+}
@@ -0,0 +1,33 @@
+//! 驱动仓库配置
+
+use serde::{Deserialize, Serialize};
+use crate::utils::{HamsterError, Result};
+use crate::t;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepositoryConfig {
+    /// 自建驱动仓库的地址，如 http://drivers.corp.example.com:8080，为空时不使用
+    pub url: Option<String>,
+    /// 仓库要求认证时使用的 API 密钥
+    pub api_key: Option<String>,
+    /// 仓库中没有适用的驱动时再从厂商网站查找
+    pub vendor_fallback: bool,
+}
+
+impl Default for RepositoryConfig {
+    fn default() -> Self {
+        Self { url: None, api_key: None, vendor_fallback: true }
+    }
+}
+
+impl RepositoryConfig {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(ref url) = self.url {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(HamsterError::ValidationError(t!("config-invalid-repository-url", url = url)));
+            }
+        }
+        Ok(())
+    }
+}
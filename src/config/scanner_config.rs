@@ -0,0 +1,47 @@
+//! 硬件扫描配置
+
+use serde::{Deserialize, Serialize};
+use crate::utils::{HamsterError, Result};
+use crate::t;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScannerConfig {
+    /// 通过 WMI 枚举设备，能补充 SetupAPI 漏掉的设备，但扫描较慢
+    pub use_wmi: bool,
+    /// 通过 SetupAPI（Get-PnpDevice）枚举设备
+    pub use_setupapi: bool,
+    /// 设备列表是否默认显示隐藏（未连接）的设备
+    pub include_hidden: bool,
+    /// 只扫描这些设备类别，为空时扫描全部
+    pub device_classes: Vec<String>,
+}
+
+impl Default for ScannerConfig {
+    fn default() -> Self {
+        Self {
+            use_wmi: false,
+            use_setupapi: true,
+            include_hidden: false,
+            device_classes: Vec::new(),
+        }
+    }
+}
+
+impl ScannerConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !self.use_wmi && !self.use_setupapi {
+            return Err(HamsterError::ValidationError(t!("config-no-scan-method")));
+        }
+        if self.device_classes.iter().any(|class| class.trim().is_empty()) {
+            return Err(HamsterError::ValidationError(t!("config-empty-device-class")));
+        }
+        Ok(())
+    }
+
+    /// 设备类别是否在扫描范围内
+    pub fn includes_class(&self, device_class: &str) -> bool {
+        self.device_classes.is_empty()
+            || self.device_classes.iter().any(|class| class.trim().eq_ignore_ascii_case(device_class))
+    }
+}
@@ -0,0 +1,163 @@
+//! 定时更新配置
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use crate::utils::{CronExpression, HamsterError, Result};
+use crate::t;
+
+const WEEKDAY_NAMES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    pub enabled: bool,
+    /// 检查间隔（小时），设置了 cron 时忽略
+    pub interval_hours: u32,
+    /// Cron表达式（分 时 日 月 周），按本地时间计算
+    pub cron: Option<String>,
+    /// 在计划时间后随机推迟的最长分钟数，避免大量机器同时请求
+    pub jitter_minutes: u32,
+    pub action: ScheduledAction,
+    /// 只在这些时间段内执行，为空时不限制
+    pub maintenance_windows: Vec<MaintenanceWindow>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledAction {
+    /// 只检查更新
+    CheckOnly,
+    /// 检查并下载更新
+    Download,
+    /// 检查、下载并安装更新
+    DownloadAndInstall,
+}
+
+impl ScheduledAction {
+    pub fn label(&self) -> String {
+        match self {
+            ScheduledAction::CheckOnly => t!("schedule-action-check-only"),
+            ScheduledAction::Download => t!("schedule-action-download"),
+            ScheduledAction::DownloadAndInstall => t!("schedule-action-download-and-install"),
+        }
+    }
+}
+
+/// 维护时间段，结束时间早于开始时间表示跨越午夜
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    /// 星期缩写（mon..sun），为空表示每天
+    #[serde(default)]
+    pub days: Vec<String>,
+    /// 开始时间 HH:MM
+    pub start: String,
+    /// 结束时间 HH:MM
+    pub end: String,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+            cron: None,
+            jitter_minutes: 30,
+            action: ScheduledAction::CheckOnly,
+            maintenance_windows: Vec::new(),
+        }
+    }
+}
+
+impl ScheduleConfig {
+    pub fn validate(&self) -> Result<()> {
+        match self.cron {
+            Some(ref cron) => {
+                CronExpression::parse(cron)?;
+            }
+            None if self.interval_hours == 0 => {
+                return Err(HamsterError::ValidationError(t!("config-zero-interval")));
+            }
+            None => {}
+        }
+        for window in &self.maintenance_windows {
+            window.validate()?;
+        }
+        Ok(())
+    }
+
+    /// 时间是否落在任一维护时间段内，没有配置时间段时总是允许
+    pub fn in_maintenance_window(&self, time: DateTime<Local>) -> bool {
+        self.maintenance_windows.is_empty()
+            || self.maintenance_windows.iter().any(|window| window.contains(time))
+    }
+
+    /// 不早于 time 的最近一个可执行时间
+    pub fn next_allowed_time(&self, time: DateTime<Local>) -> Option<DateTime<Local>> {
+        if self.in_maintenance_window(time) {
+            return Some(time);
+        }
+        self.maintenance_windows.iter()
+            .filter_map(|window| window.next_start_after(time))
+            .min()
+    }
+}
+
+impl MaintenanceWindow {
+    pub fn validate(&self) -> Result<()> {
+        parse_time(&self.start)?;
+        parse_time(&self.end)?;
+        for day in &self.days {
+            parse_weekday(day)?;
+        }
+        Ok(())
+    }
+
+    /// 跨午夜的时间段按开始那天的星期判断
+    pub fn contains(&self, time: DateTime<Local>) -> bool {
+        let (Ok(start), Ok(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        let now = time.time();
+        let weekday = time.weekday().num_days_from_monday();
+
+        if start <= end {
+            start <= now && now < end && self.allows_day(weekday)
+        } else if now >= start {
+            self.allows_day(weekday)
+        } else {
+            now < end && self.allows_day((weekday + 6) % 7)
+        }
+    }
+
+    /// time 之后（含）第一次进入该时间段的时刻
+    pub fn next_start_after(&self, time: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = parse_time(&self.start).ok()?;
+        (0..=7)
+            .filter_map(|offset| {
+                let date = time.date_naive() + Duration::days(offset);
+                if !self.allows_day(date.weekday().num_days_from_monday()) {
+                    return None;
+                }
+                Local.from_local_datetime(&date.and_time(start)).earliest()
+            })
+            .find(|candidate| *candidate >= time)
+    }
+
+    fn allows_day(&self, weekday_from_monday: u32) -> bool {
+        self.days.is_empty()
+            || self.days.iter().any(|day| parse_weekday(day).ok() == Some(weekday_from_monday))
+    }
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .map_err(|_| HamsterError::ValidationError(t!("config-invalid-time", value = value)))
+}
+
+fn parse_weekday(value: &str) -> Result<u32> {
+    let lower = value.to_ascii_lowercase();
+    WEEKDAY_NAMES.iter()
+        .position(|name| lower.starts_with(name))
+        .map(|index| index as u32)
+        .ok_or_else(|| HamsterError::ValidationError(t!("config-invalid-weekday", value = value)))
+}
@@ -0,0 +1,59 @@
+//! 各阶段超时配置
+
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::utils::{HamsterError, Result};
+use crate::t;
+
+/// 超时时间（秒），0 表示不限时
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    pub scan_secs: u64,
+    /// 单个设备的驱动匹配
+    pub match_secs: u64,
+    /// 单个驱动文件的下载
+    pub download_secs: u64,
+    /// 安装程序运行时间，超过后由看门狗终止
+    pub install_secs: u64,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            scan_secs: 300,
+            match_secs: 120,
+            download_secs: 1800,
+            install_secs: 900,
+        }
+    }
+}
+
+impl TimeoutConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.install_secs == 0 {
+            return Err(HamsterError::ValidationError(t!("config-zero-install-timeout")));
+        }
+        Ok(())
+    }
+
+    pub fn scan(&self) -> Option<Duration> {
+        to_duration(self.scan_secs)
+    }
+
+    pub fn matching(&self) -> Option<Duration> {
+        to_duration(self.match_secs)
+    }
+
+    pub fn download(&self) -> Option<Duration> {
+        to_duration(self.download_secs)
+    }
+
+    pub fn install(&self) -> Duration {
+        Duration::from_secs(self.install_secs.max(1))
+    }
+}
+
+fn to_duration(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
@@ -0,0 +1,62 @@
+//! 更新偏好：忽略的驱动版本和固定的设备
+
+use serde::{Deserialize, Serialize};
+
+/// 被忽略的驱动版本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IgnoredUpdate {
+    pub hardware_id: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdatePreferences {
+    /// 只忽略这个版本，之后出现更新的版本时仍会提示
+    pub ignored: Vec<IgnoredUpdate>,
+    /// 固定当前驱动的设备硬件ID，不再提示任何更新
+    pub pinned: Vec<String>,
+    /// 是否提示测试版（Beta）驱动，关闭时只提示稳定版
+    pub include_beta: bool,
+}
+
+impl UpdatePreferences {
+    pub fn is_pinned(&self, hardware_id: &str) -> bool {
+        self.pinned.iter().any(|id| id.eq_ignore_ascii_case(hardware_id))
+    }
+
+    pub fn is_ignored(&self, hardware_id: &str, version: &str) -> bool {
+        self.ignored.iter().any(|ignored| {
+            ignored.hardware_id.eq_ignore_ascii_case(hardware_id) && ignored.version == version
+        })
+    }
+
+    /// 该设备的这个版本是否不再提示更新
+    pub fn suppresses(&self, hardware_id: &str, version: &str) -> bool {
+        self.is_pinned(hardware_id) || self.is_ignored(hardware_id, version)
+    }
+
+    pub fn ignore(&mut self, hardware_id: &str, version: &str) {
+        if !self.is_ignored(hardware_id, version) {
+            self.ignored.push(IgnoredUpdate {
+                hardware_id: hardware_id.to_string(),
+                version: version.to_string(),
+            });
+        }
+    }
+
+    /// 取消该设备所有被忽略的版本
+    pub fn unignore(&mut self, hardware_id: &str) {
+        self.ignored.retain(|ignored| !ignored.hardware_id.eq_ignore_ascii_case(hardware_id));
+    }
+
+    pub fn pin(&mut self, hardware_id: &str) {
+        if !self.is_pinned(hardware_id) {
+            self.pinned.push(hardware_id.to_string());
+        }
+    }
+
+    pub fn unpin(&mut self, hardware_id: &str) {
+        self.pinned.retain(|id| !id.eq_ignore_ascii_case(hardware_id));
+    }
+}
@@ -1,13 +1,26 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use crate::{
+    config::{ConfigManager, RepositoryConfig, ScannerConfig, TimeoutConfig, UpdatePreferences},
+    core::{CoreEvent, EventBus, EventFilter, EventSubscription},
+    utils::{run_cancellable, CancelHandle, HamsterError},
     os_info::SystemInfo,
-    hardware::{HardwareScanner, HardwareScanResult},
-    matcher::{DriverMatcher, HardwareInfo as MatcherHardwareInfo},
-    fetcher::{DriverFetcher, DownloadProgress, DownloadTask},
-    installer::{DriverInstaller, InstallationResult, DriverInfo as InstallerDriverInfo},
+    hardware::{HardwareDevice, HardwareScanner, HardwareScanResult},
+    matcher::{compare_driver_versions, DriverChannel, DriverMatcher, HardwareInfo as MatcherHardwareInfo},
+    fetcher::{DriverFetcher, DownloadProgress},
+    driver::fetcher::{DownloadPriority, DownloadQueue, DownloadStatus, DownloadTask, DownloadWorkers, PeerCache},
+    installer::{
+        DriverInstaller, InstallationResult, DriverInfo as InstallerDriverInfo, BackupPackage, BackupStore, RestoreCandidate, SnapshotInfo,
+        InstallJournal, InstallStep, JournalEvent, JournalTransaction, TransactionKind, TransactionOutcome, TransactionSummary,
+    },
+    driver::installer::{
+        ChangeDetails, InstallationAction, InstallationLogEntry, InstallationLogger, InstallationStatus, LogFilter,
+        RollbackEntry, RollbackManager, RollbackPoint, RollbackReport,
+    },
+    t,
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -18,7 +31,7 @@ pub struct UpdateCandidate {
     pub current_version: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanProgress {
     pub current_step: String,
     pub progress: f32,
@@ -26,77 +39,207 @@ pub struct ScanProgress {
     pub current_step_number: u32,
 }
 
+#[derive(Debug, Clone)]
+pub struct RestorePlan {
+    pub package_path: String,
+    pub description: String,
+    pub created_at: String,
+    pub candidates: Vec<RestoreCandidate>,
+}
+
 pub struct DriverUpdaterCore {
     pub system_info: Option<SystemInfo>,
     pub hardware_scanner: HardwareScanner,
     pub driver_matcher: Arc<Mutex<DriverMatcher>>,
-    pub driver_fetcher: Arc<Mutex<DriverFetcher>>,
+    pub driver_fetcher: Arc<DriverFetcher>,
     pub driver_installer: DriverInstaller,
+    pub rollback_manager: RollbackManager,
+    pub install_journal: InstallJournal,
+    pub installation_logger: InstallationLogger,
     pub scan_result: Option<HardwareScanResult>,
     pub update_candidates: Vec<UpdateCandidate>,
+    pub events: EventBus,
+    pub timeouts: TimeoutConfig,
+    pub update_preferences: UpdatePreferences,
+    pub scanner_config: ScannerConfig,
+    pub repository_config: RepositoryConfig,
+    download_queue: DownloadQueue,
+    download_workers: DownloadWorkers,
+    cancel: CancelHandle,
+    data_dir: PathBuf,
+    /// 导出、安装和回滚驱动的步骤一次只执行一个，不同操作的步骤可以交替进行
+    device_changes: Mutex<()>,
 }
 
 impl DriverUpdaterCore {
-    pub async fn new(_db_path: &str, download_dir: &str) -> Result<Self> {
+    /// 创建核心控制器并应用配置
+    pub async fn new(db_path: &str, config: &ConfigManager) -> Result<Self> {
+        Self::with_data_dir(db_path, config, &crate::utils::get_app_data_dir()?).await
+    }
+
+    /// 同 new，回滚数据、安装日志、下载队列和驱动包存储都保存在 data_dir 下
+    pub async fn with_data_dir(_db_path: &str, config: &ConfigManager, data_dir: &Path) -> Result<Self> {
+        let download = &config.download_config;
         let hardware_scanner = HardwareScanner::new();
         // 不再使用数据库，直接创建驱动匹配器实例
         let driver_matcher = Arc::new(Mutex::new(DriverMatcher::new("dummy").await?));
-        let driver_fetcher = Arc::new(Mutex::new(DriverFetcher::new(
-            "localhost".to_string(),
-            6800,
-            download_dir.to_string(),
-        )));
+        let driver_fetcher = Arc::new(DriverFetcher::new(
+            download.aria2_host.clone(),
+            download.aria2_port,
+            download.download_dir.clone(),
+        ));
+        // 上次退出时未完成的下载由工作池继续
+        let download_queue = DownloadQueue::open(download.max_concurrent_downloads, data_dir.join("download_queue.json"))
+            .unwrap_or_else(|e| {
+                eprintln!("{}", t!("downloads-load-failed", error = e));
+                DownloadQueue::new(download.max_concurrent_downloads)
+            });
+        let download_workers = download_queue.start_workers(driver_fetcher.clone());
         let driver_installer = DriverInstaller::new();
+        let rollback_manager = RollbackManager::open(&data_dir.join("rollback"))?;
+        let install_journal = InstallJournal::open(&data_dir.join("journal"))?;
+        let installation_logger = InstallationLogger::new(data_dir.join("logs").join("installations.jsonl").to_string_lossy().to_string());
 
-        Ok(DriverUpdaterCore {
+        let mut core = DriverUpdaterCore {
             system_info: None,
             hardware_scanner,
             driver_matcher,
             driver_fetcher,
             driver_installer,
+            rollback_manager,
+            install_journal,
+            installation_logger,
             scan_result: None,
             update_candidates: Vec::new(),
-        })
+            events: EventBus::new(),
+            timeouts: TimeoutConfig::default(),
+            update_preferences: UpdatePreferences::default(),
+            scanner_config: ScannerConfig::default(),
+            repository_config: RepositoryConfig::default(),
+            download_queue,
+            download_workers,
+            cancel: CancelHandle::new(),
+            data_dir: data_dir.to_path_buf(),
+            device_changes: Mutex::new(()),
+        };
+        core.apply_config(config);
+        Ok(core)
     }
 
     pub async fn initialize(&mut self) -> Result<()> {
-        println!("正在初始化驱动更新核心...");
+        println!("{}", t!("core-initializing"));
         
         // 获取系统信息
         self.system_info = Some(SystemInfo::new()?);
-        println!("系统信息获取完成");
+        println!("{}", t!("core-system-info-ready"));
         
         // 尝试启动Aria2 RPC服务器，如果失败则记录警告但不中断初始化
-        {
-            let fetcher = self.driver_fetcher.lock().await;
-            match fetcher.start_aria2_rpc().await {
-                Ok(_) => println!("Aria2 RPC服务器启动完成"),
-                Err(e) => {
-                    eprintln!("Aria2 RPC服务器启动失败: {}，将继续运行但下载功能可能受限", e);
-                }
+        match self.driver_fetcher.start_aria2_rpc().await {
+            Ok(_) => println!("{}", t!("core-aria2-started")),
+            Err(e) => {
+                eprintln!("{}", t!("core-aria2-failed", error = e));
             }
         }
         
         Ok(())
     }
 
-    pub async fn scan_system(&mut self, progress_callback: impl Fn(ScanProgress) -> ()) -> Result<HardwareScanResult> {
-        println!("开始扫描系统硬件...");
-        
-        // 更新进度
-        progress_callback(ScanProgress {
-            current_step: "开始硬件扫描".to_string(),
-            progress: 0.0,
-            total_steps: 3,
-            current_step_number: 1,
+    /// 使用配置中的各阶段超时，安装超时同时作为看门狗时限
+    pub fn set_timeouts(&mut self, timeouts: TimeoutConfig) {
+        self.driver_installer.watchdog_timeout = timeouts.install();
+        self.timeouts = timeouts;
+    }
+
+    /// 应用配置中与核心相关的部分
+    pub fn apply_config(&mut self, config: &ConfigManager) {
+        self.set_timeouts(config.timeout_config.clone());
+        self.scanner_config = config.scanner_config.clone();
+        self.repository_config = config.repository_config.clone();
+        self.rollback_manager.set_max_rollback_points(config.backup_config.max_rollback_points);
+        let download = &config.download_config;
+        let fetcher = &self.driver_fetcher;
+        if (&fetcher.aria2_host, fetcher.aria2_port, &fetcher.download_dir) != (&download.aria2_host, download.aria2_port, &download.download_dir) {
+            // 旧的工作池被丢弃时中断的下载重新排队，由新的工作池继续
+            self.driver_fetcher = Arc::new(DriverFetcher::new(download.aria2_host.clone(), download.aria2_port, download.download_dir.clone()));
+            self.download_workers = self.download_queue.start_workers(self.driver_fetcher.clone());
+        }
+        self.download_queue.apply_config(download);
+        // 打不开驱动包存储时直接从厂商服务器下载
+        let peer_cache = PeerCache::open_in(&download.peer_cache, &self.data_dir).unwrap_or_else(|e| {
+            eprintln!("{}", t!("peer-cache-open-failed", error = e));
+            None
         });
-        
-        // 扫描硬件
-        let scan_result = self.hardware_scanner.scan_hardware()?;
+        self.download_queue.set_peer_cache(peer_cache);
+        self.driver_fetcher.set_rate_limits(&config.download_config);
+        self.driver_fetcher.set_repository(&config.repository_config);
+        self.set_update_preferences(config.update_preferences.clone());
+    }
+
+    /// 被忽略的版本和固定的设备不再标记为需要更新
+    pub fn set_update_preferences(&mut self, preferences: UpdatePreferences) {
+        for candidate in &mut self.update_candidates {
+            if let Some(ref driver) = candidate.matched_driver {
+                candidate.needs_update = needs_update(&preferences, &candidate.hardware_info.hardware_id, &candidate.current_version, driver);
+            }
+        }
+        self.update_preferences = preferences;
+    }
+
+    /// 保存在应用数据目录的下载队列，更新、定时下载和界面的下载管理共用
+    pub fn download_queue(&self) -> &DownloadQueue {
+        &self.download_queue
+    }
+
+    /// 用于取消正在执行的操作，可以在其他任务中调用
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// 订阅核心事件
+    pub fn subscribe(&self, filter: EventFilter) -> EventSubscription {
+        self.events.subscribe(filter)
+    }
+
+    pub async fn scan_system(&mut self, progress_callback: impl Fn(ScanProgress) -> ()) -> Result<HardwareScanResult> {
+        println!("{}", t!("core-scan-started"));
+        self.events.publish(CoreEvent::ScanStarted);
+        let events = self.events.clone();
+        let progress_callback = move |progress: ScanProgress| {
+            events.publish(CoreEvent::ScanProgress { progress: progress.clone() });
+            progress_callback(progress);
+        };
+        let operation = self.cancel.begin();
+        let scan = async {
+            // 更新进度
+            progress_callback(ScanProgress {
+                current_step: t!("scan-step-started"),
+                progress: 0.0,
+                total_steps: 3,
+                current_step_number: 1,
+            });
+
+            // 扫描是同步调用，放到阻塞线程中以便超时或取消时不再等待它
+            let scanner = HardwareScanner::with_config(self.scanner_config.clone());
+            let scan_result = run_cancellable(operation.token(), self.timeouts.scan(), &t!("scan-step-scanning"),
+                tokio::task::spawn_blocking(move || scanner.scan_hardware())).await???;
+            self.add_scan_result(scan_result, &progress_callback).await
+        };
+        let result = scan.await;
+        match result {
+            Ok(ref scan_result) => self.events.publish(CoreEvent::ScanCompleted { device_count: scan_result.devices.len() }),
+            Err(ref e) => self.events.publish(CoreEvent::Error { operation: "scan".to_string(), message: e.to_string() }),
+        }
+        result
+    }
+
+    async fn add_scan_result(&mut self, scan_result: HardwareScanResult, progress_callback: &impl Fn(ScanProgress)) -> Result<HardwareScanResult> {
         self.scan_result = Some(scan_result.clone());
+        for device in &scan_result.devices {
+            self.events.publish(CoreEvent::DeviceFound { device: Box::new(device.clone()) });
+        }
         
         progress_callback(ScanProgress {
-            current_step: "硬件扫描完成".to_string(),
+            current_step: t!("scan-step-scanned"),
             progress: 33.3,
             total_steps: 3,
             current_step_number: 1,
@@ -120,31 +263,50 @@ impl DriverUpdaterCore {
         }
         
         progress_callback(ScanProgress {
-            current_step: "硬件信息入库完成".to_string(),
+            current_step: t!("scan-step-stored"),
             progress: 66.6,
             total_steps: 3,
             current_step_number: 2,
         });
         
         progress_callback(ScanProgress {
-            current_step: "扫描完成".to_string(),
+            current_step: t!("scan-step-finished"),
             progress: 100.0,
             total_steps: 3,
             current_step_number: 3,
         });
         
-        println!("系统硬件扫描完成，共发现 {} 个设备", scan_result.devices.len());
+        println!("{}", t!("core-scan-finished", count = scan_result.devices.len()));
         Ok(scan_result)
     }
 
     pub async fn find_driver_updates(&mut self) -> Result<Vec<UpdateCandidate>> {
-        println!("开始查找驱动更新...");
+        println!("{}", t!("core-search-started"));
         
         if let Some(ref scan_result) = self.scan_result {
             let mut candidates = Vec::new();
-            let matcher = self.driver_matcher.lock().await;
+            let mut matcher = self.driver_matcher.lock().await;
+            matcher.set_repository(&self.repository_config);
+            let operation = self.cancel.begin();
+
+            // 配置了驱动仓库时先批量查询全部设备
+            let hardware_ids: Vec<String> = scan_result.devices.iter()
+                .filter(|device| !device.is_hidden())
+                .map(|device| device.hardware_id.clone())
+                .collect();
+            let prefetched = run_cancellable(operation.token(), self.timeouts.matching(), &t!("core-matching-operation"),
+                matcher.prefetch_repository(&hardware_ids)).await;
+            if let Err(e) = prefetched.map_err(anyhow::Error::from).and_then(|result| result) {
+                self.events.publish(CoreEvent::Error { operation: "match".to_string(), message: e.to_string() });
+                return Err(e);
+            }
             
             for device in &scan_result.devices {
+                operation.token().check(&t!("core-search-operation"))?;
+                // 未连接的设备无法安装驱动
+                if device.is_hidden() {
+                    continue;
+                }
                 // 创建MatcherHardwareInfo
                 let hw_info = MatcherHardwareInfo {
                     hardware_id: device.hardware_id.clone(),
@@ -154,10 +316,25 @@ impl DriverUpdaterCore {
                 };
                 
                 // 匹配驱动
-                let match_result = matcher.match_driver(&hw_info).await?;
+                let matched = run_cancellable(operation.token(), self.timeouts.matching(), &t!("core-matching-operation"),
+                    matcher.match_driver(&hw_info)).await;
+                let match_result = match matched.map_err(anyhow::Error::from).and_then(|result| result) {
+                    Ok(match_result) => match_result,
+                    Err(e) => {
+                        self.events.publish(CoreEvent::Error { operation: "match".to_string(), message: e.to_string() });
+                        return Err(e);
+                    }
+                };
+                if let Some(ref driver) = match_result.matched_driver {
+                    self.events.publish(CoreEvent::MatchFound {
+                        hardware_id: hw_info.hardware_id.clone(),
+                        driver: driver.clone(),
+                    });
+                }
                 
-                // 检查是否需要更新（这里简化为只要有匹配就认为需要更新）
-                let needs_update = match_result.matched_driver.is_some();
+                let needs_update = match_result.matched_driver.as_ref().is_some_and(|driver| {
+                    needs_update(&self.update_preferences, &hw_info.hardware_id, &device.driver_version, driver)
+                });
                 
                 let candidate = UpdateCandidate {
                     hardware_info: hw_info,
@@ -170,98 +347,734 @@ impl DriverUpdaterCore {
             }
             
             self.update_candidates = candidates;
-            println!("驱动更新查找完成，找到 {} 个更新候选", self.update_candidates.len());
+            println!("{}", t!("core-search-finished", count = self.update_candidates.len()));
             Ok(self.update_candidates.clone())
         } else {
-            Err(anyhow::anyhow!("未进行硬件扫描，无法查找驱动更新"))
-        }
-    }
-
-    pub async fn download_driver(&self, driver_info: &crate::matcher::DriverInfo, 
-                                progress_callback: impl Fn(DownloadProgress) -> ()) -> Result<String> {
-        // 创建下载任务
-        let task = DownloadTask {
-            id: format!("download_{}", driver_info.driver_id),
-            url: driver_info.driver_url.clone(),
-            file_path: "".to_string(), // 由fetcher决定
-            file_name: format!("{}_{}.{}", 
-                driver_info.driver_name.replace(" ", "_"), 
-                driver_info.driver_version.replace(".", "_"), 
-                "exe"), // 简化的文件名生成
-            expected_size: Some(driver_info.file_size),
-            checksum: Some(driver_info.checksum.clone()),
+            Err(anyhow::anyhow!(t!("core-no-scan-for-search")))
+        }
+    }
+
+    /// 通过下载队列下载驱动，返回下载文件路径
+    ///
+    /// 下载受队列的并发数、速度上限和大文件下载时间段限制；
+    /// 不在允许的时间段内的大文件留在队列中等待，返回错误。
+    pub async fn download_driver(&self, driver_info: &crate::matcher::DriverInfo, priority: DownloadPriority,
+                                progress_callback: impl Fn(DownloadProgress)) -> Result<String> {
+        let file_path = format!("{}/{}", self.driver_fetcher.download_dir, driver_info.file_name());
+        let task_id = self.download_queue.add_task_with_priority(driver_info.clone(), driver_info.driver_url.clone(), file_path, priority)?;
+
+        // 进度同时发布到事件总线
+        let hardware_id = driver_info.hardware_id.clone();
+        let report = |progress: DownloadProgress| {
+            self.events.publish(CoreEvent::DownloadProgress { hardware_id: hardware_id.clone(), progress: progress.clone() });
+            progress_callback(progress);
         };
-        
-        // 执行下载
-        {
-            let fetcher = self.driver_fetcher.lock().await;
-            fetcher.download_driver_with_progress(&task, progress_callback).await?;
+        let downloaded = self.run_queued_download(&task_id, report).await;
+        if let Err(ref e) = downloaded {
+            self.events.publish(CoreEvent::Error { operation: "download".to_string(), message: e.to_string() });
+        }
+        downloaded
+    }
+
+    // 等待队列中的任务下载结束
+    async fn run_queued_download(&self, task_id: &str, report: impl Fn(DownloadProgress)) -> Result<String> {
+        let operation = self.cancel.begin();
+        let task = self.download_queue.download(task_id, self.driver_fetcher.clone(), operation.token(), self.timeouts.download(),
+            |task| report(download_progress(task))).await?;
+        match task.status {
+            DownloadStatus::Completed => Ok(task.file_path),
+            DownloadStatus::Waiting => Err(anyhow::anyhow!(t!("core-download-waiting", file = task.driver_info.file_name()))),
+            status => Err(anyhow::anyhow!(task.error.unwrap_or_else(|| status.label()))),
         }
-        
-        // 返回下载文件路径
-        Ok(format!("{}/{}", 
-            self.driver_fetcher.lock().await.download_dir, 
-            task.file_name))
     }
 
     pub async fn install_driver(&self, driver_path: &str, hardware_id: &str) -> Result<InstallationResult> {
-        let installer_info = InstallerDriverInfo {
-            file_path: driver_path.to_string(),
-            file_name: std::path::Path::new(driver_path)
-                .file_name()
-                .and_then(|name| name.to_str())
-                .unwrap_or("driver")
-                .to_string(),
-            hardware_id: hardware_id.to_string(),
-            manufacturer: "Unknown".to_string(),
-            driver_version: "1.0.0".to_string(), // 从驱动文件中获取更准确的版本
+        let (mut transaction, rollback_point) = self.begin_install_batch(
+            &t!("transaction-install", hardware_id = hardware_id),
+            TransactionKind::Install { driver_path: driver_path.to_string() },
+            &[self.resolve_device(hardware_id)],
+            &[hardware_id.to_string()],
+        ).await?;
+
+        let result = self.install_with_rollback(&installer_info_for(driver_path, hardware_id), InstallationAction::Install, rollback_point.as_ref(), &mut transaction).await;
+        transaction.finish_with(result)
+    }
+
+    /// 安装驱动并写入安装日志，日志写入失败不影响安装
+    async fn install_with_rollback(&self, installer_info: &InstallerDriverInfo, action: InstallationAction, rollback_point: Option<&RollbackPoint>, transaction: &mut JournalTransaction) -> Result<InstallationResult> {
+        let started = std::time::Instant::now();
+        let device = self.resolve_device(&installer_info.hardware_id);
+        let rollback_entry = rollback_point.and_then(|point| {
+            point.affected_drivers.iter()
+                .find(|entry| entry.hardware_id.eq_ignore_ascii_case(&device.hardware_id))
+                .map(|entry| (point, entry))
+        });
+        let change = ChangeDetails {
+            device_name: device.device_name.clone(),
+            previous_version: rollback_entry.map(|(_, entry)| entry.driver_version.clone())
+                .or_else(|| Some(device.driver_version.clone()).filter(|version| !version.is_empty())),
+            rollback_id: rollback_entry.map(|(point, _)| point.id.clone()),
+            reverts: None,
         };
-        
-        self.driver_installer.install_driver(&installer_info).await
+        let log_id = match self.installation_logger.log_change_start(&installer_info.hardware_id, installer_info.clone(), action, change) {
+            Ok(log_id) => Some(log_id),
+            Err(e) => {
+                eprintln!("{}", t!("core-log-write-failed", error = e));
+                None
+            }
+        };
+
+        let result = self.install_and_verify(installer_info, rollback_point, transaction).await;
+
+        if let Some(log_id) = log_id {
+            let (status, message) = match result {
+                Ok(ref result) if result.success => (InstallationStatus::Success, result.message.clone()),
+                Ok(ref result) if result.aborted => (InstallationStatus::Cancelled, result.message.clone()),
+                Ok(ref result) => (InstallationStatus::Failed, result.message.clone()),
+                Err(ref e) => (InstallationStatus::Failed, e.to_string()),
+            };
+            let duration = u64::try_from(started.elapsed().as_millis()).ok();
+            if let Err(e) = self.installation_logger.log_installation_complete(&log_id, status, message, duration) {
+                eprintln!("{}", t!("core-log-write-failed", error = e));
+            }
+        }
+
+        result
+    }
+
+    /// 安装驱动并在安装后验证，失败时用回滚点中该设备安装前的驱动回滚
+    async fn install_and_verify(&self, installer_info: &InstallerDriverInfo, rollback_point: Option<&RollbackPoint>, transaction: &mut JournalTransaction) -> Result<InstallationResult> {
+        let hardware_id = installer_info.hardware_id.as_str();
+        let _device_changes = self.device_changes.lock().await;
+        println!("{}", t!("core-install-started", file = installer_info.file_name, hardware_id = hardware_id));
+        transaction.step_started(Some(hardware_id), InstallStep::Install)?;
+        let operation = self.cancel.begin();
+        // 安装程序出错、被看门狗终止或超时时设备可能只装了一半，同样需要回滚
+        let mut result = match self.driver_installer.install_driver_cancellable(installer_info, operation.token()).await {
+            Ok(result) => result,
+            Err(e) => InstallationResult {
+                aborted: matches!(e.downcast_ref::<HamsterError>(), Some(HamsterError::Cancelled(_) | HamsterError::TimeoutError(_))),
+                ..failed_result(installer_info.driver_version.clone(), e.to_string())
+            },
+        };
+
+        if result.success {
+            transaction.step_completed(Some(hardware_id), InstallStep::Install, None)?;
+            println!("{}", t!("core-install-succeeded", file = installer_info.file_name));
+            if result.reboot_required {
+                self.events.publish(CoreEvent::RebootRequired { hardware_id: hardware_id.to_string() });
+            }
+            if let Err(e) = journal_step(transaction, Some(hardware_id), InstallStep::VerifyAfter, self.verify_installed(hardware_id)).await {
+                result.message.push_str(&t!("install-verify-failed", error = e));
+            }
+            return Ok(result);
+        }
+
+        let device_hardware_id = self.resolve_device(hardware_id).hardware_id;
+        let rollback_target = rollback_point.and_then(|point| {
+            point.affected_drivers.iter()
+                .find(|entry| entry.hardware_id.eq_ignore_ascii_case(&device_hardware_id))
+                .map(|entry| (point, entry.hardware_id.clone()))
+        });
+        match rollback_target {
+            Some((point, hardware_id)) => {
+                eprintln!("{}", t!("core-install-failed-rolling-back", message = result.message));
+                match self.rollback_manager.rollback_device(point, &hardware_id, &self.driver_installer).await {
+                    Ok(rollback) if rollback.success => result.message.push_str(&t!("install-rolled-back")),
+                    Ok(rollback) => result.message.push_str(&t!("install-rollback-failed", error = rollback.message)),
+                    Err(e) => result.message.push_str(&t!("install-rollback-failed", error = e)),
+                }
+            }
+            None => eprintln!("{}", t!("core-install-failed-no-rollback", message = result.message)),
+        }
+
+        transaction.step_failed(Some(hardware_id), InstallStep::Install, &result.message)?;
+        Ok(result)
+    }
+
+    /// 重新扫描硬件，确认设备仍在且读取安装后的驱动版本
+    async fn verify_installed(&self, hardware_id: &str) -> Result<String> {
+        let scanner = HardwareScanner::with_config(self.scanner_config.clone());
+        let scan_result = tokio::task::spawn_blocking(move || scanner.scan_hardware()).await??;
+        let device = scan_result.devices.iter()
+            .find(|device| {
+                device.hardware_id.eq_ignore_ascii_case(hardware_id)
+                    || crate::installer::device_hardware_ids(device)
+                        .iter()
+                        .any(|id| id.eq_ignore_ascii_case(hardware_id))
+            })
+            .ok_or_else(|| anyhow::anyhow!(t!("core-device-missing-after-install", hardware_id = hardware_id)))?;
+        Ok(t!("core-current-driver-version", version = device.driver_version))
     }
 
     pub async fn update_single_driver(&self, candidate: &UpdateCandidate) -> Result<InstallationResult> {
+        let hardware_id = candidate.hardware_info.hardware_id.clone();
+        let (mut transaction, rollback_point) = self.begin_install_batch(
+            &t!("transaction-update", device = candidate.hardware_info.device_name),
+            TransactionKind::Update,
+            &[self.resolve_device(&hardware_id)],
+            &[hardware_id],
+        ).await?;
+
+        let result = self.update_candidate(candidate, DownloadPriority::User, rollback_point.as_ref(), &mut transaction).await;
+        transaction.finish_with(result)
+    }
+
+    async fn update_candidate(&self, candidate: &UpdateCandidate, priority: DownloadPriority, rollback_point: Option<&RollbackPoint>, transaction: &mut JournalTransaction) -> Result<InstallationResult> {
+        let hardware_id = candidate.hardware_info.hardware_id.as_str();
         if let Some(ref driver_info) = candidate.matched_driver {
             // 下载驱动
             let progress_callback = |progress: DownloadProgress| {
-                println!("下载进度: {:.1}% - {}", progress.progress, progress.file_name);
+                println!("{}", t!("core-download-progress", percent = format!("{:.1}", progress.progress), file = progress.file_name));
             };
             
-            let driver_path = self.download_driver(driver_info, progress_callback).await?;
+            let driver_path = journal_step(transaction, Some(hardware_id), InstallStep::Download,
+                self.download_driver(driver_info, priority, progress_callback)).await?;
+            journal_step(transaction, Some(hardware_id), InstallStep::Verify,
+                verify_downloaded_driver(&driver_path, driver_info)).await?;
             
             // 安装驱动
-            let result = self.install_driver(&driver_path, &candidate.hardware_info.hardware_id).await?;
+            let installer_info = InstallerDriverInfo {
+                manufacturer: driver_info.manufacturer.clone(),
+                driver_version: driver_info.driver_version.clone(),
+                ..installer_info_for(&driver_path, hardware_id)
+            };
+            let result = self.install_with_rollback(&installer_info, InstallationAction::Update, rollback_point, transaction).await?;
             
             Ok(result)
         } else {
-            Err(anyhow::anyhow!("没有找到匹配的驱动"))
+            transaction.step_failed(Some(hardware_id), InstallStep::Download, &t!("core-no-matching-driver"))?;
+            Err(anyhow::anyhow!(t!("core-no-matching-driver")))
         }
     }
 
-    pub async fn update_all_drivers(&self) -> Result<Vec<InstallationResult>> {
+    /// 更新所有需要更新的设备，priority 为下载驱动时在队列中的优先级
+    pub async fn update_all_drivers(&self, priority: DownloadPriority) -> Result<Vec<InstallationResult>> {
+        let hardware_ids: Vec<String> = self.update_candidates.iter()
+            .filter(|c| c.needs_update)
+            .map(|c| c.hardware_info.hardware_id.clone())
+            .collect();
+        self.update_selected_drivers(&hardware_ids, priority).await
+    }
+
+    /// 在一个事务中更新所选设备，结果按候选列表的顺序返回
+    ///
+    /// 所选设备中不需要更新的会被跳过。
+    pub async fn update_selected_drivers(&self, hardware_ids: &[String], priority: DownloadPriority) -> Result<Vec<InstallationResult>> {
         let mut results = Vec::new();
+        let operation = self.cancel.begin();
         
-        for candidate in &self.update_candidates {
-            if candidate.needs_update {
-                match self.update_single_driver(candidate).await {
-                    Ok(result) => results.push(result),
-                    Err(e) => {
-                        eprintln!("更新驱动失败: {}", e);
-                        // 创建失败结果
-                        results.push(InstallationResult {
-                            success: false,
-                            message: format!("更新失败: {}", e),
-                            driver_version: candidate.current_version.clone(),
-                            installed_at: chrono::Utc::now().to_rfc3339(),
-                        });
-                    }
+        let pending: Vec<&UpdateCandidate> = self.update_candidates.iter()
+            .filter(|c| c.needs_update && hardware_ids.iter().any(|id| id.eq_ignore_ascii_case(&c.hardware_info.hardware_id)))
+            .collect();
+        if pending.is_empty() {
+            return Ok(results);
+        }
+
+        let devices: Vec<HardwareDevice> = pending.iter()
+            .map(|c| self.resolve_device(&c.hardware_info.hardware_id))
+            .collect();
+        let hardware_ids: Vec<String> = pending.iter()
+            .map(|c| c.hardware_info.hardware_id.clone())
+            .collect();
+        let (mut transaction, rollback_point) = self.begin_install_batch(
+            &t!("transaction-batch-update"),
+            TransactionKind::Update,
+            &devices,
+            &hardware_ids,
+        ).await?;
+        
+        for candidate in pending {
+            // 取消后剩余的设备不再处理
+            if operation.token().is_cancelled() {
+                let mut result = failed_result(candidate.current_version.clone(), t!("core-update-cancelled"));
+                result.aborted = true;
+                results.push(result);
+                continue;
+            }
+            match self.update_candidate(candidate, priority, rollback_point.as_ref(), &mut transaction).await {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    eprintln!("{}", t!("core-update-failed", error = e));
+                    // 创建失败结果
+                    results.push(failed_result(candidate.current_version.clone(), t!("core-update-failed", error = e)));
                 }
             }
         }
         
+        transaction.finish_with(Ok(results))
+    }
+
+    /// 开始一批安装：写入事务日志，创建系统还原点和回滚点
+    ///
+    /// 还原点和回滚点创建失败只记录警告，事务日志写入失败则结束事务，不继续安装。
+    async fn begin_install_batch(&self, description: &str, kind: TransactionKind, devices: &[HardwareDevice], hardware_ids: &[String]) -> Result<(JournalTransaction, Option<RollbackPoint>)> {
+        let mut transaction = self.install_journal.begin(description, kind, hardware_ids)?;
+        self.publish_steps(&mut transaction);
+        match self.prepare_install_batch(&mut transaction, description, devices).await {
+            Ok(rollback_point) => Ok((transaction, rollback_point)),
+            Err(e) => transaction.finish_with(Err(e)),
+        }
+    }
+
+    // 创建系统还原点和回滚点，并写入事务日志
+    async fn prepare_install_batch(&self, transaction: &mut JournalTransaction, description: &str, devices: &[HardwareDevice]) -> Result<Option<RollbackPoint>> {
+        let _device_changes = self.device_changes.lock().await;
+        let restore_point_description = format!("{} - {}", t!("app-name"), description);
+        let create_restore_point = async {
+            self.create_system_restore_point(&restore_point_description).await
+                .map(|_| restore_point_description.clone())
+        };
+        if let Err(e) = journal_step(transaction, None, InstallStep::RestorePoint, create_restore_point).await {
+            eprintln!("{}", t!("core-restore-point-failed", error = e));
+        }
+
+        transaction.step_started(None, InstallStep::Backup)?;
+        let rollback_point = self.create_rollback_point_for_batch(description, devices).await;
+        match rollback_point {
+            Some(ref point) => {
+                transaction.record(JournalEvent::RollbackPointCreated { rollback_id: point.id.clone() })?;
+                transaction.step_completed(None, InstallStep::Backup, Some(point.id.clone()))?;
+            }
+            None => transaction.step_failed(None, InstallStep::Backup, &t!("core-no-rollback-point"))?,
+        }
+
+        Ok(rollback_point)
+    }
+
+    /// 把事务的步骤记录同时发布为安装事件
+    fn publish_steps(&self, transaction: &mut JournalTransaction) {
+        let events = self.events.clone();
+        transaction.set_step_listener(Box::new(move |hardware_id, step, status| {
+            events.publish(CoreEvent::InstallStep {
+                hardware_id: hardware_id.map(|id| id.to_string()),
+                step,
+                status: status.clone(),
+            });
+        }));
+    }
+
+    /// 安装前导出受影响设备的当前驱动并创建回滚点，失败时只记录警告
+    async fn create_rollback_point_for_batch(&self, description: &str, devices: &[HardwareDevice]) -> Option<RollbackPoint> {
+        let result = async {
+            let staging = crate::utils::get_temp_dir()?
+                .join(format!("rollback_{}", chrono::Local::now().format("%Y%m%d%H%M%S%f")));
+            let (package, hardware_ids) = self.export_current_drivers(&staging, description, devices).await?;
+            if package.manifest.entries.is_empty() {
+                let _ = std::fs::remove_dir_all(&staging);
+                return Ok::<_, anyhow::Error>(None);
+            }
+
+            let entries: Vec<RollbackEntry> = package.manifest.entries.iter()
+                .zip(hardware_ids)
+                .map(|(entry, hardware_id)| RollbackEntry {
+                    hardware_id,
+                    device_name: entry.device_name.clone(),
+                    driver_version: entry.driver_version.clone(),
+                    backup_entry_id: entry.id.clone(),
+                })
+                .collect();
+
+            let commit_result = self.rollback_manager.store().commit_package(&package);
+            let _ = std::fs::remove_dir_all(&staging);
+            let (snapshot, _) = commit_result?;
+            let point = self.rollback_manager.create_rollback_point(description, &snapshot.id, entries)?;
+            Ok(Some(point))
+        }.await;
+
+        match result {
+            Ok(Some(point)) => {
+                println!("{}", t!("core-rollback-point-created", id = point.id, count = point.affected_drivers.len()));
+                Some(point)
+            }
+            Ok(None) => {
+                eprintln!("{}", t!("core-nothing-exported"));
+                None
+            }
+            Err(e) => {
+                eprintln!("{}", t!("core-rollback-point-failed", error = e));
+                None
+            }
+        }
+    }
+
+    /// 导出设备的当前驱动到暂存目录，返回备份包及每个条目对应的硬件ID
+    async fn export_current_drivers(&self, staging: &std::path::Path, description: &str, devices: &[HardwareDevice]) -> Result<(BackupPackage, Vec<String>)> {
+        let mut package = BackupPackage::create(staging, description)?;
+        let mut hardware_ids = Vec::new();
+
+        for (index, device) in devices.iter().enumerate() {
+            let relative_dir = format!("{:03}_{}", index, sanitize_path_component(&device.device_name));
+            let export_dir = staging.join(&relative_dir);
+            std::fs::create_dir_all(&export_dir)?;
+
+            let hardware_id = primary_hardware_id(device);
+            if let Err(e) = self.driver_installer.backup_current_driver(&hardware_id, &export_dir.to_string_lossy()).await {
+                eprintln!("{}", t!("core-export-failed", device = device.device_name, error = e));
+                continue;
+            }
+            match package.add_entry(device, &device.driver_version, &relative_dir) {
+                Ok(_) => hardware_ids.push(device.hardware_id.clone()),
+                Err(e) => eprintln!("{}", t!("core-register-backup-failed", device = device.device_name, error = e)),
+            }
+        }
+
+        Ok((package, hardware_ids))
+    }
+
+    /// 按硬件ID查找已扫描的设备，找不到时只用硬件ID构造设备信息
+    fn resolve_device(&self, hardware_id: &str) -> HardwareDevice {
+        self.scan_result.as_ref()
+            .and_then(|scan_result| scan_result.devices.iter().find(|device| {
+                device.hardware_id.eq_ignore_ascii_case(hardware_id)
+                    || crate::installer::device_hardware_ids(device)
+                        .iter()
+                        .any(|id| id.eq_ignore_ascii_case(hardware_id))
+            }))
+            .cloned()
+            .unwrap_or_else(|| HardwareDevice {
+                device_id: hardware_id.to_string(),
+                device_name: hardware_id.to_string(),
+                hardware_id: hardware_id.to_string(),
+                driver_version: String::new(),
+                driver_date: String::new(),
+                manufacturer: String::new(),
+                device_class: String::new(),
+                ..Default::default()
+            })
+    }
+
+    /// 列出已保存的回滚点
+    pub fn list_rollback_points(&self) -> Result<Vec<RollbackPoint>> {
+        Ok(self.rollback_manager.list_rollback_points()?)
+    }
+
+    /// 回滚到指定回滚点，按与安装相反的顺序重新安装之前的驱动
+    pub async fn rollback(&self, rollback_id: &str) -> Result<RollbackReport> {
+        let point = self.rollback_manager.get_rollback_point(rollback_id)?
+            .ok_or_else(|| anyhow::anyhow!(t!("core-rollback-point-missing", id = rollback_id)))?;
+        let report = {
+            let _device_changes = self.device_changes.lock().await;
+            self.rollback_manager.perform_rollback(&point, &self.driver_installer).await?
+        };
+
+        for (hardware_id, result) in &report.results {
+            let entry = point.affected_drivers.iter().find(|entry| entry.hardware_id == *hardware_id);
+            let driver_info = InstallerDriverInfo {
+                file_path: String::new(),
+                file_name: entry.map(|entry| entry.device_name.clone()).unwrap_or_default(),
+                hardware_id: hardware_id.clone(),
+                manufacturer: String::new(),
+                driver_version: result.driver_version.clone(),
+            };
+            let status = if result.success { InstallationStatus::Success } else { InstallationStatus::Failed };
+            let message = t!("core-rollback-message", id = point.id, message = result.message);
+            if let Err(e) = self.installation_logger.log_installation(hardware_id, driver_info, InstallationAction::Rollback, status, message) {
+                eprintln!("{}", t!("core-log-write-failed", error = e));
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 查询安装历史
+    pub fn installation_history(&self, filter: &LogFilter) -> Result<Vec<InstallationLogEntry>> {
+        Ok(self.installation_logger.query(filter)?)
+    }
+
+    /// 撤销安装历史中的一次成功更改，从其回滚点重新安装该设备之前的驱动
+    pub async fn rollback_change(&self, log_id: &str) -> Result<InstallationResult> {
+        let history = self.installation_logger.query(&LogFilter::default())?;
+        let entry = history.iter()
+            .find(|entry| entry.id == log_id)
+            .ok_or_else(|| anyhow::anyhow!(t!("core-log-missing", id = log_id)))?;
+        if !entry.can_revert(&history) {
+            return Err(anyhow::anyhow!(t!("core-log-not-revertible", id = log_id)));
+        }
+        let rollback_id = entry.change.rollback_id.as_deref().unwrap_or_default();
+        let point = self.rollback_manager.get_rollback_point(rollback_id)?
+            .ok_or_else(|| anyhow::anyhow!(t!("core-rollback-point-missing", id = rollback_id)))?;
+        let device_hardware_id = self.resolve_device(&entry.hardware_id).hardware_id;
+        let hardware_id = point.affected_drivers.iter()
+            .find(|rollback| {
+                rollback.hardware_id.eq_ignore_ascii_case(&entry.hardware_id)
+                    || rollback.hardware_id.eq_ignore_ascii_case(&device_hardware_id)
+            })
+            .map(|rollback| rollback.hardware_id.clone())
+            .ok_or_else(|| anyhow::anyhow!(t!("core-rollback-point-no-device", id = point.id)))?;
+
+        let driver_info = InstallerDriverInfo {
+            file_path: String::new(),
+            driver_version: entry.change.previous_version.clone().unwrap_or_default(),
+            ..entry.driver_info.clone()
+        };
+        let change = ChangeDetails {
+            device_name: entry.change.device_name.clone(),
+            previous_version: Some(entry.driver_info.driver_version.clone()),
+            rollback_id: None,
+            reverts: Some(entry.id.clone()),
+        };
+        let started = std::time::Instant::now();
+        let log_id = self.installation_logger
+            .log_change_start(&entry.hardware_id, driver_info, InstallationAction::Rollback, change)
+            .map_err(|e| eprintln!("{}", t!("core-log-write-failed", error = e)))
+            .ok();
+
+        let result = {
+            let _device_changes = self.device_changes.lock().await;
+            self.rollback_manager.rollback_device(&point, &hardware_id, &self.driver_installer).await
+        };
+
+        if let Some(log_id) = log_id {
+            let (status, message) = match result {
+                Ok(ref result) if result.success => (InstallationStatus::Success, result.message.clone()),
+                Ok(ref result) => (InstallationStatus::Failed, result.message.clone()),
+                Err(ref e) => (InstallationStatus::Failed, e.to_string()),
+            };
+            let duration = u64::try_from(started.elapsed().as_millis()).ok();
+            if let Err(e) = self.installation_logger.log_installation_complete(&log_id, status, message, duration) {
+                eprintln!("{}", t!("core-log-write-failed", error = e));
+            }
+        }
+
+        let result = result?;
+        if result.success && result.reboot_required {
+            self.events.publish(CoreEvent::RebootRequired { hardware_id: entry.hardware_id.clone() });
+        }
+        Ok(result)
+    }
+
+    /// 打开备份包，校验清单哈希并与当前设备匹配
+    pub fn open_backup_package(&self, package_path: &str) -> Result<RestorePlan> {
+        let scan_result = self.scan_result.as_ref()
+            .ok_or_else(|| anyhow::anyhow!(t!("core-no-scan-for-restore")))?;
+
+        let package = BackupPackage::open(std::path::Path::new(package_path))?;
+        let candidates = package.rank_against(&scan_result.devices);
+
+        println!("{}", t!(
+            "core-restore-plan",
+            package = package_path,
+            total = candidates.len(),
+            restorable = candidates.iter().filter(|c| c.is_restorable()).count(),
+        ));
+
+        Ok(RestorePlan {
+            package_path: package_path.to_string(),
+            description: package.manifest.description.clone(),
+            created_at: package.manifest.created_at.clone(),
+            candidates,
+        })
+    }
+
+    /// 导出已扫描设备的当前驱动，写入去重备份存储
+    pub async fn backup_drivers(&self, description: &str) -> Result<SnapshotInfo> {
+        let scan_result = self.scan_result.as_ref()
+            .ok_or_else(|| anyhow::anyhow!(t!("core-no-scan-for-backup")))?;
+
+        let store = BackupStore::open_default()?;
+        let staging = crate::utils::get_temp_dir()?
+            .join(format!("backup_{}", chrono::Local::now().format("%Y%m%d%H%M%S")));
+        let (package, _) = self.export_current_drivers(&staging, description, &scan_result.devices).await?;
+
+        let commit_result = store.commit_package(&package);
+        let _ = std::fs::remove_dir_all(&staging);
+        let (snapshot, stats) = commit_result?;
+
+        println!("{}", t!(
+            "core-backup-finished",
+            drivers = snapshot.driver_count,
+            new_files = stats.new_blobs,
+            size = crate::i18n::format_size(stats.bytes_written),
+            reused = stats.reused_blobs,
+        ));
+        Ok(snapshot)
+    }
+
+    /// 列出备份存储中的快照
+    pub fn list_backup_snapshots(&self) -> Result<Vec<SnapshotInfo>> {
+        BackupStore::open_default()?.list_snapshots()
+    }
+
+    /// 从备份存储导出快照并生成恢复计划
+    pub fn open_backup_snapshot(&self, snapshot_id: &str) -> Result<RestorePlan> {
+        let store = BackupStore::open_default()?;
+        let dest = crate::utils::get_temp_dir()?.join("restore").join(snapshot_id);
+        if dest.exists() {
+            std::fs::remove_dir_all(&dest)?;
+        }
+        store.export_snapshot(snapshot_id, &dest)?;
+        self.open_backup_package(&dest.to_string_lossy())
+    }
+
+    /// 从备份包恢复用户选择的驱动
+    pub async fn restore_drivers(&self, package_path: &str, entry_ids: &[String]) -> Result<Vec<InstallationResult>> {
+        // 重新打开并校验，避免使用过期的恢复计划
+        let plan = self.open_backup_package(package_path)?;
+
+        let selected: Vec<&RestoreCandidate> = plan.candidates.iter()
+            .filter(|c| entry_ids.contains(&c.entry.id))
+            .collect();
+        if selected.len() != entry_ids.len() {
+            return Err(anyhow::anyhow!(t!("core-entry-not-in-package")));
+        }
+
+        let devices: Vec<HardwareDevice> = selected.iter()
+            .filter(|c| c.is_restorable())
+            .filter_map(|c| c.device.clone())
+            .collect();
+        if devices.is_empty() {
+            return Ok(selected.iter().map(|c| restore_rejection(c)).collect());
+        }
+
+        let hardware_ids: Vec<String> = devices.iter().map(primary_hardware_id).collect();
+        let (mut transaction, rollback_point) = self.begin_install_batch(
+            &t!("transaction-restore"),
+            TransactionKind::Restore {
+                package_path: package_path.to_string(),
+                entry_ids: entry_ids.to_vec(),
+            },
+            &devices,
+            &hardware_ids,
+        ).await?;
+
+        let results = self.restore_candidates(package_path, &selected, None, rollback_point.as_ref(), &mut transaction).await;
+        transaction.finish_with(results)
+    }
+
+    // 逐个恢复备份驱动；only 不为空时只恢复其中列出的硬件ID
+    async fn restore_candidates(&self, package_path: &str, selected: &[&RestoreCandidate], only: Option<&[String]>, rollback_point: Option<&RollbackPoint>, transaction: &mut JournalTransaction) -> Result<Vec<InstallationResult>> {
+        let package = BackupPackage::open(std::path::Path::new(package_path))?;
+        let mut results = Vec::new();
+        let operation = self.cancel.begin();
+
+        for candidate in selected {
+            let entry = &candidate.entry;
+            if operation.token().is_cancelled() {
+                let mut result = failed_result(entry.driver_version.clone(), t!("core-restore-cancelled", device = entry.device_name));
+                result.aborted = true;
+                results.push(result);
+                continue;
+            }
+            let device = match (&candidate.device, candidate.verification.valid) {
+                (Some(device), true) => device,
+                _ => {
+                    results.push(restore_rejection(candidate));
+                    continue;
+                }
+            };
+            let hardware_id = primary_hardware_id(device);
+            if only.is_some_and(|ids| !ids.contains(&hardware_id)) {
+                continue;
+            }
+
+            transaction.step_completed(Some(&hardware_id), InstallStep::Verify, Some(t!("core-backup-entry", id = entry.id)))?;
+            let inf_path = package.inf_path(entry);
+            let installer_info = InstallerDriverInfo {
+                file_path: inf_path.to_string_lossy().to_string(),
+                file_name: inf_path.file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or("backup.inf")
+                    .to_string(),
+                hardware_id,
+                manufacturer: entry.manufacturer.clone(),
+                driver_version: entry.driver_version.clone(),
+            };
+
+            println!("{}", t!("core-restoring", device = device.device_name, version = entry.driver_version));
+            match self.install_with_rollback(&installer_info, InstallationAction::Restore, rollback_point, transaction).await {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    eprintln!("{}", t!("core-restore-failed", error = e));
+                    results.push(failed_result(entry.driver_version.clone(), t!("core-restore-failed", error = e)));
+                }
+            }
+        }
+
         Ok(results)
     }
 
+    /// 查找上次运行中没有完成的安装事务
+    pub fn incomplete_install_transactions(&self) -> Result<Vec<TransactionSummary>> {
+        self.install_journal.incomplete_transactions()
+    }
+
+    /// 继续未完成的安装事务，只处理还没有完成的设备
+    pub async fn resume_install_transaction(&mut self, transaction_id: &str) -> Result<Vec<InstallationResult>> {
+        let _operation = self.cancel.begin();
+        let summary = self.install_journal.load(transaction_id)?;
+        let pending: Vec<String> = summary.pending_devices().iter()
+            .map(|device| device.hardware_id.clone())
+            .collect();
+        let rollback_point = match summary.rollback_id {
+            Some(ref rollback_id) => self.rollback_manager.get_rollback_point(rollback_id)?,
+            None => None,
+        };
+
+        // 恢复计划和更新候选都依赖扫描结果
+        if self.scan_result.is_none() {
+            self.scan_system(|_| ()).await?;
+        }
+        if summary.kind == TransactionKind::Update && self.update_candidates.is_empty() {
+            self.find_driver_updates().await?;
+        }
+
+        let mut transaction = self.install_journal.reopen(transaction_id)?;
+        self.publish_steps(&mut transaction);
+        let mut results = Vec::new();
+        match summary.kind {
+            TransactionKind::Update => {
+                for hardware_id in &pending {
+                    let candidate = self.update_candidates.iter()
+                        .find(|c| c.hardware_info.hardware_id == *hardware_id);
+                    let result = match candidate {
+                        Some(candidate) => self.update_candidate(candidate, DownloadPriority::User, rollback_point.as_ref(), &mut transaction).await,
+                        None => {
+                            transaction.step_failed(Some(hardware_id), InstallStep::Download, &t!("core-no-matching-driver"))?;
+                            Err(anyhow::anyhow!(t!("core-no-matching-driver-for", hardware_id = hardware_id)))
+                        }
+                    };
+                    results.push(result.unwrap_or_else(|e| failed_result(String::new(), t!("core-update-failed", error = e))));
+                }
+            }
+            TransactionKind::Install { ref driver_path } => {
+                for hardware_id in &pending {
+                    let installer_info = installer_info_for(driver_path, hardware_id);
+                    results.push(self.install_with_rollback(&installer_info, InstallationAction::Install, rollback_point.as_ref(), &mut transaction).await?);
+                }
+            }
+            TransactionKind::Restore { ref package_path, ref entry_ids } => {
+                let plan = self.open_backup_package(package_path)?;
+                let selected: Vec<&RestoreCandidate> = plan.candidates.iter()
+                    .filter(|c| entry_ids.contains(&c.entry.id))
+                    .collect();
+                results = self.restore_candidates(package_path, &selected, Some(&pending), rollback_point.as_ref(), &mut transaction).await?;
+            }
+        }
+
+        transaction.finish(TransactionOutcome::Committed)?;
+        Ok(results)
+    }
+
+    /// 用事务开始前创建的回滚点撤销未完成的安装事务
+    pub async fn rollback_install_transaction(&self, transaction_id: &str) -> Result<RollbackReport> {
+        let summary = self.install_journal.load(transaction_id)?;
+        let rollback_id = summary.rollback_id
+            .ok_or_else(|| anyhow::anyhow!(t!("core-transaction-no-rollback", id = transaction_id)))?;
+
+        let transaction = self.install_journal.reopen(transaction_id)?;
+        let report = self.rollback(&rollback_id).await?;
+        if report.is_success() {
+            transaction.finish(TransactionOutcome::RolledBack)?;
+        }
+        Ok(report)
+    }
+
+    /// 放弃未完成的安装事务，不再提示恢复
+    pub fn discard_install_transaction(&self, transaction_id: &str) -> Result<()> {
+        self.install_journal.reopen(transaction_id)?.finish(TransactionOutcome::Abandoned)
+    }
+
     pub async fn create_system_restore_point(&self, description: &str) -> Result<()> {
         self.driver_installer.create_system_restore_point(description).await
     }
@@ -276,28 +1089,25 @@ impl DriverUpdaterCore {
 
     pub async fn cleanup(&self) -> Result<()> {
         // 停止Aria2 RPC服务器
-        {
-            let fetcher = self.driver_fetcher.lock().await;
-            fetcher.stop_aria2_rpc().await?;
-        }
+        self.driver_fetcher.stop_aria2_rpc().await?;
         
         Ok(())
     }
 
     pub fn get_system_summary(&self) -> Option<String> {
         if let Some(ref sys_info) = self.system_info {
-            Some(format!(
-                "Windows版本: {}\n版本: {}\n内部版本号: {}\n激活状态: {}\nDirectX: {}\n制造商: {}\n型号: {}\nCPU: {}\n内存: {}\n显卡: {}",
-                sys_info.windows_edition,
-                get_windows_version_code(&sys_info.windows_edition),
-                sys_info.windows_version,
-                sys_info.windows_activation_status,
-                sys_info.directx_version,
-                sys_info.manufacturer,
-                sys_info.model,
-                sys_info.cpu,
-                sys_info.memory_info,
-                sys_info.gpu
+            Some(t!(
+                "system-summary",
+                edition = sys_info.windows_edition,
+                version = get_windows_version_code(&sys_info.windows_edition),
+                build = sys_info.windows_version,
+                activation = sys_info.windows_activation_status,
+                directx = sys_info.directx_version,
+                manufacturer = sys_info.manufacturer,
+                model = sys_info.model,
+                cpu = sys_info.cpu,
+                memory = sys_info.memory_info,
+                gpu = sys_info.gpu,
             ))
         } else {
             None
@@ -306,7 +1116,7 @@ impl DriverUpdaterCore {
 
     pub fn get_hardware_summary(&self) -> Option<String> {
         if let Some(ref scan_result) = self.scan_result {
-            Some(format!("扫描到 {} 个硬件设备", scan_result.devices.len()))
+            Some(t!("hardware-summary", count = scan_result.devices.len()))
         } else {
             None
         }
@@ -318,10 +1128,127 @@ impl DriverUpdaterCore {
             .filter(|c| c.needs_update)
             .count();
             
-        format!("总共 {} 个设备，其中 {} 个需要更新", total, need_update)
+        t!("update-summary", total = total, outdated = need_update)
+    }
+}
+
+// 队列中的下载任务转为核心发布的下载进度
+fn download_progress(task: &DownloadTask) -> DownloadProgress {
+    let status = match task.status {
+        DownloadStatus::Queued | DownloadStatus::Waiting | DownloadStatus::Paused => "pending",
+        DownloadStatus::Downloading => "downloading",
+        DownloadStatus::Completed => "completed",
+        DownloadStatus::Failed => "failed",
+        DownloadStatus::Cancelled => "cancelled",
+    };
+    DownloadProgress {
+        file_name: task.driver_info.file_name(),
+        total_size: task.total,
+        downloaded_size: task.downloaded,
+        progress: task.progress as f32,
+        status: status.to_string(),
+    }
+}
+
+fn installer_info_for(driver_path: &str, hardware_id: &str) -> InstallerDriverInfo {
+    InstallerDriverInfo {
+        file_path: driver_path.to_string(),
+        file_name: std::path::Path::new(driver_path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("driver")
+            .to_string(),
+        hardware_id: hardware_id.to_string(),
+        manufacturer: "Unknown".to_string(),
+        driver_version: "1.0.0".to_string(), // 从驱动文件中获取更准确的版本
+    }
+}
+
+/// 记录一个步骤：开始记录刷盘后才执行操作，再记录完成或失败
+async fn journal_step<T, F>(transaction: &mut JournalTransaction, hardware_id: Option<&str>, step: InstallStep, operation: F) -> Result<T>
+where
+    F: std::future::Future<Output = Result<T>>,
+    T: std::fmt::Display,
+{
+    transaction.step_started(hardware_id, step)?;
+    match operation.await {
+        Ok(value) => {
+            transaction.step_completed(hardware_id, step, Some(value.to_string()))?;
+            Ok(value)
+        }
+        Err(e) => {
+            transaction.step_failed(hardware_id, step, &e.to_string())?;
+            Err(e)
+        }
     }
 }
 
+// 校验下载的驱动文件大小和SHA-256（匹配结果中提供时）
+async fn verify_downloaded_driver(driver_path: &str, driver_info: &crate::matcher::DriverInfo) -> Result<String> {
+    let path = std::path::Path::new(driver_path);
+    let size = std::fs::metadata(path)?.len();
+    if size == 0 {
+        return Err(anyhow::anyhow!(t!("verify-empty-file", path = driver_path)));
+    }
+    if driver_info.file_size > 0 && driver_info.file_size != size {
+        return Err(anyhow::anyhow!(t!("verify-size-mismatch", expected = driver_info.file_size, actual = size)));
+    }
+    if driver_info.checksum.len() == 64 && driver_info.checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+        let actual = crate::installer::sha256_file(path)?;
+        if !actual.eq_ignore_ascii_case(&driver_info.checksum) {
+            return Err(anyhow::anyhow!(t!("verify-checksum-mismatch", path = driver_path)));
+        }
+        return Ok(t!("verify-passed-checksum", size = size, checksum = actual));
+    }
+    Ok(t!("size-bytes", count = size))
+}
+
+// 安装和导出驱动时使用的硬件ID
+fn primary_hardware_id(device: &HardwareDevice) -> String {
+    crate::installer::device_hardware_ids(device)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| device.hardware_id.clone())
+}
+
+fn restore_rejection(candidate: &RestoreCandidate) -> InstallationResult {
+    let entry = &candidate.entry;
+    if candidate.device.is_none() {
+        failed_result(entry.driver_version.clone(), t!("core-device-missing", device = entry.device_name))
+    } else {
+        failed_result(
+            entry.driver_version.clone(),
+            t!("core-backup-verify-failed", device = entry.device_name, problems = candidate.verification.problems.join("; ")),
+        )
+    }
+}
+
+/// 有匹配的驱动、没有被偏好屏蔽，且版本比当前新（无法比较时视为更新）
+/// 未开启测试版渠道时不提示 Beta 驱动
+fn needs_update(preferences: &UpdatePreferences, hardware_id: &str, current_version: &str, driver: &crate::matcher::DriverInfo) -> bool {
+    (preferences.include_beta || driver.channel() == DriverChannel::Stable)
+        && !preferences.suppresses(hardware_id, &driver.driver_version)
+        && compare_driver_versions(&driver.driver_version, current_version).is_none_or(|ordering| ordering.is_gt())
+}
+
+fn failed_result(driver_version: String, message: String) -> InstallationResult {
+    InstallationResult {
+        success: false,
+        message,
+        driver_version,
+        installed_at: chrono::Utc::now().to_rfc3339(),
+        reboot_required: false,
+        aborted: false,
+    }
+}
+
+// 将硬件ID转换为可用作目录名的字符串
+fn sanitize_path_component(value: &str) -> String {
+    value.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect()
+}
+
 // 根据Windows版本名称推断版本代号（如21H1, 21H2等）
 fn get_windows_version_code(windows_edition: &str) -> String {
     // 根据Windows版本名称判断版本代号
@@ -371,4 +1298,53 @@ fn extract_version_code_from_string(windows_edition: &str) -> String {
     }
     
     "未知".to_string()
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+    use crate::config::PeerCacheConfig;
+    use crate::driver::fetcher::{PackageStore, PeerCacheServer};
+    use crate::utils::test_support::TempDir;
+
+    #[tokio::test]
+    async fn test_update_download_served_from_peer() {
+        let root = TempDir::new("core_peer_cache");
+        let content = b"driver package".repeat(1000);
+        let hash = format!("{:x}", Sha256::digest(&content));
+        let source = root.join("driver.exe");
+        std::fs::write(&source, &content).unwrap();
+
+        // 局域网中已有该驱动包的计算机
+        let serving = PackageStore::open(&root.join("serving")).unwrap();
+        serving.insert(&source, &hash).unwrap();
+        let server = PeerCacheServer::start(serving, "127.0.0.1:0").unwrap();
+
+        let mut config = ConfigManager::new();
+        config.download_config.download_dir = root.join("downloads").display().to_string();
+        config.download_config.peer_cache = PeerCacheConfig {
+            enabled: true,
+            peers: vec![format!("http://{}", server.local_addr())],
+            ..PeerCacheConfig::default()
+        };
+        let core = DriverUpdaterCore::with_data_dir("", &config, &root.join("data")).await.unwrap();
+
+        // 厂商服务器不可用时仍然从其他计算机取得驱动包
+        let driver = crate::matcher::DriverInfo {
+            driver_id: "nvidia-1".to_string(),
+            hardware_id: "PCI\\VEN_10DE".to_string(),
+            driver_name: "NVIDIA Driver".to_string(),
+            driver_version: "1.0".to_string(),
+            driver_url: "http://127.0.0.1:9/driver.exe".to_string(),
+            manufacturer: "NVIDIA".to_string(),
+            release_date: "2024-01-01".to_string(),
+            file_size: content.len() as u64,
+            checksum: hash,
+            release_notes_url: None,
+        };
+        let path = core.download_driver(&driver, DownloadPriority::User, |_| ()).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), content);
+
+        server.stop();
+    }
+}
@@ -1,20 +1,29 @@
 //! 事件循环处理模块
 
 use std::sync::Arc;
+use serde::{Deserialize, Serialize};
 use tokio::sync::{mpsc, RwLock};
 use crate::core::state::{AppState, StateEvent, StateEventHandler};
-use crate::utils::error::Result;
+use crate::core::{DriverUpdaterCore, EventFilter};
+use crate::driver::fetcher::DownloadPriority;
+use crate::notify::NotificationDispatcher;
+use crate::utils::{HamsterError, Result};
+use crate::t;
 
 /// 应用程序命令
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AppCommand {
     /// 扫描硬件
     ScanHardware,
     /// 检查驱动更新
     CheckUpdates,
-    /// 下载驱动
+    /// 下载所有需要更新的驱动（参数为下载队列中的优先级）
+    DownloadUpdates(DownloadPriority),
+    /// 下载并安装所有需要更新的驱动（参数为下载队列中的优先级）
+    InstallUpdates(DownloadPriority),
+    /// 下载驱动（参数为硬件ID）
     DownloadDriver(String),
-    /// 安装驱动
+    /// 安装驱动（参数为硬件ID）
     InstallDriver(String),
     /// 备份驱动
     BackupDrivers,
@@ -22,6 +31,8 @@ pub enum AppCommand {
     RestoreDrivers(String),
     /// 刷新系统信息
     RefreshSystemInfo,
+    /// 取消正在执行的操作，需要由持有 CancelHandle 的一方立即处理
+    Cancel,
     /// 关闭应用
     Shutdown,
 }
@@ -34,6 +45,8 @@ pub struct EventLoop {
     event_tx: mpsc::Sender<StateEvent>,
     /// 应用状态
     state: Arc<RwLock<AppState>>,
+    /// 执行命令的核心控制器，更新、安装等只读取核心的命令持有读锁
+    core: Arc<RwLock<DriverUpdaterCore>>,
     /// 是否正在运行
     running: bool,
 }
@@ -44,11 +57,13 @@ impl EventLoop {
         command_rx: mpsc::Receiver<AppCommand>,
         event_tx: mpsc::Sender<StateEvent>,
         state: Arc<RwLock<AppState>>,
+        core: Arc<RwLock<DriverUpdaterCore>>,
     ) -> Self {
         Self {
             command_rx,
             event_tx,
             state,
+            core,
             running: false,
         }
     }
@@ -58,11 +73,28 @@ impl EventLoop {
         self.running = true;
         tracing::info!("事件循环已启动");
 
+        // 核心在执行命令期间发布的事件实时转发给状态订阅者
+        let mut subscription = self.core.read().await.subscribe(EventFilter::all());
+        let event_tx = self.event_tx.clone();
+        let forwarder = tokio::spawn(async move {
+            while let Some(event) = subscription.recv().await {
+                if event_tx.send(StateEvent::Core(Box::new(event))).await.is_err() {
+                    break;
+                }
+            }
+        });
+
         while self.running {
             tokio::select! {
                 Some(command) = self.command_rx.recv() => {
-                    if let Err(e) = self.handle_command(command).await {
-                        tracing::error!("处理命令时出错: {}", e);
+                    match self.handle_command(command.clone()).await {
+                        Ok(()) => {
+                            let _ = self.event_tx.send(StateEvent::CommandCompleted(command)).await;
+                        }
+                        Err(e) => {
+                            tracing::error!("处理命令时出错: {}", e);
+                            let _ = self.event_tx.send(StateEvent::CommandFailed(e.to_string())).await;
+                        }
                     }
                 }
                 else => {
@@ -72,6 +104,7 @@ impl EventLoop {
             }
         }
 
+        forwarder.abort();
         tracing::info!("事件循环已停止");
         Ok(())
     }
@@ -85,8 +118,14 @@ impl EventLoop {
             AppCommand::CheckUpdates => {
                 self.handle_check_updates().await?;
             }
+            AppCommand::DownloadUpdates(priority) => {
+                self.handle_download_updates(priority).await?;
+            }
+            AppCommand::InstallUpdates(priority) => {
+                self.handle_install_updates(priority).await?;
+            }
             AppCommand::DownloadDriver(driver_id) => {
-                self.handle_download_driver(&driver_id).await?;
+                self.handle_download_driver(&driver_id, DownloadPriority::User).await?;
             }
             AppCommand::InstallDriver(driver_id) => {
                 self.handle_install_driver(&driver_id).await?;
@@ -100,6 +139,10 @@ impl EventLoop {
             AppCommand::RefreshSystemInfo => {
                 self.handle_refresh_system_info().await?;
             }
+            AppCommand::Cancel => {
+                // 排到这里时之前的操作已经结束，没有需要取消的操作
+                tracing::info!("没有正在执行的操作");
+            }
             AppCommand::Shutdown => {
                 self.running = false;
             }
@@ -111,59 +154,101 @@ impl EventLoop {
     async fn handle_scan_hardware(&self) -> Result<()> {
         let _ = self.event_tx.send(StateEvent::ScanStarted).await;
 
-        // 执行扫描
-        // 实际扫描逻辑将在 hardware 模块中实现
-        let devices = Vec::new();
+        let mut core = self.core.write().await;
+        let scan_result = core.scan_system(|_| ()).await
+            .map_err(|e| HamsterError::ScanError(e.to_string()))?;
 
-        let _ = self.event_tx.send(StateEvent::ScanCompleted(devices)).await;
+        let _ = self.event_tx.send(StateEvent::ScanCompleted(scan_result.devices)).await;
         Ok(())
     }
 
-    /// 处理更新检查
+    /// 处理更新检查，尚未扫描时先扫描硬件
     async fn handle_check_updates(&self) -> Result<()> {
         let _ = self.event_tx.send(StateEvent::UpdateCheckStarted).await;
 
-        // 执行更新检查
-        // 实际检查逻辑将在 driver 模块中实现
-        let drivers = Vec::new();
+        let mut core = self.core.write().await;
+        if core.scan_result.is_none() {
+            core.scan_system(|_| ()).await
+                .map_err(|e| HamsterError::ScanError(e.to_string()))?;
+        }
+        let drivers = core.find_driver_updates().await
+            .map_err(|e| HamsterError::UpdateError(e.to_string()))?;
 
         let _ = self.event_tx.send(StateEvent::UpdateCheckCompleted(drivers)).await;
         Ok(())
     }
 
-    /// 处理驱动下载
-    async fn handle_download_driver(&self, driver_id: &str) -> Result<()> {
-        let _ = self.event_tx.send(StateEvent::DownloadStarted(driver_id.to_string())).await;
-
-        // 模拟下载进度
-        for i in 0..=10 {
-            let progress = i as f32 / 10.0;
-            let _ = self.event_tx.send(StateEvent::DownloadProgress(
-                driver_id.to_string(),
-                progress,
-            )).await;
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    /// 下载所有需要更新的驱动
+    async fn handle_download_updates(&self, priority: DownloadPriority) -> Result<()> {
+        let hardware_ids: Vec<String> = self.core.read().await.update_candidates.iter()
+            .filter(|candidate| candidate.needs_update)
+            .map(|candidate| candidate.hardware_info.hardware_id.clone())
+            .collect();
+
+        for hardware_id in hardware_ids {
+            self.handle_download_driver(&hardware_id, priority).await?;
+        }
+        Ok(())
+    }
+
+    /// 下载并安装所有需要更新的驱动
+    async fn handle_install_updates(&self, priority: DownloadPriority) -> Result<()> {
+        let core = self.core.read().await;
+        let hardware_ids: Vec<String> = core.update_candidates.iter()
+            .filter(|candidate| candidate.needs_update)
+            .map(|candidate| candidate.hardware_info.hardware_id.clone())
+            .collect();
+        for hardware_id in &hardware_ids {
+            let _ = self.event_tx.send(StateEvent::InstallStarted(hardware_id.clone())).await;
+        }
+
+        let results = core.update_all_drivers(priority).await
+            .map_err(|e| HamsterError::UpdateError(e.to_string()))?;
+
+        for (hardware_id, result) in hardware_ids.iter().zip(&results) {
+            if !result.success {
+                tracing::warn!("驱动更新失败 {}: {}", hardware_id, result.message);
+            }
+            let _ = self.event_tx.send(StateEvent::InstallCompleted(hardware_id.clone())).await;
         }
+        Ok(())
+    }
+
+    /// 处理驱动下载
+    async fn handle_download_driver(&self, hardware_id: &str, priority: DownloadPriority) -> Result<()> {
+        let core = self.core.read().await;
+        let driver = core.update_candidates.iter()
+            .find(|candidate| candidate.hardware_info.hardware_id == hardware_id)
+            .and_then(|candidate| candidate.matched_driver.clone())
+            .ok_or_else(|| HamsterError::DownloadError(t!("core-no-matching-driver-for", hardware_id = hardware_id)))?;
+
+        let _ = self.event_tx.send(StateEvent::DownloadStarted(hardware_id.to_string())).await;
 
-        let _ = self.event_tx.send(StateEvent::DownloadCompleted(driver_id.to_string())).await;
+        // 下载进度由核心事件转发
+        core.download_driver(&driver, priority, |_| ()).await
+            .map_err(|e| HamsterError::DownloadError(e.to_string()))?;
+
+        let _ = self.event_tx.send(StateEvent::DownloadCompleted(hardware_id.to_string())).await;
         Ok(())
     }
 
     /// 处理驱动安装
-    async fn handle_install_driver(&self, driver_id: &str) -> Result<()> {
-        let _ = self.event_tx.send(StateEvent::InstallStarted(driver_id.to_string())).await;
-
-        // 模拟安装进度
-        for i in 0..=10 {
-            let progress = i as f32 / 10.0;
-            let _ = self.event_tx.send(StateEvent::InstallProgress(
-                driver_id.to_string(),
-                progress,
-            )).await;
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    async fn handle_install_driver(&self, hardware_id: &str) -> Result<()> {
+        let core = self.core.read().await;
+        let candidate = core.update_candidates.iter()
+            .find(|candidate| candidate.hardware_info.hardware_id == hardware_id)
+            .cloned()
+            .ok_or_else(|| HamsterError::InstallError(t!("core-no-update-for", hardware_id = hardware_id)))?;
+
+        let _ = self.event_tx.send(StateEvent::InstallStarted(hardware_id.to_string())).await;
+
+        let result = core.update_single_driver(&candidate).await
+            .map_err(|e| HamsterError::InstallError(e.to_string()))?;
+        if !result.success {
+            return Err(HamsterError::InstallError(result.message));
         }
 
-        let _ = self.event_tx.send(StateEvent::InstallCompleted(driver_id.to_string())).await;
+        let _ = self.event_tx.send(StateEvent::InstallCompleted(hardware_id.to_string())).await;
         Ok(())
     }
 
@@ -171,20 +256,38 @@ impl EventLoop {
     async fn handle_backup_drivers(&self) -> Result<()> {
         let _ = self.event_tx.send(StateEvent::BackupStarted).await;
 
-        // 执行备份
-        // 实际备份逻辑将在 driver 模块中实现
-        let backup_path = "backup_path".to_string();
+        let mut core = self.core.write().await;
+        if core.scan_result.is_none() {
+            core.scan_system(|_| ()).await
+                .map_err(|e| HamsterError::ScanError(e.to_string()))?;
+        }
+        let snapshot = core.backup_drivers(&t!("backup-automatic")).await
+            .map_err(|e| HamsterError::BackupError(e.to_string()))?;
 
-        let _ = self.event_tx.send(StateEvent::BackupCompleted(backup_path)).await;
+        let _ = self.event_tx.send(StateEvent::BackupCompleted(snapshot.id)).await;
         Ok(())
     }
 
-    /// 处理驱动恢复
-    async fn handle_restore_drivers(&self, _path: &str) -> Result<()> {
+    /// 处理驱动恢复，恢复备份包中所有可恢复的驱动
+    async fn handle_restore_drivers(&self, path: &str) -> Result<()> {
         let _ = self.event_tx.send(StateEvent::RestoreStarted).await;
 
-        // 执行恢复
-        // 实际恢复逻辑将在 driver 模块中实现
+        let mut core = self.core.write().await;
+        if core.scan_result.is_none() {
+            core.scan_system(|_| ()).await
+                .map_err(|e| HamsterError::ScanError(e.to_string()))?;
+        }
+        let plan = core.open_backup_package(path)
+            .map_err(|e| HamsterError::RestoreError(e.to_string()))?;
+        let entry_ids: Vec<String> = plan.candidates.iter()
+            .filter(|candidate| candidate.is_restorable())
+            .map(|candidate| candidate.entry.id.clone())
+            .collect();
+        let results = core.restore_drivers(path, &entry_ids).await
+            .map_err(|e| HamsterError::RestoreError(e.to_string()))?;
+        for result in results.iter().filter(|result| !result.success) {
+            tracing::warn!("驱动恢复失败: {}", result.message);
+        }
 
         let _ = self.event_tx.send(StateEvent::RestoreCompleted).await;
         Ok(())
@@ -193,10 +296,10 @@ impl EventLoop {
     /// 处理刷新系统信息
     async fn handle_refresh_system_info(&self) -> Result<()> {
         // 刷新系统信息
-        let os_info = crate::utils::system_utils::get_os_info()?;
+        let os_info = crate::utils::get_os_info()?;
         
         let mut state = self.state.write().await;
-        state.system_summary.os = os_info;
+        state.system_summary.os = Some(os_info);
         
         Ok(())
     }
@@ -219,13 +322,16 @@ pub fn create_channels() -> (
     (cmd_tx, cmd_rx, evt_tx, evt_rx)
 }
 
-/// 事件处理任务
+/// 事件处理任务，提供了通知分发器时同时发送通知
 pub async fn event_handler_task(
     mut event_rx: mpsc::Receiver<StateEvent>,
     state: Arc<RwLock<AppState>>,
+    notifications: Option<Arc<NotificationDispatcher>>,
 ) {
     while let Some(event) = event_rx.recv().await {
-        let mut state = state.write().await;
-        state.handle_event(event);
+        state.write().await.handle_event(event.clone());
+        if let Some(ref notifications) = notifications {
+            notifications.handle_event(&event).await;
+        }
     }
 }
@@ -0,0 +1,195 @@
+//! 核心事件流
+//!
+//! 扫描、匹配、下载和安装等耗时操作都通过 EventBus 发布进度，
+//! 界面、代理和命令行按需订阅，并可以按事件类别和硬件ID过滤。
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use crate::core::ScanProgress;
+use crate::fetcher::DownloadProgress;
+use crate::hardware::HardwareDevice;
+use crate::installer::{InstallStep, StepStatus};
+
+// 订阅者跟不上时最多缓存的事件数
+const EVENT_CAPACITY: usize = 1024;
+
+/// 事件类别，用于过滤订阅
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreEventKind {
+    Scan,
+    Match,
+    Download,
+    Install,
+    Reboot,
+    Error,
+}
+
+/// 核心操作发布的事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CoreEvent {
+    ScanStarted,
+    ScanProgress { progress: ScanProgress },
+    DeviceFound { device: Box<HardwareDevice> },
+    ScanCompleted { device_count: usize },
+    MatchFound { hardware_id: String, driver: crate::matcher::DriverInfo },
+    DownloadProgress { hardware_id: String, progress: DownloadProgress },
+    InstallStep { hardware_id: Option<String>, step: InstallStep, status: StepStatus },
+    RebootRequired { hardware_id: String },
+    /// 操作失败，operation 为操作名称
+    Error { operation: String, message: String },
+}
+
+impl CoreEvent {
+    pub fn kind(&self) -> CoreEventKind {
+        match self {
+            CoreEvent::ScanStarted
+            | CoreEvent::ScanProgress { .. }
+            | CoreEvent::DeviceFound { .. }
+            | CoreEvent::ScanCompleted { .. } => CoreEventKind::Scan,
+            CoreEvent::MatchFound { .. } => CoreEventKind::Match,
+            CoreEvent::DownloadProgress { .. } => CoreEventKind::Download,
+            CoreEvent::InstallStep { .. } => CoreEventKind::Install,
+            CoreEvent::RebootRequired { .. } => CoreEventKind::Reboot,
+            CoreEvent::Error { .. } => CoreEventKind::Error,
+        }
+    }
+
+    /// 事件关联的设备
+    pub fn hardware_id(&self) -> Option<&str> {
+        match self {
+            CoreEvent::DeviceFound { device } => Some(&device.hardware_id),
+            CoreEvent::MatchFound { hardware_id, .. }
+            | CoreEvent::DownloadProgress { hardware_id, .. }
+            | CoreEvent::RebootRequired { hardware_id } => Some(hardware_id),
+            CoreEvent::InstallStep { hardware_id, .. } => hardware_id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// 订阅过滤条件，为空的条件不限制
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub kinds: Vec<CoreEventKind>,
+    pub hardware_id: Option<String>,
+}
+
+impl EventFilter {
+    /// 接收所有事件
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn kinds(kinds: &[CoreEventKind]) -> Self {
+        Self { kinds: kinds.to_vec(), ..Self::default() }
+    }
+
+    /// 只接收与该设备相关的事件
+    pub fn with_hardware_id(mut self, hardware_id: impl Into<String>) -> Self {
+        self.hardware_id = Some(hardware_id.into());
+        self
+    }
+
+    pub fn matches(&self, event: &CoreEvent) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&event.kind()))
+            && self.hardware_id.as_deref().is_none_or(|wanted| {
+                event.hardware_id().is_some_and(|id| id.eq_ignore_ascii_case(wanted))
+            })
+    }
+}
+
+/// 事件总线，克隆后共享同一个通道
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<CoreEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CAPACITY);
+        Self { sender }
+    }
+
+    /// 发布事件，没有订阅者时直接丢弃
+    pub fn publish(&self, event: CoreEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self, filter: EventFilter) -> EventSubscription {
+        EventSubscription { receiver: self.sender.subscribe(), filter }
+    }
+}
+
+/// 一个订阅，只返回满足过滤条件的事件
+pub struct EventSubscription {
+    receiver: broadcast::Receiver<CoreEvent>,
+    filter: EventFilter,
+}
+
+impl EventSubscription {
+    /// 等待下一个事件，总线释放后返回 None
+    pub async fn recv(&mut self) -> Option<CoreEvent> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) if self.filter.matches(&event) => return Some(event),
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("事件订阅处理过慢，丢弃了 {} 个事件", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// 不等待地取出下一个事件，供界面每帧轮询
+    pub fn try_recv(&mut self) -> Option<CoreEvent> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) if self.filter.matches(&event) => return Some(event),
+                Ok(_) => {}
+                Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                    tracing::warn!("事件订阅处理过慢，丢弃了 {} 个事件", skipped);
+                }
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_filtered_subscriptions() {
+        let bus = EventBus::new();
+        let mut all = bus.subscribe(EventFilter::all());
+        let mut installs = bus.subscribe(EventFilter::kinds(&[CoreEventKind::Install, CoreEventKind::Reboot])
+            .with_hardware_id("PCI\\VEN_10DE"));
+
+        bus.publish(CoreEvent::ScanStarted);
+        bus.publish(CoreEvent::RebootRequired { hardware_id: "PCI\\VEN_8086".to_string() });
+        bus.publish(CoreEvent::InstallStep {
+            hardware_id: Some("pci\\ven_10de".to_string()),
+            step: InstallStep::Install,
+            status: StepStatus::Started,
+        });
+        drop(bus);
+
+        assert!(matches!(all.recv().await, Some(CoreEvent::ScanStarted)));
+        assert!(matches!(all.recv().await, Some(CoreEvent::RebootRequired { .. })));
+        assert!(matches!(all.recv().await, Some(CoreEvent::InstallStep { .. })));
+        assert!(all.recv().await.is_none());
+
+        assert!(matches!(installs.recv().await, Some(CoreEvent::InstallStep { step: InstallStep::Install, .. })));
+        assert!(installs.recv().await.is_none());
+    }
+}
@@ -1,2 +1,10 @@
 mod driver_updater_core;
-pub use driver_updater_core::*;
\ No newline at end of file
+mod event_loop;
+mod events;
+mod scheduler;
+mod state;
+pub use driver_updater_core::*;
+pub use event_loop::*;
+pub use events::*;
+pub use scheduler::*;
+pub use state::*;
@@ -0,0 +1,370 @@
+//! 定时更新检查
+//!
+//! 按 ScheduleConfig 计算下一次执行时间，到期后向事件循环发送命令。
+//! 上次执行时间保存在应用数据目录，程序关闭期间错过的检查在启动后补做一次。
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, RwLock};
+use crate::config::{ConfigManager, ScheduleConfig, ScheduledAction};
+use crate::core::{create_channels, event_handler_task, AppCommand, AppState, DriverUpdaterCore, EventLoop};
+use crate::driver::fetcher::DownloadPriority;
+use crate::notify::NotificationDispatcher;
+use crate::utils::{CronExpression, HamsterError, Result};
+use crate::t;
+
+// 单次休眠的上限，系统休眠或修改时钟后能及时重新计算
+const MAX_SLEEP_SECS: i64 = 60;
+
+/// 持久化的调度状态
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerState {
+    pub last_run: Option<DateTime<Local>>,
+    pub next_run: Option<DateTime<Local>>,
+    pub last_result: Option<String>,
+}
+
+impl SchedulerState {
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(crate::utils::get_app_data_dir()?.join("scheduler_state.json"))
+    }
+
+    /// 文件不存在时返回空状态
+    pub fn load(path: &PathBuf) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &PathBuf) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// 计算下一次执行时间
+///
+/// 计划时间先加上按主机名确定的随机延迟；错过的执行（加上延迟后仍早于 now）
+/// 只补做一次，时间为 now。结果再推迟到最近的维护时间段。
+pub fn plan_next_run(
+    config: &ScheduleConfig,
+    state: &SchedulerState,
+    now: DateTime<Local>,
+) -> Result<Option<DateTime<Local>>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+
+    let due = match (&config.cron, state.last_run) {
+        (Some(cron), last_run) => {
+            let cron = CronExpression::parse(cron)?;
+            match cron.next_after(last_run.unwrap_or(now)) {
+                Some(due) => due,
+                None => return Ok(None),
+            }
+        }
+        (None, Some(last_run)) => last_run + Duration::hours(config.interval_hours as i64),
+        (None, None) => now,
+    };
+    // 延迟按原始计划时间计算，随 now 推移保持不变
+    let due = (due + jitter(config.jitter_minutes, due)).max(now);
+
+    Ok(config.next_allowed_time(due))
+}
+
+/// 同一台机器对同一计划时间得到固定的延迟，不同机器之间分散开
+fn jitter(max_minutes: u32, due: DateTime<Local>) -> Duration {
+    if max_minutes == 0 {
+        return Duration::zero();
+    }
+    let host = std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_default();
+    let mut hasher = DefaultHasher::new();
+    host.hash(&mut hasher);
+    due.timestamp().hash(&mut hasher);
+    Duration::seconds((hasher.finish() % (max_minutes as u64 * 60 + 1)) as i64)
+}
+
+/// 定时动作对应的命令序列，下载以定时任务的优先级排队
+pub fn scheduled_commands(action: ScheduledAction) -> Vec<AppCommand> {
+    match action {
+        ScheduledAction::CheckOnly => vec![AppCommand::CheckUpdates],
+        ScheduledAction::Download => vec![AppCommand::CheckUpdates, AppCommand::DownloadUpdates(DownloadPriority::Scheduled)],
+        ScheduledAction::DownloadAndInstall => vec![AppCommand::CheckUpdates, AppCommand::InstallUpdates(DownloadPriority::Scheduled)],
+    }
+}
+
+/// 定时更新调度器
+pub struct UpdateScheduler {
+    config: ScheduleConfig,
+    state: SchedulerState,
+    state_path: PathBuf,
+    command_tx: mpsc::Sender<AppCommand>,
+    // 已计划的执行时间，执行前不再重新计算
+    planned: Option<DateTime<Local>>,
+}
+
+impl UpdateScheduler {
+    pub fn new(config: ScheduleConfig, command_tx: mpsc::Sender<AppCommand>) -> Result<Self> {
+        Self::with_state_path(config, command_tx, SchedulerState::default_path()?)
+    }
+
+    pub fn with_state_path(
+        config: ScheduleConfig,
+        command_tx: mpsc::Sender<AppCommand>,
+        state_path: PathBuf,
+    ) -> Result<Self> {
+        config.validate()?;
+        let state = SchedulerState::load(&state_path)?;
+        Ok(Self { config, state, state_path, command_tx, planned: None })
+    }
+
+    pub fn state(&self) -> &SchedulerState {
+        &self.state
+    }
+
+    /// 运行调度循环，命令通道关闭时返回
+    pub async fn run(mut self) -> Result<()> {
+        loop {
+            let Some(remaining) = self.time_until_next_run(Local::now())? else {
+                tracing::info!("定时更新未启用或没有下一次执行时间");
+                return Ok(());
+            };
+            if remaining > 0 {
+                let secs = remaining.min(MAX_SLEEP_SECS) as u64;
+                tokio::time::sleep(std::time::Duration::from_secs(secs)).await;
+                continue;
+            }
+
+            self.trigger().await?;
+        }
+    }
+
+    /// 距下一次执行的秒数，不大于 0 时应立即执行；没有下一次执行时返回 None
+    fn time_until_next_run(&mut self, now: DateTime<Local>) -> Result<Option<i64>> {
+        let next_run = match self.planned {
+            Some(planned) => planned,
+            None => {
+                let Some(next_run) = plan_next_run(&self.config, &self.state, now)? else {
+                    return Ok(None);
+                };
+                self.planned = Some(next_run);
+                next_run
+            }
+        };
+        if self.state.next_run != Some(next_run) {
+            tracing::info!("下一次定时更新检查: {}", next_run.format("%Y-%m-%d %H:%M:%S"));
+            self.state.next_run = Some(next_run);
+            self.save_state();
+        }
+        Ok(Some((next_run - now).num_seconds()))
+    }
+
+    /// 立即发送本次计划的命令并记录执行时间
+    pub async fn trigger(&mut self) -> Result<()> {
+        self.trigger_at(Local::now()).await
+    }
+
+    async fn trigger_at(&mut self, now: DateTime<Local>) -> Result<()> {
+        tracing::info!("执行定时更新: {:?}", self.config.action);
+        for command in scheduled_commands(self.config.action) {
+            self.command_tx.send(command).await
+                .map_err(|_| HamsterError::UpdateError(t!("event-loop-closed")))?;
+        }
+        self.state.last_run = Some(now);
+        self.state.next_run = None;
+        self.planned = None;
+        self.state.last_result = Some(t!("schedule-submitted", action = self.config.action.label()));
+        self.save_state();
+        Ok(())
+    }
+
+    /// 记录执行结果，供 `schedule status` 展示
+    pub fn record_result(&mut self, result: String) {
+        self.state.last_result = Some(result);
+        self.save_state();
+    }
+
+    fn save_state(&self) {
+        if let Err(e) = self.state.save(&self.state_path) {
+            tracing::warn!("保存调度状态失败: {}", e);
+        }
+    }
+}
+
+/// 为已有的核心控制器启动事件循环，返回命令发送端和共享状态
+pub fn start_event_loop(core: Arc<RwLock<DriverUpdaterCore>>, config: &ConfigManager) -> (mpsc::Sender<AppCommand>, Arc<RwLock<AppState>>) {
+    let (command_tx, command_rx, event_tx, event_rx) = create_channels();
+    let state = Arc::new(RwLock::new(AppState::new()));
+    let mut event_loop = EventLoop::new(command_rx, event_tx, state.clone(), core);
+    let notifications = Arc::new(NotificationDispatcher::new(config.notification_config.clone()));
+    tokio::spawn(event_handler_task(event_rx, state.clone(), Some(notifications)));
+    tokio::spawn(async move {
+        if let Err(e) = event_loop.run().await {
+            tracing::error!("事件循环异常退出: {}", e);
+        }
+    });
+    (command_tx, state)
+}
+
+/// 用已有的核心控制器按配置执行定时更新，直到调度结束
+///
+/// 界面用它在自己的核心上执行定时更新，与手动操作共用下载队列和安装事务。
+pub async fn run_scheduled_updates(core: Arc<RwLock<DriverUpdaterCore>>, config: ConfigManager) -> Result<()> {
+    let (command_tx, _state) = start_event_loop(core, &config);
+    UpdateScheduler::new(config.schedule_config, command_tx)?.run().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::config::MaintenanceWindow;
+
+    fn at(s: &str) -> DateTime<Local> {
+        let naive = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&naive).earliest().unwrap()
+    }
+
+    fn config() -> ScheduleConfig {
+        ScheduleConfig { enabled: true, jitter_minutes: 0, ..ScheduleConfig::default() }
+    }
+
+    #[test]
+    fn test_plan_interval_and_catch_up() {
+        let now = at("2024-05-01 12:00");
+        let mut state = SchedulerState::default();
+        assert_eq!(plan_next_run(&config(), &state, now).unwrap(), Some(now));
+
+        state.last_run = Some(at("2024-05-01 08:00"));
+        assert_eq!(plan_next_run(&config(), &state, now).unwrap(), Some(at("2024-05-02 08:00")));
+
+        // 关机期间错过了多次，只补做一次
+        state.last_run = Some(at("2024-04-20 08:00"));
+        assert_eq!(plan_next_run(&config(), &state, now).unwrap(), Some(now));
+
+        let disabled = ScheduleConfig { enabled: false, ..config() };
+        assert_eq!(plan_next_run(&disabled, &state, now).unwrap(), None);
+    }
+
+    #[test]
+    fn test_plan_cron_window_and_jitter() {
+        let now = at("2024-05-01 12:00");
+        let state = SchedulerState { last_run: Some(at("2024-05-01 11:00")), ..Default::default() };
+        let mut config = ScheduleConfig { cron: Some("0 * * * *".to_string()), ..config() };
+        assert_eq!(plan_next_run(&config, &state, now).unwrap(), Some(at("2024-05-01 12:00")));
+
+        config.maintenance_windows = vec![MaintenanceWindow {
+            days: Vec::new(),
+            start: "22:00".to_string(),
+            end: "02:00".to_string(),
+        }];
+        assert_eq!(plan_next_run(&config, &state, now).unwrap(), Some(at("2024-05-01 22:00")));
+
+        config.maintenance_windows.clear();
+        config.jitter_minutes = 30;
+        let planned = plan_next_run(&config, &state, now).unwrap().unwrap();
+        assert!(planned >= now && planned <= now + Duration::minutes(30));
+        assert_eq!(plan_next_run(&config, &state, now).unwrap(), Some(planned));
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_fires_with_jitter() {
+        let state_path = std::env::temp_dir().join(format!("hamster_scheduler_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&state_path);
+        let (command_tx, mut command_rx) = mpsc::channel(16);
+        let config = ScheduleConfig { cron: Some("0 3 * * *".to_string()), jitter_minutes: 30, ..config() };
+        let mut scheduler = UpdateScheduler::with_state_path(config, command_tx, state_path.clone()).unwrap();
+        scheduler.state.last_run = Some(at("2024-05-01 03:10"));
+
+        // 按调度循环的方式每分钟检查一次，两天内应在每天 03:00 后的延迟范围内各执行一次
+        let mut now = at("2024-05-01 12:00");
+        let mut fired = Vec::new();
+        while now < at("2024-05-03 12:00") {
+            if scheduler.time_until_next_run(now).unwrap().unwrap() <= 0 {
+                scheduler.trigger_at(now).await.unwrap();
+                fired.push(now);
+            }
+            now += Duration::seconds(MAX_SLEEP_SECS);
+        }
+
+        assert_eq!(fired.len(), 2, "{:?}", fired);
+        for (fired, day) in fired.iter().zip(["2024-05-02 03:00", "2024-05-03 03:00"]) {
+            assert!(*fired >= at(day) && *fired <= at(day) + Duration::minutes(31), "{}", fired);
+        }
+        assert!(matches!(command_rx.try_recv(), Ok(AppCommand::CheckUpdates)));
+        let _ = std::fs::remove_file(&state_path);
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_check_reaches_dispatcher() {
+        use crate::config::{ChannelTarget, NotificationChannel, NotificationConfig, NotificationKind};
+        use crate::core::{StateEvent, UpdateCandidate};
+        use crate::matcher::HardwareInfo;
+        use crate::notify::Notification;
+        use crate::utils::test_support::TempDir;
+
+        let directory = TempDir::new("scheduler_notify");
+        let state_path = directory.join("scheduler_state.json");
+        let (command_tx, mut command_rx, event_tx, event_rx) = create_channels();
+
+        // 代替事件循环执行检查，只返回一个需要更新的设备
+        let event_loop = tokio::spawn(async move {
+            while let Some(command) = command_rx.recv().await {
+                if command == AppCommand::CheckUpdates {
+                    let candidate = UpdateCandidate {
+                        hardware_info: HardwareInfo {
+                            hardware_id: "PCI\\VEN_10DE".to_string(),
+                            device_name: "NVIDIA GeForce RTX 3060".to_string(),
+                            manufacturer: "NVIDIA".to_string(),
+                            device_class: "Display".to_string(),
+                        },
+                        matched_driver: None,
+                        needs_update: true,
+                        current_version: "1.0".to_string(),
+                    };
+                    event_tx.send(StateEvent::UpdateCheckCompleted(vec![candidate])).await.unwrap();
+                }
+                event_tx.send(StateEvent::CommandCompleted(command)).await.unwrap();
+            }
+        });
+        let channel = NotificationChannel::new(ChannelTarget::File { directory: directory.path().to_string_lossy().to_string() });
+        let dispatcher = NotificationDispatcher::new(NotificationConfig { enabled: true, channels: vec![channel] });
+        let state = Arc::new(RwLock::new(AppState::new()));
+        let handler = tokio::spawn(event_handler_task(event_rx, state.clone(), Some(Arc::new(dispatcher))));
+
+        let config = ScheduleConfig { jitter_minutes: 30, ..config() };
+        let mut scheduler = UpdateScheduler::with_state_path(config, command_tx, state_path).unwrap();
+        scheduler.state.last_run = Some(at("2024-05-01 08:00"));
+        let mut now = at("2024-05-02 07:00");
+        while scheduler.time_until_next_run(now).unwrap().unwrap() > 0 {
+            now += Duration::seconds(MAX_SLEEP_SECS);
+        }
+        scheduler.trigger_at(now).await.unwrap();
+
+        // 关闭命令通道后事件依次处理完，处理任务随之结束
+        drop(scheduler);
+        event_loop.await.unwrap();
+        handler.await.unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(directory.path()).unwrap().flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path != &directory.join("scheduler_state.json"))
+            .collect();
+        assert_eq!(files.len(), 1, "{:?}", files);
+        let notification: Notification = serde_json::from_slice(&std::fs::read(&files[0]).unwrap()).unwrap();
+        assert_eq!(notification.kind, NotificationKind::UpdatesFound);
+        assert!(notification.message.contains("NVIDIA GeForce RTX 3060"));
+        assert_eq!(state.read().await.update_candidates.len(), 1);
+    }
+}
@@ -0,0 +1,134 @@
+//! 应用程序状态
+//!
+//! 事件循环执行命令时发出 StateEvent，由 AppState 汇总成界面和后台任务可读的状态
+
+use serde::{Deserialize, Serialize};
+use crate::core::{AppCommand, CoreEvent, UpdateCandidate};
+use crate::hardware::HardwareDevice;
+use crate::types::OSInfo;
+
+/// 状态变化事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StateEvent {
+    ScanStarted,
+    ScanCompleted(Vec<HardwareDevice>),
+    UpdateCheckStarted,
+    UpdateCheckCompleted(Vec<UpdateCandidate>),
+    /// 参数为硬件ID
+    DownloadStarted(String),
+    DownloadCompleted(String),
+    InstallStarted(String),
+    InstallCompleted(String),
+    BackupStarted,
+    /// 参数为备份快照ID
+    BackupCompleted(String),
+    RestoreStarted,
+    RestoreCompleted,
+    /// 核心在执行命令过程中发布的事件，装箱避免其他事件也占用同样大小
+    Core(Box<CoreEvent>),
+    /// 命令执行完成
+    CommandCompleted(AppCommand),
+    /// 命令执行失败
+    CommandFailed(String),
+}
+
+/// 系统概况
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SystemSummary {
+    pub os: Option<OSInfo>,
+}
+
+/// 应用程序状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppState {
+    pub system_summary: SystemSummary,
+    pub devices: Vec<HardwareDevice>,
+    pub update_candidates: Vec<UpdateCandidate>,
+    /// 正在下载的硬件ID和进度
+    pub downloads: Vec<(String, f32)>,
+    /// 正在安装的硬件ID和进度
+    pub installs: Vec<(String, f32)>,
+    pub is_scanning: bool,
+    /// 扫描进度（0.0 - 1.0）
+    pub scan_progress: f32,
+    pub is_checking_updates: bool,
+    pub is_backing_up: bool,
+    pub is_restoring: bool,
+    pub last_backup: Option<String>,
+    pub last_error: Option<String>,
+    /// 安装后需要重启的硬件ID
+    pub reboot_required: Vec<String>,
+}
+
+/// 状态事件处理
+pub trait StateEventHandler {
+    fn handle_event(&mut self, event: StateEvent);
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StateEventHandler for AppState {
+    fn handle_event(&mut self, event: StateEvent) {
+        match event {
+            StateEvent::ScanStarted => {
+                self.is_scanning = true;
+                self.scan_progress = 0.0;
+            }
+            StateEvent::ScanCompleted(devices) => {
+                self.is_scanning = false;
+                self.devices = devices;
+            }
+            StateEvent::UpdateCheckStarted => self.is_checking_updates = true,
+            StateEvent::UpdateCheckCompleted(candidates) => {
+                self.is_checking_updates = false;
+                self.update_candidates = candidates;
+            }
+            StateEvent::DownloadStarted(id) => set_progress(&mut self.downloads, id, 0.0),
+            StateEvent::DownloadCompleted(id) => self.downloads.retain(|(download, _)| *download != id),
+            StateEvent::InstallStarted(id) => set_progress(&mut self.installs, id, 0.0),
+            StateEvent::InstallCompleted(id) => self.installs.retain(|(install, _)| *install != id),
+            StateEvent::BackupStarted => self.is_backing_up = true,
+            StateEvent::BackupCompleted(snapshot_id) => {
+                self.is_backing_up = false;
+                self.last_backup = Some(snapshot_id);
+            }
+            StateEvent::RestoreStarted => self.is_restoring = true,
+            StateEvent::RestoreCompleted => self.is_restoring = false,
+            StateEvent::Core(event) => self.handle_core_event(*event),
+            StateEvent::CommandCompleted(_) => {}
+            StateEvent::CommandFailed(message) => {
+                self.is_scanning = false;
+                self.is_checking_updates = false;
+                self.is_backing_up = false;
+                self.is_restoring = false;
+                self.last_error = Some(message);
+            }
+        }
+    }
+}
+
+impl AppState {
+    fn handle_core_event(&mut self, event: CoreEvent) {
+        match event {
+            CoreEvent::ScanProgress { progress } => self.scan_progress = progress.progress / 100.0,
+            CoreEvent::DownloadProgress { hardware_id, progress } => {
+                set_progress(&mut self.downloads, hardware_id, progress.progress / 100.0);
+            }
+            CoreEvent::RebootRequired { hardware_id } if !self.reboot_required.contains(&hardware_id) => {
+                self.reboot_required.push(hardware_id);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn set_progress(items: &mut Vec<(String, f32)>, id: String, progress: f32) {
+    match items.iter_mut().find(|(item, _)| *item == id) {
+        Some(item) => item.1 = progress,
+        None => items.push((id, progress)),
+    }
+}
@@ -1,197 +1,1020 @@
 //! 下载队列管理器
 //!
-//! 负责管理驱动下载队列
+//! 负责管理驱动下载队列，支持暂停、继续、重试和取消，队列状态保存到文件，重启后恢复。
+//! 任务按优先级和加入顺序下载，同时限制总并发数和每个主机的并发数。
+//! 下载速度可以限制总速度和单个任务的速度，大文件只在配置的时间段内下载，
+//! 时间段结束时暂停并在下一个时间段开始时自动继续。
+//! 启用局域网缓存时，有校验和的驱动先从本机缓存、共享文件夹和其他计算机获取。
 
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use crate::types::driver_types::DriverInfo;
-use crate::utils::error::{HamsterError, Result};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tokio::task::{JoinHandle, JoinSet};
+use crate::config::{DownloadConfig, MaintenanceWindow};
+use crate::driver::fetcher::{PeerCache, ProgressTracker};
+use crate::fetcher::DriverFetcher;
+use crate::matcher::DriverInfo;
+use crate::t;
+use crate::utils::{CancellationToken, HamsterError, RateLimiter, Result};
 
-#[derive(Debug, Clone)]
+// 等待下载结束时检查新加入和恢复的任务的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DownloadStatus {
     Queued,
     Downloading,
+    /// 大文件等待允许下载的时间段
+    Waiting,
     Paused,
     Completed,
     Failed,
     Cancelled,
 }
 
-#[derive(Debug, Clone)]
+impl DownloadStatus {
+    pub fn label(&self) -> String {
+        match self {
+            DownloadStatus::Queued => t!("download-status-queued"),
+            DownloadStatus::Downloading => t!("download-status-downloading"),
+            DownloadStatus::Waiting => t!("download-status-waiting"),
+            DownloadStatus::Paused => t!("download-status-paused"),
+            DownloadStatus::Completed => t!("download-status-completed"),
+            DownloadStatus::Failed => t!("download-status-failed"),
+            DownloadStatus::Cancelled => t!("download-status-cancelled"),
+        }
+    }
+
+    /// 不会再自动继续的状态
+    pub fn is_finished(&self) -> bool {
+        matches!(self, DownloadStatus::Completed | DownloadStatus::Failed | DownloadStatus::Cancelled)
+    }
+}
+
+/// 下载优先级，排在前面的先下载
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadPriority {
+    /// 用户手动发起
+    #[default]
+    User,
+    /// 定时任务发起
+    Scheduled,
+}
+
+impl DownloadPriority {
+    pub fn label(&self) -> String {
+        match self {
+            DownloadPriority::User => t!("download-priority-user"),
+            DownloadPriority::Scheduled => t!("download-priority-scheduled"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadTask {
     pub id: String,
     pub driver_info: DriverInfo,
     pub download_url: String,
     pub file_path: String,
     pub status: DownloadStatus,
+    #[serde(default)]
+    pub priority: DownloadPriority,
     pub progress: f64, // 0.0 to 100.0
-    pub created_at: std::time::SystemTime,
+    #[serde(default)]
+    pub downloaded: u64,
+    /// 总大小未知时为0
+    #[serde(default)]
+    pub total: u64,
+    /// 字节/秒，只在下载中有效
+    #[serde(skip)]
+    pub speed: u64,
+    #[serde(skip)]
+    pub eta: Option<Duration>,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub created_at: SystemTime,
 }
 
+impl DownloadTask {
+    pub fn can_pause(&self) -> bool {
+        matches!(self.status, DownloadStatus::Queued | DownloadStatus::Downloading | DownloadStatus::Waiting)
+    }
+
+    pub fn can_resume(&self) -> bool {
+        self.status == DownloadStatus::Paused
+    }
+
+    pub fn can_retry(&self) -> bool {
+        matches!(self.status, DownloadStatus::Failed | DownloadStatus::Cancelled)
+    }
+
+    pub fn can_cancel(&self) -> bool {
+        !self.status.is_finished()
+    }
+
+    /// 下载地址的主机名，用于限制每个主机的并发数
+    pub fn host(&self) -> String {
+        reqwest::Url::parse(&self.download_url).ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            .unwrap_or_default()
+    }
+
+    /// 已知大小（下载中得到的总大小或驱动信息中的文件大小）不小于 threshold，threshold 为0时不区分大小
+    pub fn is_large(&self, threshold: u64) -> bool {
+        threshold > 0 && self.total.max(self.driver_info.file_size) >= threshold
+    }
+
+    /// 再次请求同一文件时可以直接使用的任务：未结束，或已完成且文件还在
+    fn is_reusable(&self) -> bool {
+        !self.status.is_finished()
+            || (self.status == DownloadStatus::Completed && Path::new(&self.file_path).exists())
+    }
+}
+
+/// 下载队列，克隆后共享同一队列
+#[derive(Clone)]
 pub struct DownloadQueue {
     tasks: Arc<Mutex<HashMap<String, DownloadTask>>>,
-    max_concurrent_downloads: usize,
-    active_downloads: Arc<Mutex<usize>>,
+    max_concurrent_downloads: Arc<AtomicUsize>,
+    max_downloads_per_host: Arc<AtomicUsize>,
+    tracker: Arc<ProgressTracker>,
+    /// 正在下载的任务的取消令牌，暂停和取消时用来停止下载
+    running: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    /// 有任务可以开始时唤醒工作池
+    wakeup: Arc<Notify>,
+    /// 所有下载共享的速度上限
+    rate_limiter: Arc<RateLimiter>,
+    /// 单个任务的速度上限，每个任务从它创建自己的限速器
+    task_rate_limiter: Arc<RateLimiter>,
+    large_downloads: Arc<Mutex<LargeDownloadPolicy>>,
+    peer_cache: Arc<Mutex<Option<PeerCache>>>,
+    /// 正在运行的后台工作池数量
+    workers: Arc<AtomicUsize>,
+    state_path: Option<PathBuf>,
+}
+
+/// 大文件的下载时间段
+#[derive(Debug, Clone, Default)]
+struct LargeDownloadPolicy {
+    /// 字节，0 表示不区分大小
+    threshold: u64,
+    /// 为空时随时可以下载
+    windows: Vec<MaintenanceWindow>,
+}
+
+impl LargeDownloadPolicy {
+    fn allows(&self, task: &DownloadTask, time: DateTime<Local>) -> bool {
+        !task.is_large(self.threshold)
+            || self.windows.is_empty()
+            || self.windows.iter().any(|window| window.contains(time))
+    }
+}
+
+/// 后台下载工作池，停止或丢弃时中断正在进行的下载并让它们重新排队
+pub struct DownloadWorkers {
+    shutdown: CancellationToken,
+    handle: JoinHandle<()>,
+}
+
+impl DownloadWorkers {
+    /// 停止工作池，已下载的部分保留，下次启动时断点续传
+    pub fn stop(&self) {
+        self.shutdown.cancel();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
+
+impl Drop for DownloadWorkers {
+    fn drop(&mut self) {
+        self.shutdown.cancel();
+    }
 }
 
 impl DownloadQueue {
+    /// 创建不保存状态的队列，默认不单独限制每个主机的并发数
     pub fn new(max_concurrent_downloads: usize) -> Self {
         Self {
             tasks: Arc::new(Mutex::new(HashMap::new())),
-            max_concurrent_downloads,
-            active_downloads: Arc::new(Mutex::new(0)),
+            max_concurrent_downloads: Arc::new(AtomicUsize::new(max_concurrent_downloads.max(1))),
+            max_downloads_per_host: Arc::new(AtomicUsize::new(usize::MAX)),
+            tracker: Arc::new(ProgressTracker::new()),
+            running: Arc::new(Mutex::new(HashMap::new())),
+            wakeup: Arc::new(Notify::new()),
+            rate_limiter: Arc::new(RateLimiter::new(0)),
+            task_rate_limiter: Arc::new(RateLimiter::new(0)),
+            large_downloads: Arc::new(Mutex::new(LargeDownloadPolicy::default())),
+            peer_cache: Arc::new(Mutex::new(None)),
+            workers: Arc::new(AtomicUsize::new(0)),
+            state_path: None,
         }
     }
 
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(crate::utils::get_app_data_dir()?.join("download_queue.json"))
+    }
+
+    /// 修改同时下载的数量，正在下载的任务不受影响
+    pub fn set_max_concurrent_downloads(&self, max_concurrent_downloads: usize) {
+        self.max_concurrent_downloads.store(max_concurrent_downloads.max(1), Ordering::Relaxed);
+        self.wakeup.notify_one();
+    }
+
+    pub fn max_concurrent_downloads(&self) -> usize {
+        self.max_concurrent_downloads.load(Ordering::Relaxed)
+    }
+
+    /// 修改同一主机同时下载的数量，正在下载的任务不受影响
+    pub fn set_max_downloads_per_host(&self, max_downloads_per_host: usize) {
+        self.max_downloads_per_host.store(max_downloads_per_host.max(1), Ordering::Relaxed);
+        self.wakeup.notify_one();
+    }
+
+    pub fn max_downloads_per_host(&self) -> usize {
+        self.max_downloads_per_host.load(Ordering::Relaxed)
+    }
+
+    /// 应用下载配置中的并发数、速度上限和大文件下载时间段，正在下载的任务立即按新的速度上限下载
+    pub fn apply_config(&self, config: &DownloadConfig) {
+        self.max_concurrent_downloads.store(config.max_concurrent_downloads.max(1), Ordering::Relaxed);
+        self.max_downloads_per_host.store(config.max_downloads_per_host.max(1), Ordering::Relaxed);
+        self.rate_limiter.set_rate(config.max_download_rate());
+        self.task_rate_limiter.set_rate(config.max_task_rate());
+        *self.lock_large_downloads() = LargeDownloadPolicy {
+            threshold: config.large_download_threshold(),
+            windows: config.large_download_windows.clone(),
+        };
+        self.wakeup.notify_one();
+    }
+
+    /// 设置局域网缓存，之后开始的下载生效，None 表示只从下载地址下载
+    pub fn set_peer_cache(&self, peer_cache: Option<PeerCache>) {
+        *self.peer_cache.lock().unwrap_or_else(|e| e.into_inner()) = peer_cache;
+    }
+
+    /// 按时间段暂停或继续大文件的下载，返回是否有任务的状态改变
+    ///
+    /// 时间段外的大文件转为等待，正在下载的停止下载并保留已下载的部分；
+    /// 进入时间段或不再属于大文件的等待任务重新排队。
+    pub fn apply_download_windows(&self, time: DateTime<Local>) -> bool {
+        let policy = self.lock_large_downloads().clone();
+        let mut changed = Vec::new();
+        {
+            let mut tasks = self.lock_tasks();
+            let running = self.lock_running();
+            for task in tasks.values_mut() {
+                let allowed = policy.allows(task, time);
+                let status = match task.status {
+                    DownloadStatus::Waiting if allowed => DownloadStatus::Queued,
+                    DownloadStatus::Queued | DownloadStatus::Downloading if !allowed => DownloadStatus::Waiting,
+                    _ => continue,
+                };
+                if task.status == DownloadStatus::Downloading {
+                    if let Some(token) = running.get(&task.id) {
+                        token.cancel();
+                    }
+                }
+                task.status = status;
+                task.speed = 0;
+                task.eta = None;
+                changed.push((task.id.clone(), status));
+            }
+        }
+        if changed.is_empty() {
+            return false;
+        }
+        for (task_id, status) in &changed {
+            let _ = self.tracker.set_status(task_id, *status, status.label());
+        }
+        if let Err(e) = self.save() {
+            tracing::warn!("保存下载队列失败: {}", e);
+        }
+        self.wakeup.notify_one();
+        true
+    }
+
+    /// 打开保存在 state_path 的队列，上次退出时未完成的下载重新排队
+    pub fn open(max_concurrent_downloads: usize, state_path: PathBuf) -> Result<Self> {
+        let mut queue = Self::new(max_concurrent_downloads);
+        if state_path.exists() {
+            let content = std::fs::read_to_string(&state_path)?;
+            let saved: Vec<DownloadTask> = serde_json::from_str(&content)
+                .map_err(|e| HamsterError::ParseError(format!("下载队列文件损坏: {}", e)))?;
+            let mut tasks = queue.lock_tasks();
+            for mut task in saved {
+                if task.status == DownloadStatus::Downloading {
+                    task.status = DownloadStatus::Queued;
+                }
+                tasks.insert(task.id.clone(), task);
+            }
+        }
+        queue.state_path = Some(state_path);
+        Ok(queue)
+    }
+
+    /// 保存队列状态，没有指定文件时什么也不做
+    ///
+    /// 先写临时文件再替换，保存过程中退出不会损坏已有的队列文件。
+    pub fn save(&self) -> Result<()> {
+        let Some(ref path) = self.state_path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(&self.get_all_tasks())?;
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, content)?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    }
+
+    /// 以用户发起的优先级添加下载任务
+    pub fn add_task(&self, driver_info: DriverInfo, download_url: String, file_path: String) -> Result<String> {
+        self.add_task_with_priority(driver_info, download_url, file_path, DownloadPriority::User)
+    }
+
     /// 添加下载任务
-    pub async fn add_task(&self, driver_info: DriverInfo, download_url: String, file_path: String) -> Result<String> {
+    ///
+    /// 同一驱动、同一地址或同一校验和的文件已在队列中（未结束，或已完成且文件还在）时返回已有任务，
+    /// 新请求的优先级更高时提升已有任务的优先级。
+    pub fn add_task_with_priority(&self, driver_info: DriverInfo, download_url: String, file_path: String,
+                                  priority: DownloadPriority) -> Result<String> {
         let task_id = self.generate_task_id(&driver_info);
-        
-        let task = DownloadTask {
-            id: task_id.clone(),
-            driver_info,
-            download_url,
-            file_path,
-            status: DownloadStatus::Queued,
-            progress: 0.0,
-            created_at: std::time::SystemTime::now(),
-        };
 
-        let mut tasks = self.tasks.lock().await;
-        tasks.insert(task_id.clone(), task);
-        drop(tasks);
+        {
+            let mut tasks = self.lock_tasks();
+            let checksum = driver_info.checksum.trim();
+            let existing = tasks.values_mut()
+                .filter(|task| task.is_reusable())
+                .find(|task| {
+                    task.id == task_id
+                        || task.download_url == download_url
+                        || (!checksum.is_empty() && task.driver_info.checksum.trim().eq_ignore_ascii_case(checksum))
+                });
+            if let Some(task) = existing {
+                let id = task.id.clone();
+                if priority < task.priority {
+                    task.priority = priority;
+                    drop(tasks);
+                    self.save()?;
+                }
+                return Ok(id);
+            }
+            tasks.insert(task_id.clone(), DownloadTask {
+                id: task_id.clone(),
+                driver_info,
+                download_url,
+                file_path,
+                status: DownloadStatus::Queued,
+                priority,
+                progress: 0.0,
+                downloaded: 0,
+                total: 0,
+                speed: 0,
+                eta: None,
+                error: None,
+                created_at: SystemTime::now(),
+            });
+        }
 
+        self.save()?;
+        self.wakeup.notify_one();
         Ok(task_id)
     }
 
-    /// 开始下载任务
-    pub async fn start_task(&self, task_id: &str) -> Result<()> {
-        let mut tasks = self.tasks.lock().await;
+    /// 下一个可以开始的任务：优先级最高、加入最早，且总并发数和所在主机的并发数都未达到上限
+    pub fn next_task(&self) -> Option<DownloadTask> {
+        let tasks = self.lock_tasks();
+        let running = self.lock_running();
+        let active: Vec<String> = tasks.values()
+            .filter(|task| task.status == DownloadStatus::Downloading)
+            .map(DownloadTask::host)
+            .collect();
+        if active.len() >= self.max_concurrent_downloads() {
+            return None;
+        }
+        let mut queued: Vec<&DownloadTask> = tasks.values()
+            // 被中断的下载结束前不重新开始
+            .filter(|task| task.status == DownloadStatus::Queued && !running.contains_key(&task.id))
+            .collect();
+        queued.sort_by(|a, b| (a.priority, a.created_at, &a.id).cmp(&(b.priority, b.created_at, &b.id)));
+        queued.into_iter()
+            .find(|task| {
+                let host = task.host();
+                active.iter().filter(|active| **active == host).count() < self.max_downloads_per_host()
+            })
+            .cloned()
+    }
+
+    /// 开始下载任务，返回暂停或取消时会被触发的令牌
+    pub fn start_task(&self, task_id: &str) -> Result<CancellationToken> {
+        {
+            let mut tasks = self.lock_tasks();
+            let task = tasks.get(task_id)
+                .ok_or_else(|| HamsterError::DownloadError("任务不存在".to_string()))?;
+            if task.status != DownloadStatus::Queued || self.lock_running().contains_key(task_id) {
+                return Err(HamsterError::DownloadError("任务状态不允许开始下载".to_string()));
+            }
+            let host = task.host();
+            let active: Vec<String> = tasks.values()
+                .filter(|task| task.status == DownloadStatus::Downloading)
+                .map(DownloadTask::host)
+                .collect();
+            if active.len() >= self.max_concurrent_downloads() {
+                return Err(HamsterError::DownloadError("达到最大并发下载数".to_string()));
+            }
+            if active.iter().filter(|active| **active == host).count() >= self.max_downloads_per_host() {
+                return Err(HamsterError::DownloadError(format!("达到 {} 的最大并发下载数", host)));
+            }
+            let task = tasks.get_mut(task_id)
+                .ok_or_else(|| HamsterError::DownloadError("任务不存在".to_string()))?;
+            task.status = DownloadStatus::Downloading;
+            task.error = None;
+            self.tracker.start_tracking(task_id.to_string(), task.downloaded, task.total)?;
+        }
+
+        let token = CancellationToken::new();
+        self.lock_running().insert(task_id.to_string(), token.clone());
+        self.save()?;
+        Ok(token)
+    }
+
+    /// 更新下载进度，total 为0表示总大小未知
+    pub fn update_progress(&self, task_id: &str, downloaded: u64, total: u64) -> Result<()> {
+        let Some(progress) = self.tracker.update_progress(task_id, downloaded, total)? else {
+            return Err(HamsterError::DownloadError("任务不存在".to_string()));
+        };
+        let mut tasks = self.lock_tasks();
         if let Some(task) = tasks.get_mut(task_id) {
-            if matches!(task.status, DownloadStatus::Queued) {
-                let mut active_count = self.active_downloads.lock().await;
-                if *active_count < self.max_concurrent_downloads {
-                    task.status = DownloadStatus::Downloading;
-                    *active_count += 1;
-                    Ok(())
-                } else {
-                    Err(HamsterError::DownloadError("达到最大并发下载数".to_string()))
+            task.downloaded = progress.downloaded;
+            task.total = progress.total;
+            task.progress = progress.percentage;
+            task.speed = progress.speed;
+            task.eta = progress.eta;
+        }
+        Ok(())
+    }
+
+    /// 下载结束。任务已被暂停或转为等待时保留已下载的部分，已被取消时删除
+    pub fn complete_task(&self, task_id: &str, result: std::result::Result<(), String>) -> Result<()> {
+        self.lock_running().remove(task_id);
+        let remove_partial = {
+            let mut tasks = self.lock_tasks();
+            let task = tasks.get_mut(task_id)
+                .ok_or_else(|| HamsterError::DownloadError("任务不存在".to_string()))?;
+            if task.status == DownloadStatus::Downloading {
+                match result {
+                    Ok(()) => {
+                        task.status = DownloadStatus::Completed;
+                        task.progress = 100.0;
+                        task.total = task.total.max(task.downloaded);
+                    }
+                    Err(error) => {
+                        task.status = DownloadStatus::Failed;
+                        task.error = Some(error);
+                    }
                 }
-            } else {
-                Err(HamsterError::DownloadError("任务状态不允许开始下载".to_string()))
             }
-        } else {
-            Err(HamsterError::DownloadError("任务不存在".to_string()))
+            task.speed = 0;
+            task.eta = None;
+            self.tracker.set_status(task_id, task.status, task.status.label())?;
+            (task.status == DownloadStatus::Cancelled).then(|| task.file_path.clone())
+        };
+
+        if let Some(path) = remove_partial {
+            let _ = std::fs::remove_file(path);
         }
+        self.wakeup.notify_one();
+        self.save()
     }
 
-    /// 更新下载进度
-    pub async fn update_progress(&self, task_id: &str, progress: f64) -> Result<()> {
-        let tasks = self.tasks.lock().await;
-        if let Some(task) = tasks.get(task_id) {
-            let mut _task = task.clone();
-            drop(tasks);
-            
-            let mut tasks = self.tasks.lock().await;
-            if let Some(existing_task) = tasks.get_mut(task_id) {
-                existing_task.progress = progress;
-            }
-            Ok(())
-        } else {
-            Err(HamsterError::DownloadError("任务不存在".to_string()))
+    /// 暂停任务，正在下载时停止下载并保留已下载的部分
+    pub fn pause_task(&self, task_id: &str) -> Result<()> {
+        self.transition(task_id, DownloadTask::can_pause, DownloadStatus::Paused, "任务无法暂停")?;
+        self.stop_download(task_id);
+        self.save()
+    }
+
+    /// 继续已暂停的任务，从断点续传
+    pub fn resume_task(&self, task_id: &str) -> Result<()> {
+        self.transition(task_id, DownloadTask::can_resume, DownloadStatus::Queued, "任务没有暂停")?;
+        self.wakeup.notify_one();
+        self.save()
+    }
+
+    /// 重新下载失败或已取消的任务
+    pub fn retry_task(&self, task_id: &str) -> Result<()> {
+        self.transition(task_id, DownloadTask::can_retry, DownloadStatus::Queued, "任务无法重试")?;
+        self.wakeup.notify_one();
+        self.save()
+    }
+
+    /// 取消任务，已下载的部分会被删除
+    pub fn cancel_task(&self, task_id: &str) -> Result<()> {
+        let task = self.transition(task_id, DownloadTask::can_cancel, DownloadStatus::Cancelled, "任务无法取消")?;
+        // 正在下载的任务在下载停止后由 complete_task 删除文件
+        if !self.stop_download(task_id) {
+            let _ = std::fs::remove_file(&task.file_path);
         }
+        self.save()
     }
 
-    /// 完成下载任务
-    pub async fn complete_task(&self, task_id: &str, success: bool) -> Result<()> {
-        let mut tasks = self.tasks.lock().await;
-        if let Some(task) = tasks.get_mut(task_id) {
-            task.status = if success {
-                DownloadStatus::Completed
-            } else {
-                DownloadStatus::Failed
-            };
-            
-            // 减少活跃下载数
-            let mut active_count = self.active_downloads.lock().await;
-            if *active_count > 0 {
-                *active_count -= 1;
+    /// 移除已结束的任务，不删除已下载的文件
+    pub fn remove_task(&self, task_id: &str) -> Result<()> {
+        {
+            let mut tasks = self.lock_tasks();
+            match tasks.get(task_id) {
+                Some(task) if task.status.is_finished() => {
+                    tasks.remove(task_id);
+                }
+                Some(_) => return Err(HamsterError::DownloadError("任务尚未结束".to_string())),
+                None => return Err(HamsterError::DownloadError("任务不存在".to_string())),
             }
-            Ok(())
-        } else {
-            Err(HamsterError::DownloadError("任务不存在".to_string()))
         }
+        self.tracker.remove_tracking(task_id)?;
+        self.save()
+    }
+
+    /// 移除所有已结束的任务
+    pub fn clear_finished(&self) -> Result<()> {
+        self.lock_tasks().retain(|_, task| !task.status.is_finished());
+        self.save()
+    }
+
+    pub fn get_task(&self, task_id: &str) -> Option<DownloadTask> {
+        self.lock_tasks().get(task_id).cloned()
     }
 
     /// 获取任务状态
-    pub async fn get_task_status(&self, task_id: &str) -> Result<DownloadStatus> {
-        let tasks = self.tasks.lock().await;
-        if let Some(task) = tasks.get(task_id) {
-            Ok(task.status.clone())
-        } else {
-            Err(HamsterError::DownloadError("任务不存在".to_string()))
+    pub fn get_task_status(&self, task_id: &str) -> Result<DownloadStatus> {
+        self.get_task(task_id)
+            .map(|task| task.status)
+            .ok_or_else(|| HamsterError::DownloadError("任务不存在".to_string()))
+    }
+
+    /// 获取所有任务，按加入顺序排列（与下载顺序无关）
+    pub fn get_all_tasks(&self) -> Vec<DownloadTask> {
+        let mut tasks: Vec<DownloadTask> = self.lock_tasks().values().cloned().collect();
+        tasks.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+        tasks
+    }
+
+    /// 获取特定状态的任务
+    pub fn get_tasks_by_status(&self, status: DownloadStatus) -> Vec<DownloadTask> {
+        self.get_all_tasks().into_iter()
+            .filter(|task| task.status == status)
+            .collect()
+    }
+
+    /// 获取活跃下载数量
+    pub fn get_active_download_count(&self) -> usize {
+        self.lock_tasks().values()
+            .filter(|task| task.status == DownloadStatus::Downloading)
+            .count()
+    }
+
+    /// 获取队列大小
+    pub fn get_queue_size(&self) -> usize {
+        self.lock_tasks().len()
+    }
+
+    /// 是否有排队或正在下载的任务
+    pub fn has_pending(&self) -> bool {
+        self.lock_tasks().values()
+            .any(|task| matches!(task.status, DownloadStatus::Queued | DownloadStatus::Downloading))
+    }
+
+    /// 下载排队的任务，直到没有排队和正在下载的任务
+    pub async fn process(&self, fetcher: Arc<DriverFetcher>) -> Result<()> {
+        let mut workers = JoinSet::new();
+        loop {
+            self.apply_download_windows(Local::now());
+            self.start_available(&fetcher, &mut workers)?;
+            if workers.is_empty() {
+                return Ok(());
+            }
+            tokio::select! {
+                _ = workers.join_next() => {}
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
         }
     }
 
-    /// 获取任务进度
-    pub async fn get_task_progress(&self, task_id: &str) -> Result<f64> {
-        let tasks = self.tasks.lock().await;
-        if let Some(task) = tasks.get(task_id) {
-            Ok(task.progress)
-        } else {
-            Err(HamsterError::DownloadError("任务不存在".to_string()))
+    /// 下载排队的任务直到 task_id 结束、暂停或转为等待，返回该任务最后的状态
+    ///
+    /// 其他排队的任务同时下载；后台工作池在运行时由工作池下载，这里只等待该任务。
+    /// report 定期收到该任务的进度；cancel 被触发或超过 timeout 时取消该任务，
+    /// 等待下载停止后返回错误。
+    pub async fn download(&self, task_id: &str, fetcher: Arc<DriverFetcher>, cancel: &CancellationToken,
+                          timeout: Option<Duration>, mut report: impl FnMut(&DownloadTask)) -> Result<DownloadTask> {
+        let deadline = timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+        let workers_running = self.workers.load(Ordering::Relaxed) > 0;
+        let process = async {
+            if workers_running {
+                self.wakeup.notify_one();
+                std::future::pending().await
+            } else {
+                self.process(fetcher).await
+            }
+        };
+        tokio::pin!(process);
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        let mut stopped = None;
+        loop {
+            tokio::select! {
+                result = &mut process => {
+                    result?;
+                    break;
+                }
+                _ = ticker.tick() => {
+                    let task = self.get_task(task_id)
+                        .ok_or_else(|| HamsterError::DownloadError("任务不存在".to_string()))?;
+                    report(&task);
+                    if workers_running && !matches!(task.status, DownloadStatus::Queued | DownloadStatus::Downloading) {
+                        break;
+                    }
+                }
+                _ = cancel.cancelled(), if stopped.is_none() => {
+                    self.cancel_task(task_id)?;
+                    stopped = Some(HamsterError::Cancelled("下载驱动".to_string()));
+                }
+                // 未设置超时时该分支不会被执行
+                _ = tokio::time::sleep_until(deadline.unwrap_or_else(tokio::time::Instant::now)), if deadline.is_some() && stopped.is_none() => {
+                    self.cancel_task(task_id)?;
+                    let seconds = timeout.map_or(0, |timeout| timeout.as_secs());
+                    stopped = Some(HamsterError::TimeoutError(t!("timeout-exceeded", operation = "下载驱动", seconds = seconds)));
+                }
+            }
+        }
+
+        let task = self.get_task(task_id)
+            .ok_or_else(|| HamsterError::DownloadError("任务不存在".to_string()))?;
+        report(&task);
+        match stopped {
+            Some(e) => Err(e),
+            None => Ok(task),
         }
     }
 
-    /// 获取所有任务
-    pub async fn get_all_tasks(&self) -> Result<Vec<DownloadTask>> {
-        let tasks = self.tasks.lock().await;
-        Ok(tasks.values().cloned().collect())
+    /// 启动后台工作池，有任务加入、继续或重试时自动开始下载，直到工作池被停止
+    pub fn start_workers(&self, fetcher: Arc<DriverFetcher>) -> DownloadWorkers {
+        let shutdown = CancellationToken::new();
+        let queue = self.clone();
+        let stopped = shutdown.clone();
+        self.workers.fetch_add(1, Ordering::Relaxed);
+        let handle = tokio::spawn(async move {
+            let mut workers = JoinSet::new();
+            loop {
+                queue.apply_download_windows(Local::now());
+                if let Err(e) = queue.start_available(&fetcher, &mut workers) {
+                    tracing::warn!("开始下载任务失败: {}", e);
+                }
+                tokio::select! {
+                    Some(_) = workers.join_next(), if !workers.is_empty() => {}
+                    _ = queue.wakeup.notified() => {}
+                    _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                    _ = stopped.cancelled() => break,
+                }
+            }
+            queue.requeue_running();
+            while workers.join_next().await.is_some() {}
+            queue.workers.fetch_sub(1, Ordering::Relaxed);
+            if let Err(e) = queue.save() {
+                tracing::warn!("保存下载队列失败: {}", e);
+            }
+        });
+        DownloadWorkers { shutdown, handle }
     }
 
-    /// 获取特定状态的任务
-    pub async fn get_tasks_by_status(&self, status: &DownloadStatus) -> Result<Vec<DownloadTask>> {
-        let tasks = self.tasks.lock().await;
-        Ok(tasks.values()
-            .filter(|task| matches!(task.status, ref s if std::mem::discriminant(s) == std::mem::discriminant(status)))
-            .cloned()
-            .collect())
+    // 在并发数允许的范围内开始排队的任务
+    fn start_available(&self, fetcher: &Arc<DriverFetcher>, workers: &mut JoinSet<()>) -> Result<()> {
+        while let Some(task) = self.next_task() {
+            let cancel = self.start_task(&task.id)?;
+            let queue = self.clone();
+            let fetcher = fetcher.clone();
+            let task_limiter = self.task_rate_limiter.bucket();
+            let peer_cache = self.peer_cache.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            workers.spawn(async move {
+                let limiters = [queue.rate_limiter.as_ref(), &task_limiter];
+                let mut report = |downloaded, total| {
+                    let _ = queue.update_progress(&task.id, downloaded, total);
+                };
+                let checksum = &task.driver_info.checksum;
+                let from_cache = match peer_cache {
+                    Some(ref cache) => cache.fetch(checksum, task.driver_info.file_size, Path::new(&task.file_path), &cancel, &mut report).await,
+                    None => Ok(None),
+                };
+                let result = match from_cache {
+                    Ok(Some((source, _))) => {
+                        tracing::info!("从局域网缓存获取驱动包: {} {:?}", task.id, source);
+                        Ok(())
+                    }
+                    Err(e @ HamsterError::Cancelled(_)) => Err(e.to_string()),
+                    other => {
+                        if let Err(e) = other {
+                            tracing::warn!("查找局域网缓存失败: {}", e);
+                        }
+                        fetcher.download_resumable(&task.download_url, &task.file_path, &cancel, &limiters, &mut report).await
+                            .map(|_| ())
+                            .map_err(|e| e.to_string())
+                    }
+                };
+                if let (Ok(()), Some(cache)) = (&result, peer_cache) {
+                    if let Err(e) = cache.publish(Path::new(&task.file_path), checksum).await {
+                        tracing::warn!("保存驱动包到缓存失败: {} {}", task.id, e);
+                    }
+                }
+                if let Err(e) = queue.complete_task(&task.id, result) {
+                    tracing::warn!("更新下载任务状态失败: {} {}", task.id, e);
+                }
+            });
+        }
+        Ok(())
     }
 
-    /// 取消任务
-    pub async fn cancel_task(&self, task_id: &str) -> Result<()> {
-        let mut tasks = self.tasks.lock().await;
-        if let Some(task) = tasks.get_mut(task_id) {
-            task.status = DownloadStatus::Cancelled;
-            Ok(())
-        } else {
-            Err(HamsterError::DownloadError("任务不存在".to_string()))
+    // 中断正在进行的下载并重新排队，保留已下载的部分
+    fn requeue_running(&self) {
+        let mut tasks = self.lock_tasks();
+        for (task_id, token) in self.lock_running().iter() {
+            if let Some(task) = tasks.get_mut(task_id) {
+                if task.status == DownloadStatus::Downloading {
+                    task.status = DownloadStatus::Queued;
+                    task.speed = 0;
+                    task.eta = None;
+                }
+            }
+            token.cancel();
         }
     }
 
+    /// 检查并修改任务状态，返回修改后的任务
+    fn transition(&self, task_id: &str, allowed: fn(&DownloadTask) -> bool,
+                  status: DownloadStatus, error: &str) -> Result<DownloadTask> {
+        let mut tasks = self.lock_tasks();
+        let task = tasks.get_mut(task_id)
+            .ok_or_else(|| HamsterError::DownloadError("任务不存在".to_string()))?;
+        if !allowed(task) {
+            return Err(HamsterError::DownloadError(error.to_string()));
+        }
+        if task.status == DownloadStatus::Cancelled {
+            // 取消时已删除下载的文件
+            task.downloaded = 0;
+            task.progress = 0.0;
+        }
+        task.status = status;
+        task.error = None;
+        task.speed = 0;
+        task.eta = None;
+        self.tracker.set_status(task_id, status, status.label())?;
+        Ok(task.clone())
+    }
+
+    // 停止正在进行的下载，返回任务是否正在下载
+    fn stop_download(&self, task_id: &str) -> bool {
+        match self.lock_running().get(task_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn lock_tasks(&self) -> MutexGuard<'_, HashMap<String, DownloadTask>> {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_large_downloads(&self) -> MutexGuard<'_, LargeDownloadPolicy> {
+        self.large_downloads.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_running(&self) -> MutexGuard<'_, HashMap<String, CancellationToken>> {
+        self.running.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     /// 生成任务ID
     fn generate_task_id(&self, driver_info: &DriverInfo) -> String {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
-        
+
         let mut hasher = DefaultHasher::new();
-        driver_info.name.hash(&mut hasher);
-        driver_info.current_version.to_string().hash(&mut hasher);
+        driver_info.driver_name.hash(&mut hasher);
+        driver_info.driver_version.hash(&mut hasher);
         driver_info.hardware_id.hash(&mut hasher);
-        
+
         format!("{:x}", hasher.finish())
     }
+}
 
-    /// 获取活跃下载数量
-    pub async fn get_active_download_count(&self) -> usize {
-        let active_count = self.active_downloads.lock().await;
-        *active_count
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::{http_response, HttpStub, TempDir};
+    use chrono::TimeZone;
+
+    fn driver(hardware_id: &str) -> DriverInfo {
+        DriverInfo {
+            driver_id: hardware_id.to_string(),
+            hardware_id: hardware_id.to_string(),
+            driver_name: "Audio Driver".to_string(),
+            driver_version: "1.0".to_string(),
+            driver_url: "https://example.com/driver.exe".to_string(),
+            manufacturer: "Realtek".to_string(),
+            release_date: String::new(),
+            file_size: 0,
+            checksum: String::new(),
+            release_notes_url: None,
+        }
     }
 
-    /// 获取队列大小
-    pub async fn get_queue_size(&self) -> usize {
-        let tasks = self.tasks.lock().await;
-        tasks.len()
+    #[test]
+    fn test_pause_resume_cancel_and_restore() {
+        let root = TempDir::new("download_queue");
+        let state_path = root.join("download_queue.json");
+        let partial = root.join("b.exe");
+
+        let queue = DownloadQueue::open(1, state_path.clone()).unwrap();
+        let a = queue.add_task(driver("PCI\\A"), "https://example.com/a".to_string(), root.join("a.exe").display().to_string()).unwrap();
+        let b = queue.add_task(driver("PCI\\B"), "https://example.com/b".to_string(), partial.display().to_string()).unwrap();
+        assert_eq!(queue.add_task(driver("PCI\\A"), String::new(), String::new()).unwrap(), a);
+
+        let token = queue.start_task(&a).unwrap();
+        assert!(queue.start_task(&b).is_err(), "超过并发数");
+        queue.update_progress(&a, 50, 200).unwrap();
+        assert_eq!(queue.get_task(&a).unwrap().progress, 25.0);
+
+        // 暂停会停止下载，下载随后以取消结束但保留进度
+        queue.pause_task(&a).unwrap();
+        assert!(token.is_cancelled());
+        queue.complete_task(&a, Err("已取消".to_string())).unwrap();
+        let task = queue.get_task(&a).unwrap();
+        assert_eq!((task.status, task.downloaded, task.error), (DownloadStatus::Paused, 50, None));
+        queue.resume_task(&a).unwrap();
+        let _ = queue.start_task(&a).unwrap();
+
+        std::fs::write(&partial, b"partial").unwrap();
+        queue.cancel_task(&b).unwrap();
+        assert!(!partial.exists());
+        queue.retry_task(&b).unwrap();
+        assert_eq!(queue.get_task_status(&b).unwrap(), DownloadStatus::Queued);
+
+        // 重启后正在下载的任务重新排队
+        let restored = DownloadQueue::open(1, state_path).unwrap();
+        let tasks = restored.get_all_tasks();
+        assert_eq!(tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>(), vec![a.clone(), b]);
+        assert_eq!((tasks[0].status, tasks[0].downloaded, tasks[0].total), (DownloadStatus::Queued, 50, 200));
+    }
+
+    #[test]
+    fn test_priority_host_limit_and_dedup() {
+        let queue = DownloadQueue::new(3);
+        queue.set_max_downloads_per_host(1);
+        let add = |hardware_id: &str, url: &str, priority| {
+            queue.add_task_with_priority(driver(hardware_id), url.to_string(), format!("{}.exe", hardware_id), priority).unwrap()
+        };
+        let a = add("PCI\\A", "https://vendor.com/a", DownloadPriority::Scheduled);
+        let b = add("PCI\\B", "https://vendor.com/b", DownloadPriority::User);
+        let c = add("PCI\\C", "https://mirror.com/c", DownloadPriority::Scheduled);
+
+        // 同一地址再次请求时复用已有任务并提升优先级
+        assert_eq!(add("PCI\\A2", "https://vendor.com/a", DownloadPriority::User), a);
+        assert_eq!(queue.get_task(&a).unwrap().priority, DownloadPriority::User);
+        assert_eq!(queue.get_queue_size(), 3);
+
+        assert_eq!(queue.next_task().unwrap().id, a);
+        queue.start_task(&a).unwrap();
+        // vendor.com 已达到每个主机的上限，b 的优先级更高也要等待
+        assert_eq!(queue.next_task().unwrap().id, c);
+        assert!(queue.start_task(&b).is_err());
+        queue.start_task(&c).unwrap();
+        assert!(queue.next_task().is_none());
+
+        queue.complete_task(&a, Ok(())).unwrap();
+        assert_eq!(queue.next_task().unwrap().id, b);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_large_downloads_wait_for_window() {
+        let queue = DownloadQueue::new(2);
+        queue.apply_config(&DownloadConfig {
+            large_download_threshold_mb: 100,
+            large_download_windows: vec![MaintenanceWindow {
+                days: Vec::new(),
+                start: "19:00".to_string(),
+                end: "07:00".to_string(),
+            }],
+            ..DownloadConfig::default()
+        });
+        let mut large = driver("PCI\\LARGE");
+        large.file_size = 200 * 1024 * 1024;
+        let big = queue.add_task(large, "https://vendor.com/big".to_string(), "big.exe".to_string()).unwrap();
+        let small = queue.add_task(driver("PCI\\SMALL"), "https://vendor.com/small".to_string(), "small.exe".to_string()).unwrap();
+        let at = |hour| Local.with_ymd_and_hms(2024, 3, 4, hour, 0, 0).unwrap();
+
+        // 白天大文件等待，小文件照常下载
+        assert!(queue.apply_download_windows(at(12)));
+        assert_eq!(queue.get_task_status(&big).unwrap(), DownloadStatus::Waiting);
+        assert_eq!(queue.next_task().unwrap().id, small);
+        assert!(!queue.apply_download_windows(at(13)));
+
+        // 进入时间段后继续，时间段结束时停止下载并保留进度
+        assert!(queue.apply_download_windows(at(20)));
+        let token = queue.start_task(&big).unwrap();
+        queue.update_progress(&big, 1024, 200 * 1024 * 1024).unwrap();
+        assert!(queue.apply_download_windows(at(8)));
+        assert!(token.is_cancelled());
+        queue.complete_task(&big, Err("已取消".to_string())).unwrap();
+        let task = queue.get_task(&big).unwrap();
+        assert_eq!((task.status, task.downloaded), (DownloadStatus::Waiting, 1024));
+    }
+
+    #[tokio::test]
+    async fn test_download_until_finished_or_cancelled() {
+        // 第一个请求返回驱动包，之后的请求不响应
+        let server = HttpStub::serve_then_stall(vec![http_response("200 OK", &[], "driver")]).await;
+        let url = server.url();
+
+        let root = TempDir::new("queue_download");
+        let fetcher = Arc::new(DriverFetcher::new("localhost".to_string(), 6800, root.path().display().to_string()));
+        let queue = DownloadQueue::new(2);
+        let path = root.join("a.exe");
+        let a = queue.add_task_with_priority(driver("PCI\\A"), format!("{}/a", url), path.display().to_string(), DownloadPriority::Scheduled).unwrap();
+        let mut reported = Vec::new();
+        let task = queue.download(&a, fetcher.clone(), &CancellationToken::new(), None, |task| reported.push(task.status)).await.unwrap();
+        assert_eq!((task.status, task.priority), (DownloadStatus::Completed, DownloadPriority::Scheduled));
+        assert_eq!(std::fs::read(&path).unwrap(), b"driver");
+        assert_eq!(reported.last(), Some(&DownloadStatus::Completed));
+
+        // 取消后等待下载停止，任务以取消结束
+        let b = queue.add_task(driver("PCI\\B"), format!("{}/b", url), root.join("b.exe").display().to_string()).unwrap();
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            canceller.cancel();
+        });
+        let error = queue.download(&b, fetcher, &cancel, None, |_| ()).await.unwrap_err();
+        assert!(matches!(error, HamsterError::Cancelled(_)), "{}", error);
+        assert_eq!(queue.get_task_status(&b).unwrap(), DownloadStatus::Cancelled);
+        assert!(queue.lock_running().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_download_waits_for_running_workers() {
+        let server = HttpStub::serve(vec![http_response("200 OK", &[], "driver")]).await;
+        let root = TempDir::new("queue_workers_download");
+        let state_path = root.join("download_queue.json");
+        let fetcher = Arc::new(DriverFetcher::new("localhost".to_string(), 6800, root.path().display().to_string()));
+        let queue = DownloadQueue::open(1, state_path.clone()).unwrap();
+        let workers = queue.start_workers(fetcher.clone());
+
+        // 工作池负责下载，download 只等待任务结束，不会再次请求
+        let path = root.join("a.exe");
+        let a = queue.add_task(driver("PCI\\A"), format!("{}/a", server.url()), path.display().to_string()).unwrap();
+        let task = queue.download(&a, fetcher, &CancellationToken::new(), None, |_| ()).await.unwrap();
+        assert_eq!(task.status, DownloadStatus::Completed);
+        assert_eq!(server.requests().await.len(), 1);
+
+        workers.stop();
+        while !workers.is_finished() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let reopened = DownloadQueue::open(1, state_path).unwrap();
+        assert_eq!(reopened.get_task_status(&a).unwrap(), DownloadStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_download_waits_outside_window() {
+        use chrono::{Datelike, Duration as ChronoDuration};
+
+        // 时间段只在后天，现在开始的大文件下载必须等待，不能发出请求
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/big", listener.local_addr().unwrap());
+        let day = (Local::now() + ChronoDuration::days(2)).weekday().to_string().to_lowercase();
+        let queue = DownloadQueue::new(1);
+        queue.apply_config(&DownloadConfig {
+            large_download_threshold_mb: 1,
+            large_download_windows: vec![MaintenanceWindow { days: vec![day], start: "00:00".to_string(), end: "23:59".to_string() }],
+            ..DownloadConfig::default()
+        });
+        let mut large = driver("PCI\\LARGE");
+        large.file_size = 2 * 1024 * 1024;
+        let root = TempDir::new("queue_window");
+        let big = queue.add_task_with_priority(large, url, root.join("big.exe").display().to_string(), DownloadPriority::Scheduled).unwrap();
+
+        let fetcher = Arc::new(DriverFetcher::new("localhost".to_string(), 6800, root.path().display().to_string()));
+        let task = queue.download(&big, fetcher, &CancellationToken::new(), None, |_| ()).await.unwrap();
+        assert_eq!((task.status, task.downloaded), (DownloadStatus::Waiting, 0));
+        assert!(tokio::time::timeout(Duration::from_millis(100), listener.accept()).await.is_err());
+        assert!(!root.join("big.exe").exists());
+    }
+}
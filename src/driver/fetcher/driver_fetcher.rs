@@ -2,13 +2,14 @@
 
 use crate::types::hardware_types::DeviceInfo;
 use crate::types::driver_types::{DriverInfo, DriverStatus};
-use crate::utils::error::{HamsterError, Result};
+use crate::utils::error::Result;
+use crate::utils::{http_client, HttpClient};
 use async_trait::async_trait;
 
 /// 驱动获取器
 pub struct DriverFetcher {
     /// HTTP客户端
-    client: reqwest::Client,
+    client: HttpClient,
     /// 缓存管理器
     cache: std::sync::Arc<tokio::sync::Mutex<super::cache_manager::CacheManager>>,
 }
@@ -16,10 +17,7 @@ pub struct DriverFetcher {
 impl DriverFetcher {
     /// 创建新的驱动获取器
     pub fn new() -> Result<Self> {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .map_err(|e| HamsterError::NetworkError(format!("创建HTTP客户端失败: {}", e)))?;
+        let client = http_client();
 
         let cache = std::sync::Arc::new(tokio::sync::Mutex::new(super::cache_manager::CacheManager::new()?));
         
@@ -0,0 +1,11 @@
+//! 驱动下载队列、进度跟踪和局域网驱动包缓存
+
+mod download_queue;
+mod package_store;
+mod peer_cache;
+mod progress_tracker;
+
+pub use download_queue::*;
+pub use package_store::*;
+pub use peer_cache::*;
+pub use progress_tracker::*;
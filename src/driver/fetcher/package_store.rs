@@ -0,0 +1,100 @@
+//! 驱动包内容存储
+//!
+//! 下载完成的驱动包按 SHA-256 保存一份（packages/ab/abcdef...），供本机再次使用和局域网中的其他计算机下载。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use sha2::{Digest, Sha256};
+use crate::installer::copy_file_verified;
+use crate::utils::{HamsterError, Result};
+
+const TEMP_SUFFIX: &str = ".tmp";
+
+/// 64 位十六进制的 SHA-256 转为小写，其他格式返回 None
+pub fn normalize_sha256(value: &str) -> Option<String> {
+    let value = value.trim();
+    (value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit())).then(|| value.to_ascii_lowercase())
+}
+
+/// 按 SHA-256 存放驱动包的目录
+#[derive(Debug, Clone)]
+pub struct PackageStore {
+    root: PathBuf,
+}
+
+impl PackageStore {
+    /// 在应用数据目录下打开存储
+    pub fn open_default() -> Result<Self> {
+        Self::open_in(&crate::utils::get_app_data_dir()?)
+    }
+
+    /// 在指定的应用数据目录下打开存储
+    pub fn open_in(data_dir: &Path) -> Result<Self> {
+        Self::open(&data_dir.join("packages"))
+    }
+
+    pub fn open(root: &Path) -> Result<Self> {
+        fs::create_dir_all(root)?;
+        Ok(Self { root: root.to_path_buf() })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 已保存的驱动包路径，不存在或哈希格式不对时返回 None
+    pub fn get(&self, sha256: &str) -> Option<PathBuf> {
+        let path = package_path(&self.root, &normalize_sha256(sha256)?);
+        path.is_file().then_some(path)
+    }
+
+    /// 复制文件到存储并校验哈希
+    ///
+    /// 总是复制而不使用硬链接，之后改动原文件不会影响存储中的驱动包。
+    /// 阻塞执行，在异步任务中应放到 spawn_blocking 中调用。
+    pub fn insert(&self, file: &Path, sha256: &str) -> Result<PathBuf> {
+        let hash = normalize_sha256(sha256)
+            .ok_or_else(|| HamsterError::ValidationError(format!("无效的SHA-256: {}", sha256)))?;
+        let path = package_path(&self.root, &hash);
+        if path.is_file() {
+            return Ok(path);
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        copy_verified(file, &path, &hash)?;
+        Ok(path)
+    }
+}
+
+/// 存储目录或共享文件夹中驱动包的位置
+pub fn package_path(root: &Path, sha256: &str) -> PathBuf {
+    root.join(&sha256[..2]).join(sha256)
+}
+
+/// 复制文件到 dest 并校验 SHA-256，不匹配时删除已复制的文件，返回文件大小
+///
+/// 先写到同目录的临时文件，校验通过后再替换 dest。
+pub fn copy_verified(source: &Path, dest: &Path, sha256: &str) -> Result<u64> {
+    let (size, actual) = copy_file_verified(source, dest, sha256)
+        .map_err(|e| HamsterError::IoError(e.to_string()))?;
+    if !actual.eq_ignore_ascii_case(sha256.trim()) {
+        return Err(HamsterError::ValidationError(format!("文件校验和不匹配: 期望 {}，实际 {}", sha256.trim(), actual)));
+    }
+    Ok(size)
+}
+
+/// 哈希结果与期望的 SHA-256 比较
+pub fn verify_digest(hasher: Sha256, sha256: &str) -> Result<()> {
+    let actual = format!("{:x}", hasher.finalize());
+    if !actual.eq_ignore_ascii_case(sha256.trim()) {
+        return Err(HamsterError::ValidationError(format!("文件校验和不匹配: 期望 {}，实际 {}", sha256.trim(), actual)));
+    }
+    Ok(())
+}
+
+pub fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(TEMP_SUFFIX);
+    path.with_file_name(name)
+}
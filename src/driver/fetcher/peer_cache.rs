@@ -0,0 +1,370 @@
+//! 局域网驱动包缓存
+//!
+//! 内置的 HTTP 服务按 SHA-256 提供本机存储中的驱动包（GET /packages/<sha256>），
+//! 下载有校验和的驱动时先在本机存储、共享文件夹和其他计算机中查找，收到后校验哈希，
+//! 都没有时再从厂商服务器下载。
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use futures_util::StreamExt;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use crate::config::PeerCacheConfig;
+use crate::driver::fetcher::{copy_verified, normalize_sha256, package_path, temp_path, verify_digest, PackageStore};
+use crate::utils::{lan_http_client, CancellationToken, HamsterError, Result};
+
+const PACKAGES_PATH: &str = "/packages/";
+// 同时处理的连接数
+const MAX_CONNECTIONS: usize = 8;
+// 请求头的最大长度
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+// 读取请求头的超时时间，超时后关闭连接，避免不发送请求的连接占满连接数
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// 不知道驱动包大小时从其他计算机下载的上限
+const MAX_PACKAGE_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+
+/// 驱动包的来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeerSource {
+    Local,
+    SharedFolder(PathBuf),
+    Peer(String),
+}
+
+/// 按配置查找和发布驱动包，克隆后共享同一存储
+#[derive(Debug, Clone)]
+pub struct PeerCache {
+    config: PeerCacheConfig,
+    store: Arc<PackageStore>,
+}
+
+impl PeerCache {
+    pub fn new(config: PeerCacheConfig, store: PackageStore) -> Self {
+        Self { config, store: Arc::new(store) }
+    }
+
+    /// 配置需要本机存储时在默认位置打开，否则返回 None
+    pub fn open_default(config: &PeerCacheConfig) -> Result<Option<Self>> {
+        if !config.uses_store() {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(config.clone(), PackageStore::open_default()?)))
+    }
+
+    /// 同 open_default，存储位于指定的应用数据目录
+    pub fn open_in(config: &PeerCacheConfig, data_dir: &Path) -> Result<Option<Self>> {
+        if !config.uses_store() {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(config.clone(), PackageStore::open_in(data_dir)?)))
+    }
+
+    pub fn config(&self) -> &PeerCacheConfig {
+        &self.config
+    }
+
+    pub fn store(&self) -> &PackageStore {
+        &self.store
+    }
+
+    /// 依次从本机存储、共享文件夹和其他计算机获取驱动包到 dest，校验通过后返回来源和大小，都没有时返回 None
+    ///
+    /// size 为驱动信息中的文件大小（未知时为0），从其他计算机下载时不接收超过该大小的内容。
+    pub async fn fetch(&self, sha256: &str, size: u64, dest: &Path, cancel: &CancellationToken,
+                       mut progress: impl FnMut(u64, u64)) -> Result<Option<(PeerSource, u64)>> {
+        let Some(hash) = normalize_sha256(sha256) else {
+            return Ok(None);
+        };
+        if !self.config.enabled {
+            return Ok(None);
+        }
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut candidates: Vec<(PeerSource, PathBuf)> = self.store.get(&hash)
+            .map(|path| (PeerSource::Local, path))
+            .into_iter()
+            .collect();
+        if let Some(ref folder) = self.config.shared_folder {
+            let folder = Path::new(folder);
+            for path in [package_path(folder, &hash), folder.join(&hash)] {
+                candidates.push((PeerSource::SharedFolder(path.clone()), path));
+            }
+        }
+        for (source, path) in candidates {
+            cancel.check("获取驱动包")?;
+            if !path.is_file() {
+                continue;
+            }
+            let (dest, hash) = (dest.to_path_buf(), hash.clone());
+            let copied = tokio::task::spawn_blocking(move || copy_verified(&path, &dest, &hash)).await
+                .map_err(|e| HamsterError::IoError(e.to_string()))?;
+            match copied {
+                Ok(size) => {
+                    progress(size, size);
+                    return Ok(Some((source, size)));
+                }
+                Err(e) => tracing::warn!("{:?} 中的驱动包不可用: {}", source, e),
+            }
+        }
+
+        for peer in &self.config.peers {
+            cancel.check("获取驱动包")?;
+            let url = format!("{}{}{}", peer.trim_end_matches('/'), PACKAGES_PATH, hash);
+            // 先用短超时确认对方有该驱动包，避免离线的计算机拖慢下载
+            let available = lan_http_client().head(&url)
+                .timeout(self.config.peer_timeout())
+                .send().await
+                .is_ok_and(|response| response.status().is_success());
+            if !available {
+                continue;
+            }
+            match download_from_peer(&url, dest, &hash, size, cancel, &mut progress).await {
+                Ok(size) => return Ok(Some((PeerSource::Peer(peer.clone()), size))),
+                Err(HamsterError::Cancelled(operation)) => return Err(HamsterError::Cancelled(operation)),
+                Err(e) => tracing::warn!("从 {} 获取驱动包失败: {}", peer, e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// 把下载完成的驱动包保存到本机存储，哈希不匹配时不保存
+    pub async fn publish(&self, file: &Path, sha256: &str) -> Result<()> {
+        if normalize_sha256(sha256).is_none() {
+            return Ok(());
+        }
+        let (store, file, sha256) = (self.store.clone(), file.to_path_buf(), sha256.to_string());
+        tokio::task::spawn_blocking(move || store.insert(&file, &sha256)).await
+            .map_err(|e| HamsterError::IoError(e.to_string()))?
+            .map(|_| ())
+    }
+}
+
+async fn download_from_peer(url: &str, dest: &Path, sha256: &str, size: u64, cancel: &CancellationToken,
+                            progress: &mut impl FnMut(u64, u64)) -> Result<u64> {
+    let limit = if size > 0 { size } else { MAX_PACKAGE_SIZE };
+    let response = lan_http_client().get(url).send().await?.error_for_status()?;
+    let total = response.content_length().unwrap_or(0);
+    if total > limit {
+        return Err(HamsterError::ValidationError(format!("驱动包大小 {} 超过预期的 {} 字节", total, limit)));
+    }
+    let temp = temp_path(dest);
+    let result = async {
+        let mut file = tokio::fs::File::create(&temp).await?;
+        let mut hasher = Sha256::new();
+        let mut downloaded = 0u64;
+        let mut stream = Box::pin(response.bytes_stream());
+        loop {
+            let chunk = tokio::select! {
+                chunk = stream.next() => chunk,
+                _ = cancel.cancelled() => return Err(HamsterError::Cancelled("获取驱动包".to_string())),
+            };
+            let Some(chunk) = chunk else { break };
+            let chunk = chunk?;
+            if downloaded + chunk.len() as u64 > limit {
+                return Err(HamsterError::ValidationError(format!("驱动包超过预期的 {} 字节", limit)));
+            }
+            hasher.update(&chunk);
+            file.write_all(&chunk).await?;
+            downloaded += chunk.len() as u64;
+            progress(downloaded, total);
+        }
+        file.flush().await?;
+        drop(file);
+        verify_digest(hasher, sha256)?;
+        tokio::fs::rename(&temp, dest).await?;
+        Ok(downloaded)
+    }.await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&temp).await;
+    }
+    result
+}
+
+/// 向局域网提供驱动包的 HTTP 服务，停止或丢弃时关闭
+pub struct PeerCacheServer {
+    local_addr: SocketAddr,
+    shutdown: CancellationToken,
+    handle: JoinHandle<()>,
+}
+
+impl PeerCacheServer {
+    /// 在 address 上开始提供 store 中的驱动包，需要在 tokio 运行时中调用
+    pub fn start(store: PackageStore, address: &str) -> Result<Self> {
+        let listener = std::net::TcpListener::bind(address)
+            .map_err(|e| HamsterError::NetworkError(format!("无法监听 {}: {}", address, e)))?;
+        listener.set_nonblocking(true)?;
+        let listener = TcpListener::from_std(listener)?;
+        let local_addr = listener.local_addr()?;
+
+        let shutdown = CancellationToken::new();
+        let stopped = shutdown.clone();
+        let store = Arc::new(store);
+        let handle = tokio::spawn(async move {
+            let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+            loop {
+                let (stream, peer) = tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            tracing::warn!("接受驱动包缓存连接失败: {}", e);
+                            continue;
+                        }
+                    },
+                    _ = stopped.cancelled() => break,
+                };
+                let Ok(permit) = connections.clone().try_acquire_owned() else {
+                    let _ = respond(stream, "503 Service Unavailable").await;
+                    continue;
+                };
+                let store = store.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_connection(stream, &store).await {
+                        tracing::debug!("处理 {} 的驱动包请求失败: {}", peer, e);
+                    }
+                    drop(permit);
+                });
+            }
+        });
+        Ok(Self { local_addr, shutdown, handle })
+    }
+
+    /// 配置了提供驱动包时在配置的地址启动
+    pub fn start_from_config(config: &PeerCacheConfig) -> Result<Option<Self>> {
+        if !config.serve {
+            return Ok(None);
+        }
+        Ok(Some(Self::start(PackageStore::open_default()?, &config.listen_address)?))
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn stop(&self) {
+        self.shutdown.cancel();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
+
+impl Drop for PeerCacheServer {
+    fn drop(&mut self) {
+        self.shutdown.cancel();
+    }
+}
+
+// 处理一个请求，只支持 GET 和 HEAD /packages/<sha256>，响应后关闭连接
+async fn serve_connection(mut stream: TcpStream, store: &PackageStore) -> Result<()> {
+    let head = match tokio::time::timeout(REQUEST_TIMEOUT, read_request_head(&mut stream)).await {
+        Ok(Ok(Some(head))) => head,
+        Ok(Ok(None)) => return Ok(()),
+        Ok(Err(e)) => return Err(e),
+        Err(_) => return respond(stream, "408 Request Timeout").await,
+    };
+    if head.len() > MAX_REQUEST_HEAD {
+        return respond(stream, "431 Request Header Fields Too Large").await;
+    }
+    let head = String::from_utf8_lossy(&head);
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+    let (method, target) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+    if method != "GET" && method != "HEAD" {
+        return respond(stream, "405 Method Not Allowed").await;
+    }
+    let Some(path) = target.strip_prefix(PACKAGES_PATH).and_then(|hash| store.get(hash)) else {
+        return respond(stream, "404 Not Found").await;
+    };
+    let mut file = tokio::fs::File::open(&path).await?;
+    let length = file.metadata().await?.len();
+    respond_headers(&mut stream, "200 OK", length).await?;
+    if method == "GET" {
+        tokio::io::copy(&mut file, &mut stream).await?;
+    }
+    stream.shutdown().await?;
+    Ok(())
+}
+
+// 读取到请求头结束或超过长度上限，请求头完整前连接关闭时返回 None
+async fn read_request_head(stream: &mut TcpStream) -> Result<Option<Vec<u8>>> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") && head.len() <= MAX_REQUEST_HEAD {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+    Ok(Some(head))
+}
+
+async fn respond(mut stream: TcpStream, status: &str) -> Result<()> {
+    respond_headers(&mut stream, status, status.len() as u64).await?;
+    stream.write_all(status.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+async fn respond_headers(stream: &mut TcpStream, status: &str, length: u64) -> Result<()> {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nContent-Type: application/octet-stream\r\nConnection: close\r\n\r\n",
+        status, length
+    );
+    stream.write_all(head.as_bytes()).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::TempDir;
+
+    #[tokio::test]
+    async fn test_fetch_from_peer_and_shared_folder() {
+        let root = TempDir::new("peer_cache");
+        let content = b"driver package".repeat(1000);
+        let hash = format!("{:x}", Sha256::digest(&content));
+        let source = root.join("driver.exe");
+        std::fs::write(&source, &content).unwrap();
+
+        // 提供驱动包的计算机
+        let serving = PackageStore::open(&root.join("serving")).unwrap();
+        serving.insert(&source, &hash).unwrap();
+        let server = PeerCacheServer::start(serving, "127.0.0.1:0").unwrap();
+
+        let config = PeerCacheConfig {
+            enabled: true,
+            peers: vec![format!("http://{}", server.local_addr())],
+            ..PeerCacheConfig::default()
+        };
+        let cache = PeerCache::new(config.clone(), PackageStore::open(&root.join("local")).unwrap());
+        let dest = root.join("downloads").join("driver.exe");
+        let cancel = CancellationToken::new();
+        // 超过驱动信息中大小的内容不会被接收
+        assert!(cache.fetch(&hash, 100, &dest, &cancel, |_, _| {}).await.unwrap().is_none());
+        assert!(!dest.exists());
+        let (source, size) = cache.fetch(&hash, content.len() as u64, &dest, &cancel, |_, _| {}).await.unwrap().unwrap();
+        assert_eq!(source, PeerSource::Peer(config.peers[0].clone()));
+        assert_eq!((size, std::fs::read(&dest).unwrap()), (content.len() as u64, content.clone()));
+
+        // 共享文件夹中内容被改动的文件不会被使用，其他计算机也没有的驱动包返回 None
+        let shared = root.join("shared");
+        std::fs::create_dir_all(&shared).unwrap();
+        let other = "0".repeat(64);
+        std::fs::write(shared.join(&other), b"tampered").unwrap();
+        let cache = PeerCache::new(PeerCacheConfig { shared_folder: Some(shared.display().to_string()), ..config },
+                                   PackageStore::open(&root.join("local")).unwrap());
+        assert!(cache.fetch(&other, 0, &root.join("other.exe"), &cancel, |_, _| {}).await.unwrap().is_none());
+        assert!(!root.join("other.exe").exists());
+
+        server.stop();
+    }
+}
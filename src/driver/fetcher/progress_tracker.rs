@@ -0,0 +1,190 @@
+//! 进度跟踪器
+//!
+//! 负责跟踪下载进度的组件，根据进度采样计算速度和剩余时间
+
+use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use crate::driver::fetcher::DownloadStatus;
+use crate::utils::{HamsterError, Result};
+
+// 两次采样间隔不足时不重新计算速度，避免小块数据造成速度抖动
+const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+// 新速度样本在平滑速度中的权重
+const SPEED_SMOOTHING: f64 = 0.3;
+
+#[derive(Debug, Clone)]
+pub struct TransferProgress {
+    pub downloaded: u64,
+    pub total: u64,
+    pub speed: u64, // 字节/秒
+    pub percentage: f64,
+    /// 速度或总大小未知时为空
+    pub eta: Option<Duration>,
+    pub status: DownloadStatus,
+    pub message: String,
+    last_sample: Option<(Instant, u64)>,
+}
+
+impl TransferProgress {
+    fn record(&mut self, downloaded: u64, total: u64, now: Instant) {
+        if total > 0 {
+            self.total = total;
+        }
+        self.downloaded = if self.total > 0 { downloaded.min(self.total) } else { downloaded };
+        self.percentage = if self.total > 0 {
+            (self.downloaded as f64 / self.total as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        match self.last_sample {
+            Some((at, bytes)) => {
+                let elapsed = now.saturating_duration_since(at);
+                if elapsed < MIN_SAMPLE_INTERVAL {
+                    return;
+                }
+                let sample = self.downloaded.saturating_sub(bytes) as f64 / elapsed.as_secs_f64();
+                self.speed = if self.speed == 0 {
+                    sample as u64
+                } else {
+                    (self.speed as f64 * (1.0 - SPEED_SMOOTHING) + sample * SPEED_SMOOTHING) as u64
+                };
+            }
+            None => self.speed = 0,
+        }
+        self.last_sample = Some((now, self.downloaded));
+        self.eta = if self.speed > 0 && self.total > 0 {
+            Some(Duration::from_secs(self.total.saturating_sub(self.downloaded) / self.speed))
+        } else {
+            None
+        };
+    }
+}
+
+#[derive(Default)]
+pub struct ProgressTracker {
+    progresses: Arc<Mutex<HashMap<String, TransferProgress>>>,
+}
+
+impl ProgressTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始跟踪下载，downloaded 为续传时已有的字节数
+    pub fn start_tracking(&self, download_id: String, downloaded: u64, total_size: u64) -> Result<()> {
+        let mut progresses = self.progresses.lock()
+            .map_err(|_| HamsterError::Unknown("锁获取失败".to_string()))?;
+
+        let mut progress = TransferProgress {
+            downloaded: 0,
+            total: total_size,
+            speed: 0,
+            percentage: 0.0,
+            eta: None,
+            status: DownloadStatus::Downloading,
+            message: "开始下载".to_string(),
+            last_sample: None,
+        };
+        progress.record(downloaded, total_size, Instant::now());
+        progresses.insert(download_id, progress);
+
+        Ok(())
+    }
+
+    /// 更新下载进度，total 为 0 表示沿用已知的总大小
+    pub fn update_progress(&self, download_id: &str, downloaded: u64, total: u64) -> Result<Option<TransferProgress>> {
+        self.update_progress_at(download_id, downloaded, total, Instant::now())
+    }
+
+    fn update_progress_at(&self, download_id: &str, downloaded: u64, total: u64, now: Instant) -> Result<Option<TransferProgress>> {
+        let mut progresses = self.progresses.lock()
+            .map_err(|_| HamsterError::Unknown("锁获取失败".to_string()))?;
+
+        Ok(progresses.get_mut(download_id).map(|progress| {
+            progress.record(downloaded, total, now);
+            progress.clone()
+        }))
+    }
+
+    /// 设置下载状态
+    pub fn set_status(&self, download_id: &str, status: DownloadStatus, message: String) -> Result<()> {
+        let mut progresses = self.progresses.lock()
+            .map_err(|_| HamsterError::Unknown("锁获取失败".to_string()))?;
+
+        if let Some(progress) = progresses.get_mut(download_id) {
+            if status != DownloadStatus::Downloading {
+                progress.speed = 0;
+                progress.eta = None;
+                progress.last_sample = None;
+            }
+            progress.status = status;
+            progress.message = message;
+        }
+
+        Ok(())
+    }
+
+    /// 获取下载进度
+    pub fn get_progress(&self, download_id: &str) -> Result<Option<TransferProgress>> {
+        let progresses = self.progresses.lock()
+            .map_err(|_| HamsterError::Unknown("锁获取失败".to_string()))?;
+
+        Ok(progresses.get(download_id).cloned())
+    }
+
+    /// 获取所有下载进度
+    pub fn get_all_progress(&self) -> Result<Vec<(String, TransferProgress)>> {
+        let progresses = self.progresses.lock()
+            .map_err(|_| HamsterError::Unknown("锁获取失败".to_string()))?;
+
+        Ok(progresses.iter()
+            .map(|(id, progress)| (id.clone(), progress.clone()))
+            .collect::<Vec<_>>())
+    }
+
+    /// 移除下载跟踪
+    pub fn remove_tracking(&self, download_id: &str) -> Result<()> {
+        let mut progresses = self.progresses.lock()
+            .map_err(|_| HamsterError::Unknown("锁获取失败".to_string()))?;
+
+        progresses.remove(download_id);
+        Ok(())
+    }
+
+    /// 检查下载是否完成
+    pub fn is_complete(&self, download_id: &str) -> Result<bool> {
+        let progresses = self.progresses.lock()
+            .map_err(|_| HamsterError::Unknown("锁获取失败".to_string()))?;
+
+        Ok(progresses.get(download_id)
+            .is_some_and(|progress| progress.status == DownloadStatus::Completed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speed_and_eta() {
+        let tracker = ProgressTracker::new();
+        tracker.start_tracking("a".to_string(), 1000, 11_000).unwrap();
+        let start = tracker.progresses.lock().unwrap()["a"].last_sample.unwrap().0;
+
+        // 采样间隔太短时不计算速度
+        let progress = tracker.update_progress_at("a", 1500, 0, start + Duration::from_millis(100)).unwrap().unwrap();
+        assert_eq!(progress.speed, 0);
+        assert_eq!(progress.eta, None);
+
+        let progress = tracker.update_progress_at("a", 3000, 0, start + Duration::from_secs(1)).unwrap().unwrap();
+        assert_eq!(progress.speed, 2000);
+        assert_eq!(progress.eta, Some(Duration::from_secs(4)));
+        assert!((progress.percentage - 3000.0 / 11_000.0 * 100.0).abs() < 1e-9);
+
+        tracker.set_status("a", DownloadStatus::Paused, "已暂停".to_string()).unwrap();
+        let progress = tracker.get_progress("a").unwrap().unwrap();
+        assert_eq!((progress.speed, progress.eta), (0, None));
+    }
+}
@@ -1,14 +1,20 @@
 //! 安装日志记录
 //!
 //! 负责记录驱动安装过程的日志
+//!
+//! 日志以JSON Lines格式追加写入，同一操作的开始和完成记录共用一个ID，查询时合并为一条。
 
-use std::fs::OpenOptions;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
-use crate::types::driver_types::{DriverInfo, DriverVersion};
-use crate::utils::error::{HamsterError, Result};
+use serde::{Deserialize, Serialize};
+use crate::installer::DriverInfo;
+use crate::utils::{HamsterError, Result};
+use crate::t;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstallationLogEntry {
     pub id: String,
     pub hardware_id: String,
@@ -18,18 +24,34 @@ pub struct InstallationLogEntry {
     pub timestamp: DateTime<Utc>,
     pub message: String,
     pub duration: Option<u64>, // 持续时间（毫秒）
+    #[serde(default)]
+    pub change: ChangeDetails,
+}
+
+/// 操作改变了什么，用于显示历史和撤销更改
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChangeDetails {
+    pub device_name: String,
+    /// 操作前的驱动版本，未知时为空
+    pub previous_version: Option<String>,
+    /// 保存了操作前驱动的回滚点
+    pub rollback_id: Option<String>,
+    /// 回滚操作撤销的日志记录
+    pub reverts: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum InstallationAction {
     Install,
     Update,
     Uninstall,
     Backup,
     Restore,
+    Rollback,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum InstallationStatus {
     Success,
     Failed,
@@ -37,10 +59,85 @@ pub enum InstallationStatus {
     InProgress,
 }
 
+/// 日志查询条件，未设置的条件不参与过滤
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    pub hardware_id: Option<String>,
+    pub action: Option<InstallationAction>,
+    pub status: Option<InstallationStatus>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// 耗时统计（毫秒）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DurationStats {
+    pub count: usize,
+    pub total: u64,
+    pub min: u64,
+    pub max: u64,
+    pub average: u64,
+}
+
+/// 按状态汇总的日志统计
+#[derive(Debug, Clone, Default)]
+pub struct InstallationStats {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    pub in_progress: usize,
+    pub durations: DurationStats,
+}
+
 pub struct InstallationLogger {
     log_file_path: String,
 }
 
+impl InstallationLogEntry {
+    /// 成功的安装、更新或还原，有回滚点且尚未被撤销时可以撤销
+    pub fn can_revert(&self, history: &[InstallationLogEntry]) -> bool {
+        self.status == InstallationStatus::Success
+            && matches!(self.action, InstallationAction::Install | InstallationAction::Update | InstallationAction::Restore)
+            && self.change.rollback_id.is_some()
+            && !history.iter().any(|entry| {
+                entry.status == InstallationStatus::Success && entry.change.reverts.as_deref() == Some(self.id.as_str())
+            })
+    }
+}
+
+impl LogFilter {
+    pub fn with_hardware_id(mut self, hardware_id: &str) -> Self {
+        self.hardware_id = Some(hardware_id.to_string());
+        self
+    }
+
+    pub fn with_action(mut self, action: InstallationAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    pub fn with_status(mut self, status: InstallationStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn with_date_range(mut self, since: Option<DateTime<Utc>>, until: Option<DateTime<Utc>>) -> Self {
+        self.since = since;
+        self.until = until;
+        self
+    }
+
+    /// 硬件ID不区分大小写
+    pub fn matches(&self, entry: &InstallationLogEntry) -> bool {
+        self.hardware_id.as_ref().is_none_or(|id| entry.hardware_id.eq_ignore_ascii_case(id))
+            && self.action.is_none_or(|action| entry.action == action)
+            && self.status.is_none_or(|status| entry.status == status)
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp < until)
+    }
+}
+
 impl InstallationLogger {
     pub fn new(log_file_path: String) -> Self {
         Self {
@@ -48,8 +145,19 @@ impl InstallationLogger {
         }
     }
 
+    /// 使用日志目录下的 installations.jsonl
+    pub fn open_default() -> Result<Self> {
+        let path = crate::utils::get_log_dir()?.join("installations.jsonl");
+        Ok(Self::new(path.to_string_lossy().to_string()))
+    }
+
     /// 记录安装日志
     pub fn log_installation(&self, hardware_id: &str, driver_info: DriverInfo, action: InstallationAction, status: InstallationStatus, message: String) -> Result<String> {
+        self.log_change(hardware_id, driver_info, action, status, message, ChangeDetails::default())
+    }
+
+    /// 记录带有更改详情的日志
+    pub fn log_change(&self, hardware_id: &str, driver_info: DriverInfo, action: InstallationAction, status: InstallationStatus, message: String, change: ChangeDetails) -> Result<String> {
         let entry = InstallationLogEntry {
             id: self.generate_log_id(hardware_id, &action, &status),
             hardware_id: hardware_id.to_string(),
@@ -59,6 +167,7 @@ impl InstallationLogger {
             timestamp: Utc::now(),
             message,
             duration: None,
+            change,
         };
 
         self.write_log_entry(&entry)?;
@@ -67,16 +176,21 @@ impl InstallationLogger {
 
     /// 记录安装开始
     pub fn log_installation_start(&self, hardware_id: &str, driver_info: DriverInfo, action: InstallationAction) -> Result<String> {
-        let action_clone = action.clone();
+        self.log_change_start(hardware_id, driver_info, action, ChangeDetails::default())
+    }
+
+    /// 记录安装开始，完成记录沿用这里的更改详情
+    pub fn log_change_start(&self, hardware_id: &str, driver_info: DriverInfo, action: InstallationAction, change: ChangeDetails) -> Result<String> {
         let entry = InstallationLogEntry {
-            id: self.generate_log_id(hardware_id, &action_clone, &InstallationStatus::InProgress),
+            id: self.generate_log_id(hardware_id, &action, &InstallationStatus::InProgress),
             hardware_id: hardware_id.to_string(),
             driver_info,
             action,
             status: InstallationStatus::InProgress,
             timestamp: Utc::now(),
-            message: format!("开始执行 {} 操作", self.action_to_string(&action_clone)),
+            message: t!("history-action-started", action = self.action_to_string(&action)),
             duration: None,
+            change,
         };
 
         self.write_log_entry(&entry)?;
@@ -84,16 +198,28 @@ impl InstallationLogger {
     }
 
     /// 记录安装完成
+    ///
+    /// 硬件ID、驱动信息和操作类型取自开始记录；未提供耗时时按开始时间计算。
     pub fn log_installation_complete(&self, log_id: &str, status: InstallationStatus, message: String, duration: Option<u64>) -> Result<()> {
+        let start = self.read_entries()?
+            .into_iter()
+            .find(|entry| entry.id == log_id)
+            .ok_or_else(|| HamsterError::ValidationError(format!("找不到日志记录: {}", log_id)))?;
+
+        let timestamp = Utc::now();
+        let duration = duration.or_else(|| {
+            u64::try_from((timestamp - start.timestamp).num_milliseconds()).ok()
+        });
         let entry = InstallationLogEntry {
             id: log_id.to_string(),
-            hardware_id: "unknown".to_string(), // 在实际实现中，可能需要从某种存储中检索原始硬件ID
-            driver_info: DriverInfo::new("unknown", "unknown"), // 在实际实现中，可能需要从某种存储中检索原始驱动信息
-            action: InstallationAction::Install, // 在实际实现中，可能需要从某种存储中检索原始操作
+            hardware_id: start.hardware_id,
+            driver_info: start.driver_info,
+            action: start.action,
             status,
-            timestamp: Utc::now(),
+            timestamp,
             message,
             duration,
+            change: start.change,
         };
 
         self.write_log_entry(&entry)?;
@@ -102,23 +228,17 @@ impl InstallationLogger {
 
     /// 写入日志条目到文件
     fn write_log_entry(&self, entry: &InstallationLogEntry) -> Result<()> {
+        if let Some(parent) = Path::new(&self.log_file_path).parent() {
+            crate::utils::ensure_dir(parent)?;
+        }
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
             .open(&self.log_file_path)
             .map_err(|e| HamsterError::IoError(format!("打开日志文件失败: {}", e)))?;
 
-        let log_line = format!(
-            "[{}] {} - Hardware: {}, Driver: {} v{}, Action: {:?}, Status: {:?}, Message: {}\n",
-            entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
-            entry.id,
-            entry.hardware_id,
-            entry.driver_info.name,
-            entry.driver_info.current_version,
-            entry.action,
-            entry.status,
-            entry.message
-        );
+        let mut log_line = serde_json::to_string(entry)?;
+        log_line.push('\n');
 
         file.write_all(log_line.as_bytes())
             .map_err(|e| HamsterError::IoError(format!("写入日志文件失败: {}", e)))?;
@@ -126,46 +246,300 @@ impl InstallationLogger {
         Ok(())
     }
 
+    /// 读取日志文件中的所有记录，跳过无法解析的行（如旧版的纯文本日志）
+    fn read_entries(&self) -> Result<Vec<InstallationLogEntry>> {
+        let content = match fs::read_to_string(&self.log_file_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(HamsterError::IoError(format!("读取日志文件失败: {}", e))),
+        };
+
+        let mut skipped = 0;
+        let entries = content.lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(_) => {
+                    skipped += 1;
+                    None
+                }
+            })
+            .collect();
+        if skipped > 0 {
+            tracing::warn!("安装日志中有 {} 行无法解析，已跳过", skipped);
+        }
+        Ok(entries)
+    }
+
+    /// 合并同一操作的多条记录，只保留最后一条，按时间从新到旧排序
+    fn read_operations(&self) -> Result<Vec<InstallationLogEntry>> {
+        let mut order = Vec::new();
+        let mut latest: HashMap<String, InstallationLogEntry> = HashMap::new();
+        for entry in self.read_entries()? {
+            if !latest.contains_key(&entry.id) {
+                order.push(entry.id.clone());
+            }
+            latest.insert(entry.id.clone(), entry);
+        }
+
+        let mut operations: Vec<InstallationLogEntry> = order.into_iter()
+            .filter_map(|id| latest.remove(&id))
+            .collect();
+        operations.sort_by_key(|entry| std::cmp::Reverse(entry.timestamp));
+        Ok(operations)
+    }
+
     /// 生成日志ID
     fn generate_log_id(&self, hardware_id: &str, action: &InstallationAction, status: &InstallationStatus) -> String {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
-        
+
         let mut hasher = DefaultHasher::new();
         hardware_id.hash(&mut hasher);
         format!("{:?}-{:?}", action, status).hash(&mut hasher);
+        Utc::now().timestamp_nanos_opt().hash(&mut hasher);
+        std::process::id().hash(&mut hasher);
         let hash = hasher.finish();
-        
+
         format!("log_{:x}", hash)
     }
 
     /// 将操作转换为字符串
     fn action_to_string(&self, action: &InstallationAction) -> String {
-        match action {
-            InstallationAction::Install => "安装".to_string(),
-            InstallationAction::Update => "更新".to_string(),
-            InstallationAction::Uninstall => "卸载".to_string(),
-            InstallationAction::Backup => "备份".to_string(),
-            InstallationAction::Restore => "还原".to_string(),
-        }
+        action_label(action)
+    }
+
+    /// 按条件查询日志，按时间从新到旧排序
+    pub fn query(&self, filter: &LogFilter) -> Result<Vec<InstallationLogEntry>> {
+        Ok(self.read_operations()?
+            .into_iter()
+            .filter(|entry| filter.matches(entry))
+            .collect())
     }
 
     /// 读取最近的日志条目
-    pub fn read_recent_logs(&self, _count: usize) -> Result<Vec<InstallationLogEntry>> {
-        // 在实际实现中，这将从日志文件中读取最近的条目
-        // 由于实现复杂性，这里返回空向量
-        Ok(Vec::new())
+    pub fn read_recent_logs(&self, count: usize) -> Result<Vec<InstallationLogEntry>> {
+        let mut entries = self.query(&LogFilter::default())?;
+        entries.truncate(count);
+        Ok(entries)
     }
 
-    /// 清理旧日志
-    pub fn cleanup_old_logs(&self, _days: u32) -> Result<()> {
-        // 在实际实现中，这将清理指定天数之前的日志
-        Ok(())
+    /// 清理旧日志，返回删除的操作数量
+    pub fn cleanup_old_logs(&self, days: u32) -> Result<usize> {
+        let cutoff = Utc::now() - chrono::Duration::days(i64::from(days));
+        let operations = self.read_operations()?;
+        let keep: std::collections::HashSet<&str> = operations.iter()
+            .filter(|entry| entry.timestamp >= cutoff)
+            .map(|entry| entry.id.as_str())
+            .collect();
+        let removed = operations.len() - keep.len();
+        if removed == 0 {
+            return Ok(0);
+        }
+
+        let mut content = String::new();
+        for entry in self.read_entries()?.iter().filter(|entry| keep.contains(entry.id.as_str())) {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+
+        let temp_path = PathBuf::from(format!("{}.tmp", self.log_file_path));
+        fs::write(&temp_path, content)?;
+        fs::rename(&temp_path, &self.log_file_path)?;
+        Ok(removed)
     }
 
     /// 获取特定硬件的日志
-    pub fn get_logs_for_hardware(&self, _hardware_id: &str) -> Result<Vec<InstallationLogEntry>> {
-        // 在实际实现中，这将返回特定硬件ID的日志条目
-        Ok(Vec::new())
+    pub fn get_logs_for_hardware(&self, hardware_id: &str) -> Result<Vec<InstallationLogEntry>> {
+        self.query(&LogFilter::default().with_hardware_id(hardware_id))
+    }
+
+    /// 统计符合条件的操作数量和耗时
+    pub fn statistics(&self, filter: &LogFilter) -> Result<InstallationStats> {
+        let entries = self.query(filter)?;
+        let mut stats = InstallationStats {
+            total: entries.len(),
+            ..Default::default()
+        };
+
+        for entry in &entries {
+            match entry.status {
+                InstallationStatus::Success => stats.succeeded += 1,
+                InstallationStatus::Failed => stats.failed += 1,
+                InstallationStatus::Cancelled => stats.cancelled += 1,
+                InstallationStatus::InProgress => stats.in_progress += 1,
+            }
+        }
+
+        let durations: Vec<u64> = entries.iter().filter_map(|entry| entry.duration).collect();
+        if !durations.is_empty() {
+            let total: u64 = durations.iter().sum();
+            stats.durations = DurationStats {
+                count: durations.len(),
+                total,
+                min: durations.iter().copied().min().unwrap_or(0),
+                max: durations.iter().copied().max().unwrap_or(0),
+                average: total / durations.len() as u64,
+            };
+        }
+
+        Ok(stats)
+    }
+
+    /// 导出符合条件的日志为CSV，返回导出的条数
+    pub fn export_csv(&self, filter: &LogFilter, output_path: &Path) -> Result<usize> {
+        let entries = self.query(filter)?;
+        let mut content = String::from("id,timestamp,hardware_id,device_name,driver_file,previous_version,driver_version,manufacturer,action,status,duration_ms,message\n");
+
+        for entry in &entries {
+            let fields = [
+                entry.id.clone(),
+                entry.timestamp.to_rfc3339(),
+                entry.hardware_id.clone(),
+                entry.change.device_name.clone(),
+                entry.driver_info.file_name.clone(),
+                entry.change.previous_version.clone().unwrap_or_default(),
+                entry.driver_info.driver_version.clone(),
+                entry.driver_info.manufacturer.clone(),
+                format!("{:?}", entry.action),
+                format!("{:?}", entry.status),
+                entry.duration.map(|d| d.to_string()).unwrap_or_default(),
+                entry.message.clone(),
+            ];
+            let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            content.push_str(&line.join(","));
+            content.push('\n');
+        }
+
+        fs::write(output_path, content)
+            .map_err(|e| HamsterError::IoError(format!("写入CSV失败: {}", e)))?;
+        Ok(entries.len())
+    }
+}
+
+/// 操作类型的显示名称
+pub fn action_label(action: &InstallationAction) -> String {
+    match action {
+        InstallationAction::Install => t!("history-action-install"),
+        InstallationAction::Update => t!("history-action-update"),
+        InstallationAction::Uninstall => t!("history-action-uninstall"),
+        InstallationAction::Backup => t!("history-action-backup"),
+        InstallationAction::Restore => t!("history-action-restore"),
+        InstallationAction::Rollback => t!("history-action-rollback"),
+    }
+}
+
+/// 操作结果的显示名称
+pub fn status_label(status: &InstallationStatus) -> String {
+    match status {
+        InstallationStatus::Success => t!("history-status-success"),
+        InstallationStatus::Failed => t!("history-status-failed"),
+        InstallationStatus::Cancelled => t!("history-status-cancelled"),
+        InstallationStatus::InProgress => t!("history-status-in-progress"),
+    }
+}
+
+// 含逗号、引号或换行的字段加引号，内部引号写两次（RFC 4180）
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::TempDir;
+
+    fn temp_logger(name: &str) -> (InstallationLogger, TempDir) {
+        let root = TempDir::new(&format!("log_{}", name));
+        let logger = InstallationLogger::new(root.join("installations.jsonl").to_string_lossy().to_string());
+        (logger, root)
+    }
+
+    fn driver(version: &str) -> DriverInfo {
+        DriverInfo {
+            file_path: "C:\\drivers\\nv.inf".to_string(),
+            file_name: "nv.inf".to_string(),
+            hardware_id: "PCI\\VEN_10DE".to_string(),
+            manufacturer: "NVIDIA, Inc.".to_string(),
+            driver_version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_start_and_complete_are_merged_and_filtered() {
+        let (logger, root) = temp_logger("merge");
+
+        let change = ChangeDetails {
+            device_name: "NVIDIA GeForce".to_string(),
+            previous_version: Some("1.0".to_string()),
+            rollback_id: Some("rb_1".to_string()),
+            reverts: None,
+        };
+        let id = logger.log_change_start("PCI\\VEN_10DE", driver("2.0"), InstallationAction::Update, change.clone()).unwrap();
+        logger.log_installation_complete(&id, InstallationStatus::Success, "安装成功".to_string(), Some(1500)).unwrap();
+        let failed = logger.log_installation_start("USB\\VID_046D", driver("1.1"), InstallationAction::Install).unwrap();
+        logger.log_installation_complete(&failed, InstallationStatus::Failed, "签名无效, \"拒绝\"".to_string(), Some(500)).unwrap();
+
+        let entries = logger.read_recent_logs(10).unwrap();
+        assert_eq!(entries.len(), 2);
+        let nvidia = logger.get_logs_for_hardware("pci\\ven_10de").unwrap();
+        assert_eq!(nvidia.len(), 1);
+        assert_eq!(nvidia[0].status, InstallationStatus::Success);
+        assert_eq!(nvidia[0].action, InstallationAction::Update);
+        assert_eq!(nvidia[0].driver_info.driver_version, "2.0");
+        assert_eq!(nvidia[0].change, change);
+        assert!(nvidia[0].can_revert(&entries));
+
+        let failures = logger.query(&LogFilter::default().with_status(InstallationStatus::Failed)).unwrap();
+        assert_eq!(failures.len(), 1);
+        let future = logger.query(&LogFilter::default().with_date_range(Some(Utc::now() + chrono::Duration::days(1)), None)).unwrap();
+        assert!(future.is_empty());
+
+        let stats = logger.statistics(&LogFilter::default()).unwrap();
+        assert_eq!((stats.total, stats.succeeded, stats.failed), (2, 1, 1));
+        assert_eq!(stats.durations, DurationStats { count: 2, total: 2000, min: 500, max: 1500, average: 1000 });
+
+        let csv_path = root.join("history.csv");
+        assert_eq!(logger.export_csv(&LogFilter::default(), &csv_path).unwrap(), 2);
+        let csv = fs::read_to_string(&csv_path).unwrap();
+        assert!(csv.contains("\"NVIDIA, Inc.\""));
+        assert!(csv.contains("\"签名无效, \"\"拒绝\"\"\""));
+
+        // 撤销成功后不能再次撤销
+        let revert = ChangeDetails { reverts: Some(id.clone()), ..ChangeDetails::default() };
+        logger.log_change("PCI\\VEN_10DE", driver("1.0"), InstallationAction::Rollback, InstallationStatus::Success, String::new(), revert).unwrap();
+        assert!(!nvidia[0].can_revert(&logger.read_recent_logs(10).unwrap()));
+    }
+
+    #[test]
+    fn test_cleanup_removes_old_operations() {
+        let (logger, _root) = temp_logger("cleanup");
+
+        let mut old = InstallationLogEntry {
+            id: "log_old".to_string(),
+            hardware_id: "PCI\\VEN_8086".to_string(),
+            driver_info: driver("1.0"),
+            action: InstallationAction::Install,
+            status: InstallationStatus::Success,
+            timestamp: Utc::now() - chrono::Duration::days(40),
+            message: String::new(),
+            duration: None,
+            change: ChangeDetails::default(),
+        };
+        logger.write_log_entry(&old).unwrap();
+        old.id = "log_new".to_string();
+        old.timestamp = Utc::now();
+        logger.write_log_entry(&old).unwrap();
+        fs::OpenOptions::new().append(true).open(&logger.log_file_path).unwrap()
+            .write_all(b"[2024-01-01 00:00:00] legacy text line\n").unwrap();
+
+        assert_eq!(logger.cleanup_old_logs(30).unwrap(), 1);
+        let remaining = logger.read_recent_logs(10).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, "log_new");
     }
-}
\ No newline at end of file
+}
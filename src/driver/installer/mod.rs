@@ -0,0 +1,7 @@
+//! 驱动安装辅助模块
+
+mod installation_log;
+mod rollback_manager;
+
+pub use installation_log::*;
+pub use rollback_manager::*;
@@ -2,84 +2,240 @@
 //!
 //! 负责在驱动安装失败时回滚到之前的状态
 
-use crate::utils::error::{HamsterError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::config::BackupConfig;
+use crate::installer::{BackupStore, DriverInstaller, DriverInfo as InstallerDriverInfo, InstallationResult};
+use crate::utils::{HamsterError, Result};
 
-pub struct RollbackManager;
+pub struct RollbackManager {
+    index_path: PathBuf,
+    store: BackupStore,
+    max_rollback_points: usize,
+}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RollbackPoint {
     pub id: String,
     pub description: String,
-    pub backup_path: String,  // 备份文件路径
+    pub snapshot_id: String,  // 备份存储中保存安装前驱动的快照
     pub creation_time: String,
-    pub affected_drivers: Vec<String>, // 受影响的驱动列表
+    pub affected_drivers: Vec<RollbackEntry>, // 受影响的驱动列表，按安装顺序排列
+    pub status: RollbackStatus,
+}
+
+/// 回滚点中单个设备的安装前驱动
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollbackEntry {
+    pub hardware_id: String,
+    pub device_name: String,
+    pub driver_version: String,
+    pub backup_entry_id: String, // 快照清单中的驱动ID
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RollbackStatus {
+    Available,
+    RolledBack,
+}
+
+/// 回滚操作结果
+#[derive(Debug, Clone)]
+pub struct RollbackReport {
+    pub rollback_id: String,
+    pub results: Vec<(String, InstallationResult)>, // (硬件ID, 安装结果)
+}
+
+impl RollbackReport {
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|(_, result)| result.success)
+    }
 }
 
 impl RollbackManager {
-    pub fn new() -> Self {
-        Self
+    /// 使用应用数据目录下的回滚目录
+    pub fn new() -> Result<Self> {
+        Self::open(&crate::utils::get_app_data_dir()?.join("rollback"))
+    }
+
+    /// 在 dir 中保存回滚点索引和快照
+    ///
+    /// 快照使用单独的备份存储，清理用户备份时不会被删除，也不占用备份的保留数量。
+    pub fn open(dir: &Path) -> Result<Self> {
+        let store = BackupStore::open(&dir.join("store"))
+            .map_err(|e| HamsterError::BackupError(format!("打开备份存储失败: {}", e)))?;
+        Ok(Self::with_store(dir.join("rollback_points.json"), store))
+    }
+
+    pub fn with_store(index_path: PathBuf, store: BackupStore) -> Self {
+        Self {
+            index_path,
+            store,
+            max_rollback_points: BackupConfig::default().max_rollback_points,
+        }
+    }
+
+    /// 设置最大回滚点数量
+    pub fn with_max_rollback_points(mut self, max_rollback_points: usize) -> Self {
+        self.set_max_rollback_points(max_rollback_points);
+        self
+    }
+
+    /// 修改最大回滚点数量，下次创建回滚点时删除超出的旧回滚点
+    pub fn set_max_rollback_points(&mut self, max_rollback_points: usize) {
+        self.max_rollback_points = max_rollback_points.max(1);
+    }
+
+    /// 获取回滚点使用的备份存储
+    pub fn store(&self) -> &BackupStore {
+        &self.store
     }
 
     /// 创建回滚点
-    pub fn create_rollback_point(&self, description: &str, backup_path: &str, affected_drivers: Vec<String>) -> Result<RollbackPoint> {
+    ///
+    /// 超过最大数量时先删除最旧的回滚点及其快照。
+    pub fn create_rollback_point(&self, description: &str, snapshot_id: &str, affected_drivers: Vec<RollbackEntry>) -> Result<RollbackPoint> {
+        self.store.load_snapshot(snapshot_id)
+            .map_err(|e| HamsterError::BackupError(format!("回滚点引用的快照无效: {}", e)))?;
+
+        let mut points = self.load_points()?;
+        while !self.can_create_rollback_point(&points) {
+            let oldest = points.remove(0);
+            tracing::info!("回滚点数量已达上限，删除最旧的回滚点: {}", oldest.id);
+            self.remove_snapshot(&oldest);
+        }
+
+        let creation_time = chrono::Utc::now().to_rfc3339();
         let rollback_point = RollbackPoint {
-            id: self.generate_rollback_id(description, backup_path),
+            id: self.generate_rollback_id(description, snapshot_id, &creation_time),
             description: description.to_string(),
-            backup_path: backup_path.to_string(),
-            creation_time: chrono::Utc::now().to_rfc3339(),
+            snapshot_id: snapshot_id.to_string(),
+            creation_time,
             affected_drivers,
+            status: RollbackStatus::Available,
         };
 
-        // 在实际实现中，这里会验证备份文件是否存在
+        points.push(rollback_point.clone());
+        self.save_points(&points)?;
+        self.collect_garbage();
+
         Ok(rollback_point)
     }
 
+    /// 列出所有回滚点，按创建时间从旧到新排列
+    pub fn list_rollback_points(&self) -> Result<Vec<RollbackPoint>> {
+        self.load_points()
+    }
+
+    /// 按ID获取回滚点
+    pub fn get_rollback_point(&self, id: &str) -> Result<Option<RollbackPoint>> {
+        Ok(self.load_points()?.into_iter().find(|point| point.id == id))
+    }
+
     /// 执行回滚操作
-    pub fn perform_rollback(&self, rollback_point: &RollbackPoint) -> Result<()> {
-        println!("正在执行回滚操作: {}", rollback_point.description);
-        
-        // 在实际实现中，这将执行以下操作：
-        // 1. 恢复备份的驱动文件
-        // 2. 恢复注册表设置
-        // 3. 重新启动相关服务或设备
-        // 4. 验证回滚是否成功
-        
-        // 这里我们只是模拟操作
-        println!("回滚完成，已恢复到之前的状态");
-        Ok(())
+    ///
+    /// 按与安装相反的顺序重新安装回滚点中保存的驱动。
+    pub async fn perform_rollback(&self, rollback_point: &RollbackPoint, installer: &DriverInstaller) -> Result<RollbackReport> {
+        tracing::info!("正在执行回滚操作: {}", rollback_point.description);
+        self.precheck_rollback_feasibility(rollback_point)?;
+
+        let export_dir = self.export_rollback_point(rollback_point)?;
+        let mut results = Vec::new();
+
+        for entry in rollback_point.affected_drivers.iter().rev() {
+            let result = self.reinstall_entry(&export_dir, entry, installer).await;
+            results.push((entry.hardware_id.clone(), result));
+        }
+
+        let _ = fs::remove_dir_all(&export_dir);
+
+        let report = RollbackReport {
+            rollback_id: rollback_point.id.clone(),
+            results,
+        };
+
+        if report.is_success() {
+            self.mark_rolled_back(&rollback_point.id)?;
+            tracing::info!("回滚完成，已恢复到之前的状态");
+        } else {
+            tracing::warn!("回滚部分失败: {}", rollback_point.id);
+        }
+
+        Ok(report)
+    }
+
+    /// 只回滚回滚点中的单个设备
+    pub async fn rollback_device(&self, rollback_point: &RollbackPoint, hardware_id: &str, installer: &DriverInstaller) -> Result<InstallationResult> {
+        let entry = rollback_point.affected_drivers.iter()
+            .find(|entry| entry.hardware_id == hardware_id)
+            .ok_or_else(|| HamsterError::ValidationError(format!("回滚点中没有该设备: {}", hardware_id)))?;
+
+        self.precheck_rollback_feasibility(rollback_point)?;
+        let export_dir = self.export_rollback_point(rollback_point)?;
+        let result = self.reinstall_entry(&export_dir, entry, installer).await;
+        let _ = fs::remove_dir_all(&export_dir);
+
+        Ok(result)
     }
 
     /// 验证回滚点的有效性
-    pub fn validate_rollback_point(&self, _rollback_point: &RollbackPoint) -> Result<bool> {
-        // 在实际实现中，这将检查备份文件是否完整且可访问
-        // 这里我们假设所有回滚点都是有效的
-        Ok(true)
+    ///
+    /// 快照必须仍在备份存储中、文件哈希一致，且包含每个受影响设备的驱动。
+    pub fn validate_rollback_point(&self, rollback_point: &RollbackPoint) -> Result<bool> {
+        let manifest = match self.store.load_snapshot(&rollback_point.snapshot_id) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                tracing::warn!("回滚点 {} 的快照不可用: {}", rollback_point.id, e);
+                return Ok(false);
+            }
+        };
+
+        for entry in &rollback_point.affected_drivers {
+            if !manifest.entries.iter().any(|backup| backup.id == entry.backup_entry_id) {
+                tracing::warn!("回滚点 {} 缺少设备 {} 的驱动", rollback_point.id, entry.hardware_id);
+                return Ok(false);
+            }
+        }
+
+        let problems = self.store.verify_snapshot(&rollback_point.snapshot_id)
+            .map_err(|e| HamsterError::BackupError(e.to_string()))?;
+        for problem in &problems {
+            tracing::warn!("回滚点 {} 校验失败: {}", rollback_point.id, problem);
+        }
+
+        Ok(problems.is_empty())
     }
 
     /// 生成回滚点ID
-    fn generate_rollback_id(&self, description: &str, backup_path: &str) -> String {
+    fn generate_rollback_id(&self, description: &str, snapshot_id: &str, creation_time: &str) -> String {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
-        
+
         let mut hasher = DefaultHasher::new();
         description.hash(&mut hasher);
-        backup_path.hash(&mut hasher);
+        snapshot_id.hash(&mut hasher);
+        creation_time.hash(&mut hasher);
         let hash = hasher.finish();
-        
+
         format!("rb_{:x}", hash)
     }
 
-    /// 清理回滚点（删除备份文件）
+    /// 清理回滚点（删除索引记录和备份快照）
     pub fn cleanup_rollback_point(&self, rollback_point: &RollbackPoint) -> Result<()> {
-        // 在实际实现中，这将删除备份文件
-        println!("清理回滚点: {}", rollback_point.id);
+        let mut points = self.load_points()?;
+        points.retain(|point| point.id != rollback_point.id);
+        self.save_points(&points)?;
+
+        self.remove_snapshot(rollback_point);
+        self.collect_garbage();
+        tracing::info!("清理回滚点: {}", rollback_point.id);
         Ok(())
     }
 
     /// 获取系统支持的最大回滚点数量
     pub fn get_max_rollback_points(&self) -> usize {
-        // 在实际实现中，这可能从配置中读取
-        5
+        self.max_rollback_points
     }
 
     /// 检查是否可以创建新的回滚点
@@ -88,9 +244,207 @@ impl RollbackManager {
     }
 
     /// 预检查回滚可行性
-    pub fn precheck_rollback_feasibility(&self, _rollback_point: &RollbackPoint) -> Result<()> {
-        // 在实际实现中，这将检查系统状态以确定是否可以安全回滚
-        // 检查磁盘空间、权限、系统状态等
+    pub fn precheck_rollback_feasibility(&self, rollback_point: &RollbackPoint) -> Result<()> {
+        if rollback_point.status == RollbackStatus::RolledBack {
+            return Err(HamsterError::ValidationError(format!("回滚点已使用过: {}", rollback_point.id)));
+        }
+        if rollback_point.affected_drivers.is_empty() {
+            return Err(HamsterError::ValidationError(format!("回滚点不包含任何驱动: {}", rollback_point.id)));
+        }
+        if !self.validate_rollback_point(rollback_point)? {
+            return Err(HamsterError::ValidationError(format!("回滚点备份已损坏或不存在: {}", rollback_point.id)));
+        }
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn export_rollback_point(&self, rollback_point: &RollbackPoint) -> Result<PathBuf> {
+        let export_dir = crate::utils::get_temp_dir()?.join("rollback").join(&rollback_point.id);
+        if export_dir.exists() {
+            fs::remove_dir_all(&export_dir)?;
+        }
+        self.store.export_snapshot(&rollback_point.snapshot_id, &export_dir)
+            .map_err(|e| HamsterError::RestoreError(format!("导出回滚快照失败: {}", e)))?;
+        Ok(export_dir)
+    }
+
+    async fn reinstall_entry(&self, export_dir: &Path, entry: &RollbackEntry, installer: &DriverInstaller) -> InstallationResult {
+        let package = match crate::installer::BackupPackage::open(export_dir) {
+            Ok(package) => package,
+            Err(e) => return rollback_failure(entry, format!("打开回滚快照失败: {}", e)),
+        };
+        let backup_entry = match package.entry(&entry.backup_entry_id) {
+            Some(backup_entry) => backup_entry,
+            None => return rollback_failure(entry, format!("快照中缺少驱动: {}", entry.backup_entry_id)),
+        };
+
+        let inf_path = package.inf_path(backup_entry);
+        let installer_info = InstallerDriverInfo {
+            file_path: inf_path.to_string_lossy().to_string(),
+            file_name: inf_path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "backup.inf".to_string()),
+            hardware_id: entry.hardware_id.clone(),
+            manufacturer: backup_entry.manufacturer.clone(),
+            driver_version: entry.driver_version.clone(),
+        };
+
+        tracing::info!("回滚设备 {} 到驱动版本 {}", entry.device_name, entry.driver_version);
+        match installer.install_driver(&installer_info).await {
+            Ok(result) => result,
+            Err(e) => rollback_failure(entry, format!("回滚安装失败: {}", e)),
+        }
+    }
+
+    fn mark_rolled_back(&self, id: &str) -> Result<()> {
+        let mut points = self.load_points()?;
+        if let Some(point) = points.iter_mut().find(|point| point.id == id) {
+            point.status = RollbackStatus::RolledBack;
+        }
+        self.save_points(&points)
+    }
+
+    fn remove_snapshot(&self, rollback_point: &RollbackPoint) {
+        if let Err(e) = self.store.remove_snapshot(&rollback_point.snapshot_id) {
+            tracing::warn!("删除回滚快照失败 {}: {}", rollback_point.snapshot_id, e);
+        }
+    }
+
+    fn collect_garbage(&self) {
+        if let Err(e) = self.store.collect_garbage() {
+            tracing::warn!("清理备份存储失败: {}", e);
+        }
+    }
+
+    fn load_points(&self) -> Result<Vec<RollbackPoint>> {
+        if !self.index_path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.index_path)?;
+        let mut points: Vec<RollbackPoint> = serde_json::from_str(&content)
+            .map_err(|e| HamsterError::ParseError(format!("解析回滚点索引失败: {}", e)))?;
+        points.sort_by(|a, b| a.creation_time.cmp(&b.creation_time));
+        Ok(points)
+    }
+
+    fn save_points(&self, points: &[RollbackPoint]) -> Result<()> {
+        if let Some(parent) = self.index_path.parent() {
+            crate::utils::ensure_dir(parent)?;
+        }
+        let content = serde_json::to_string_pretty(points)?;
+        let temp_path = self.index_path.with_extension("json.tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(&temp_path, &self.index_path)?;
+        Ok(())
+    }
+}
+
+fn rollback_failure(entry: &RollbackEntry, message: String) -> InstallationResult {
+    InstallationResult {
+        success: false,
+        message,
+        driver_version: entry.driver_version.clone(),
+        installed_at: chrono::Utc::now().to_rfc3339(),
+        reboot_required: false,
+        aborted: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::TempDir;
+    use crate::hardware::HardwareDevice;
+    use crate::installer::{BackupPackage, RetentionPolicy};
+
+    fn commit_snapshot(store: &BackupStore, staging: &Path, content: &str) -> (String, RollbackEntry) {
+        let device = HardwareDevice {
+            device_id: "PCI\\VEN_8086&DEV_15B8\\3&1".to_string(),
+            device_name: "测试网卡".to_string(),
+            hardware_id: "PCI\\VEN_8086&DEV_15B8".to_string(),
+            driver_version: "2.0".to_string(),
+            driver_date: "Unknown".to_string(),
+            manufacturer: "Intel".to_string(),
+            device_class: "网络适配器".to_string(),
+            ..Default::default()
+        };
+        fs::create_dir_all(staging.join("nic")).unwrap();
+        fs::write(staging.join("nic/e1d.inf"), "[Version]").unwrap();
+        fs::write(staging.join("nic/e1d.sys"), content).unwrap();
+        let mut package = BackupPackage::create(staging, "回滚测试").unwrap();
+        let backup_entry_id = package.add_entry(&device, "2.0", "nic").unwrap().id.clone();
+        let (snapshot, _) = store.commit_package(&package).unwrap();
+
+        let entry = RollbackEntry {
+            hardware_id: device.hardware_id.clone(),
+            device_name: device.device_name.clone(),
+            driver_version: "2.0".to_string(),
+            backup_entry_id,
+        };
+        (snapshot.id, entry)
+    }
+
+    #[test]
+    fn test_rollback_points_persist_and_prune() {
+        let root = TempDir::new("rollback_prune");
+        let index_path = root.join("rollback_points.json");
+        let manager = RollbackManager::with_store(index_path.clone(), BackupStore::open(&root.join("store")).unwrap())
+            .with_max_rollback_points(2);
+
+        let mut ids = Vec::new();
+        for i in 0..3 {
+            let (snapshot_id, entry) = commit_snapshot(manager.store(), &root.join(format!("staging{}", i)), &format!("sys{}", i));
+            ids.push(manager.create_rollback_point("批量更新", &snapshot_id, vec![entry]).unwrap().id);
+        }
+
+        let reopened = RollbackManager::with_store(index_path, BackupStore::open(&root.join("store")).unwrap());
+        let points = reopened.list_rollback_points().unwrap();
+        assert_eq!(points.iter().map(|p| p.id.clone()).collect::<Vec<_>>(), ids[1..].to_vec());
+        assert_eq!(reopened.store().list_snapshots().unwrap().len(), 2);
+        assert!(reopened.validate_rollback_point(&points[0]).unwrap());
+
+        reopened.cleanup_rollback_point(&points[0]).unwrap();
+        assert_eq!(reopened.list_rollback_points().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pruning_backups_keeps_rollback_snapshots() {
+        let root = TempDir::new("rollback_backup_prune");
+        let backups = BackupStore::open(&root.join("backups")).unwrap();
+        let manager = RollbackManager::open(&root.join("rollback")).unwrap();
+        let (snapshot_id, entry) = commit_snapshot(manager.store(), &root.join("staging"), "rollback");
+        let point = manager.create_rollback_point("批量更新", &snapshot_id, vec![entry]).unwrap();
+
+        // 与 backup prune 相同：按保留策略删除用户备份后清理未引用的文件
+        for i in 0..2 {
+            commit_snapshot(&backups, &root.join(format!("backup{}", i)), &format!("backup{}", i));
+        }
+        let policy = RetentionPolicy { keep_last: 1, keep_daily: 0, keep_weekly: 0, keep_monthly: 0 };
+        assert_eq!(backups.apply_retention(&policy, false).unwrap().removed.len(), 1);
+        backups.collect_garbage().unwrap();
+
+        assert!(manager.validate_rollback_point(&point).unwrap());
+        let report = manager.perform_rollback(&point, &DriverInstaller::new()).await.unwrap();
+        assert_eq!(report.results.len(), 1);
+    }
+
+    #[test]
+    fn test_validation_detects_corrupt_backup() {
+        let root = TempDir::new("rollback_validate");
+        let manager = RollbackManager::with_store(root.join("rollback_points.json"), BackupStore::open(&root.join("store")).unwrap());
+        let (snapshot_id, entry) = commit_snapshot(manager.store(), &root.join("staging"), "original");
+        let point = manager.create_rollback_point("更新网卡", &snapshot_id, vec![entry.clone()]).unwrap();
+        assert!(manager.precheck_rollback_feasibility(&point).is_ok());
+
+        let mut missing_entry = point.clone();
+        missing_entry.affected_drivers[0].backup_entry_id = "entry_99".to_string();
+        assert!(!manager.validate_rollback_point(&missing_entry).unwrap());
+
+        for blob in walkdir::WalkDir::new(root.join("store/blobs")).into_iter().filter_map(|e| e.ok()) {
+            if blob.file_type().is_file() {
+                fs::write(blob.path(), "tampered").unwrap();
+            }
+        }
+        assert!(!manager.validate_rollback_point(&point).unwrap());
+        assert!(manager.precheck_rollback_feasibility(&point).is_err());
+    }
+}
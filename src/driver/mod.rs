@@ -0,0 +1,4 @@
+//! 驱动相关的扩展模块
+
+pub mod fetcher;
+pub mod installer;
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use crate::error::HamsterError;
 use crate::scan::{DriverInfo, DriverStatus};
+use crate::utils::http_client;
 
 #[derive(Serialize, Deserialize, Debug)]
 struct DriverDatabaseResponse {
@@ -31,7 +32,7 @@ pub async fn query_driver_update(hardware_id: &str) -> Result<Option<DriverInfo>
     // 实际实现中，这里会发送HTTP请求到驱动数据库服务器
     
     // 连接到真正的驱动数据库服务器
-    let client = reqwest::Client::new();
+    let client = http_client();
     let url = format!("https://drivers.hamsterdrive.com/api/v1/drivers/{}", hardware_id);
     
     // 发送GET请求
@@ -75,7 +76,7 @@ pub async fn query_multiple_drivers(hardware_ids: &[String]) -> Result<Vec<Drive
 
 /// 获取驱动兼容性信息
 pub async fn get_driver_compatibility(hardware_id: &str) -> Result<Option<String>, HamsterError> {
-    let client = reqwest::Client::new();
+    let client = http_client();
     let url = format!("https://drivers.hamsterdrive.com/api/v1/drivers/{}/compatibility", hardware_id);
     
     let response = client.get(&url).send().await
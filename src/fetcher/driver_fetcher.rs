@@ -4,8 +4,13 @@ use std::process::Command;
 use tokio::fs;
 use tokio::time::{sleep, Duration};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::Mutex;
 use std::process::Child;
+use crate::config::{DownloadConfig, NetworkConfig, RepositoryConfig};
+use crate::repository::RepositoryClient;
+use crate::utils::{download_http_client, network_config, run_cancellable, CancellationToken, HamsterError, RateLimiter};
+use crate::t;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DownloadProgress {
@@ -31,6 +36,11 @@ pub struct DriverFetcher {
     pub aria2_port: u16,
     pub download_dir: String,
     aria2_process: Arc<Mutex<Option<Child>>>,
+    /// aria2 的总速度和单个任务的速度上限，字节/秒，0 表示不限制
+    max_download_rate: AtomicU64,
+    max_task_rate: AtomicU64,
+    /// 配置的驱动仓库，下载其中的驱动包时带上 API 密钥
+    repository: std::sync::RwLock<Option<RepositoryClient>>,
 }
 
 impl DriverFetcher {
@@ -40,9 +50,45 @@ impl DriverFetcher {
             aria2_port,
             download_dir,
             aria2_process: Arc::new(Mutex::new(None)),
+            max_download_rate: AtomicU64::new(0),
+            max_task_rate: AtomicU64::new(0),
+            repository: std::sync::RwLock::new(None),
         }
     }
 
+    /// 按下载配置限制 aria2 的下载速度，之后启动的 aria2 生效
+    ///
+    /// 通过下载队列的下载由队列限速，大文件下载时间段也只对队列生效。
+    pub fn set_rate_limits(&self, config: &DownloadConfig) {
+        self.max_download_rate.store(config.max_download_rate(), Ordering::Relaxed);
+        self.max_task_rate.store(config.max_task_rate(), Ordering::Relaxed);
+    }
+
+    /// 按仓库配置设置下载驱动包时的认证
+    pub fn set_repository(&self, config: &RepositoryConfig) {
+        *self.repository.write().unwrap_or_else(|e| e.into_inner()) = RepositoryClient::from_config(config);
+    }
+
+    // 下载地址是配置的驱动仓库中的驱动包时需要的 Authorization 头
+    fn authorization(&self, url: &str) -> Option<String> {
+        self.repository.read().unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .and_then(|repository| repository.package_authorization(url))
+    }
+
+    // aria2 的认证参数
+    fn aria2_auth_args(&self, url: &str) -> Option<String> {
+        self.authorization(url).map(|value| format!("--header=Authorization: {}", value))
+    }
+
+    // aria2 的速度上限参数
+    fn aria2_rate_args(&self) -> [String; 2] {
+        [
+            format!("--max-overall-download-limit={}", self.max_download_rate.load(Ordering::Relaxed)),
+            format!("--max-download-limit={}", self.max_task_rate.load(Ordering::Relaxed)),
+        ]
+    }
+
     pub async fn download_driver(&self, task: &DownloadTask) -> Result<DownloadProgress> {
         // 确保下载目录存在
         fs::create_dir_all(&self.download_dir).await?;
@@ -52,16 +98,17 @@ impl DriverFetcher {
         
         // 构建Aria2命令
         let output = Command::new("aria2c")
-            .args(&[
+            .args([
                 "--continue=true",
                 "--max-connection-per-server=8",
                 "--split=8",
-                "--max-tries=3",
-                "--timeout=60",
                 &format!("--dir={}", self.download_dir),
                 &format!("--out={}", task.file_name),
-                &task.url,
             ])
+            .args(aria2_network_args(&network_config())?)
+            .args(self.aria2_rate_args())
+            .args(self.aria2_auth_args(&task.url))
+            .arg(&task.url)
             .output()?;
 
         if output.status.success() {
@@ -82,8 +129,12 @@ impl DriverFetcher {
         }
     }
 
-    pub async fn download_driver_with_progress(&self, task: &DownloadTask, 
-                                              progress_callback: impl Fn(DownloadProgress) -> ()) -> Result<()> {
+    /// 下载驱动并报告进度，取消或超过 timeout 时终止 aria2 并删除未完成的文件
+    pub async fn download_driver_with_progress(&self, task: &DownloadTask,
+                                              progress_callback: impl Fn(DownloadProgress),
+                                              cancel: &CancellationToken,
+                                              timeout: Option<Duration>) -> Result<()> {
+        cancel.check("下载驱动")?;
         // 确保下载目录存在
         fs::create_dir_all(&self.download_dir).await?;
 
@@ -91,23 +142,45 @@ impl DriverFetcher {
         let download_path = format!("{}/{}", self.download_dir, task.file_name);
         
         // 首先获取文件大小
-        let file_size = self.get_remote_file_size(&task.url).await.unwrap_or(0);
+        let file_size = run_cancellable(cancel, timeout, "获取文件大小", self.get_remote_file_size(&task.url)).await?
+            .unwrap_or(0);
         
-        // 通过Aria2下载
-        let output = Command::new("aria2c")
-            .args(&[
+        // 通过Aria2下载，进程随 future 一起被丢弃时会被终止
+        let mut command = tokio::process::Command::new("aria2c");
+        command.args([
                 "--continue=true",
                 "--max-connection-per-server=8",
                 "--split=8",
-                "--max-tries=3",
-                "--timeout=60",
                 "--enable-rpc=true",
                 "--rpc-listen-port=6800",
                 &format!("--dir={}", self.download_dir),
                 &format!("--out={}", task.file_name),
-                &task.url,
             ])
-            .output()?;
+            .args(aria2_network_args(&network_config())?)
+            .args(self.aria2_rate_args())
+            .args(self.aria2_auth_args(&task.url))
+            .arg(&task.url)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+        let output = match command.spawn() {
+            Ok(child) => run_cancellable(cancel, timeout, "下载驱动", child.wait_with_output()).await,
+            Err(e) => Ok(Err(e)),
+        };
+        let output = match output {
+            Ok(output) => output?,
+            Err(e) => {
+                remove_partial_download(&download_path).await;
+                progress_callback(DownloadProgress {
+                    file_name: task.file_name.clone(),
+                    total_size: file_size,
+                    downloaded_size: 0,
+                    progress: 0.0,
+                    status: if matches!(e, HamsterError::Cancelled(_)) { "cancelled" } else { "failed" }.to_string(),
+                });
+                return Err(e.into());
+            }
+        };
 
         if output.status.success() {
             // 下载完成，报告进度
@@ -141,7 +214,11 @@ impl DriverFetcher {
 
     // 通过HTTP获取远程文件大小
     async fn get_remote_file_size(&self, url: &str) -> Result<u64> {
-        let response = reqwest::get(url).await?;
+        let mut request = download_http_client().head(url);
+        if let Some(value) = self.authorization(url) {
+            request = request.header(reqwest::header::AUTHORIZATION.as_str(), value);
+        }
+        let response = request.send().await?;
         if let Some(content_length) = response.headers().get(reqwest::header::CONTENT_LENGTH) {
             let size_str = content_length.to_str()?;
             let size = size_str.parse::<u64>()?;
@@ -172,7 +249,11 @@ impl DriverFetcher {
         let download_path = format!("{}/{}", self.download_dir, task.file_name);
         let mut file = tokio::fs::File::create(&download_path).await?;
         
-        let response = reqwest::get(&task.url).await?;
+        let mut request = download_http_client().get(&task.url);
+        if let Some(value) = self.authorization(&task.url) {
+            request = request.header(reqwest::header::AUTHORIZATION.as_str(), value);
+        }
+        let response = request.send().await?.error_for_status()?;
         let total_size = response.content_length().unwrap_or(0);
         
         let mut downloaded: u64 = 0;
@@ -206,6 +287,87 @@ impl DriverFetcher {
         })
     }
 
+    /// 通过HTTP下载到 file_path，文件已存在时用 Range 从断点续传。
+    /// progress 收到已下载和总字节数（总大小未知时为0），取消时保留已下载的部分。
+    pub async fn download_resumable(&self, url: &str, file_path: &str,
+                                    cancel: &CancellationToken,
+                                    limiters: &[&RateLimiter],
+                                    mut progress: impl FnMut(u64, u64)) -> Result<u64> {
+        use futures_util::StreamExt;
+        use tokio::io::AsyncWriteExt;
+
+        cancel.check("下载驱动")?;
+        if let Some(parent) = std::path::Path::new(file_path).parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let mut downloaded = fs::metadata(file_path).await.map(|m| m.len()).unwrap_or(0);
+
+        let mut request = download_http_client().get(url);
+        if let Some(value) = self.authorization(url) {
+            request = request.header(reqwest::header::AUTHORIZATION.as_str(), value);
+        }
+        if downloaded > 0 {
+            request = request.header(reqwest::header::RANGE.as_str(), format!("bytes={}-", downloaded));
+        }
+        let response = tokio::select! {
+            response = request.send() => response?,
+            _ = cancel.cancelled() => return Err(HamsterError::Cancelled("下载驱动".to_string()).into()),
+        };
+
+        // 服务器认为文件已完整
+        if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            progress(downloaded, downloaded);
+            return Ok(downloaded);
+        }
+        let response = response.error_for_status()?;
+        let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+        if !resumed {
+            downloaded = 0;
+        }
+        let total = response.content_length().map_or(0, |len| len + downloaded);
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(file_path)
+            .await?;
+        progress(downloaded, total);
+
+        let mut stream = Box::pin(response.bytes_stream());
+        loop {
+            let chunk = tokio::select! {
+                chunk = stream.next() => chunk,
+                _ = cancel.cancelled() => {
+                    file.flush().await?;
+                    return Err(HamsterError::Cancelled("下载驱动".to_string()).into());
+                }
+            };
+            let Some(chunk) = chunk else { break };
+            let chunk = chunk?;
+            file.write_all(&chunk).await?;
+            downloaded += chunk.len() as u64;
+            progress(downloaded, total);
+            // 依次满足全局和单个任务的速度上限
+            for limiter in limiters {
+                tokio::select! {
+                    _ = limiter.acquire(chunk.len() as u64) => {}
+                    _ = cancel.cancelled() => {
+                        file.flush().await?;
+                        return Err(HamsterError::Cancelled("下载驱动".to_string()).into());
+                    }
+                }
+            }
+        }
+        file.flush().await?;
+
+        if total > 0 && downloaded != total {
+            return Err(anyhow::anyhow!("下载不完整: {}/{} 字节", downloaded, total));
+        }
+        Ok(downloaded)
+    }
+
     // 启动Aria2 RPC服务器
     pub async fn start_aria2_rpc(&self) -> Result<()> {
         // 检查Aria2是否已安装
@@ -218,7 +380,7 @@ impl DriverFetcher {
         let mut process_guard = self.aria2_process.lock().await;
         if process_guard.is_none() {
             let aria2_process = std::process::Command::new("aria2c")
-                .args(&[
+                .args([
                     "--enable-rpc=true",
                     &format!("--rpc-listen-port={}", self.aria2_port),
                     "--rpc-allow-origin-all=true",
@@ -226,6 +388,8 @@ impl DriverFetcher {
                     "--max-connection-per-server=8",
                     "--split=8",
                 ])
+                .args(aria2_network_args(&network_config())?)
+                .args(self.aria2_rate_args())
                 .spawn()?;
             *process_guard = Some(aria2_process);
         }
@@ -267,6 +431,59 @@ impl DriverFetcher {
     }
 }
 
+/// aria2 的代理、证书、超时、重试和 User-Agent 参数，与共享的 HTTP 客户端使用同一网络配置
+///
+/// aria2 不支持 SOCKS 代理，配置了 SOCKS 代理时返回错误，避免绕过代理直接连接。
+pub fn aria2_network_args(config: &NetworkConfig) -> Result<Vec<String>> {
+    let mut args = vec![
+        format!("--user-agent={}", config.user_agent()),
+        format!("--connect-timeout={}", config.connect_timeout().as_secs()),
+        format!("--timeout={}", config.request_timeout().as_secs()),
+        format!("--max-tries={}", config.max_retries + 1),
+        format!("--retry-wait={}", config.retry_backoff_ms.div_ceil(1000)),
+    ];
+
+    if let Some(ref proxy) = config.proxy {
+        if proxy.trim().to_ascii_lowercase().starts_with("socks") {
+            return Err(anyhow::anyhow!("aria2 不支持 SOCKS 代理: {}", proxy));
+        }
+        args.push(format!("--all-proxy={}", proxy.trim()));
+        if let Some(ref username) = config.proxy_username {
+            args.push(format!("--all-proxy-user={}", username));
+            args.push(format!("--all-proxy-passwd={}", config.proxy_password.as_deref().unwrap_or_default()));
+        }
+        if !config.no_proxy.is_empty() {
+            args.push(format!("--no-proxy={}", config.no_proxy.join(",")));
+        }
+    }
+
+    // aria2 只接受一个证书文件，配置了多个时合并成一个
+    match config.ca_certificates.as_slice() {
+        [] => {}
+        [path] => args.push(format!("--ca-certificate={}", path)),
+        paths => {
+            let mut bundle = Vec::new();
+            for path in paths {
+                bundle.extend(std::fs::read(path).map_err(|e| anyhow::anyhow!("无法读取证书 {}: {}", path, e))?);
+                bundle.push(b'\n');
+            }
+            let bundle_path = std::env::temp_dir().join(format!("hamster_aria2_ca_{}.pem", std::process::id()));
+            std::fs::write(&bundle_path, bundle)?;
+            args.push(format!("--ca-certificate={}", bundle_path.display()));
+        }
+    }
+    Ok(args)
+}
+
+// 删除未完成的下载文件和aria2的断点续传控制文件
+async fn remove_partial_download(download_path: &str) {
+    for path in [download_path.to_string(), format!("{}.aria2", download_path)] {
+        if fs::remove_file(&path).await.is_ok() {
+            println!("{}", t!("download-partial-removed", path = path));
+        }
+    }
+}
+
 // 为Aria2 RPC功能添加辅助函数
 pub mod aria2_rpc {
     use anyhow::Result;
@@ -346,4 +563,37 @@ pub mod aria2_rpc {
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aria2_network_args() {
+        let config = NetworkConfig {
+            proxy: Some("http://proxy.corp:3128".to_string()),
+            proxy_username: Some("user".to_string()),
+            proxy_password: Some("pass".to_string()),
+            user_agent: "Test/1.0".to_string(),
+            ..NetworkConfig::default()
+        };
+        let args = aria2_network_args(&config).unwrap();
+        for expected in ["--all-proxy=http://proxy.corp:3128", "--all-proxy-user=user", "--all-proxy-passwd=pass",
+                         "--no-proxy=localhost,127.0.0.1", "--user-agent=Test/1.0", "--max-tries=3"] {
+            assert!(args.iter().any(|arg| arg == expected), "{} {:?}", expected, args);
+        }
+
+        // 不支持的代理不能被忽略，否则会绕过代理直接连接
+        let socks = NetworkConfig { proxy: Some("socks5://127.0.0.1:1080".to_string()), ..NetworkConfig::default() };
+        assert!(aria2_network_args(&socks).is_err());
+        assert!(!aria2_network_args(&NetworkConfig::default()).unwrap().iter().any(|arg| arg.starts_with("--all-proxy")));
+    }
+
+    #[test]
+    fn test_aria2_rate_args() {
+        let fetcher = DriverFetcher::new("localhost".to_string(), 6800, String::new());
+        assert_eq!(fetcher.aria2_rate_args(), ["--max-overall-download-limit=0", "--max-download-limit=0"]);
+        fetcher.set_rate_limits(&DownloadConfig { max_download_rate_kbps: 512, max_task_rate_kbps: 128, ..DownloadConfig::default() });
+        assert_eq!(fetcher.aria2_rate_args(), ["--max-overall-download-limit=524288", "--max-download-limit=131072"]);
+    }
+}
@@ -1,6 +1,9 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::process::Command;
+use crate::config::ScannerConfig;
+use crate::t;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HardwareDevice {
@@ -11,6 +14,89 @@ pub struct HardwareDevice {
     pub driver_date: String,
     pub manufacturer: String,
     pub device_class: String,
+    #[serde(default)]
+    pub status: DeviceStatus,
+    /// 设备管理器中的问题代码，0 表示没有问题
+    #[serde(default)]
+    pub problem_code: u32,
+    /// 设备当前是否连接，未连接的设备在设备管理器中默认隐藏
+    #[serde(default = "default_present")]
+    pub present: bool,
+    #[serde(default)]
+    pub hardware_ids: Vec<String>,
+    #[serde(default)]
+    pub compatible_ids: Vec<String>,
+    #[serde(default)]
+    pub driver_provider: String,
+    #[serde(default)]
+    pub inf_name: String,
+}
+
+fn default_present() -> bool {
+    true
+}
+
+impl Default for HardwareDevice {
+    fn default() -> Self {
+        Self {
+            device_id: String::new(),
+            device_name: String::new(),
+            hardware_id: String::new(),
+            driver_version: "Unknown".to_string(),
+            driver_date: "Unknown".to_string(),
+            manufacturer: "Unknown".to_string(),
+            device_class: "其他设备".to_string(),
+            status: DeviceStatus::Unknown,
+            problem_code: 0,
+            present: true,
+            hardware_ids: Vec::new(),
+            compatible_ids: Vec::new(),
+            driver_provider: String::new(),
+            inf_name: String::new(),
+        }
+    }
+}
+
+impl HardwareDevice {
+    /// 设备报告了错误或带有问题代码
+    pub fn has_problem(&self) -> bool {
+        self.status == DeviceStatus::Error || self.problem_code != 0
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        !self.present
+    }
+}
+
+/// 设备状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceStatus {
+    Ok,
+    Error,
+    Degraded,
+    #[default]
+    Unknown,
+}
+
+impl DeviceStatus {
+    fn parse(status: &str) -> Self {
+        match status.to_ascii_uppercase().as_str() {
+            "OK" => DeviceStatus::Ok,
+            "ERROR" => DeviceStatus::Error,
+            "DEGRADED" => DeviceStatus::Degraded,
+            _ => DeviceStatus::Unknown,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            DeviceStatus::Ok => t!("device-status-ok"),
+            DeviceStatus::Error => t!("device-status-error"),
+            DeviceStatus::Degraded => t!("device-status-degraded"),
+            DeviceStatus::Unknown => t!("device-status-unknown"),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -18,86 +104,109 @@ pub struct HardwareScanResult {
     pub devices: Vec<HardwareDevice>,
 }
 
-pub struct HardwareScanner;
+#[derive(Default)]
+pub struct HardwareScanner {
+    config: ScannerConfig,
+}
+
+// 包括未连接的设备，以便在界面中按需显示隐藏设备
+const PNP_DEVICES_COMMAND: &str = "Get-PnpDevice | Select-Object FriendlyName, InstanceId, Class, Manufacturer, Status, Present, ConfigManagerErrorCode, HardwareID, CompatibleID | ConvertTo-Json -Compress";
+// 字段名与 Get-PnpDevice 的输出一致，以便用同一个解析函数
+const WMI_DEVICES_COMMAND: &str = "Get-CimInstance Win32_PnPEntity | Select-Object @{n='FriendlyName';e={$_.Name}}, @{n='InstanceId';e={$_.PNPDeviceID}}, @{n='Class';e={$_.PNPClass}}, Manufacturer, Status, Present, ConfigManagerErrorCode, HardwareID, CompatibleID | ConvertTo-Json -Compress";
+const SIGNED_DRIVERS_COMMAND: &str = "Get-CimInstance Win32_PnPSignedDriver | Select-Object DeviceID, DriverVersion, DriverProviderName, InfName, @{n='DriverDate';e={if ($_.DriverDate) { $_.DriverDate.ToString('yyyy-MM-dd') }}} | ConvertTo-Json -Compress";
 
 impl HardwareScanner {
     pub fn new() -> Self {
-        HardwareScanner
+        Self::default()
+    }
+
+    /// 按配置选择扫描方式和设备类别
+    pub fn with_config(config: ScannerConfig) -> Self {
+        Self { config }
     }
 
     pub fn scan_hardware(&self) -> Result<HardwareScanResult> {
-        // 使用PowerShell命令获取硬件信息
-        let mut devices = Vec::new();
+        let mut devices: Vec<HardwareDevice> = Vec::new();
         
-        // 通过PowerShell获取PnP设备信息
-        devices.extend(self.scan_with_powershell()?);
+        // 通过PowerShell获取PnP设备信息，两种方式都启用时按实例ID去重
+        let commands = [(self.config.use_setupapi, PNP_DEVICES_COMMAND), (self.config.use_wmi, WMI_DEVICES_COMMAND)];
+        for (_, command) in commands.iter().filter(|(enabled, _)| *enabled) {
+            for device in self.scan_with_powershell(command)? {
+                if !devices.iter().any(|d| d.device_id.eq_ignore_ascii_case(&device.device_id)) {
+                    devices.push(device);
+                }
+            }
+        }
+        devices.retain(|device| self.config.includes_class(&device.device_class));
         
         Ok(HardwareScanResult { devices })
     }
 
-    fn scan_with_powershell(&self) -> Result<Vec<HardwareDevice>> {
+    fn scan_with_powershell(&self, devices_command: &str) -> Result<Vec<HardwareDevice>> {
         let output = Command::new("powershell")
-            .args(&["-Command", "Get-PnpDevice -PresentOnly | Where Status -eq 'OK' | Select-Object FriendlyName, InstanceId | ConvertTo-Json -Compress"])
+            .args(["-Command", devices_command])
             .output()?;
-
-        let output_str = String::from_utf8_lossy(&output.stdout).to_string();
+        let devices_json = String::from_utf8_lossy(&output.stdout).to_string();
         
-        // 解析PowerShell输出的JSON
-        let mut devices = Vec::new();
-        
-        // 直接解析完整的JSON输出
-        if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(&output_str) {
-            match &json_value {
-                serde_json::Value::Array(arr) => {
-                    for item in arr {
-                        if let (Some(friendly_name), Some(instance_id)) = 
-                            (item["FriendlyName"].as_str(), item["InstanceId"].as_str()) {
-                            // 跳过空的设备名称
-                            if friendly_name.is_empty() {
-                                continue;
-                            }
-                            let device = HardwareDevice {
-                                device_id: instance_id.to_string(),
-                                device_name: friendly_name.to_string(),
-                                hardware_id: self.extract_hardware_id(instance_id).unwrap_or_else(|| "Unknown".to_string()),
-                                driver_version: "Unknown".to_string(),
-                                driver_date: "Unknown".to_string(),
-                                manufacturer: self.extract_manufacturer(friendly_name).unwrap_or("Unknown".to_string()),
-                                device_class: self.get_device_class_from_name(friendly_name).unwrap_or_else(|_| "其他设备".to_string()),
-                            };
-                            devices.push(device);
-                        }
-                    }
-                },
-                serde_json::Value::Object(obj) => {
-                    if let (Some(friendly_name), Some(instance_id)) = 
-                        (obj["FriendlyName"].as_str(), obj["InstanceId"].as_str()) {
-                        let device = HardwareDevice {
-                            device_id: instance_id.to_string(),
-                            device_name: friendly_name.to_string(),
-                            hardware_id: self.extract_hardware_id(instance_id).unwrap_or_else(|| "Unknown".to_string()),
-                            driver_version: "Unknown".to_string(),
-                            driver_date: "Unknown".to_string(),
-                            manufacturer: self.extract_manufacturer(friendly_name).unwrap_or("Unknown".to_string()),
-                            device_class: self.get_device_class_from_name(friendly_name).unwrap_or_else(|_| "其他设备".to_string()),
-                        };
-                        devices.push(device);
-                    }
-                },
-                _ => {
-                    // 如果JSON解析失败，尝试简单的文本解析
-                    for line in output_str.lines() {
-                        let line = line.trim();
-                        if !line.is_empty() && !line.starts_with('{') {
-                            // 简单解析非JSON格式的输出
-                            // 这里可以添加备用解析逻辑
-                        }
-                    }
-                }
+        // 驱动信息查询失败时仍然返回设备列表
+        let drivers_json = match Command::new("powershell").args(["-Command", SIGNED_DRIVERS_COMMAND]).output() {
+            Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
+            Err(e) => {
+                tracing::warn!("读取已安装驱动信息失败: {}", e);
+                String::new()
             }
-        }
+        };
+        
+        Ok(self.parse_pnp_devices(&devices_json, &drivers_json))
+    }
+
+    /// 解析 PowerShell 输出的设备和已签名驱动 JSON，按实例ID合并
+    fn parse_pnp_devices(&self, devices_json: &str, drivers_json: &str) -> Vec<HardwareDevice> {
+        let drivers: HashMap<String, serde_json::Value> = json_items(drivers_json)
+            .into_iter()
+            .filter_map(|item| {
+                let device_id = item["DeviceID"].as_str()?.to_ascii_uppercase();
+                Some((device_id, item))
+            })
+            .collect();
         
-        Ok(devices)
+        json_items(devices_json)
+            .iter()
+            .filter_map(|item| {
+                let friendly_name = item["FriendlyName"].as_str().filter(|name| !name.is_empty())?;
+                let instance_id = item["InstanceId"].as_str()?;
+                let driver = drivers.get(&instance_id.to_ascii_uppercase());
+                let driver_field = |key: &str| driver
+                    .and_then(|driver| driver[key].as_str())
+                    .filter(|value| !value.is_empty())
+                    .map(|value| value.to_string());
+                
+                let manufacturer = self.extract_manufacturer(friendly_name)
+                    .or_else(|| item["Manufacturer"].as_str().filter(|m| !m.is_empty()).map(|m| m.to_string()))
+                    .unwrap_or_else(|| "Unknown".to_string());
+                let device_class = item["Class"].as_str()
+                    .and_then(class_label)
+                    .map(|class| class.to_string())
+                    .unwrap_or_else(|| self.get_device_class_from_name(friendly_name).unwrap_or_else(|_| "其他设备".to_string()));
+                
+                Some(HardwareDevice {
+                    device_id: instance_id.to_string(),
+                    device_name: friendly_name.to_string(),
+                    hardware_id: self.extract_hardware_id(instance_id).unwrap_or_else(|| "Unknown".to_string()),
+                    driver_version: driver_field("DriverVersion").unwrap_or_else(|| "Unknown".to_string()),
+                    driver_date: driver_field("DriverDate").unwrap_or_else(|| "Unknown".to_string()),
+                    manufacturer,
+                    device_class,
+                    status: DeviceStatus::parse(item["Status"].as_str().unwrap_or_default()),
+                    problem_code: item["ConfigManagerErrorCode"].as_u64().unwrap_or(0) as u32,
+                    present: item["Present"].as_bool().unwrap_or(true),
+                    hardware_ids: string_list(&item["HardwareID"]),
+                    compatible_ids: string_list(&item["CompatibleID"]),
+                    driver_provider: driver_field("DriverProviderName").unwrap_or_default(),
+                    inf_name: driver_field("InfName").unwrap_or_default(),
+                })
+            })
+            .collect()
     }
     
     /// 从设备ID中提取硬件ID
@@ -286,6 +395,52 @@ impl HardwareScanner {
     }
 }
 
+/// ConvertTo-Json 对单个对象不输出数组，统一成列表
+fn json_items(json: &str) -> Vec<serde_json::Value> {
+    match serde_json::from_str::<serde_json::Value>(json.trim()) {
+        Ok(serde_json::Value::Array(items)) => items,
+        Ok(item @ serde_json::Value::Object(_)) => vec![item],
+        _ => Vec::new(),
+    }
+}
+
+fn string_list(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::Array(items) => items.iter()
+            .filter_map(|item| item.as_str().map(|s| s.to_string()))
+            .collect(),
+        serde_json::Value::String(s) => vec![s.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// PnP 设备类别对应的中文名称
+/// 扫描结果中可能出现的设备类别
+pub const DEVICE_CLASSES: [&str; 14] = [
+    "显示适配器", "声音设备", "网络适配器", "USB设备", "存储设备", "蓝牙设备", "摄像头",
+    "打印机", "显示器", "键盘", "鼠标", "输入设备", "系统设备", "其他设备",
+];
+
+fn class_label(class: &str) -> Option<&'static str> {
+    let label = match class {
+        "Display" => "显示适配器",
+        "Media" | "AudioEndpoint" => "声音设备",
+        "Net" => "网络适配器",
+        "USB" => "USB设备",
+        "DiskDrive" | "SCSIAdapter" | "HDC" => "存储设备",
+        "Bluetooth" => "蓝牙设备",
+        "Camera" | "Image" => "摄像头",
+        "Printer" => "打印机",
+        "Monitor" => "显示器",
+        "Keyboard" => "键盘",
+        "Mouse" => "鼠标",
+        "HIDClass" => "输入设备",
+        "System" => "系统设备",
+        _ => return None,
+    };
+    Some(label)
+}
+
 // 使用sysinfo库获取系统信息的补充方法
 use sysinfo::System;
 
@@ -307,4 +462,35 @@ pub fn get_system_info_sysinfo() -> Result<String> {
     }
     
     Ok(info)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pnp_devices() {
+        let devices_json = r#"[
+            {"FriendlyName":"NVIDIA GeForce RTX 3060","InstanceId":"PCI\\VEN_10DE&DEV_2503\\4&1","Class":"Display","Status":"OK","Present":true,"ConfigManagerErrorCode":0,"HardwareID":["PCI\\VEN_10DE&DEV_2503&SUBSYS_1","PCI\\VEN_10DE&DEV_2503"],"CompatibleID":"PCI\\CC_0300"},
+            {"FriendlyName":"Unknown Camera","InstanceId":"USB\\VID_046D&PID_0825\\1","Class":"Camera","Status":"Error","Present":false,"ConfigManagerErrorCode":28},
+            {"FriendlyName":"","InstanceId":"ROOT\\NONAME\\0"}
+        ]"#;
+        let drivers_json = r#"{"DeviceID":"pci\\ven_10de&dev_2503\\4&1","DriverVersion":"31.0.15.3623","DriverProviderName":"NVIDIA","InfName":"oem12.inf","DriverDate":"2023-06-15"}"#;
+
+        let devices = HardwareScanner::new().parse_pnp_devices(devices_json, drivers_json);
+        assert_eq!(devices.len(), 2);
+
+        let gpu = &devices[0];
+        assert_eq!(gpu.device_class, "显示适配器");
+        assert_eq!(gpu.driver_version, "31.0.15.3623");
+        assert_eq!(gpu.driver_date, "2023-06-15");
+        assert_eq!(gpu.inf_name, "oem12.inf");
+        assert_eq!(gpu.hardware_ids.len(), 2);
+        assert_eq!(gpu.compatible_ids, vec!["PCI\\CC_0300".to_string()]);
+        assert!(!gpu.has_problem() && !gpu.is_hidden());
+
+        let camera = &devices[1];
+        assert_eq!(camera.driver_version, "Unknown");
+        assert_eq!(camera.problem_code, 28);
+        assert!(camera.has_problem() && camera.is_hidden());
+    }
+}
@@ -0,0 +1,106 @@
+//! 消息目录
+//!
+//! 解析 Fluent 格式的子集：`键 = 文本`，缩进 4 个空格的行接在上一条消息后面（换行分隔），
+//! `#` 开头的行是注释，文本中的 `{ $参数 }` 在格式化时替换，`{ "文字" }` 用于保留首尾空格。
+
+use std::collections::HashMap;
+use crate::utils::{HamsterError, Result};
+
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut messages = HashMap::new();
+        let mut current: Option<String> = None;
+        // 多行消息中间的空行在遇到下一个续行时补回
+        let mut blank_lines = 0;
+
+        for (index, line) in source.lines().enumerate() {
+            if line.trim().is_empty() {
+                blank_lines += 1;
+                continue;
+            }
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+            if line.starts_with([' ', '\t']) {
+                let message: &mut String = current.as_ref()
+                    .and_then(|key| messages.get_mut(key))
+                    .ok_or_else(|| HamsterError::ParseError(format!("第 {} 行: 续行前没有消息", index + 1)))?;
+                if !message.is_empty() {
+                    message.push_str(&"\n".repeat(blank_lines + 1));
+                }
+                blank_lines = 0;
+                let line = line.strip_prefix("    ").or_else(|| line.strip_prefix('\t')).unwrap_or(line.trim_start());
+                message.push_str(line.trim_end());
+                continue;
+            }
+
+            let (key, value) = line.split_once('=')
+                .ok_or_else(|| HamsterError::ParseError(format!("第 {} 行: 缺少 =", index + 1)))?;
+            let key = key.trim();
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                return Err(HamsterError::ParseError(format!("第 {} 行: 无效的键 {}", index + 1, key)));
+            }
+            messages.insert(key.to_string(), value.trim().to_string());
+            current = Some(key.to_string());
+            blank_lines = 0;
+        }
+
+        Ok(Self { messages })
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.messages.contains_key(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.messages.keys().map(String::as_str)
+    }
+
+    /// 替换消息中的参数，没有提供的参数原样保留
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> Option<String> {
+        let message = self.messages.get(key)?;
+        let mut output = String::with_capacity(message.len());
+        let mut rest = message.as_str();
+
+        while let Some(start) = rest.find('{') {
+            output.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+            let placeable = &rest[start..start + end + 1];
+            let inner = placeable[1..placeable.len() - 1].trim();
+            let literal = inner.strip_prefix('"').and_then(|inner| inner.strip_suffix('"'));
+            let value = inner.strip_prefix('$')
+                .and_then(|name| args.iter().find(|(arg, _)| *arg == name));
+            match (literal, value) {
+                (Some(literal), _) => output.push_str(literal),
+                (None, Some((_, value))) => output.push_str(value),
+                (None, None) => output.push_str(placeable),
+            }
+            rest = &rest[start + end + 1..];
+        }
+        output.push_str(rest);
+        Some(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format() {
+        let catalog = Catalog::parse("# 注释\nscan-found = 发现 { $count } 个设备\nseparator = { \", \" }\nusage =\n    第一行\n      第二行\n\n    第三行\n").unwrap();
+        assert_eq!(catalog.format("scan-found", &[("count", "3".to_string())]).unwrap(), "发现 3 个设备");
+        assert_eq!(catalog.format("scan-found", &[]).unwrap(), "发现 { $count } 个设备");
+        assert_eq!(catalog.format("usage", &[]).unwrap(), "第一行\n  第二行\n\n第三行");
+        assert_eq!(catalog.format("separator", &[]).unwrap(), ", ");
+        assert_eq!(catalog.format("missing", &[]), None);
+        assert!(Catalog::parse("no equals sign").is_err());
+    }
+}
@@ -0,0 +1,38 @@
+//! 按当前语言格式化大小和时间
+
+use chrono::{DateTime, Local, TimeZone};
+use crate::i18n::{current_locale, Locale};
+use crate::t;
+
+/// 文件大小，如 "1.5 MB"，小于 1 KB 时显示字节数
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return t!("size-bytes", count = bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// 本地时间的日期和时间，精确到分钟
+pub fn format_datetime<Tz: TimeZone>(time: &DateTime<Tz>) -> String {
+    let time = time.with_timezone(&Local);
+    match current_locale() {
+        Locale::ZhCn => time.format("%Y-%m-%d %H:%M").to_string(),
+        Locale::EnUs => time.format("%b %-d, %Y %-I:%M %p").to_string(),
+    }
+}
+
+/// 本地时间的日期
+pub fn format_date<Tz: TimeZone>(time: &DateTime<Tz>) -> String {
+    let time = time.with_timezone(&Local);
+    match current_locale() {
+        Locale::ZhCn => time.format("%Y年%-m月%-d日").to_string(),
+        Locale::EnUs => time.format("%b %-d, %Y").to_string(),
+    }
+}
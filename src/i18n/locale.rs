@@ -0,0 +1,117 @@
+//! 界面语言
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use once_cell::sync::Lazy;
+use crate::i18n::Catalog;
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+static CATALOGS: Lazy<Vec<Catalog>> = Lazy::new(|| {
+    Locale::ALL.iter()
+        .map(|locale| Catalog::parse(locale.source()).unwrap_or_else(|e| {
+            // 错误的 Display 会用到消息目录，这里只能输出原始说明
+            eprintln!("读取语言文件 {} 失败: {}", locale.code(), e.detail());
+            Catalog::default()
+        }))
+        .collect()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    ZhCn,
+    EnUs,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::EnUs];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::EnUs => "en-US",
+        }
+    }
+
+    /// 用该语言自己的文字显示的名称
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "简体中文",
+            Locale::EnUs => "English",
+        }
+    }
+
+    /// 接受 zh-CN、en_US、en 等写法，不区分大小写
+    pub fn from_code(code: &str) -> Option<Self> {
+        let language = code.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+        match language.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "en" => Some(Locale::EnUs),
+            _ => None,
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => include_str!("../../assets/locales/zh-CN.ftl"),
+            Locale::EnUs => include_str!("../../assets/locales/en-US.ftl"),
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+pub fn current_locale() -> Locale {
+    Locale::ALL[CURRENT.load(Ordering::Relaxed) as usize]
+}
+
+/// 切换界面语言，之后绘制的文本立即使用新语言
+pub fn set_locale(locale: Locale) {
+    CURRENT.store(locale.index() as u8, Ordering::Relaxed);
+}
+
+pub fn catalog(locale: Locale) -> &'static Catalog {
+    &CATALOGS[locale.index()]
+}
+
+/// 按当前语言格式化消息，找不到时回退到简体中文，仍找不到时返回键本身
+pub fn translate(key: &str, args: &[(&str, String)]) -> String {
+    [current_locale(), Locale::ZhCn].iter()
+        .find_map(|locale| catalog(*locale).format(key, args))
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// 按当前语言取消息，参数写成 `名称 = 值`
+///
+/// ```ignore
+/// t!("scan-finished", count = devices.len())
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::translate($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_have_same_keys() {
+        let chinese = catalog(Locale::ZhCn);
+        let english = catalog(Locale::EnUs);
+        assert!(chinese.contains("nav-settings"));
+        let missing: Vec<&str> = chinese.keys().filter(|key| !english.contains(key))
+            .chain(english.keys().filter(|key| !chinese.contains(key)))
+            .collect();
+        assert!(missing.is_empty(), "两种语言的消息不一致: {:?}", missing);
+        assert_eq!(Locale::from_code("en_us"), Some(Locale::EnUs));
+        assert_eq!(Locale::from_code("fr"), None);
+    }
+}
@@ -0,0 +1,10 @@
+//! 本地化
+//!
+//! 界面文本、命令行输出和错误信息保存在 assets/locales/<语言>.ftl 中，
+//! 代码中通过 `t!` 宏按键取当前语言的文本。
+mod catalog;
+mod format;
+mod locale;
+pub use catalog::*;
+pub use format::*;
+pub use locale::*;
@@ -0,0 +1,428 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use crate::hardware::HardwareDevice;
+
+/// 备份包清单文件名
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// 备份包清单，记录包内每个驱动及其文件哈希
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupManifest {
+    pub created_at: String,
+    pub description: String,
+    pub entries: Vec<BackupEntry>,
+}
+
+/// 备份包中的单个驱动
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupEntry {
+    pub id: String,
+    pub device_name: String,
+    pub manufacturer: String,
+    pub driver_version: String,
+    // 按从具体到通用的顺序排列
+    pub hardware_ids: Vec<String>,
+    // 相对于备份包根目录的INF路径
+    pub inf_path: String,
+    pub files: Vec<BackupFile>,
+}
+
+impl BackupManifest {
+    /// 检查清单中的路径都在备份包内，拒绝绝对路径和 `..`
+    pub fn validate_paths(&self) -> Result<()> {
+        for entry in &self.entries {
+            let paths = std::iter::once(&entry.inf_path).chain(entry.files.iter().map(|file| &file.path));
+            for path in paths {
+                if !is_package_relative(path) {
+                    return Err(anyhow::anyhow!("备份清单中的路径无效: {}", path));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// 单个驱动的校验结果
+#[derive(Debug, Clone)]
+pub struct EntryVerification {
+    pub entry_id: String,
+    pub valid: bool,
+    pub problems: Vec<String>,
+}
+
+/// 备份驱动与当前设备的匹配程度
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchRank {
+    NotPresent,
+    // 匹配的硬件ID段数，越多越具体
+    Compatible(usize),
+    ExactHardwareId,
+}
+
+/// 可供用户选择的恢复项
+#[derive(Debug, Clone)]
+pub struct RestoreCandidate {
+    pub entry: BackupEntry,
+    pub device: Option<HardwareDevice>,
+    pub rank: MatchRank,
+    pub verification: EntryVerification,
+}
+
+impl RestoreCandidate {
+    /// 只有校验通过且设备当前存在的驱动才可恢复
+    pub fn is_restorable(&self) -> bool {
+        self.verification.valid && self.device.is_some()
+    }
+}
+
+pub struct BackupPackage {
+    pub root: PathBuf,
+    pub manifest: BackupManifest,
+}
+
+impl BackupPackage {
+    /// 创建新的空备份包
+    pub fn create(root: &Path, description: &str) -> Result<Self> {
+        fs::create_dir_all(root)?;
+        Ok(BackupPackage {
+            root: root.to_path_buf(),
+            manifest: BackupManifest {
+                created_at: chrono::Utc::now().to_rfc3339(),
+                description: description.to_string(),
+                entries: Vec::new(),
+            },
+        })
+    }
+
+    /// 打开已有备份包并读取清单
+    pub fn open(root: &Path) -> Result<Self> {
+        let manifest_path = root.join(MANIFEST_FILE_NAME);
+        if !manifest_path.exists() {
+            return Err(anyhow::anyhow!("备份清单不存在: {}", manifest_path.display()));
+        }
+
+        let content = fs::read_to_string(&manifest_path)?;
+        let manifest: BackupManifest = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("解析备份清单失败: {}", e))?;
+        manifest.validate_paths()?;
+
+        Ok(BackupPackage {
+            root: root.to_path_buf(),
+            manifest,
+        })
+    }
+
+    /// 将包内某个目录中已导出的驱动文件登记到清单
+    pub fn add_entry(&mut self, device: &HardwareDevice, driver_version: &str, relative_dir: &str) -> Result<&BackupEntry> {
+        let entry_dir = self.root.join(relative_dir);
+        let mut files = Vec::new();
+        let mut inf_path = None;
+
+        for dir_entry in WalkDir::new(&entry_dir).sort_by_file_name() {
+            let dir_entry = dir_entry?;
+            if !dir_entry.file_type().is_file() {
+                continue;
+            }
+
+            let relative_path = relative_path_string(&self.root, dir_entry.path())?;
+            let is_inf = dir_entry.path()
+                .extension()
+                .and_then(std::ffi::OsStr::to_str)
+                .map(|ext| ext.eq_ignore_ascii_case("inf"))
+                .unwrap_or(false);
+            if is_inf && inf_path.is_none() {
+                inf_path = Some(relative_path.clone());
+            }
+
+            files.push(BackupFile {
+                path: relative_path,
+                size: dir_entry.metadata()?.len(),
+                sha256: sha256_file(dir_entry.path())?,
+            });
+        }
+
+        let inf_path = inf_path
+            .ok_or_else(|| anyhow::anyhow!("备份目录中没有INF文件: {}", entry_dir.display()))?;
+
+        self.manifest.entries.push(BackupEntry {
+            id: format!("entry_{}", self.manifest.entries.len() + 1),
+            device_name: device.device_name.clone(),
+            manufacturer: device.manufacturer.clone(),
+            driver_version: driver_version.to_string(),
+            hardware_ids: device_hardware_ids(device),
+            inf_path,
+            files,
+        });
+
+        Ok(&self.manifest.entries[self.manifest.entries.len() - 1])
+    }
+
+    /// 写入清单文件
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(&self.manifest)?;
+        fs::write(self.root.join(MANIFEST_FILE_NAME), content)?;
+        Ok(())
+    }
+
+    /// 获取指定ID的驱动
+    pub fn entry(&self, entry_id: &str) -> Option<&BackupEntry> {
+        self.manifest.entries.iter().find(|entry| entry.id == entry_id)
+    }
+
+    /// 获取驱动INF文件的绝对路径
+    pub fn inf_path(&self, entry: &BackupEntry) -> PathBuf {
+        self.root.join(&entry.inf_path)
+    }
+
+    /// 校验单个驱动的所有文件哈希
+    pub fn verify_entry(&self, entry: &BackupEntry) -> EntryVerification {
+        let mut problems = Vec::new();
+
+        if !entry.files.iter().any(|file| file.path == entry.inf_path) {
+            problems.push(format!("INF文件未登记在清单中: {}", entry.inf_path));
+        }
+
+        for file in &entry.files {
+            let file_path = self.root.join(&file.path);
+            if !file_path.exists() {
+                problems.push(format!("文件缺失: {}", file.path));
+                continue;
+            }
+
+            match sha256_file(&file_path) {
+                Ok(hash) if hash.eq_ignore_ascii_case(&file.sha256) => {}
+                Ok(_) => problems.push(format!("文件哈希不匹配: {}", file.path)),
+                Err(e) => problems.push(format!("读取文件失败 {}: {}", file.path, e)),
+            }
+        }
+
+        EntryVerification {
+            entry_id: entry.id.clone(),
+            valid: problems.is_empty(),
+            problems,
+        }
+    }
+
+    /// 校验整个备份包
+    pub fn verify(&self) -> Vec<EntryVerification> {
+        self.manifest.entries.iter()
+            .map(|entry| self.verify_entry(entry))
+            .collect()
+    }
+
+    /// 按硬件ID将备份驱动与当前设备匹配，匹配度高的排在前面
+    pub fn rank_against(&self, devices: &[HardwareDevice]) -> Vec<RestoreCandidate> {
+        let mut candidates: Vec<RestoreCandidate> = self.manifest.entries.iter()
+            .map(|entry| {
+                let best = devices.iter()
+                    .map(|device| (rank_entry_for_device(entry, device), device))
+                    .filter(|(rank, _)| *rank != MatchRank::NotPresent)
+                    .max_by(|a, b| a.0.cmp(&b.0));
+
+                let (rank, device) = match best {
+                    Some((rank, device)) => (rank, Some(device.clone())),
+                    None => (MatchRank::NotPresent, None),
+                };
+
+                RestoreCandidate {
+                    entry: entry.clone(),
+                    device,
+                    rank,
+                    verification: self.verify_entry(entry),
+                }
+            })
+            .collect();
+
+        candidates.sort_by(|a, b| b.rank.cmp(&a.rank));
+        candidates
+    }
+}
+
+/// 计算备份驱动与设备的匹配程度
+pub fn rank_entry_for_device(entry: &BackupEntry, device: &HardwareDevice) -> MatchRank {
+    let device_ids = device_hardware_ids(device);
+    let mut best = MatchRank::NotPresent;
+
+    for backup_id in &entry.hardware_ids {
+        for device_id in &device_ids {
+            let rank = rank_hardware_id(backup_id, device_id);
+            if rank > best {
+                best = rank;
+            }
+        }
+    }
+
+    best
+}
+
+/// 比较两个硬件ID，例如 PCI\VEN_10DE&DEV_1C82&SUBSYS_11111 与 PCI\VEN_10DE&DEV_1C82
+fn rank_hardware_id(backup_id: &str, device_id: &str) -> MatchRank {
+    let backup_id = backup_id.trim().to_uppercase();
+    let device_id = device_id.trim().to_uppercase();
+
+    if backup_id.is_empty() || device_id.is_empty() {
+        return MatchRank::NotPresent;
+    }
+    if backup_id == device_id {
+        return MatchRank::ExactHardwareId;
+    }
+
+    let backup_parts: Vec<&str> = backup_id.split('&').collect();
+    let device_parts: Vec<&str> = device_id.split('&').collect();
+    let matched = backup_parts.iter()
+        .zip(device_parts.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    // 总线和厂商段必须一致，且备份ID不能比设备ID更具体
+    if matched >= 2 && matched == backup_parts.len() {
+        MatchRank::Compatible(matched)
+    } else {
+        MatchRank::NotPresent
+    }
+}
+
+/// 收集设备可用于匹配的硬件ID
+pub fn device_hardware_ids(device: &HardwareDevice) -> Vec<String> {
+    let mut ids = Vec::new();
+
+    // 实例ID形如 PCI\VEN_xxxx&DEV_xxxx&SUBSYS_xxxx\4&xxxx，去掉最后的实例段即为硬件ID
+    if let Some((hardware_part, _instance)) = device.device_id.rsplit_once('\\') {
+        if hardware_part.contains('\\') {
+            ids.push(hardware_part.to_string());
+        }
+    }
+
+    // 只取形如 总线\标识 的完整硬件ID，扫描器提取失败时可能只剩总线名
+    if device.hardware_id.contains('\\') && !ids.contains(&device.hardware_id) {
+        ids.push(device.hardware_id.clone());
+    }
+
+    ids
+}
+
+/// 计算文件的SHA-256
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+// 清单可能在其他系统上生成，按 / 和 \ 两种分隔符检查
+fn is_package_relative(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with(['/', '\\'])
+        && !path.contains(':')
+        && path.split(['/', '\\']).all(|segment| segment != "..")
+}
+
+fn relative_path_string(root: &Path, path: &Path) -> Result<String> {
+    let relative = path.strip_prefix(root)?;
+    Ok(relative.to_string_lossy().replace('\\', "/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::TempDir;
+
+    fn device(device_id: &str) -> HardwareDevice {
+        HardwareDevice {
+            device_id: device_id.to_string(),
+            device_name: "测试显卡".to_string(),
+            hardware_id: "PCI".to_string(),
+            driver_version: "1.0".to_string(),
+            driver_date: "Unknown".to_string(),
+            manufacturer: "NVIDIA".to_string(),
+            device_class: "显示适配器".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_hardware_id_ranking() {
+        assert_eq!(
+            rank_hardware_id("PCI\\VEN_10DE&DEV_1C82&SUBSYS_11111", "pci\\ven_10de&dev_1c82&subsys_11111"),
+            MatchRank::ExactHardwareId
+        );
+        assert_eq!(
+            rank_hardware_id("PCI\\VEN_10DE&DEV_1C82", "PCI\\VEN_10DE&DEV_1C82&SUBSYS_11111"),
+            MatchRank::Compatible(2)
+        );
+        assert_eq!(
+            rank_hardware_id("PCI\\VEN_10DE&DEV_1C82&SUBSYS_11111", "PCI\\VEN_10DE&DEV_1C82"),
+            MatchRank::NotPresent
+        );
+        assert_eq!(
+            rank_hardware_id("PCI\\VEN_10DE&DEV_1C82", "PCI\\VEN_8086&DEV_1C82"),
+            MatchRank::NotPresent
+        );
+    }
+
+    #[test]
+    fn test_verify_and_rank_package() {
+        let root = TempDir::new("backup_test");
+        fs::create_dir_all(root.join("gpu")).unwrap();
+        fs::write(root.join("gpu/nv.inf"), "[Version]").unwrap();
+        fs::write(root.join("gpu/nv.sys"), "driver").unwrap();
+
+        let present = device("PCI\\VEN_10DE&DEV_1C82&SUBSYS_11111\\4&1234");
+        let mut package = BackupPackage::create(root.path(), "测试备份").unwrap();
+        package.add_entry(&present, "31.0.15.1", "gpu").unwrap();
+        package.save().unwrap();
+
+        let package = BackupPackage::open(root.path()).unwrap();
+        let candidates = package.rank_against(&[device("USB\\VID_046D&PID_C52B\\5&1"), present.clone()]);
+        assert_eq!(candidates[0].rank, MatchRank::ExactHardwareId);
+        assert!(candidates[0].is_restorable());
+
+        fs::write(root.join("gpu/nv.sys"), "tampered").unwrap();
+        let verification = package.verify();
+        assert!(!verification[0].valid);
+        assert!(verification[0].problems[0].contains("gpu/nv.sys"));
+    }
+
+    #[test]
+    fn test_manifest_rejects_paths_outside_package() {
+        assert!(is_package_relative("gpu/nv.inf"));
+        for path in ["", "/etc/passwd", "\\\\server\\share\\x.sys", "C:\\Windows\\x.sys", "gpu/../../x.sys", "..\\x.sys"] {
+            assert!(!is_package_relative(path), "{}", path);
+        }
+
+        let root = TempDir::new("backup_paths");
+        let mut package = BackupPackage::create(root.path(), "测试备份").unwrap();
+        package.manifest.entries.push(BackupEntry {
+            id: "escape".to_string(),
+            device_name: String::new(),
+            manufacturer: String::new(),
+            driver_version: String::new(),
+            hardware_ids: Vec::new(),
+            inf_path: "../outside.inf".to_string(),
+            files: Vec::new(),
+        });
+        package.save().unwrap();
+        assert!(BackupPackage::open(root.path()).is_err());
+    }
+}
@@ -0,0 +1,693 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Local};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use crate::installer::{BackupManifest, BackupPackage};
+
+const BLOBS_DIR: &str = "blobs";
+const SNAPSHOTS_DIR: &str = "snapshots";
+const TEMP_SUFFIX: &str = ".tmp";
+const LOCK_FILE: &str = "store.lock";
+// 等待其他进程释放存储锁的最长时间
+const LOCK_WAIT: Duration = Duration::from_secs(30);
+// 超过该时间仍未释放的锁视为崩溃遗留
+const STALE_LOCK_AGE: Duration = Duration::from_secs(6 * 60 * 60);
+
+// 将快照时间映射到保留周期（日/周/月）
+type PeriodKey = fn(&DateTime<Local>) -> String;
+
+/// 内容寻址的备份存储
+///
+/// 文件按SHA-256只保存一份（blobs/ab/abcdef...），每次备份只写一个引用这些文件的清单（snapshots/<id>.json）。
+pub struct BackupStore {
+    root: PathBuf,
+}
+
+/// 备份快照摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub created_at: String,
+    pub description: String,
+    pub driver_count: usize,
+    pub total_size: u64,
+}
+
+/// 写入快照时的去重统计
+#[derive(Debug, Clone, Default)]
+pub struct CommitStats {
+    pub files: usize,
+    pub new_blobs: usize,
+    pub reused_blobs: usize,
+    pub bytes_written: u64,
+}
+
+/// 保留策略，与restic的forget规则一致：每个周期保留最新的一个快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub keep_last: usize,
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+    pub keep_monthly: usize,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_last: 5,
+            keep_daily: 7,
+            keep_weekly: 4,
+            keep_monthly: 6,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.keep_last == 0 && self.keep_daily == 0 && self.keep_weekly == 0 && self.keep_monthly == 0
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RetentionReport {
+    pub kept: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GcReport {
+    pub removed_blobs: usize,
+    pub freed_bytes: u64,
+}
+
+/// 完整性检查结果
+#[derive(Debug, Clone, Default)]
+pub struct FsckReport {
+    pub snapshots_checked: usize,
+    pub blobs_checked: usize,
+    // (快照ID, 文件哈希)
+    pub missing_blobs: Vec<(String, String)>,
+    pub corrupt_blobs: Vec<String>,
+    pub unreadable_snapshots: Vec<String>,
+    pub orphaned_blobs: Vec<String>,
+}
+
+impl FsckReport {
+    /// 未引用的文件只占空间，不算损坏
+    pub fn is_healthy(&self) -> bool {
+        self.missing_blobs.is_empty() && self.corrupt_blobs.is_empty() && self.unreadable_snapshots.is_empty()
+    }
+}
+
+impl BackupStore {
+    /// 在默认备份目录打开存储
+    pub fn open_default() -> Result<Self> {
+        Self::open(&crate::utils::get_backup_dir()?)
+    }
+
+    pub fn open(root: &Path) -> Result<Self> {
+        fs::create_dir_all(root.join(BLOBS_DIR))?;
+        fs::create_dir_all(root.join(SNAPSHOTS_DIR))?;
+        Ok(BackupStore {
+            root: root.to_path_buf(),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 将备份包中的文件写入存储，并保存为新快照
+    pub fn commit_package(&self, package: &BackupPackage) -> Result<(SnapshotInfo, CommitStats)> {
+        // 持有锁直到快照写入，避免垃圾回收删除已写入但尚未被引用的文件
+        let _lock = StoreLock::acquire(&self.root)?;
+        let mut stats = CommitStats::default();
+
+        for entry in &package.manifest.entries {
+            for file in &entry.files {
+                let written = self.put_blob(&package.root.join(&file.path), &file.sha256)?;
+                stats.files += 1;
+                if written {
+                    stats.new_blobs += 1;
+                    stats.bytes_written += file.size;
+                } else {
+                    stats.reused_blobs += 1;
+                }
+            }
+        }
+
+        let id = self.new_snapshot_id(&package.manifest);
+        let content = serde_json::to_string_pretty(&package.manifest)?;
+        write_atomic(&self.snapshot_path(&id), content.as_bytes())?;
+
+        Ok((snapshot_info(&id, &package.manifest), stats))
+    }
+
+    /// 列出所有快照，最新的在前
+    pub fn list_snapshots(&self) -> Result<Vec<SnapshotInfo>> {
+        let mut snapshots = Vec::new();
+        for id in self.snapshot_ids()? {
+            let manifest = self.load_snapshot(&id)?;
+            snapshots.push(snapshot_info(&id, &manifest));
+        }
+        snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.id.cmp(&a.id)));
+        Ok(snapshots)
+    }
+
+    /// 读取快照清单
+    pub fn load_snapshot(&self, id: &str) -> Result<BackupManifest> {
+        let path = self.snapshot_path(id);
+        if !path.exists() {
+            return Err(anyhow::anyhow!("备份快照不存在: {}", id));
+        }
+        let content = fs::read_to_string(&path)?;
+        let manifest: BackupManifest = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("解析备份快照失败 {}: {}", id, e))?;
+        manifest.validate_paths()?;
+        Ok(manifest)
+    }
+
+    /// 将快照还原为普通备份包目录，供恢复流程使用
+    pub fn export_snapshot(&self, id: &str, dest: &Path) -> Result<BackupPackage> {
+        let manifest = self.load_snapshot(id)?;
+
+        for entry in &manifest.entries {
+            for file in &entry.files {
+                let blob = self.blob_path(&file.sha256)?;
+                if !blob.exists() {
+                    return Err(anyhow::anyhow!("备份文件缺失: {} ({})", file.path, file.sha256));
+                }
+                let target = dest.join(&file.path);
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(&blob, &target)?;
+            }
+        }
+
+        let package = BackupPackage {
+            root: dest.to_path_buf(),
+            manifest,
+        };
+        package.save()?;
+        Ok(package)
+    }
+
+    /// 校验单个快照引用的文件，返回发现的问题
+    pub fn verify_snapshot(&self, id: &str) -> Result<Vec<String>> {
+        let manifest = self.load_snapshot(id)?;
+        let mut problems = Vec::new();
+
+        for entry in &manifest.entries {
+            for file in &entry.files {
+                let blob = match self.blob_path(&file.sha256) {
+                    Ok(blob) => blob,
+                    Err(e) => {
+                        problems.push(format!("{}: {}", file.path, e));
+                        continue;
+                    }
+                };
+                if !blob.exists() {
+                    problems.push(format!("文件缺失: {}", file.path));
+                    continue;
+                }
+                match crate::installer::sha256_file(&blob) {
+                    Ok(hash) if hash.eq_ignore_ascii_case(&file.sha256) => {}
+                    Ok(_) => problems.push(format!("文件哈希不匹配: {}", file.path)),
+                    Err(e) => problems.push(format!("读取文件失败 {}: {}", file.path, e)),
+                }
+            }
+        }
+
+        Ok(problems)
+    }
+
+    /// 删除快照清单（文件由垃圾回收清理）
+    pub fn remove_snapshot(&self, id: &str) -> Result<()> {
+        let path = self.snapshot_path(id);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// 按保留策略删除旧快照
+    pub fn apply_retention(&self, policy: &RetentionPolicy, dry_run: bool) -> Result<RetentionReport> {
+        if policy.is_empty() {
+            return Err(anyhow::anyhow!("保留策略为空，拒绝删除所有备份"));
+        }
+
+        let snapshots = self.list_snapshots()?;
+        let keep = select_snapshots_to_keep(&snapshots, policy);
+        let mut report = RetentionReport::default();
+
+        for snapshot in snapshots {
+            if keep.contains(&snapshot.id) {
+                report.kept.push(snapshot.id);
+            } else {
+                if !dry_run {
+                    self.remove_snapshot(&snapshot.id)?;
+                }
+                report.removed.push(snapshot.id);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 删除不再被任何快照引用的文件
+    pub fn collect_garbage(&self) -> Result<GcReport> {
+        let _lock = StoreLock::acquire(&self.root)?;
+        let referenced = self.referenced_blobs()?;
+        let mut report = GcReport::default();
+
+        for (hash, path) in self.blob_files()? {
+            if !referenced.contains(&hash) {
+                report.freed_bytes += fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                fs::remove_file(&path)?;
+                report.removed_blobs += 1;
+            }
+        }
+
+        // 清理中断写入留下的临时文件
+        for entry in walkdir::WalkDir::new(self.root.join(BLOBS_DIR)) {
+            let entry = entry?;
+            if entry.file_type().is_file() && entry.file_name().to_string_lossy().ends_with(TEMP_SUFFIX) {
+                fs::remove_file(entry.path())?;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 完整性检查：快照可读、引用的文件存在且哈希正确
+    pub fn fsck(&self) -> Result<FsckReport> {
+        let mut report = FsckReport::default();
+        let mut referenced = HashSet::new();
+
+        for id in self.snapshot_ids()? {
+            report.snapshots_checked += 1;
+            let manifest = match self.load_snapshot(&id) {
+                Ok(manifest) => manifest,
+                Err(_) => {
+                    report.unreadable_snapshots.push(id);
+                    continue;
+                }
+            };
+
+            for entry in &manifest.entries {
+                for file in &entry.files {
+                    let hash = file.sha256.to_lowercase();
+                    if !self.blob_path(&hash).is_ok_and(|blob| blob.exists()) {
+                        report.missing_blobs.push((id.clone(), hash.clone()));
+                    }
+                    referenced.insert(hash);
+                }
+            }
+        }
+
+        for (hash, path) in self.blob_files()? {
+            report.blobs_checked += 1;
+            match crate::installer::sha256_file(&path) {
+                Ok(actual) if actual == hash => {}
+                _ => report.corrupt_blobs.push(hash.clone()),
+            }
+            if !referenced.contains(&hash) {
+                report.orphaned_blobs.push(hash);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 写入单个文件，已存在时跳过；返回是否实际写入
+    fn put_blob(&self, source: &Path, expected_hash: &str) -> Result<bool> {
+        let expected_hash = expected_hash.to_lowercase();
+        let blob = self.blob_path(&expected_hash)?;
+        if blob.exists() {
+            return Ok(false);
+        }
+
+        if let Some(parent) = blob.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // 边复制边计算哈希，确认源文件在登记后没有变化
+        let (_, actual_hash) = copy_file_verified(source, &blob, &expected_hash)?;
+        if actual_hash != expected_hash {
+            return Err(anyhow::anyhow!("文件在备份过程中被修改: {}", source.display()));
+        }
+        Ok(true)
+    }
+
+    // 清单中的哈希来自磁盘上的文件，不是64位十六进制时拒绝拼接路径
+    fn blob_path(&self, hash: &str) -> Result<PathBuf> {
+        if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(anyhow::anyhow!("无效的SHA-256: {}", hash));
+        }
+        let hash = hash.to_lowercase();
+        Ok(self.root.join(BLOBS_DIR).join(&hash[..2]).join(&hash))
+    }
+
+    fn snapshot_path(&self, id: &str) -> PathBuf {
+        self.root.join(SNAPSHOTS_DIR).join(format!("{}.json", id))
+    }
+
+    fn snapshot_ids(&self) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(self.root.join(SNAPSHOTS_DIR))? {
+            let path = entry?.path();
+            if path.extension().and_then(std::ffi::OsStr::to_str) == Some("json") {
+                if let Some(stem) = path.file_stem().and_then(std::ffi::OsStr::to_str) {
+                    ids.push(stem.to_string());
+                }
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn blob_files(&self) -> Result<Vec<(String, PathBuf)>> {
+        let mut blobs = Vec::new();
+        for entry in walkdir::WalkDir::new(self.root.join(BLOBS_DIR)).min_depth(2).max_depth(2) {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.file_type().is_file() && !name.ends_with(TEMP_SUFFIX) {
+                blobs.push((name, entry.path().to_path_buf()));
+            }
+        }
+        Ok(blobs)
+    }
+
+    fn referenced_blobs(&self) -> Result<HashSet<String>> {
+        let mut referenced = HashSet::new();
+        for id in self.snapshot_ids()? {
+            // 无法读取的快照可能仍引用文件，为安全起见中止回收
+            let manifest = self.load_snapshot(&id)?;
+            for entry in &manifest.entries {
+                for file in &entry.files {
+                    referenced.insert(file.sha256.to_lowercase());
+                }
+            }
+        }
+        Ok(referenced)
+    }
+
+    fn new_snapshot_id(&self, manifest: &BackupManifest) -> String {
+        let base = Local::now().format("%Y%m%dT%H%M%S").to_string();
+        let mut hasher = Sha256::new();
+        hasher.update(manifest.created_at.as_bytes());
+        hasher.update(manifest.description.as_bytes());
+        for entry in &manifest.entries {
+            for file in &entry.files {
+                hasher.update(file.sha256.as_bytes());
+            }
+        }
+        let digest = format!("{:x}", hasher.finalize());
+
+        let mut id = format!("{}-{}", base, &digest[..8]);
+        let mut counter = 1;
+        while self.snapshot_path(&id).exists() {
+            id = format!("{}-{}-{}", base, &digest[..8], counter);
+            counter += 1;
+        }
+        id
+    }
+}
+
+/// 写入与垃圾回收之间的存储锁，跨进程有效，释放时删除锁文件
+struct StoreLock {
+    path: PathBuf,
+}
+
+impl StoreLock {
+    fn acquire(root: &Path) -> Result<Self> {
+        let path = root.join(LOCK_FILE);
+        let started = Instant::now();
+        loop {
+            match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = writeln!(file, "{}", std::process::id());
+                    return Ok(StoreLock { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > STALE_LOCK_AGE);
+                    if stale {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if started.elapsed() > LOCK_WAIT {
+                        return Err(anyhow::anyhow!("备份存储正被其他操作使用: {}", path.display()));
+                    }
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn snapshot_info(id: &str, manifest: &BackupManifest) -> SnapshotInfo {
+    SnapshotInfo {
+        id: id.to_string(),
+        created_at: manifest.created_at.clone(),
+        description: manifest.description.clone(),
+        driver_count: manifest.entries.len(),
+        total_size: manifest.entries.iter()
+            .flat_map(|entry| entry.files.iter())
+            .map(|file| file.size)
+            .sum(),
+    }
+}
+
+/// 按保留策略选出要保留的快照，snapshots需按时间从新到旧排列
+pub fn select_snapshots_to_keep(snapshots: &[SnapshotInfo], policy: &RetentionPolicy) -> HashSet<String> {
+    let mut keep = HashSet::new();
+
+    for snapshot in snapshots.iter().take(policy.keep_last) {
+        keep.insert(snapshot.id.clone());
+    }
+
+    let dated: Vec<(&SnapshotInfo, DateTime<Local>)> = snapshots.iter()
+        .filter_map(|snapshot| {
+            DateTime::parse_from_rfc3339(&snapshot.created_at)
+                .ok()
+                .map(|time| (snapshot, time.with_timezone(&Local)))
+        })
+        .collect();
+
+    let buckets: [(usize, PeriodKey); 3] = [
+        (policy.keep_daily, |t| t.format("%Y-%m-%d").to_string()),
+        (policy.keep_weekly, |t| {
+            let week = t.iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        }),
+        (policy.keep_monthly, |t| t.format("%Y-%m").to_string()),
+    ];
+
+    for (count, bucket_of) in buckets {
+        // 每个周期只保留最新的一个快照
+        let mut seen = HashSet::new();
+        for (snapshot, time) in &dated {
+            if seen.len() >= count {
+                break;
+            }
+            if seen.insert(bucket_of(time)) {
+                keep.insert(snapshot.id.clone());
+            }
+        }
+    }
+
+    keep
+}
+
+/// 边复制边计算 SHA-256，先写到 dest 同目录的临时文件并同步到磁盘，
+/// 哈希与 expected 一致时替换 dest，否则删除临时文件。返回文件大小和实际的哈希
+pub fn copy_file_verified(source: &Path, dest: &Path, expected: &str) -> Result<(u64, String)> {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(TEMP_SUFFIX);
+    let temp = dest.with_file_name(name);
+    let result = (|| {
+        let mut input = fs::File::open(source)?;
+        let mut output = fs::File::create(&temp)?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut size = 0u64;
+        loop {
+            let read = input.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            output.write_all(&buffer[..read])?;
+            size += read as u64;
+        }
+        output.sync_all()?;
+        drop(output);
+
+        let actual = format!("{:x}", hasher.finalize());
+        if actual.eq_ignore_ascii_case(expected.trim()) {
+            fs::rename(&temp, dest)?;
+        } else {
+            fs::remove_file(&temp)?;
+        }
+        Ok((size, actual))
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    let temp = path.with_extension("json.tmp");
+    let mut file = fs::File::create(&temp)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::TempDir;
+    use crate::hardware::HardwareDevice;
+
+    fn package_with_file(root: &Path, content: &str) -> BackupPackage {
+        let device = HardwareDevice {
+            device_id: "PCI\\VEN_10DE&DEV_1C82\\4&1".to_string(),
+            device_name: "测试显卡".to_string(),
+            hardware_id: "PCI".to_string(),
+            driver_version: "1.0".to_string(),
+            driver_date: "Unknown".to_string(),
+            manufacturer: "NVIDIA".to_string(),
+            device_class: "显示适配器".to_string(),
+            ..Default::default()
+        };
+        fs::create_dir_all(root.join("gpu")).unwrap();
+        fs::write(root.join("gpu/nv.inf"), "[Version]").unwrap();
+        fs::write(root.join("gpu/nv.sys"), content).unwrap();
+        let mut package = BackupPackage::create(root, "测试").unwrap();
+        package.add_entry(&device, "1.0", "gpu").unwrap();
+        package
+    }
+
+    fn snapshot(id: &str, created_at: &str) -> SnapshotInfo {
+        SnapshotInfo {
+            id: id.to_string(),
+            created_at: created_at.to_string(),
+            description: String::new(),
+            driver_count: 0,
+            total_size: 0,
+        }
+    }
+
+    #[test]
+    fn test_commit_deduplicates_and_gc() {
+        let root = TempDir::new("store_dedup");
+        let store = BackupStore::open(&root.join("store")).unwrap();
+
+        let (first, stats) = store.commit_package(&package_with_file(&root.join("a"), "same")).unwrap();
+        assert_eq!(stats.new_blobs, 2);
+        let (_, stats) = store.commit_package(&package_with_file(&root.join("b"), "same")).unwrap();
+        assert_eq!(stats.new_blobs, 0);
+        assert_eq!(stats.reused_blobs, 2);
+        store.commit_package(&package_with_file(&root.join("c"), "different")).unwrap();
+
+        let exported = store.export_snapshot(&first.id, &root.join("export")).unwrap();
+        assert!(exported.verify().iter().all(|v| v.valid));
+
+        // 删除唯一引用"different"的快照后，该文件应被回收
+        let latest = store.list_snapshots().unwrap()[0].id.clone();
+        store.remove_snapshot(&latest).unwrap();
+        assert_eq!(store.fsck().unwrap().orphaned_blobs.len(), 1);
+        assert_eq!(store.collect_garbage().unwrap().removed_blobs, 1);
+        assert!(store.fsck().unwrap().is_healthy());
+    }
+
+    #[test]
+    fn test_gc_waits_for_commit_lock() {
+        let root = TempDir::new("store_lock");
+        let store = BackupStore::open(&root.join("store")).unwrap();
+        let temp = root.join("store").join(BLOBS_DIR).join("ab").join(format!("{}{}", "ab".repeat(32), TEMP_SUFFIX));
+        fs::create_dir_all(temp.parent().unwrap()).unwrap();
+        fs::write(&temp, "partial").unwrap();
+
+        // 写入进行中时，回收不能删除正在写入的临时文件
+        let lock = StoreLock::acquire(store.root()).unwrap();
+        let gc = {
+            let store = BackupStore::open(store.root()).unwrap();
+            std::thread::spawn(move || store.collect_garbage())
+        };
+        std::thread::sleep(Duration::from_millis(300));
+        assert!(temp.exists());
+        drop(lock);
+        gc.join().unwrap().unwrap();
+        assert!(!temp.exists());
+        assert!(!store.root().join(LOCK_FILE).exists());
+    }
+
+    #[test]
+    fn test_blob_path_rejects_invalid_hash() {
+        let root = TempDir::new("store_hash");
+        let store = BackupStore::open(&root.join("store")).unwrap();
+        assert!(store.blob_path(&"AB".repeat(32)).is_ok());
+        assert!(store.blob_path("../../outside").is_err());
+        assert!(store.blob_path(&format!("../{}", "a".repeat(61))).is_err());
+    }
+
+    #[test]
+    fn test_fsck_detects_corruption() {
+        let root = TempDir::new("store_fsck");
+        let store = BackupStore::open(&root.join("store")).unwrap();
+        store.commit_package(&package_with_file(&root.join("a"), "payload")).unwrap();
+
+        let (_, blob) = store.blob_files().unwrap().into_iter()
+            .find(|(_, path)| fs::read_to_string(path).map(|c| c == "payload").unwrap_or(false))
+            .unwrap();
+        fs::write(&blob, "corrupted").unwrap();
+
+        let report = store.fsck().unwrap();
+        assert_eq!(report.corrupt_blobs.len(), 1);
+        assert!(!report.is_healthy());
+    }
+
+    #[test]
+    fn test_retention_keeps_newest_per_period() {
+        // 从新到旧排列
+        let snapshots = vec![
+            snapshot("d3b", "2026-03-03T10:00:00+00:00"),
+            snapshot("d3a", "2026-03-03T08:00:00+00:00"),
+            snapshot("d2", "2026-03-02T12:00:00+00:00"),
+            snapshot("feb", "2026-02-10T12:00:00+00:00"),
+            snapshot("jan", "2026-01-10T12:00:00+00:00"),
+        ];
+
+        let policy = RetentionPolicy { keep_last: 1, keep_daily: 2, keep_weekly: 0, keep_monthly: 3 };
+        let keep = select_snapshots_to_keep(&snapshots, &policy);
+
+        assert!(keep.contains("d3b"));
+        assert!(!keep.contains("d3a"));
+        assert!(keep.contains("d2"));
+        assert!(keep.contains("feb"));
+        assert!(keep.contains("jan"));
+        assert_eq!(keep.len(), 4);
+    }
+}
@@ -2,10 +2,14 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::process::Command;
 use std::path::Path;
+use std::time::Duration;
 use tokio::fs;
+use crate::utils::{run_command_with_watchdog, CancellationToken, HamsterError};
 
+// 安装成功但需要重启（ERROR_SUCCESS_REBOOT_REQUIRED）
+const EXIT_REBOOT_REQUIRED: i32 = 3010;
 
-
+const DEFAULT_WATCHDOG_TIMEOUT: Duration = Duration::from_secs(900);
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct InstallationResult {
@@ -13,6 +17,12 @@ pub struct InstallationResult {
     pub message: String,
     pub driver_version: String,
     pub installed_at: String,
+    /// 安装成功但需要重启才能生效
+    #[serde(default)]
+    pub reboot_required: bool,
+    /// 安装因取消或超时被终止
+    #[serde(default)]
+    pub aborted: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -26,16 +36,29 @@ pub struct DriverInfo {
 
 pub struct DriverInstaller {
     pub needs_elevation: bool,
+    /// 安装程序运行超过该时间后被看门狗终止
+    pub watchdog_timeout: Duration,
 }
 
 impl DriverInstaller {
     pub fn new() -> Self {
         DriverInstaller {
             needs_elevation: false,
+            watchdog_timeout: DEFAULT_WATCHDOG_TIMEOUT,
         }
     }
 
+    pub fn with_watchdog_timeout(mut self, timeout: Duration) -> Self {
+        self.watchdog_timeout = timeout;
+        self
+    }
+
     pub async fn install_driver(&self, driver_info: &DriverInfo) -> Result<InstallationResult> {
+        self.install_driver_cancellable(driver_info, &CancellationToken::new()).await
+    }
+
+    /// 安装驱动，取消或超过看门狗时限时终止安装程序
+    pub async fn install_driver_cancellable(&self, driver_info: &DriverInfo, cancel: &CancellationToken) -> Result<InstallationResult> {
         // 检查是否需要提升权限
         if !self.has_admin_privileges() {
             return Err(anyhow::anyhow!("需要管理员权限来安装驱动程序"));
@@ -49,72 +72,86 @@ impl DriverInstaller {
             .to_lowercase();
 
         let result = match file_extension.as_str() {
-            "inf" => self.install_inf_driver(driver_info).await,
-            "exe" => self.install_exe_driver(driver_info).await,
-            "msi" => self.install_msi_driver(driver_info).await,
+            "inf" => self.install_inf_driver(driver_info, cancel).await,
+            "exe" => self.install_exe_driver(driver_info, cancel).await,
+            "msi" => self.install_msi_driver(driver_info, cancel).await,
             _ => Err(anyhow::anyhow!("不支持的驱动文件格式: {}", file_extension)),
         };
 
         match result {
-            Ok(_success) => {
+            Ok(reboot_required) => {
                 Ok(InstallationResult {
                     success: true,
                     message: format!("驱动程序安装成功: {}", driver_info.file_name),
                     driver_version: driver_info.driver_version.clone(),
                     installed_at: chrono::Utc::now().to_rfc3339(),
+                    reboot_required,
+                    aborted: false,
                 })
             }
             Err(e) => {
+                let aborted = matches!(
+                    e.downcast_ref::<HamsterError>(),
+                    Some(HamsterError::Cancelled(_) | HamsterError::TimeoutError(_))
+                );
                 Ok(InstallationResult {
                     success: false,
                     message: format!("驱动程序安装失败: {}", e),
                     driver_version: driver_info.driver_version.clone(),
                     installed_at: chrono::Utc::now().to_rfc3339(),
+                    reboot_required: false,
+                    aborted,
                 })
             }
         }
     }
 
-    async fn install_inf_driver(&self, driver_info: &DriverInfo) -> Result<()> {
+    async fn install_inf_driver(&self, driver_info: &DriverInfo, cancel: &CancellationToken) -> Result<bool> {
         // 验证路径长度
         if driver_info.file_path.is_empty() || driver_info.file_path.len() > 32767 {
             return Err(anyhow::anyhow!("驱动文件路径无效: {}", driver_info.file_path));
         }
         
         // 使用pnputil安装INF驱动
-        let output = Command::new("pnputil")
-            .args(&["/add-driver", &driver_info.file_path, "/install"])
-            .output()?;
+        let mut command = tokio::process::Command::new("pnputil");
+        command.args(["/add-driver", &driver_info.file_path, "/install"]);
+        let output = run_command_with_watchdog(command, self.watchdog_timeout, cancel).await?;
 
         if output.status.success() {
-            Ok(())
+            Ok(false)
+        } else if output.status.code() == Some(EXIT_REBOOT_REQUIRED) {
+            Ok(true)
         } else {
             let error_msg = String::from_utf8_lossy(&output.stderr);
             Err(anyhow::anyhow!("pnputil安装失败: {}", error_msg))
         }
     }
 
-    async fn install_exe_driver(&self, driver_info: &DriverInfo) -> Result<()> {
+    async fn install_exe_driver(&self, driver_info: &DriverInfo, cancel: &CancellationToken) -> Result<bool> {
         // 验证路径长度
         if driver_info.file_path.is_empty() || driver_info.file_path.len() > 32767 {
             return Err(anyhow::anyhow!("驱动文件路径无效: {}", driver_info.file_path));
         }
         
         // 使用静默参数安装EXE驱动
-        let output = Command::new(&driver_info.file_path)
-            .args(&["/S", "/SILENT", "/VERYSILENT"]) // 尝试多种静默安装参数
-            .output()?;
+        let mut command = tokio::process::Command::new(&driver_info.file_path);
+        command.args(["/S", "/SILENT", "/VERYSILENT"]); // 尝试多种静默安装参数
+        let output = run_command_with_watchdog(command, self.watchdog_timeout, cancel).await?;
 
         if output.status.success() {
-            Ok(())
+            Ok(false)
+        } else if output.status.code() == Some(EXIT_REBOOT_REQUIRED) {
+            Ok(true)
         } else {
             // 如果标准参数失败，尝试其他参数
-            let output = Command::new(&driver_info.file_path)
-                .args(&["/quiet", "/quietinstall"])
-                .output()?;
+            let mut command = tokio::process::Command::new(&driver_info.file_path);
+            command.args(["/quiet", "/quietinstall"]);
+            let output = run_command_with_watchdog(command, self.watchdog_timeout, cancel).await?;
 
             if output.status.success() {
-                Ok(())
+                Ok(false)
+            } else if output.status.code() == Some(EXIT_REBOOT_REQUIRED) {
+                Ok(true)
             } else {
                 let error_msg = String::from_utf8_lossy(&output.stderr);
                 Err(anyhow::anyhow!("EXE驱动安装失败: {}", error_msg))
@@ -122,19 +159,21 @@ impl DriverInstaller {
         }
     }
 
-    async fn install_msi_driver(&self, driver_info: &DriverInfo) -> Result<()> {
+    async fn install_msi_driver(&self, driver_info: &DriverInfo, cancel: &CancellationToken) -> Result<bool> {
         // 验证路径长度
         if driver_info.file_path.is_empty() || driver_info.file_path.len() > 32767 {
             return Err(anyhow::anyhow!("驱动文件路径无效: {}", driver_info.file_path));
         }
         
         // 使用msiexec安装MSI驱动
-        let output = Command::new("msiexec")
-            .args(&["/i", &driver_info.file_path, "/quiet", "/norestart"])
-            .output()?;
+        let mut command = tokio::process::Command::new("msiexec");
+        command.args(["/i", &driver_info.file_path, "/quiet", "/norestart"]);
+        let output = run_command_with_watchdog(command, self.watchdog_timeout, cancel).await?;
 
         if output.status.success() {
-            Ok(())
+            Ok(false)
+        } else if output.status.code() == Some(EXIT_REBOOT_REQUIRED) {
+            Ok(true)
         } else {
             let error_msg = String::from_utf8_lossy(&output.stderr);
             Err(anyhow::anyhow!("MSI驱动安装失败: {}", error_msg))
@@ -282,7 +321,8 @@ impl DriverInstaller {
                 hardware_id: "".to_string(),
                 manufacturer: "".to_string(),
                 driver_version: "".to_string(),
-            }).await
+            }, &CancellationToken::new()).await?;
+            Ok(())
         } else {
             Err(anyhow::anyhow!("不支持的备份文件格式"))
         }
@@ -0,0 +1,509 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use crate::t;
+
+const JOURNAL_EXTENSION: &str = "jsonl";
+
+/// 安装事务日志（预写日志）
+///
+/// 每个事务一个JSON Lines文件，每条记录在执行对应操作之前写入并刷盘，
+/// 程序或系统中途崩溃后可据此判断哪些设备做到了哪一步。
+pub struct InstallJournal {
+    dir: PathBuf,
+}
+
+/// 单台设备安装过程中的步骤
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InstallStep {
+    RestorePoint,
+    Backup,
+    Download,
+    Verify,
+    Install,
+    VerifyAfter,
+}
+
+impl InstallStep {
+    pub fn label(&self) -> String {
+        let key = match self {
+            InstallStep::RestorePoint => "step-restore-point",
+            InstallStep::Backup => "step-backup",
+            InstallStep::Download => "step-download",
+            InstallStep::Verify => "step-verify",
+            InstallStep::Install => "step-install",
+            InstallStep::VerifyAfter => "step-verify-after",
+        };
+        t!(key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum StepStatus {
+    Started,
+    Completed { detail: Option<String> },
+    Failed { error: String },
+}
+
+/// 事务类型，恢复时据此决定如何继续
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TransactionKind {
+    Update,
+    Install { driver_path: String },
+    Restore { package_path: String, entry_ids: Vec<String> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionOutcome {
+    Committed,
+    RolledBack,
+    Abandoned,
+    /// 因错误提前结束，出错的设备已按失败处理，不需要恢复
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEvent {
+    Begin {
+        description: String,
+        kind: TransactionKind,
+        hardware_ids: Vec<String>,
+    },
+    Step {
+        hardware_id: Option<String>, // None 表示整批共用的步骤
+        step: InstallStep,
+        #[serde(flatten)]
+        status: StepStatus,
+    },
+    RollbackPointCreated { rollback_id: String },
+    Resumed,
+    End { outcome: TransactionOutcome },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalRecord {
+    pub seq: u64,
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+/// 步骤记录写入后的通知
+pub type StepListener = Box<dyn Fn(Option<&str>, InstallStep, &StepStatus) + Send + Sync>;
+
+/// 正在写入的事务
+pub struct JournalTransaction {
+    id: String,
+    file: File,
+    next_seq: u64,
+    step_listener: Option<StepListener>,
+}
+
+/// 单台设备在事务中的进度
+#[derive(Debug, Clone, Default)]
+pub struct DeviceProgress {
+    pub hardware_id: String,
+    pub completed: Vec<InstallStep>,
+    pub in_progress: Option<InstallStep>,
+    pub failed: Option<(InstallStep, String)>,
+}
+
+impl DeviceProgress {
+    /// 安装后验证完成或已明确失败的设备不需要恢复
+    pub fn is_finished(&self) -> bool {
+        self.completed.contains(&InstallStep::VerifyAfter) || self.failed.is_some()
+    }
+
+    /// 是否在安装驱动的过程中中断，此时设备驱动状态不确定
+    pub fn interrupted_during_install(&self) -> bool {
+        self.in_progress == Some(InstallStep::Install)
+            || (self.completed.contains(&InstallStep::Install) && !self.is_finished())
+    }
+}
+
+/// 从日志重建的事务状态
+#[derive(Debug, Clone)]
+pub struct TransactionSummary {
+    pub id: String,
+    pub description: String,
+    pub kind: TransactionKind,
+    pub started_at: String,
+    pub last_update: String,
+    pub rollback_id: Option<String>,
+    pub devices: Vec<DeviceProgress>,
+    pub outcome: Option<TransactionOutcome>,
+}
+
+impl TransactionSummary {
+    pub fn is_incomplete(&self) -> bool {
+        self.outcome.is_none()
+    }
+
+    pub fn pending_devices(&self) -> Vec<&DeviceProgress> {
+        self.devices.iter().filter(|device| !device.is_finished()).collect()
+    }
+}
+
+impl InstallJournal {
+    pub fn open_default() -> Result<Self> {
+        Self::open(&crate::utils::get_app_data_dir()?.join("journal"))
+    }
+
+    pub fn open(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self { dir: dir.to_path_buf() })
+    }
+
+    /// 开始新事务，Begin记录刷盘后才返回
+    pub fn begin(&self, description: &str, kind: TransactionKind, hardware_ids: &[String]) -> Result<JournalTransaction> {
+        let now = chrono::Local::now();
+        let id = format!("{}-{:08x}", now.format("%Y%m%dT%H%M%S"), now.timestamp_subsec_nanos());
+        let file = OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(self.journal_path(&id))?;
+        sync_dir(&self.dir);
+
+        let mut transaction = JournalTransaction { id, file, next_seq: 0, step_listener: None };
+        transaction.record(JournalEvent::Begin {
+            description: description.to_string(),
+            kind,
+            hardware_ids: hardware_ids.to_vec(),
+        })?;
+        Ok(transaction)
+    }
+
+    /// 重新打开未完成的事务继续追加记录，先截掉崩溃留下的不完整记录
+    pub fn reopen(&self, id: &str) -> Result<JournalTransaction> {
+        let (records, valid_len) = self.read_records(id)?;
+        if records.iter().any(|record| matches!(record.event, JournalEvent::End { .. })) {
+            return Err(anyhow::anyhow!("事务已结束: {}", id));
+        }
+        let file = OpenOptions::new().write(true).open(self.journal_path(id))?;
+        file.set_len(valid_len)?;
+        drop(file);
+        let file = OpenOptions::new().append(true).open(self.journal_path(id))?;
+        let next_seq = records.last().map(|record| record.seq + 1).unwrap_or(0);
+
+        let mut transaction = JournalTransaction { id: id.to_string(), file, next_seq, step_listener: None };
+        transaction.record(JournalEvent::Resumed)?;
+        Ok(transaction)
+    }
+
+    /// 读取事务并重建每台设备的进度
+    pub fn load(&self, id: &str) -> Result<TransactionSummary> {
+        let (records, _) = self.read_records(id)?;
+        summarize(id, &records)
+    }
+
+    /// 列出所有没有结束记录的事务，按开始时间排序
+    pub fn incomplete_transactions(&self) -> Result<Vec<TransactionSummary>> {
+        let mut summaries = Vec::new();
+        for id in self.transaction_ids()? {
+            match self.load(&id) {
+                Ok(summary) if summary.is_incomplete() => summaries.push(summary),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("{}", t!("journal-unreadable", id = id, error = e)),
+            }
+        }
+        summaries.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+        Ok(summaries)
+    }
+
+    /// 删除已结束事务的日志，返回删除数量
+    pub fn remove_finished(&self) -> Result<usize> {
+        let mut removed = 0;
+        for id in self.transaction_ids()? {
+            if matches!(self.load(&id), Ok(summary) if !summary.is_incomplete()) {
+                fs::remove_file(self.journal_path(&id))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn transaction_ids(&self) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some(JOURNAL_EXTENSION) {
+                if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    ids.push(stem.to_string());
+                }
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn journal_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", id, JOURNAL_EXTENSION))
+    }
+
+    // 崩溃可能留下写了一半的最后一行，忽略它；中间的行损坏则视为日志不可用。
+    // 同时返回完整记录的字节长度
+    fn read_records(&self, id: &str) -> Result<(Vec<JournalRecord>, u64)> {
+        let content = fs::read_to_string(self.journal_path(id))?;
+        let lines: Vec<&str> = content.split_inclusive('\n').collect();
+        let mut records = Vec::new();
+        let mut valid_len = 0;
+
+        for (index, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                valid_len += line.len() as u64;
+                continue;
+            }
+            let parsed = if line.ends_with('\n') {
+                serde_json::from_str::<JournalRecord>(line).map_err(anyhow::Error::from)
+            } else {
+                Err(anyhow::anyhow!("缺少换行符"))
+            };
+            match parsed {
+                Ok(record) => records.push(record),
+                Err(_) if index + 1 == lines.len() => {
+                    tracing::warn!("{}", t!("journal-truncated-record", id = id));
+                    break;
+                }
+                Err(e) => return Err(anyhow::anyhow!("安装日志第 {} 行损坏: {}", index + 1, e)),
+            }
+            valid_len += line.len() as u64;
+        }
+        Ok((records, valid_len))
+    }
+}
+
+impl JournalTransaction {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 每个步骤记录写入后调用 listener，用于发布安装进度
+    pub fn set_step_listener(&mut self, listener: StepListener) {
+        self.step_listener = Some(listener);
+    }
+
+    /// 追加一条记录并刷盘
+    pub fn record(&mut self, event: JournalEvent) -> Result<()> {
+        let record = JournalRecord {
+            seq: self.next_seq,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event,
+        };
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.file.sync_data()?;
+        self.next_seq += 1;
+        Ok(())
+    }
+
+    pub fn step_started(&mut self, hardware_id: Option<&str>, step: InstallStep) -> Result<()> {
+        self.step(hardware_id, step, StepStatus::Started)
+    }
+
+    pub fn step_completed(&mut self, hardware_id: Option<&str>, step: InstallStep, detail: Option<String>) -> Result<()> {
+        self.step(hardware_id, step, StepStatus::Completed { detail })
+    }
+
+    pub fn step_failed(&mut self, hardware_id: Option<&str>, step: InstallStep, error: &str) -> Result<()> {
+        self.step(hardware_id, step, StepStatus::Failed { error: error.to_string() })
+    }
+
+    /// 写入结束记录，之后该事务不再出现在待恢复列表中
+    pub fn finish(mut self, outcome: TransactionOutcome) -> Result<()> {
+        self.record(JournalEvent::End { outcome })
+    }
+
+    /// 按操作结果写入结束记录：成功时为 Committed，出错时为 Failed
+    ///
+    /// 出错时结束记录写入失败只记录警告，返回原来的错误。
+    pub fn finish_with<T>(self, result: Result<T>) -> Result<T> {
+        match result {
+            Ok(value) => {
+                self.finish(TransactionOutcome::Committed)?;
+                Ok(value)
+            }
+            Err(e) => {
+                let id = self.id.clone();
+                if let Err(finish_error) = self.finish(TransactionOutcome::Failed) {
+                    tracing::warn!("写入安装事务 {} 的结束记录失败: {}", id, finish_error);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    fn step(&mut self, hardware_id: Option<&str>, step: InstallStep, status: StepStatus) -> Result<()> {
+        self.record(JournalEvent::Step {
+            hardware_id: hardware_id.map(|id| id.to_string()),
+            step,
+            status: status.clone(),
+        })?;
+        if let Some(ref listener) = self.step_listener {
+            listener(hardware_id, step, &status);
+        }
+        Ok(())
+    }
+}
+
+fn summarize(id: &str, records: &[JournalRecord]) -> Result<TransactionSummary> {
+    let first = records.first().ok_or_else(|| anyhow::anyhow!("安装日志为空: {}", id))?;
+    let (description, kind, hardware_ids) = match &first.event {
+        JournalEvent::Begin { description, kind, hardware_ids } => (description.clone(), kind.clone(), hardware_ids.clone()),
+        _ => return Err(anyhow::anyhow!("安装日志缺少开始记录: {}", id)),
+    };
+
+    let mut devices: HashMap<String, DeviceProgress> = hardware_ids.iter()
+        .map(|hardware_id| (hardware_id.clone(), DeviceProgress {
+            hardware_id: hardware_id.clone(),
+            ..Default::default()
+        }))
+        .collect();
+    let mut summary = TransactionSummary {
+        id: id.to_string(),
+        description,
+        kind,
+        started_at: first.timestamp.clone(),
+        last_update: first.timestamp.clone(),
+        rollback_id: None,
+        devices: Vec::new(),
+        outcome: None,
+    };
+
+    for record in &records[1..] {
+        summary.last_update = record.timestamp.clone();
+        match &record.event {
+            JournalEvent::Step { hardware_id: Some(hardware_id), step, status } => {
+                let device = devices.entry(hardware_id.clone()).or_insert_with(|| DeviceProgress {
+                    hardware_id: hardware_id.clone(),
+                    ..Default::default()
+                });
+                match status {
+                    StepStatus::Started => {
+                        device.in_progress = Some(*step);
+                        device.failed = None;
+                    }
+                    StepStatus::Completed { .. } => {
+                        device.in_progress = None;
+                        if !device.completed.contains(step) {
+                            device.completed.push(*step);
+                        }
+                    }
+                    StepStatus::Failed { error } => {
+                        device.in_progress = None;
+                        device.failed = Some((*step, error.clone()));
+                    }
+                }
+            }
+            JournalEvent::RollbackPointCreated { rollback_id } => summary.rollback_id = Some(rollback_id.clone()),
+            JournalEvent::End { outcome } => summary.outcome = Some(*outcome),
+            _ => {}
+        }
+    }
+
+    summary.devices = hardware_ids.iter()
+        .filter_map(|hardware_id| devices.remove(hardware_id))
+        .collect();
+    summary.devices.extend(devices.into_values());
+    Ok(summary)
+}
+
+// 新建日志文件后刷新目录项，Windows上无法对目录调用fsync
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::TempDir;
+
+    #[test]
+    fn test_incomplete_transaction_is_recovered() {
+        let root = TempDir::new("journal_recover");
+        let journal = InstallJournal::open(root.path()).unwrap();
+        let ids = vec!["PCI\\VEN_1".to_string(), "PCI\\VEN_2".to_string()];
+
+        let mut transaction = journal.begin("批量更新驱动", TransactionKind::Update, &ids).unwrap();
+        transaction.record(JournalEvent::RollbackPointCreated { rollback_id: "rb_1".to_string() }).unwrap();
+        for step in [InstallStep::Download, InstallStep::Verify, InstallStep::Install, InstallStep::VerifyAfter] {
+            transaction.step_started(Some(&ids[0]), step).unwrap();
+            transaction.step_completed(Some(&ids[0]), step, None).unwrap();
+        }
+        transaction.step_started(Some(&ids[1]), InstallStep::Download).unwrap();
+        transaction.step_completed(Some(&ids[1]), InstallStep::Download, None).unwrap();
+        transaction.step_started(Some(&ids[1]), InstallStep::Install).unwrap();
+        let id = transaction.id().to_string();
+        drop(transaction);
+
+        // 模拟写到一半时断电
+        let path = root.join(format!("{}.jsonl", id));
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"seq\":99,\"timest").unwrap();
+
+        let incomplete = journal.incomplete_transactions().unwrap();
+        assert_eq!(incomplete.len(), 1);
+        let summary = &incomplete[0];
+        assert_eq!(summary.rollback_id.as_deref(), Some("rb_1"));
+        let pending = summary.pending_devices();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].hardware_id, ids[1]);
+        assert!(pending[0].interrupted_during_install());
+
+        let mut resumed = journal.reopen(&id).unwrap();
+        resumed.step_failed(Some(&ids[1]), InstallStep::Install, "安装中断").unwrap();
+        drop(resumed);
+        let summary = journal.load(&id).unwrap();
+        assert!(summary.pending_devices().is_empty());
+        assert!(summary.is_incomplete());
+    }
+
+    #[test]
+    fn test_finished_transactions_are_not_pending() {
+        let root = TempDir::new("journal_finish");
+        let journal = InstallJournal::open(root.path()).unwrap();
+        let ids = vec!["USB\\VID_1".to_string()];
+
+        let transaction = journal.begin("安装驱动", TransactionKind::Install { driver_path: "a.inf".to_string() }, &ids).unwrap();
+        let id = transaction.id().to_string();
+        drop(transaction);
+
+        let mut resumed = journal.reopen(&id).unwrap();
+        resumed.step_started(Some(&ids[0]), InstallStep::Install).unwrap();
+        resumed.step_failed(Some(&ids[0]), InstallStep::Install, "pnputil 失败").unwrap();
+        resumed.finish(TransactionOutcome::Committed).unwrap();
+
+        assert!(journal.incomplete_transactions().unwrap().is_empty());
+        assert!(journal.reopen(&id).is_err());
+        assert_eq!(journal.remove_finished().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_install_error_leaves_no_pending_transaction() {
+        let root = TempDir::new("journal_error");
+        let journal = InstallJournal::open(root.path()).unwrap();
+        let ids = vec!["PCI\\VEN_10DE".to_string()];
+
+        let mut transaction = journal.begin("安装驱动", TransactionKind::Install { driver_path: "nv.inf".to_string() }, &ids).unwrap();
+        let id = transaction.id().to_string();
+        transaction.step_started(Some(&ids[0]), InstallStep::Install).unwrap();
+        transaction.step_failed(Some(&ids[0]), InstallStep::Install, "安装程序被终止").unwrap();
+        let result: Result<()> = transaction.finish_with(Err(anyhow::anyhow!("安装后验证失败")));
+
+        assert!(result.is_err());
+        assert!(journal.incomplete_transactions().unwrap().is_empty());
+        assert_eq!(journal.load(&id).unwrap().outcome, Some(TransactionOutcome::Failed));
+    }
+}
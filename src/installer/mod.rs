@@ -1,2 +1,8 @@
 mod driver_installer;
-pub use driver_installer::*;
\ No newline at end of file
+mod backup_package;
+mod backup_store;
+mod install_journal;
+pub use driver_installer::*;
+pub use backup_package::*;
+pub use backup_store::*;
+pub use install_journal::*;
@@ -4,6 +4,13 @@ pub mod hardware;
 pub mod matcher;
 pub mod fetcher;
 pub mod installer;
+pub mod driver;
 pub mod core;
 pub mod ui;
 pub mod utils;
+pub mod i18n;
+pub mod config;
+pub mod notify;
+pub mod repository;
+pub mod agent;
+pub mod cli;
\ No newline at end of file
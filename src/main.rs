@@ -1,10 +1,24 @@
 
 
+use hamster_drivers::t;
 use hamster_drivers::ui::HamsterDriveApp;
 
 #[tokio::main]
 async fn main() {
-    env_logger::init(); // 初始化日志
+    // 初始化日志、界面语言和网络设置，RUST_LOG 未设置时使用配置中的日志级别
+    let config = hamster_drivers::config::ConfigManager::load_or_default().unwrap_or_default();
+    let app_config = &config.app_config;
+    hamster_drivers::i18n::set_locale(app_config.locale());
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(app_config.log_level.as_str())).init();
+    if let Err(e) = hamster_drivers::utils::set_network_config(&config.network_config) {
+        tracing::warn!("网络配置无效，使用默认设置: {}", e);
+    }
+    
+    // 带参数时执行命令行命令，不启动界面
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(hamster_drivers::cli::run(&args));
+    }
     
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -13,10 +27,10 @@ async fn main() {
     };
     
     eframe::run_native(
-        "仓鼠驱动管家",
+        &t!("app-name"),
         options,
         Box::new(|cc| {
             Box::new(HamsterDriveApp::new(cc))
         }),
-    ).expect("启动GUI失败");
+    ).expect(&t!("gui-start-failed"));
 }
\ No newline at end of file
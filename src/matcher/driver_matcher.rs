@@ -1,6 +1,11 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use crate::config::RepositoryConfig;
 use crate::matcher::scraper::HardwareScraper;
+use crate::repository::{DriverQuery, RepositoryClient};
+use crate::t;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HardwareInfo {
@@ -10,7 +15,7 @@ pub struct HardwareInfo {
     pub device_class: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct DriverInfo {
     pub driver_id: String,
     pub hardware_id: String,
@@ -21,6 +26,83 @@ pub struct DriverInfo {
     pub release_date: String,
     pub file_size: u64,
     pub checksum: String,
+    /// 发布说明页面，厂商未提供时为空
+    #[serde(default)]
+    pub release_notes_url: Option<String>,
+}
+
+/// 驱动发布渠道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriverChannel {
+    Stable,
+    Beta,
+}
+
+impl DriverChannel {
+    pub fn label(&self) -> String {
+        match self {
+            DriverChannel::Stable => t!("channel-stable"),
+            DriverChannel::Beta => t!("channel-beta"),
+        }
+    }
+}
+
+impl DriverInfo {
+    /// 根据名称和版本号判断发布渠道
+    pub fn channel(&self) -> DriverChannel {
+        let text = format!("{} {}", self.driver_name, self.driver_version).to_lowercase();
+        if ["beta", "preview", "insider", "-rc", " rc"].iter().any(|tag| text.contains(tag)) {
+            DriverChannel::Beta
+        } else {
+            DriverChannel::Stable
+        }
+    }
+
+    /// 下载来源的主机名
+    pub fn source(&self) -> &str {
+        let without_scheme = self.driver_url.split("://").nth(1).unwrap_or(&self.driver_url);
+        without_scheme.split('/').next().unwrap_or_default()
+    }
+
+    /// 下载后保存的安装程序文件名
+    pub fn file_name(&self) -> String {
+        format!("{}_{}.exe", self.driver_name.replace(' ', "_"), self.driver_version.replace('.', "_"))
+    }
+}
+
+/// 比较两个驱动版本号，任意一方无法解析时返回 None
+pub fn compare_driver_versions(a: &str, b: &str) -> Option<std::cmp::Ordering> {
+    let parse = |version: &str| -> Option<Vec<u64>> {
+        version.trim().split('.').map(|part| part.trim().parse().ok()).collect()
+    };
+    let (mut a, mut b) = (parse(a)?, parse(b)?);
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    Some(a.cmp(&b))
+}
+
+/// 解析网页上的文件大小，如 "700MB"、"1.2 GB"，无法解析时返回 0
+pub fn parse_file_size(size: &str) -> u64 {
+    let size = size.trim().to_uppercase();
+    let split = size.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(size.len());
+    let (number, unit) = size.split_at(split);
+    let multiplier = match unit.trim() {
+        "" | "B" => 1u64,
+        "KB" | "K" => 1 << 10,
+        "MB" | "M" => 1 << 20,
+        "GB" | "G" => 1 << 30,
+        _ => return 0,
+    };
+    number.parse::<f64>().map(|n| (n * multiplier as f64) as u64).unwrap_or(0)
+}
+
+/// 驱动链接本身是网页时才可以作为发布说明
+fn release_notes_page(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next().unwrap_or_default().to_lowercase();
+    let is_file = [".exe", ".zip", ".msi", ".cab", ".7z", ".inf"].iter().any(|ext| path.ends_with(ext));
+    (url.starts_with("http") && !is_file).then(|| url.to_string())
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -33,6 +115,11 @@ pub struct MatchResult {
 
 pub struct DriverMatcher {
     scraper: HardwareScraper,
+    /// 配置了自建驱动仓库时先查询仓库
+    repository: Option<RepositoryClient>,
+    vendor_fallback: bool,
+    /// 批量查询得到的仓库结果，键为大写的硬件ID
+    repository_results: Mutex<HashMap<String, Option<DriverInfo>>>,
 }
 
 impl DriverMatcher {
@@ -40,24 +127,93 @@ impl DriverMatcher {
         // 不再使用数据库，直接返回带爬虫的实例
         Ok(DriverMatcher { 
             scraper: HardwareScraper::new(),
+            repository: None,
+            vendor_fallback: true,
+            repository_results: Mutex::new(HashMap::new()),
         })
     }
 
+    /// 按配置设置驱动仓库
+    pub fn set_repository(&mut self, config: &RepositoryConfig) {
+        self.repository = RepositoryClient::from_config(config);
+        self.vendor_fallback = config.vendor_fallback;
+        self.repository_results.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// 一次查询多个设备在仓库中的驱动，之后匹配这些设备时不再逐个请求
+    pub async fn prefetch_repository(&self, hardware_ids: &[String]) -> Result<()> {
+        let Some(ref repository) = self.repository else {
+            return Ok(());
+        };
+        let queries: Vec<_> = hardware_ids.iter().map(|id| DriverQuery::new(id)).collect();
+        match repository.batch(&queries).await {
+            Ok(results) => {
+                let mut cached = self.repository_results.lock().unwrap_or_else(|e| e.into_inner());
+                cached.extend(results.into_iter().map(|result| (result.hardware_id.to_uppercase(), result.driver)));
+                Ok(())
+            }
+            Err(e) if self.vendor_fallback => {
+                tracing::warn!("批量查询驱动仓库 {} 失败，改为逐个查询: {}", repository.base_url(), e);
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // 从仓库查找驱动，仓库不可用且不允许使用厂商网站时返回错误
+    async fn repository_driver(&self, hardware_id: &str) -> Result<Option<DriverInfo>> {
+        let Some(ref repository) = self.repository else {
+            return Ok(None);
+        };
+        if let Some(driver) = self.repository_results.lock().unwrap_or_else(|e| e.into_inner()).get(&hardware_id.to_uppercase()) {
+            return Ok(driver.clone());
+        }
+        match repository.latest_driver(hardware_id).await {
+            Ok(record) => Ok(record.map(|record| record.driver)),
+            Err(e) if self.vendor_fallback => {
+                tracing::warn!("查询驱动仓库 {} 失败，改为从厂商网站查找: {}", repository.base_url(), e);
+                Ok(None)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // 没有配置仓库或允许回退时才从厂商网站查找
+    fn uses_vendor_sites(&self) -> bool {
+        self.repository.is_none() || self.vendor_fallback
+    }
+
 
 
     pub async fn match_driver(&self, hw_info: &HardwareInfo) -> Result<MatchResult> {
-        // 直接从硬件厂商官网爬取驱动信息
-        if let Some(driver_info) = self.scraper.search_generic_driver(&hw_info.hardware_id).await? {
+        if let Some(driver) = self.repository_driver(&hw_info.hardware_id).await? {
+            return Ok(MatchResult {
+                hardware_info: hw_info.clone(),
+                matched_driver: Some(driver),
+                confidence: 1.0, // 仓库中的驱动已经过审核
+                reason: "从驱动仓库获取".to_string(),
+            });
+        }
+
+        // 从硬件厂商官网爬取驱动信息，只使用仓库时不访问厂商网站
+        let vendor_driver = if self.uses_vendor_sites() {
+            self.scraper.search_generic_driver(&hw_info.hardware_id).await?
+        } else {
+            None
+        };
+        if let Some(driver_info) = vendor_driver {
             // 将HardwareDriverInfo转换为DriverInfo
             let driver = DriverInfo {
                 driver_id: format!("{}-{}", driver_info.hardware_id, driver_info.driver_version),
                 hardware_id: driver_info.hardware_id,
                 driver_name: driver_info.driver_name,
                 driver_version: driver_info.driver_version,
+                release_notes_url: release_notes_page(&driver_info.driver_url),
                 driver_url: driver_info.driver_url,
                 manufacturer: driver_info.manufacturer,
                 release_date: driver_info.release_date,
-                file_size: 0, // 从网页可能无法直接获取精确大小
+                // 网页上的大小只是大概值，未知时为 0
+                file_size: parse_file_size(&driver_info.file_size),
                 checksum: driver_info.checksum,
             };
             
@@ -90,6 +246,12 @@ impl DriverMatcher {
     }
 
     pub async fn get_latest_driver_for_hardware(&self, hardware_id: &str) -> Result<Option<DriverInfo>> {
+        if let Some(driver) = self.repository_driver(hardware_id).await? {
+            return Ok(Some(driver));
+        }
+        if !self.uses_vendor_sites() {
+            return Ok(None);
+        }
         // 通过爬虫获取最新的驱动信息
         if let Some(driver_info) = self.scraper.search_generic_driver(hardware_id).await? {
             Ok(Some(DriverInfo {
@@ -97,10 +259,12 @@ impl DriverMatcher {
                 hardware_id: driver_info.hardware_id,
                 driver_name: driver_info.driver_name,
                 driver_version: driver_info.driver_version,
+                release_notes_url: release_notes_page(&driver_info.driver_url),
                 driver_url: driver_info.driver_url,
                 manufacturer: driver_info.manufacturer,
                 release_date: driver_info.release_date,
-                file_size: 0, // 从网页可能无法直接获取精确大小
+                // 网页上的大小只是大概值，未知时为 0
+                file_size: parse_file_size(&driver_info.file_size),
                 checksum: driver_info.checksum,
             }))
         } else {
@@ -112,4 +276,21 @@ impl DriverMatcher {
         // 暂时返回空列表，因为按名称搜索需要更复杂的爬虫实现
         Ok(Vec::new())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_versions_and_sizes() {
+        assert_eq!(compare_driver_versions("31.0.15.3623", "31.0.15.3598"), Some(Ordering::Greater));
+        assert_eq!(compare_driver_versions("2.0", "2.0.0.0"), Some(Ordering::Equal));
+        assert_eq!(compare_driver_versions("Unknown", "1.0"), None);
+        assert_eq!(parse_file_size("700MB"), 700 << 20);
+        assert_eq!(parse_file_size("1.5 GB"), 3 << 29);
+        assert_eq!(parse_file_size("Unknown"), 0);
+        assert_eq!(release_notes_page("https://www.nvidia.com/download/driverResults.aspx/1"), Some("https://www.nvidia.com/download/driverResults.aspx/1".to_string()));
+        assert_eq!(release_notes_page("https://us.download.nvidia.com/a/driver.exe"), None);
+    }
+}
@@ -1,8 +1,8 @@
 use anyhow::Result;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use reqwest;
 use scraper::{Html, Selector};
+use crate::utils::{http_client, HttpClient};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HardwareDriverInfo {
@@ -19,18 +19,13 @@ pub struct HardwareDriverInfo {
 
 #[allow(dead_code)]
 pub struct HardwareScraper {
-    client: reqwest::Client,
+    client: HttpClient,
 }
 
 impl HardwareScraper {
     pub fn new() -> Self {
-        let client = reqwest::Client::builder()
-            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .unwrap();
-        
-        HardwareScraper { client }
+        // 代理、超时和重试使用网络配置，厂商网站的请求各自带浏览器的 User-Agent
+        HardwareScraper { client: http_client() }
     }
 
     /// 从硬件厂商官网爬取驱动信息
@@ -117,14 +112,16 @@ impl HardwareScraper {
             Ok(response) => {
                 if response.status().is_success() {
                     if let Ok(text) = response.text().await {
-                        // 使用scraper解析HTML页面
-                        let document = Html::parse_document(&text);
-                        
-                        // 查找驱动下载链接
-                        let search_selector = Selector::parse("input[name='search']").unwrap();
+                        // 使用scraper解析HTML页面，解析结果不能跨 await 持有
+                        let has_search = {
+                            let document = Html::parse_document(&text);
+                            // 查找驱动下载链接
+                            let search_selector = Selector::parse("input[name='search']").unwrap();
+                            document.select(&search_selector).next().is_some()
+                        };
                         
                         // 如果页面包含搜索功能，构造搜索请求
-                        if document.select(&search_selector).next().is_some() {
+                        if has_search {
                             // 这里我们直接构造一个搜索API请求
                             let api_url = format!("https://www.amd.com/support/search/drivers?q={}", gpu_name);
                             
@@ -180,18 +177,20 @@ impl HardwareScraper {
             Ok(response) => {
                 if response.status().is_success() {
                     if let Ok(text) = response.text().await {
-                        // 使用scraper解析HTML页面
-                        let document = Html::parse_document(&text);
-                        
-                        // 查找Intel产品搜索API的端点
-                        let search_script_selector = Selector::parse("script").unwrap();
-                        
-                        // 搜索Intel驱动下载API
-                        for element in document.select(&search_script_selector) {
-                            if let Some(script_content) = element.text().next() {
-                                if script_content.contains("search") && script_content.contains("driver") {
-                                    // 尝试从脚本中提取API端点
-                                    // 实际实现中，需要更复杂的正则表达式或字符串解析
+                        // 使用scraper解析HTML页面，解析结果不能跨 await 持有
+                        {
+                            let document = Html::parse_document(&text);
+                            
+                            // 查找Intel产品搜索API的端点
+                            let search_script_selector = Selector::parse("script").unwrap();
+                            
+                            // 搜索Intel驱动下载API
+                            for element in document.select(&search_script_selector) {
+                                if let Some(script_content) = element.text().next() {
+                                    if script_content.contains("search") && script_content.contains("driver") {
+                                        // 尝试从脚本中提取API端点
+                                        // 实际实现中，需要更复杂的正则表达式或字符串解析
+                                    }
                                 }
                             }
                         }
@@ -260,6 +259,8 @@ impl HardwareScraper {
             Ok(response) => {
                 if response.status().is_success() {
                     if let Ok(text) = response.text().await {
+                        // 解析后的页面不能跨 await 持有，先提取出产品页面链接
+                        let product_urls: Vec<String> = {
                         // 使用scraper解析HTML页面
                         let document = Html::parse_document(&text);
                         
@@ -291,14 +292,18 @@ impl HardwareScraper {
                         
                         // 如果没有找到直接的驱动链接，尝试查找产品页面
                         let product_selector = Selector::parse("a[href*='product'], a[href*='component']").unwrap();
-                        for element in document.select(&product_selector) {
-                            if let Some(href) = element.value().attr("href") {
-                                let product_url = if href.starts_with("http") {
-                                    href.to_string()
-                                } else {
-                                    format!("https://www.realtek.com{}", href)
-                                };
-                                
+                        document.select(&product_selector)
+                            .filter_map(|element| element.value().attr("href"))
+                            .map(|href| if href.starts_with("http") {
+                                href.to_string()
+                            } else {
+                                format!("https://www.realtek.com{}", href)
+                            })
+                            .collect()
+                        };
+
+                        for product_url in product_urls {
+                            {
                                 // 访问产品页面查找驱动
                                 if let Ok(product_response) = self.client
                                     .get(&product_url)
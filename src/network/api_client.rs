@@ -2,15 +2,15 @@
 //!
 //! 负责与厂商服务器和云端API通信
 
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use crate::types::driver_types::{DriverInfo, DriverVersion};
 use crate::types::hardware_types::DeviceInfo;
 use crate::utils::error::{HamsterError, Result};
+use crate::utils::{http_client, HttpClient};
 
 #[derive(Debug, Clone)]
 pub struct ApiClient {
-    client: Client,
+    client: HttpClient,
     base_url: String,
     api_key: Option<String>,
 }
@@ -32,7 +32,7 @@ pub struct DriverResponse {
 impl ApiClient {
     pub fn new(base_url: String, api_key: Option<String>) -> Self {
         Self {
-            client: Client::new(),
+            client: http_client(),
             base_url,
             api_key,
         }
@@ -0,0 +1,62 @@
+//! 桌面通知
+//!
+//! Windows 上通过 PowerShell 调用系统的 Toast 通知，macOS 使用 osascript，
+//! 其他平台使用 notify-send。
+
+use std::time::Duration;
+use tokio::process::Command;
+use crate::notify::Notification;
+use crate::utils::{HamsterError, Result};
+
+// 通知程序卡住时最多等待的时间
+const DESKTOP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 显示桌面通知
+pub async fn show_desktop_notification(notification: &Notification) -> Result<()> {
+    let mut command = desktop_command(&notification.title, &notification.message);
+    command.kill_on_drop(true);
+    let output = tokio::time::timeout(DESKTOP_TIMEOUT, command.output()).await
+        .map_err(|_| HamsterError::TimeoutError(format!("显示桌面通知超过 {} 秒未完成", DESKTOP_TIMEOUT.as_secs())))?
+        .map_err(|e| HamsterError::IoError(format!("无法显示桌面通知: {}", e)))?;
+    if !output.status.success() {
+        return Err(HamsterError::IoError(format!(
+            "显示桌面通知失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn desktop_command(title: &str, message: &str) -> Command {
+    // PowerShell 单引号字符串中的单引号写两次，文本节点由 CreateTextNode 转义
+    let quote = |text: &str| format!("'{}'", text.replace('\'', "''"));
+    let script = format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+         $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $texts = $xml.GetElementsByTagName('text'); \
+         $texts.Item(0).AppendChild($xml.CreateTextNode({})) > $null; \
+         $texts.Item(1).AppendChild($xml.CreateTextNode({})) > $null; \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('HamsterDrivers').Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+        quote(title),
+        quote(message),
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn desktop_command(title: &str, message: &str) -> Command {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(format!("display notification {} with title {}", quote(message), quote(title)));
+    command
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn desktop_command(title: &str, message: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name=HamsterDrivers", "--", title, message]);
+    command
+}
@@ -0,0 +1,91 @@
+//! 通知分发
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::config::{ChannelTarget, NotificationChannel, NotificationConfig, NotificationKind};
+use crate::core::StateEvent;
+use crate::notify::{drop_notification_file, send_webhook, show_desktop_notification, Notification};
+use crate::utils::{http_client, HttpClient, Result};
+
+/// 把通知发送到配置中接受该事件的所有渠道
+pub struct NotificationDispatcher {
+    config: NotificationConfig,
+    client: HttpClient,
+    /// 需要重启在重启前只提示一次
+    reboot_notified: AtomicBool,
+}
+
+impl NotificationDispatcher {
+    pub fn new(config: NotificationConfig) -> Self {
+        Self { config, client: http_client(), reboot_notified: AtomicBool::new(false) }
+    }
+
+    /// 未启用或没有渠道时不发送任何通知
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled && !self.config.channels.is_empty()
+    }
+
+    /// 依次发送到接受该通知的渠道，一个渠道失败不影响其他渠道
+    pub async fn send(&self, notification: &Notification) -> Vec<(&NotificationChannel, Result<()>)> {
+        let mut results = Vec::new();
+        if !self.config.enabled {
+            return results;
+        }
+        for channel in self.config.channels.iter().filter(|channel| channel.accepts(notification.kind)) {
+            let result = match &channel.target {
+                ChannelTarget::Desktop => show_desktop_notification(notification).await,
+                ChannelTarget::Webhook { url, template, headers } => {
+                    send_webhook(&self.client, url, *template, headers, notification).await
+                }
+                ChannelTarget::File { directory } => drop_notification_file(Path::new(directory), notification).map(|_| ()),
+            };
+            results.push((channel, result));
+        }
+        results
+    }
+
+    /// 处理状态事件，需要通知时发送并记录失败的渠道
+    pub async fn handle_event(&self, event: &StateEvent) {
+        if !self.is_enabled() {
+            return;
+        }
+        let Some(notification) = Notification::from_state_event(event) else {
+            return;
+        };
+        if notification.kind == NotificationKind::RebootRequired && self.reboot_notified.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        for (channel, result) in self.send(&notification).await {
+            if let Err(e) = result {
+                tracing::warn!("发送{}失败: {}", channel.target.label(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::CoreEvent;
+    use crate::utils::test_support::TempDir;
+
+    #[tokio::test]
+    async fn test_dispatch_to_matching_channels() {
+        let directory = TempDir::new("notify");
+        let mut channel = NotificationChannel::new(ChannelTarget::File { directory: directory.path().to_string_lossy().to_string() });
+        channel.events = vec![NotificationKind::RebootRequired];
+        let dispatcher = NotificationDispatcher::new(NotificationConfig { enabled: true, channels: vec![channel] });
+
+        let reboot = StateEvent::Core(Box::new(CoreEvent::RebootRequired { hardware_id: "PCI\\VEN_10DE".to_string() }));
+        dispatcher.handle_event(&reboot).await;
+        dispatcher.handle_event(&reboot).await;
+        dispatcher.handle_event(&StateEvent::CommandFailed("下载失败".to_string())).await;
+
+        // 错误事件不在该渠道的事件列表中，重复的重启提示只发送一次
+        let files: Vec<_> = std::fs::read_dir(directory.path()).unwrap().flatten().map(|entry| entry.path()).collect();
+        assert_eq!(files.len(), 1);
+        let notification: Notification = serde_json::from_slice(&std::fs::read(&files[0]).unwrap()).unwrap();
+        assert_eq!(notification.kind, NotificationKind::RebootRequired);
+        assert!(notification.message.contains("PCI\\VEN_10DE"));
+    }
+}
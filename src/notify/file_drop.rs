@@ -0,0 +1,27 @@
+//! 文件投递通知
+
+use std::path::{Path, PathBuf};
+use crate::notify::Notification;
+use crate::utils::{HamsterError, Result};
+
+/// 在目录中写入一个 JSON 文件，返回文件路径
+///
+/// 先写临时文件再重命名，监视目录的程序不会读到写了一半的文件。
+pub fn drop_notification_file(directory: &Path, notification: &Notification) -> Result<PathBuf> {
+    std::fs::create_dir_all(directory)?;
+    let kind = serde_json::to_value(notification.kind)?;
+    let name = format!("{}-{}", notification.timestamp.format("%Y%m%d-%H%M%S%3f"), kind.as_str().unwrap_or("notification"));
+
+    let mut path = directory.join(format!("{}.json", name));
+    let mut index = 1;
+    while path.exists() {
+        path = directory.join(format!("{}-{}.json", name, index));
+        index += 1;
+    }
+    let temp = path.with_extension("json.tmp");
+    let content = serde_json::to_vec_pretty(notification)?;
+    std::fs::write(&temp, content)?;
+    std::fs::rename(&temp, &path)
+        .map_err(|e| HamsterError::IoError(format!("写入通知文件 {} 失败: {}", path.display(), e)))?;
+    Ok(path)
+}
@@ -0,0 +1,16 @@
+//! 通知
+//!
+//! 定时检查或代理在后台发现更新、需要重启或操作失败时，按配置通过桌面通知、
+//! Webhook 和文件投递告知用户，不依赖主界面是否打开。
+
+mod desktop;
+mod dispatcher;
+mod file_drop;
+mod notification;
+mod webhook;
+
+pub use desktop::*;
+pub use dispatcher::*;
+pub use file_drop::*;
+pub use notification::*;
+pub use webhook::*;
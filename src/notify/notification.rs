@@ -0,0 +1,77 @@
+//! 通知内容
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use crate::config::NotificationKind;
+use crate::core::{CoreEvent, StateEvent};
+use crate::t;
+
+// 通知正文中最多列出的设备数
+const MAX_LISTED_DEVICES: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub message: String,
+    /// 发出通知的计算机，多台机器共用一个 Webhook 时用于区分
+    pub host: String,
+    pub timestamp: DateTime<Local>,
+}
+
+impl Notification {
+    pub fn new(kind: NotificationKind, title: String, message: String) -> Self {
+        Self {
+            kind,
+            title,
+            message,
+            host: sysinfo::System::host_name().unwrap_or_default(),
+            timestamp: Local::now(),
+        }
+    }
+
+    /// 需要通知用户的状态事件转换为通知，其余事件返回 None
+    pub fn from_state_event(event: &StateEvent) -> Option<Self> {
+        match event {
+            StateEvent::UpdateCheckCompleted(candidates) => {
+                let devices: Vec<&str> = candidates.iter()
+                    .filter(|candidate| candidate.needs_update)
+                    .map(|candidate| candidate.hardware_info.device_name.as_str())
+                    .collect();
+                if devices.is_empty() {
+                    return None;
+                }
+                let listed = devices.iter().take(MAX_LISTED_DEVICES).copied().collect::<Vec<_>>().join(&t!("list-separator"));
+                let message = if devices.len() > MAX_LISTED_DEVICES {
+                    t!("notify-updates-found-more", devices = listed, count = devices.len() - MAX_LISTED_DEVICES)
+                } else {
+                    t!("notify-updates-found", devices = listed)
+                };
+                Some(Self::new(NotificationKind::UpdatesFound, t!("notify-updates-found-title", count = devices.len()), message))
+            }
+            StateEvent::Core(core_event) => match core_event.as_ref() {
+                CoreEvent::RebootRequired { hardware_id } => Some(Self::new(
+                    NotificationKind::RebootRequired,
+                    t!("notify-reboot-required-title"),
+                    t!("notify-reboot-required", hardware_id = hardware_id),
+                )),
+                _ => None,
+            },
+            StateEvent::CommandFailed(message) => Some(Self::new(
+                NotificationKind::Error,
+                t!("notify-error-title"),
+                message.clone(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// 标题和正文合成的一段文字，用于只支持纯文本的渠道
+    pub fn text(&self) -> String {
+        if self.host.is_empty() {
+            format!("{}\n{}", self.title, self.message)
+        } else {
+            format!("{} ({})\n{}", self.title, self.host, self.message)
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Webhook 通知
+
+use std::collections::BTreeMap;
+use serde_json::{json, Value};
+use crate::config::WebhookTemplate;
+use crate::notify::Notification;
+use crate::utils::{HamsterError, HttpClient, Result};
+
+/// 按模板生成请求体
+pub fn webhook_payload(template: WebhookTemplate, notification: &Notification) -> Value {
+    let text = notification.text();
+    match template {
+        WebhookTemplate::Generic => json!(notification),
+        WebhookTemplate::Slack => json!({ "text": text }),
+        WebhookTemplate::Discord => json!({ "content": text }),
+        WebhookTemplate::DingTalk | WebhookTemplate::WeCom => json!({ "msgtype": "text", "text": { "content": text } }),
+        WebhookTemplate::Feishu => json!({ "msg_type": "text", "content": { "text": text } }),
+    }
+}
+
+/// 发送 JSON POST 请求，HTTP 状态或机器人接口返回的错误码表示失败时返回错误
+pub async fn send_webhook(
+    client: &HttpClient,
+    url: &str,
+    template: WebhookTemplate,
+    headers: &BTreeMap<String, String>,
+    notification: &Notification,
+) -> Result<()> {
+    let mut request = client.post(url).json(&webhook_payload(template, notification));
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(HamsterError::NetworkError(format!("{} 返回 {}: {}", url, status, body.trim())));
+    }
+
+    // 钉钉、企业微信和飞书出错时仍返回 200，错误码在响应体中
+    let Ok(reply) = serde_json::from_str::<Value>(&body) else {
+        return Ok(());
+    };
+    let code = reply.get("errcode").or_else(|| reply.get("code")).and_then(Value::as_i64).unwrap_or(0);
+    if code != 0 {
+        let message = reply.get("errmsg").or_else(|| reply.get("msg")).and_then(Value::as_str).unwrap_or_default();
+        return Err(HamsterError::NetworkError(format!("{} 返回错误码 {}: {}", url, code, message)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{NetworkConfig, NotificationKind};
+    use crate::utils::test_support::{http_response, HttpStub};
+
+    /// 只处理一个请求的本地 HTTP 服务，返回 webhook 地址
+    async fn serve_once(reply: &str) -> (String, HttpStub) {
+        let server = HttpStub::serve(vec![http_response("200 OK", &[("Content-Type", "application/json")], reply)]).await;
+        (format!("{}/hook", server.url()), server)
+    }
+
+    #[tokio::test]
+    async fn test_send_webhook_to_local_server() {
+        let notification = Notification::new(NotificationKind::UpdatesFound, "发现 2 个驱动更新".to_string(), "显卡、网卡".to_string());
+        let client = HttpClient::new(&NetworkConfig::default()).unwrap();
+        let headers = BTreeMap::from([("X-Token".to_string(), "secret".to_string())]);
+
+        let (url, server) = serve_once("{}").await;
+        send_webhook(&client, &url, WebhookTemplate::Generic, &headers, &notification).await.unwrap();
+        let request = server.requests().await.remove(0);
+        assert!(request.starts_with("POST /hook "));
+        assert!(request.to_ascii_lowercase().contains("x-token: secret"));
+        let body: Value = serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+        assert_eq!(body["kind"], "updates_found");
+        assert_eq!(body["message"], "显卡、网卡");
+
+        // 机器人接口在响应体中返回错误码
+        let (url, server) = serve_once(r#"{"errcode":310000,"errmsg":"keywords not in content"}"#).await;
+        let result = send_webhook(&client, &url, WebhookTemplate::DingTalk, &BTreeMap::new(), &notification).await;
+        assert!(matches!(result, Err(HamsterError::NetworkError(message)) if message.contains("310000")));
+        let request = server.requests().await.remove(0);
+        assert!(request.contains(r#""msgtype":"text""#));
+    }
+}
@@ -0,0 +1,236 @@
+//! 驱动仓库 API（v1）
+//!
+//! 客户端和服务共用的请求和响应类型。配置了 API 密钥时，除 health 和驱动包下载外的接口需要
+//! `Authorization: Bearer <密钥>`。出错时返回 ErrorResponse 和对应的状态码。
+//!
+//! | 方法 | 路径 | 请求 | 响应 |
+//! |------|------|------|------|
+//! | GET  | /api/v1/health | | HealthResponse |
+//! | GET  | /api/v1/drivers/<硬件ID>?os=&arch= | | DriverRecord |
+//! | GET  | /api/v1/drivers/<硬件ID>/compatibility | | CompatibilityInfo |
+//! | POST | /api/v1/drivers/query | DriverQuery | DriverQueryResponse（分页） |
+//! | POST | /api/v1/drivers/batch | BatchQuery | BatchResponse |
+//! | POST | /api/v1/drivers/download | DownloadRequest | DownloadResponse |
+//! | GET  | /api/v1/packages/<驱动标识> | | 驱动包 |
+//!
+//! 路径中的硬件ID包含 `\` 和 `&`，需要按百分号编码。旧版客户端使用的 /api/health、
+//! /api/drivers/query 和 /api/drivers/download 仍然可用。
+
+use serde::{Deserialize, Serialize};
+use crate::matcher::DriverInfo;
+
+pub const API_VERSION: u32 = 1;
+
+pub const HEALTH_PATH: &str = "/api/v1/health";
+pub const DRIVERS_PATH: &str = "/api/v1/drivers/";
+pub const QUERY_PATH: &str = "/api/v1/drivers/query";
+pub const BATCH_PATH: &str = "/api/v1/drivers/batch";
+pub const DOWNLOAD_PATH: &str = "/api/v1/drivers/download";
+pub const PACKAGES_PATH: &str = "/api/v1/packages/";
+pub const LEGACY_HEALTH_PATH: &str = "/api/health";
+pub const LEGACY_QUERY_PATH: &str = "/api/drivers/query";
+pub const LEGACY_DOWNLOAD_PATH: &str = "/api/drivers/download";
+
+/// 查询结果每页的默认数量和最大数量
+pub const DEFAULT_PAGE_SIZE: u32 = 20;
+pub const MAX_PAGE_SIZE: u32 = 100;
+/// 一次批量查询的最大设备数
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// GET /api/v1/health
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthResponse {
+    pub status: String,
+    #[serde(default = "default_api_version")]
+    pub api_version: u32,
+    /// 目录中的驱动数量
+    pub drivers: usize,
+}
+
+fn default_api_version() -> u32 {
+    API_VERSION
+}
+
+/// GET /api/v1/drivers/<硬件ID> 返回的最新驱动
+///
+/// 除驱动信息外还包含旧版客户端使用的 latest_version、download_url 和 size。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriverRecord {
+    #[serde(flatten)]
+    pub driver: DriverInfo,
+    pub latest_version: String,
+    pub download_url: String,
+    pub size: String,
+}
+
+impl From<DriverInfo> for DriverRecord {
+    fn from(driver: DriverInfo) -> Self {
+        Self {
+            latest_version: driver.driver_version.clone(),
+            download_url: driver.driver_url.clone(),
+            size: driver.file_size.to_string(),
+            driver,
+        }
+    }
+}
+
+/// GET /api/v1/drivers/<硬件ID>/compatibility
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompatibilityInfo {
+    pub hardware_id: String,
+    pub driver_id: String,
+    /// 支持的 Windows 版本，为空表示不限
+    pub os_versions: Vec<String>,
+    /// 支持的架构，为空表示不限
+    pub architectures: Vec<String>,
+}
+
+/// POST /api/v1/drivers/query，也是批量查询中的一项
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DriverQuery {
+    pub hardware_id: String,
+    #[serde(default)]
+    pub device_name: String,
+    /// 已安装的版本，提供时只返回更新的驱动
+    #[serde(default)]
+    pub current_version: Option<String>,
+    #[serde(default)]
+    pub os_version: Option<String>,
+    #[serde(default)]
+    pub architecture: Option<String>,
+    /// 页码，从1开始，默认第一页
+    #[serde(default)]
+    pub page: Option<u32>,
+    /// 每页数量，默认 DEFAULT_PAGE_SIZE，最多 MAX_PAGE_SIZE
+    #[serde(default)]
+    pub per_page: Option<u32>,
+}
+
+impl DriverQuery {
+    pub fn new(hardware_id: &str) -> Self {
+        Self { hardware_id: hardware_id.to_string(), ..Self::default() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriverQueryResponse {
+    pub hardware_id: String,
+    /// 当前页的驱动，最匹配、最新的在前
+    pub available_drivers: Vec<DriverInfo>,
+    /// 所有页中最新的版本
+    pub latest_version: Option<String>,
+    #[serde(default = "first_page")]
+    pub page: u32,
+    #[serde(default)]
+    pub per_page: u32,
+    /// 所有页的驱动总数
+    #[serde(default)]
+    pub total: usize,
+    /// 下一页的页码，已是最后一页时为 None
+    #[serde(default)]
+    pub next_page: Option<u32>,
+}
+
+fn first_page() -> u32 {
+    1
+}
+
+/// POST /api/v1/drivers/batch
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchQuery {
+    /// 最多 MAX_BATCH_SIZE 项，分页参数被忽略
+    pub queries: Vec<DriverQuery>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchResponse {
+    /// 与请求中的查询一一对应
+    pub results: Vec<BatchResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub hardware_id: String,
+    /// 最新的适用驱动，没有时为 None
+    pub driver: Option<DriverInfo>,
+}
+
+/// POST /api/v1/drivers/download
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DownloadRequest {
+    pub hardware_id: String,
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DownloadResponse {
+    pub download_url: String,
+    pub checksum: String,
+    pub file_size: u64,
+}
+
+/// 错误类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    BadRequest,
+    Unauthorized,
+    NotFound,
+    MethodNotAllowed,
+    PayloadTooLarge,
+    HeadersTooLarge,
+    RequestTimeout,
+    Busy,
+    #[serde(other)]
+    Unknown,
+}
+
+impl ErrorCode {
+    /// HTTP 状态行中的状态
+    pub fn status(&self) -> &'static str {
+        match self {
+            ErrorCode::BadRequest | ErrorCode::Unknown => "400 Bad Request",
+            ErrorCode::Unauthorized => "401 Unauthorized",
+            ErrorCode::NotFound => "404 Not Found",
+            ErrorCode::MethodNotAllowed => "405 Method Not Allowed",
+            ErrorCode::PayloadTooLarge => "413 Payload Too Large",
+            ErrorCode::HeadersTooLarge => "431 Request Header Fields Too Large",
+            ErrorCode::RequestTimeout => "408 Request Timeout",
+            ErrorCode::Busy => "503 Service Unavailable",
+        }
+    }
+}
+
+/// 错误响应
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub code: ErrorCode,
+    pub error: String,
+}
+
+/// 按百分号编码路径中的一段，只保留字母、数字和 `-._~`
+pub fn encode_path_segment(value: &str) -> String {
+    value.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
+
+/// 解码百分号编码，格式不对或不是 UTF-8 时返回 None
+pub fn decode_path_segment(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = value.get(index + 1..index + 3).filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
@@ -0,0 +1,189 @@
+//! 驱动仓库的目录
+//!
+//! 目录中每个驱动有一个 .toml 清单，清单中的 file 是相对清单所在文件夹的驱动包路径：
+//!
+//! ```toml
+//! name = "Intel Wi-Fi 6 AX201"
+//! version = "23.60.0.10"
+//! manufacturer = "Intel"
+//! release_date = "2024-05-01"
+//! file = "intel-wifi-23.60.exe"
+//! hardware_ids = ['PCI\VEN_8086&DEV_2723']
+//! os_versions = ["10", "11"]
+//! architectures = ["x64"]
+//! ```
+//!
+//! 加载时计算驱动包的大小和 SHA-256，清单中写了 sha256 时必须一致。
+
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use crate::t;
+use walkdir::WalkDir;
+use crate::matcher::{compare_driver_versions, DriverInfo};
+use crate::utils::{HamsterError, Result};
+
+/// 驱动清单
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DriverManifest {
+    /// 驱动的唯一标识，未填写时使用清单的文件名
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub manufacturer: String,
+    #[serde(default)]
+    pub release_date: String,
+    pub file: String,
+    /// 适用的硬件ID，设备的硬件ID以其开头即匹配，如 PCI\VEN_8086&DEV_2723
+    pub hardware_ids: Vec<String>,
+    #[serde(default)]
+    pub os_versions: Vec<String>,
+    #[serde(default)]
+    pub architectures: Vec<String>,
+    #[serde(default)]
+    pub release_notes_url: Option<String>,
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+/// 目录中的一个驱动
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogEntry {
+    pub id: String,
+    pub manifest: DriverManifest,
+    /// 驱动包的完整路径
+    pub path: PathBuf,
+    pub size: u64,
+    pub sha256: String,
+}
+
+impl CatalogEntry {
+    /// 设备硬件ID与清单中最长的匹配项的长度，不匹配时返回 None
+    pub fn match_length(&self, hardware_id: &str) -> Option<usize> {
+        self.manifest.hardware_ids.iter()
+            .filter(|id| hardware_id_matches(id, hardware_id))
+            .map(|id| id.trim().len())
+            .max()
+    }
+
+    /// 是否支持该系统版本和架构，未指定的条件不检查
+    pub fn supports(&self, os_version: Option<&str>, architecture: Option<&str>) -> bool {
+        let matches = |values: &[String], value: Option<&str>| {
+            values.is_empty() || value.is_none_or(|value| values.iter().any(|v| v.trim().eq_ignore_ascii_case(value.trim())))
+        };
+        matches(&self.manifest.os_versions, os_version) && matches(&self.manifest.architectures, architecture)
+    }
+
+    /// 转为客户端使用的驱动信息，download_url 为驱动包的下载地址
+    pub fn driver_info(&self, hardware_id: &str, download_url: String) -> DriverInfo {
+        DriverInfo {
+            driver_id: self.id.clone(),
+            hardware_id: hardware_id.to_string(),
+            driver_name: self.manifest.name.clone(),
+            driver_version: self.manifest.version.clone(),
+            driver_url: download_url,
+            manufacturer: self.manifest.manufacturer.clone(),
+            release_date: self.manifest.release_date.clone(),
+            file_size: self.size,
+            checksum: self.sha256.clone(),
+            release_notes_url: self.manifest.release_notes_url.clone(),
+        }
+    }
+}
+
+/// 从目录加载的全部驱动
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    root: PathBuf,
+    entries: Vec<CatalogEntry>,
+}
+
+impl Catalog {
+    /// 加载目录及子目录中的清单，任意一个清单无效时返回错误
+    pub fn load(root: &Path) -> Result<Self> {
+        if !root.is_dir() {
+            return Err(HamsterError::ConfigError(t!("repository-catalog-missing", path = root.display())));
+        }
+        let mut entries: Vec<CatalogEntry> = Vec::new();
+        for item in WalkDir::new(root).sort_by_file_name() {
+            let item = item.map_err(|e| HamsterError::IoError(e.to_string()))?;
+            let path = item.path();
+            if !item.file_type().is_file() || path.extension().is_none_or(|ext| ext != "toml") {
+                continue;
+            }
+            let entry = load_entry(path)
+                .map_err(|e| HamsterError::ConfigError(t!("repository-manifest-invalid", path = path.display(), error = e)))?;
+            if entries.iter().any(|other| other.id == entry.id) {
+                return Err(HamsterError::ConfigError(t!("repository-duplicate-id", id = entry.id)));
+            }
+            entries.push(entry);
+        }
+        Ok(Self { root: root.to_path_buf(), entries })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn entries(&self) -> &[CatalogEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: &str) -> Option<&CatalogEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// 适用于设备的驱动，硬件ID匹配得最精确、版本最新的在前
+    pub fn find(&self, hardware_id: &str, os_version: Option<&str>, architecture: Option<&str>) -> Vec<&CatalogEntry> {
+        let mut matches: Vec<(usize, &CatalogEntry)> = self.entries.iter()
+            .filter(|entry| entry.supports(os_version, architecture))
+            .filter_map(|entry| Some((entry.match_length(hardware_id)?, entry)))
+            .collect();
+        matches.sort_by(|(a_len, a), (b_len, b)| {
+            b_len.cmp(a_len).then_with(|| compare_versions(&b.manifest.version, &a.manifest.version))
+        });
+        matches.into_iter().map(|(_, entry)| entry).collect()
+    }
+}
+
+/// 设备硬件ID以清单中的硬件ID开头，且在 `&` 或 `\` 处断开，不区分大小写
+pub fn hardware_id_matches(pattern: &str, hardware_id: &str) -> bool {
+    let (pattern, hardware_id) = (pattern.trim().to_ascii_uppercase(), hardware_id.trim().to_ascii_uppercase());
+    if pattern.is_empty() || !hardware_id.starts_with(&pattern) {
+        return false;
+    }
+    matches!(hardware_id[pattern.len()..].chars().next(), None | Some('&') | Some('\\'))
+}
+
+// 无法解析的版本号按字符串比较
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    compare_driver_versions(a, b).unwrap_or_else(|| a.cmp(b))
+}
+
+fn load_entry(manifest_path: &Path) -> Result<CatalogEntry> {
+    let text = std::fs::read_to_string(manifest_path)?;
+    let manifest: DriverManifest = toml::from_str(&text)
+        .map_err(|e| HamsterError::ParseError(e.to_string()))?;
+    if manifest.hardware_ids.iter().all(|id| id.trim().is_empty()) {
+        return Err(HamsterError::ValidationError(t!("repository-missing-hardware-id")));
+    }
+    let id = match manifest.id {
+        Some(ref id) if !id.trim().is_empty() => id.trim().to_string(),
+        _ => manifest_path.file_stem().unwrap_or_default().to_string_lossy().into_owned(),
+    };
+    let path = manifest_path.parent().unwrap_or(Path::new(".")).join(&manifest.file);
+    let size = std::fs::metadata(&path)
+        .map_err(|e| HamsterError::IoError(t!("repository-package-unreadable", path = path.display(), error = e)))?
+        .len();
+    let sha256 = crate::installer::sha256_file(&path).map_err(|e| HamsterError::IoError(e.to_string()))?;
+    if let Some(ref expected) = manifest.sha256 {
+        if !expected.trim().eq_ignore_ascii_case(&sha256) {
+            return Err(HamsterError::ValidationError(
+                t!("repository-checksum-mismatch", expected = expected.trim(), actual = sha256)
+            ));
+        }
+    }
+    Ok(CatalogEntry { id, manifest, path, size, sha256 })
+}
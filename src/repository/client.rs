@@ -0,0 +1,190 @@
+//! 驱动仓库客户端
+//!
+//! 请求使用共享的网络配置，连接失败、超时、429 和 5xx 按网络配置的重试次数退避重试，
+//! 服务器返回 Retry-After 时按其等待。
+
+use serde::de::DeserializeOwned;
+use crate::config::RepositoryConfig;
+use crate::matcher::DriverInfo;
+use crate::repository::{
+    encode_path_segment, BatchQuery, BatchResponse, BatchResult, CompatibilityInfo, DownloadRequest, DownloadResponse,
+    DriverQuery, DriverQueryResponse, DriverRecord, ErrorResponse, HealthResponse, API_VERSION, BATCH_PATH,
+    DOWNLOAD_PATH, DRIVERS_PATH, HEALTH_PATH, MAX_BATCH_SIZE, MAX_PAGE_SIZE, PACKAGES_PATH, QUERY_PATH,
+};
+use crate::utils::{http_client, HamsterError, HttpClient, HttpRequest, Result};
+
+/// 访问驱动仓库的客户端
+#[derive(Debug, Clone)]
+pub struct RepositoryClient {
+    base_url: String,
+    api_key: Option<String>,
+    client: HttpClient,
+}
+
+impl RepositoryClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim().trim_end_matches('/').to_string(),
+            api_key: None,
+            client: http_client(),
+        }
+    }
+
+    /// 配置了仓库地址时创建客户端
+    pub fn from_config(config: &RepositoryConfig) -> Option<Self> {
+        let url = config.url.as_deref().filter(|url| !url.trim().is_empty())?;
+        Some(Self::new(url).with_api_key(config.api_key.clone()))
+    }
+
+    /// 请求时带上 Bearer 认证
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key.filter(|key| !key.trim().is_empty());
+        self
+    }
+
+    /// 使用指定的 HTTP 客户端，默认使用共享的客户端
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// 检查服务是否可用，API 主版本不同时返回错误
+    pub async fn health(&self) -> Result<HealthResponse> {
+        let health: HealthResponse = parse(self.get(HEALTH_PATH).send().await?).await?
+            .ok_or_else(|| HamsterError::NetworkError("驱动仓库不可用".to_string()))?;
+        if health.api_version != API_VERSION {
+            return Err(HamsterError::NetworkError(format!("驱动仓库的 API 版本 {} 不受支持，需要 {}", health.api_version, API_VERSION)));
+        }
+        Ok(health)
+    }
+
+    /// 适用于设备的最新驱动，仓库中没有时返回 None
+    pub async fn latest_driver(&self, hardware_id: &str) -> Result<Option<DriverRecord>> {
+        let path = format!("{}{}", DRIVERS_PATH, encode_path_segment(hardware_id));
+        parse(self.get(&path).send().await?).await
+    }
+
+    pub async fn compatibility(&self, hardware_id: &str) -> Result<Option<CompatibilityInfo>> {
+        let path = format!("{}{}/compatibility", DRIVERS_PATH, encode_path_segment(hardware_id));
+        parse(self.get(&path).send().await?).await
+    }
+
+    /// 查询适用于设备的驱动，只返回 query 指定的一页
+    pub async fn query(&self, query: &DriverQuery) -> Result<DriverQueryResponse> {
+        let response = self.post(QUERY_PATH).json(query).send().await?;
+        Ok(parse(response).await?.unwrap_or_else(|| DriverQueryResponse {
+            hardware_id: query.hardware_id.clone(),
+            available_drivers: Vec::new(),
+            latest_version: None,
+            page: query.page.unwrap_or(1),
+            per_page: query.per_page.unwrap_or_default(),
+            total: 0,
+            next_page: None,
+        }))
+    }
+
+    /// 逐页查询，返回适用于设备的全部驱动
+    pub async fn query_all(&self, query: &DriverQuery) -> Result<Vec<DriverInfo>> {
+        let mut query = DriverQuery { page: Some(1), per_page: Some(MAX_PAGE_SIZE), ..query.clone() };
+        let mut drivers = Vec::new();
+        loop {
+            let response = self.query(&query).await?;
+            drivers.extend(response.available_drivers);
+            // 服务返回的页码不递增时停止，避免死循环
+            match response.next_page {
+                Some(next) if next > response.page => query.page = Some(next),
+                _ => return Ok(drivers),
+            }
+        }
+    }
+
+    /// 批量查询每个设备的最新驱动，结果与 queries 一一对应，超过 MAX_BATCH_SIZE 时分多次请求
+    pub async fn batch(&self, queries: &[DriverQuery]) -> Result<Vec<BatchResult>> {
+        let mut results = Vec::with_capacity(queries.len());
+        for chunk in queries.chunks(MAX_BATCH_SIZE) {
+            let batch = BatchQuery { queries: chunk.to_vec() };
+            let response: BatchResponse = parse(self.post(BATCH_PATH).json(&batch).send().await?).await?
+                .ok_or_else(|| HamsterError::NetworkError("驱动仓库不支持批量查询".to_string()))?;
+            if response.results.len() != chunk.len() {
+                return Err(HamsterError::ParseError("批量查询的结果数量与请求不一致".to_string()));
+            }
+            results.extend(response.results);
+        }
+        Ok(results)
+    }
+
+    /// 驱动包的下载地址和校验和
+    pub async fn download(&self, request: &DownloadRequest) -> Result<Option<DownloadResponse>> {
+        parse(self.post(DOWNLOAD_PATH).json(request).send().await?).await
+    }
+
+    /// 下载本仓库的驱动包时需要的 Authorization 头，其他地址或没有密钥时返回 None
+    pub fn package_authorization(&self, url: &str) -> Option<String> {
+        let api_key = self.api_key.as_ref()?;
+        url.starts_with(&format!("{}{}", self.base_url, PACKAGES_PATH))
+            .then(|| format!("Bearer {}", api_key))
+    }
+
+    fn get(&self, path: &str) -> HttpRequest {
+        self.authorize(self.client.get(&format!("{}{}", self.base_url, path)))
+    }
+
+    fn post(&self, path: &str) -> HttpRequest {
+        self.authorize(self.client.post(&format!("{}{}", self.base_url, path)))
+    }
+
+    fn authorize(&self, request: HttpRequest) -> HttpRequest {
+        match self.api_key {
+            Some(ref api_key) => request.bearer_auth(api_key),
+            None => request,
+        }
+    }
+}
+
+// 404 返回 None，其他错误状态返回服务器给出的错误信息
+async fn parse<T: DeserializeOwned>(response: reqwest::Response) -> Result<Option<T>> {
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        let message = response.json::<ErrorResponse>().await.map(|e| e.error).unwrap_or_default();
+        return Err(HamsterError::NetworkError(format!("驱动仓库返回错误 {}: {}", status, message)));
+    }
+    response.json().await
+        .map(Some)
+        .map_err(|e| HamsterError::ParseError(format!("解析驱动仓库响应失败: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NetworkConfig;
+    use crate::utils::test_support::{http_response, HttpStub};
+
+    #[tokio::test]
+    async fn test_retry_after_and_authorization() {
+        // 第一次返回 429 并要求立即重试，第二次检查认证头后返回结果
+        let server = HttpStub::serve(vec![
+            http_response("429 Too Many Requests", &[("Retry-After", "0")], ""),
+            http_response("200 OK", &[("Content-Type", "application/json")], r#"{"results":[{"hardware_id":"PCI\\VEN_1234","driver":null}]}"#),
+        ]).await;
+
+        // 退避时间很长，只有按 Retry-After 重试才能很快完成
+        let config = NetworkConfig { max_retries: 1, retry_backoff_ms: 60_000, ..NetworkConfig::default() };
+        let client = RepositoryClient::new(server.url())
+            .with_api_key(Some("secret".to_string()))
+            .with_http_client(HttpClient::new(&config).unwrap());
+        let results = tokio::time::timeout(std::time::Duration::from_secs(10), client.batch(&[DriverQuery::new(r"PCI\VEN_1234")]))
+            .await.unwrap().unwrap();
+        assert_eq!(results, vec![BatchResult { hardware_id: r"PCI\VEN_1234".to_string(), driver: None }]);
+
+        let requests = server.requests().await;
+        assert!(requests.iter().all(|request| request.starts_with("POST /api/v1/drivers/batch ")));
+        assert!(requests[1].to_lowercase().contains("authorization: bearer secret"));
+    }
+}
@@ -0,0 +1,14 @@
+//! 自建驱动仓库
+//!
+//! 仓库服务（hamster-repository）按本地目录提供经过审核的驱动，客户端在设置中填写仓库地址后，
+//! 匹配驱动时先查询仓库。
+
+mod api;
+mod catalog;
+mod client;
+mod server;
+
+pub use api::*;
+pub use catalog::*;
+pub use client::*;
+pub use server::*;
@@ -0,0 +1,414 @@
+//! 驱动仓库服务
+//!
+//! 按目录提供驱动查询、兼容性和驱动包下载，每个连接处理一个请求后关闭。
+
+use std::cmp::Ordering;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use serde::Serialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use crate::matcher::compare_driver_versions;
+use crate::repository::{
+    decode_path_segment, encode_path_segment, BatchQuery, BatchResponse, BatchResult, Catalog, CatalogEntry,
+    CompatibilityInfo, DownloadRequest, DownloadResponse, DriverQuery, DriverQueryResponse, DriverRecord, ErrorCode,
+    ErrorResponse, HealthResponse, API_VERSION, BATCH_PATH, DEFAULT_PAGE_SIZE, DOWNLOAD_PATH, DRIVERS_PATH, HEALTH_PATH,
+    LEGACY_DOWNLOAD_PATH, LEGACY_HEALTH_PATH, LEGACY_QUERY_PATH, MAX_BATCH_SIZE, MAX_PAGE_SIZE, PACKAGES_PATH, QUERY_PATH,
+};
+use crate::t;
+use crate::utils::{CancellationToken, HamsterError, Result};
+
+// 同时处理的连接数
+const MAX_CONNECTIONS: usize = 32;
+// 请求头和请求体的最大长度
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+const MAX_REQUEST_BODY: usize = 64 * 1024;
+// 读取完整请求的超时时间，避免迟迟不发送请求的连接一直占用连接数
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 运行中的驱动仓库服务，drop 时停止
+pub struct RepositoryServer {
+    local_addr: SocketAddr,
+    shutdown: CancellationToken,
+    handle: JoinHandle<()>,
+}
+
+/// 服务选项
+#[derive(Debug, Clone, Default)]
+pub struct ServerOptions {
+    /// 客户端访问本服务的地址，用于生成下载链接，未设置时使用监听地址
+    pub public_url: Option<String>,
+    /// 设置后查询接口和驱动包下载需要 Bearer 认证
+    pub api_key: Option<String>,
+}
+
+struct ServerState {
+    catalog: Catalog,
+    options: ServerOptions,
+    local_addr: SocketAddr,
+}
+
+impl RepositoryServer {
+    /// 在 address 上开始提供 catalog 中的驱动，需要在 tokio 运行时中调用
+    pub fn start(catalog: Catalog, address: &str, mut options: ServerOptions) -> Result<Self> {
+        let listener = std::net::TcpListener::bind(address)
+            .map_err(|e| HamsterError::NetworkError(t!("repository-listen-failed", address = address, error = e)))?;
+        listener.set_nonblocking(true)?;
+        let listener = TcpListener::from_std(listener)?;
+        let local_addr = listener.local_addr()?;
+
+        options.public_url = options.public_url.map(|url| url.trim_end_matches('/').to_string());
+        options.api_key = options.api_key.filter(|key| !key.is_empty());
+        if options.public_url.is_none() && local_addr.ip().is_unspecified() {
+            tracing::warn!("{}", t!("repository-public-url-missing", address = local_addr));
+        }
+        let state = Arc::new(ServerState { catalog, options, local_addr });
+        let shutdown = CancellationToken::new();
+        let stopped = shutdown.clone();
+        let handle = tokio::spawn(async move {
+            let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+            loop {
+                let (mut stream, peer) = tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            tracing::warn!("接受驱动仓库连接失败: {}", e);
+                            continue;
+                        }
+                    },
+                    _ = stopped.cancelled() => break,
+                };
+                let Ok(permit) = connections.clone().try_acquire_owned() else {
+                    let _ = write_response(&mut stream, Response::error(ErrorCode::Busy, &t!("repository-busy")), false).await;
+                    continue;
+                };
+                let state = state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_connection(stream, &state).await {
+                        tracing::debug!("处理 {} 的驱动仓库请求失败: {}", peer, e);
+                    }
+                    drop(permit);
+                });
+            }
+        });
+        Ok(Self { local_addr, shutdown, handle })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// 本机访问服务的地址，如 http://127.0.0.1:8080
+    pub fn url(&self) -> String {
+        format!("http://{}", self.local_addr)
+    }
+
+    pub fn stop(&self) {
+        self.shutdown.cancel();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
+
+impl Drop for RepositoryServer {
+    fn drop(&mut self) {
+        self.shutdown.cancel();
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn query_param(&self, name: &str) -> Option<String> {
+        self.query.split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .and_then(|(_, value)| decode_path_segment(&value.replace('+', " ")))
+            .filter(|value| !value.trim().is_empty())
+    }
+}
+
+enum Body {
+    Bytes(Vec<u8>),
+    File(PathBuf),
+}
+
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: Body,
+}
+
+impl Response {
+    fn json<T: Serialize>(value: &T) -> Self {
+        Self {
+            status: "200 OK",
+            content_type: "application/json",
+            body: Body::Bytes(serde_json::to_vec(value).unwrap_or_default()),
+        }
+    }
+
+    fn error(code: ErrorCode, message: &str) -> Self {
+        Self { status: code.status(), ..Self::json(&ErrorResponse { code, error: message.to_string() }) }
+    }
+
+    fn not_found(message: &str) -> Self {
+        Self::error(ErrorCode::NotFound, message)
+    }
+
+    fn method_not_allowed() -> Self {
+        Self::error(ErrorCode::MethodNotAllowed, &t!("repository-method-not-allowed"))
+    }
+}
+
+async fn serve_connection(mut stream: TcpStream, state: &ServerState) -> Result<()> {
+    let request = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await
+        .unwrap_or_else(|_| Ok(Some(Err(Response::error(ErrorCode::RequestTimeout, &t!("repository-request-timeout"))))));
+    let (response, head_only) = match request? {
+        Some(Ok(request)) => (route(&request, state), request.method == "HEAD"),
+        Some(Err(response)) => (response, false),
+        None => return Ok(()),
+    };
+    write_response(&mut stream, response, head_only).await
+}
+
+// 读取请求，连接提前关闭时返回 None，请求无效时返回错误响应
+async fn read_request(stream: &mut TcpStream) -> Result<Option<std::result::Result<Request, Response>>> {
+    let mut data = Vec::new();
+    let mut buffer = [0u8; 4096];
+    let head_end = loop {
+        if let Some(end) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end + 4;
+        }
+        if data.len() > MAX_REQUEST_HEAD {
+            return Ok(Some(Err(Response::error(ErrorCode::HeadersTooLarge, &t!("repository-headers-too-large")))));
+        }
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        data.extend_from_slice(&buffer[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&data[..head_end]).into_owned();
+    let mut lines = head.lines();
+    let mut parts = lines.next().unwrap_or_default().split_whitespace();
+    let (method, target) = (parts.next().unwrap_or_default().to_string(), parts.next().unwrap_or_default());
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut authorization = None;
+    let mut content_length = 0usize;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else { continue };
+        if name.trim().eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.trim().to_string());
+        } else if name.trim().eq_ignore_ascii_case("content-length") {
+            let Ok(length) = value.trim().parse() else {
+                return Ok(Some(Err(Response::error(ErrorCode::BadRequest, &t!("repository-invalid-content-length")))));
+            };
+            content_length = length;
+        }
+    }
+    if content_length > MAX_REQUEST_BODY {
+        return Ok(Some(Err(Response::error(ErrorCode::PayloadTooLarge, &t!("repository-payload-too-large")))));
+    }
+
+    let mut body = data.split_off(head_end);
+    while body.len() < content_length {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        body.extend_from_slice(&buffer[..read]);
+    }
+    body.truncate(content_length);
+    Ok(Some(Ok(Request { method, path: path.to_string(), query: query.to_string(), authorization, body })))
+}
+
+fn route(request: &Request, state: &ServerState) -> Response {
+    let path = request.path.as_str();
+    let read_only = matches!(request.method.as_str(), "GET" | "HEAD");
+    // health 不需要认证，客户端用它检查服务是否可用
+    if path == HEALTH_PATH || path == LEGACY_HEALTH_PATH {
+        if !read_only {
+            return Response::method_not_allowed();
+        }
+        return Response::json(&HealthResponse {
+            status: "ok".to_string(),
+            api_version: API_VERSION,
+            drivers: state.catalog.entries().len(),
+        });
+    }
+    if !authorized(request, state) {
+        return Response::error(ErrorCode::Unauthorized, &t!("repository-invalid-api-key"));
+    }
+    if let Some(id) = path.strip_prefix(PACKAGES_PATH) {
+        if !read_only {
+            return Response::method_not_allowed();
+        }
+        return match decode_path_segment(id).and_then(|id| state.catalog.get(&id)) {
+            Some(entry) => Response { status: "200 OK", content_type: "application/octet-stream", body: Body::File(entry.path.clone()) },
+            None => Response::not_found(&t!("repository-package-not-found")),
+        };
+    }
+
+    match path {
+        QUERY_PATH | LEGACY_QUERY_PATH => post(request, |query| Response::json(&query_drivers(state, &query))),
+        BATCH_PATH => post(request, |batch| batch_query(state, batch)),
+        DOWNLOAD_PATH | LEGACY_DOWNLOAD_PATH => post(request, |download| download_driver(state, &download)),
+        _ => match path.strip_prefix(DRIVERS_PATH) {
+            Some(_) if !read_only => Response::method_not_allowed(),
+            Some(rest) => driver_details(request, state, rest),
+            None => Response::not_found(&t!("repository-endpoint-not-found")),
+        },
+    }
+}
+
+fn authorized(request: &Request, state: &ServerState) -> bool {
+    let Some(ref api_key) = state.options.api_key else {
+        return true;
+    };
+    request.authorization.as_deref()
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| token.trim() == api_key)
+}
+
+// 解析 POST 请求的 JSON 后交给 handler
+fn post<T: serde::de::DeserializeOwned>(request: &Request, handler: impl FnOnce(T) -> Response) -> Response {
+    if request.method != "POST" {
+        return Response::method_not_allowed();
+    }
+    match serde_json::from_slice(&request.body) {
+        Ok(body) => handler(body),
+        Err(e) => Response::error(ErrorCode::BadRequest, &t!("repository-invalid-request", error = e)),
+    }
+}
+
+// GET /api/v1/drivers/<硬件ID>[/compatibility]
+fn driver_details(request: &Request, state: &ServerState, rest: &str) -> Response {
+    let (segment, compatibility) = match rest.strip_suffix("/compatibility") {
+        Some(segment) => (segment, true),
+        None => (rest, false),
+    };
+    let Some(hardware_id) = decode_path_segment(segment).filter(|id| !id.trim().is_empty()) else {
+        return Response::error(ErrorCode::BadRequest, &t!("repository-invalid-hardware-id"));
+    };
+    let (os_version, architecture) = (request.query_param("os"), request.query_param("arch"));
+    let Some(entry) = state.catalog.find(&hardware_id, os_version.as_deref(), architecture.as_deref()).into_iter().next() else {
+        return Response::not_found(&t!("repository-no-driver", hardware_id = hardware_id));
+    };
+    if compatibility {
+        return Response::json(&CompatibilityInfo {
+            hardware_id,
+            driver_id: entry.id.clone(),
+            os_versions: entry.manifest.os_versions.clone(),
+            architectures: entry.manifest.architectures.clone(),
+        });
+    }
+    let driver = entry.driver_info(&hardware_id, download_url(state, entry));
+    Response::json(&DriverRecord::from(driver))
+}
+
+// 适用于查询的驱动，无法比较版本号时也返回，由客户端判断
+fn available<'a>(state: &'a ServerState, query: &DriverQuery) -> Vec<&'a CatalogEntry> {
+    state.catalog
+        .find(&query.hardware_id, query.os_version.as_deref(), query.architecture.as_deref())
+        .into_iter()
+        .filter(|entry| query.current_version.as_deref().is_none_or(|current| {
+            !matches!(compare_driver_versions(&entry.manifest.version, current), Some(Ordering::Less | Ordering::Equal))
+        }))
+        .collect()
+}
+
+fn query_drivers(state: &ServerState, query: &DriverQuery) -> DriverQueryResponse {
+    let entries = available(state, query);
+    let per_page = query.per_page.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let page = query.page.unwrap_or(1).max(1);
+    let start = (page as usize - 1).saturating_mul(per_page as usize);
+    let available_drivers = entries.iter()
+        .skip(start)
+        .take(per_page as usize)
+        .map(|entry| entry.driver_info(&query.hardware_id, download_url(state, entry)))
+        .collect();
+    DriverQueryResponse {
+        hardware_id: query.hardware_id.clone(),
+        available_drivers,
+        latest_version: entries.first().map(|entry| entry.manifest.version.clone()),
+        page,
+        per_page,
+        total: entries.len(),
+        next_page: (start.saturating_add(per_page as usize) < entries.len()).then_some(page + 1),
+    }
+}
+
+fn batch_query(state: &ServerState, batch: BatchQuery) -> Response {
+    if batch.queries.len() > MAX_BATCH_SIZE {
+        return Response::error(ErrorCode::PayloadTooLarge, &t!("repository-batch-too-large", max = MAX_BATCH_SIZE));
+    }
+    let results = batch.queries.into_iter().map(|query| BatchResult {
+        driver: available(state, &query).first()
+            .map(|entry| entry.driver_info(&query.hardware_id, download_url(state, entry))),
+        hardware_id: query.hardware_id,
+    }).collect();
+    Response::json(&BatchResponse { results })
+}
+
+fn download_driver(state: &ServerState, download: &DownloadRequest) -> Response {
+    let entry = state.catalog.find(&download.hardware_id, None, None).into_iter()
+        .find(|entry| download.version.as_deref().is_none_or(|version| entry.manifest.version == version.trim()));
+    match entry {
+        Some(entry) => Response::json(&DownloadResponse {
+            download_url: download_url(state, entry),
+            checksum: entry.sha256.clone(),
+            file_size: entry.size,
+        }),
+        None => Response::not_found(&t!("repository-no-driver", hardware_id = download.hardware_id)),
+    }
+}
+
+// 驱动包的下载地址，不使用客户端发送的 Host，避免被伪造的请求头改写链接
+fn download_url(state: &ServerState, entry: &CatalogEntry) -> String {
+    let base = state.options.public_url.clone()
+        .unwrap_or_else(|| format!("http://{}", state.local_addr));
+    format!("{}{}{}", base, PACKAGES_PATH, encode_path_segment(&entry.id))
+}
+
+async fn write_response(stream: &mut TcpStream, response: Response, head_only: bool) -> Result<()> {
+    match response.body {
+        Body::Bytes(bytes) => {
+            write_head(stream, response.status, response.content_type, bytes.len() as u64).await?;
+            if !head_only {
+                stream.write_all(&bytes).await?;
+            }
+        }
+        Body::File(path) => {
+            let mut file = tokio::fs::File::open(&path).await?;
+            write_head(stream, response.status, response.content_type, file.metadata().await?.len()).await?;
+            if !head_only {
+                tokio::io::copy(&mut file, stream).await?;
+            }
+        }
+    }
+    stream.shutdown().await?;
+    Ok(())
+}
+
+async fn write_head(stream: &mut TcpStream, status: &str, content_type: &str, length: u64) -> Result<()> {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nContent-Type: {}\r\nConnection: close\r\n\r\n",
+        status, length, content_type
+    );
+    stream.write_all(head.as_bytes()).await?;
+    Ok(())
+}
@@ -1,80 +0,0 @@
-use std::path::Path;
-use crate::error::HamsterError;
-use crate::scan::DriverInfo;
-
-/// 从备份恢复驱动配置
-pub fn restore_driver_config() -> Result<(), HamsterError> {
-    // 从备份恢复驱动配置
-    // 实际实现中，这里会从备份位置恢复配置文件
-    
-    // 示例：检查备份是否存在
-    if !Path::new("backups/configs").exists() {
-        return Err(HamsterError::RestoreError("备份目录不存在".to_string()));
-    }
-    
-    // 示例：模拟恢复配置
-    // fs::copy("backups/configs/hosts.bak", "C:\\Windows\\System32\\drivers\\etc\\hosts")?;
-    
-    Ok(())
-}
-
-/// 从备份恢复单个驱动
-pub fn restore_single_driver(driver_name: &str) -> Result<(), HamsterError> {
-    // 从备份恢复单个驱动
-    // 实际实现中，这里会从备份位置恢复驱动文件
-    
-    let backup_dir = Path::new("backups/drivers").join(driver_name.replace("/", "_").replace("\\", "_"));
-    
-    // 检查备份是否存在
-    if !backup_dir.exists() {
-        return Err(HamsterError::RestoreError(format!("驱动备份不存在: {}", driver_name)));
-    }
-    
-    // 这里应该实际恢复驱动文件，但现在只是模拟
-    println!("恢复驱动: {}", driver_name);
-    
-    Ok(())
-}
-
-/// 从备份恢复多个驱动
-pub fn restore_multiple_drivers(drivers: &[DriverInfo]) -> Result<Vec<String>, HamsterError> {
-    let mut results = Vec::new();
-    
-    for driver in drivers {
-        match restore_single_driver(&driver.name) {
-            Ok(_) => {
-                results.push(format!("成功恢复: {}", driver.name));
-            },
-            Err(e) => {
-                results.push(format!("恢复失败 {}: {}", driver.name, e));
-            }
-        }
-    }
-    
-    Ok(results)
-}
-
-/// 从备份恢复驱动文件
-pub fn restore_driver_files() -> Result<(), HamsterError> {
-    // 从备份恢复驱动文件
-    // 实际实现中，这里会从备份位置恢复驱动文件
-    
-    // 示例：检查备份是否存在
-    if !Path::new("backups/files").exists() {
-        return Err(HamsterError::RestoreError("备份目录不存在".to_string()));
-    }
-    
-    // 示例：模拟恢复文件
-    // fs::copy("backups/files/example.sys.bak", "C:\\Windows\\System32\\drivers\\example.sys")?;
-    
-    Ok(())
-}
-
-/// 完整驱动恢复
-pub fn restore_drivers() -> Result<(), HamsterError> {
-    // 从备份恢复驱动
-    restore_driver_config()?;
-    restore_driver_files()?;
-    
-    Ok(())
-}
@@ -2,40 +2,48 @@
 //!
 //! 用于显示单个硬件设备信息的UI组件
 
-use crate::types::hardware_types::DeviceInfo;
-use crate::types::driver_types::DriverStatus;
-use crate::utils::error::Result;
+use eframe::egui;
+use crate::core::UpdateCandidate;
+use crate::hardware::HardwareDevice;
+use crate::t;
+
+/// 设备驱动的更新状态
+#[derive(Debug, Clone, PartialEq)]
+pub enum DriverStatus {
+    /// 还没有查找过更新
+    Unknown,
+    UpToDate,
+    Outdated { latest_version: String },
+}
 
 pub struct DeviceCard {
-    pub device_info: DeviceInfo,
+    pub device: HardwareDevice,
     pub driver_status: DriverStatus,
     pub is_selected: bool,
 }
 
 impl DeviceCard {
-    pub fn new(device_info: DeviceInfo) -> Self {
-        let driver_status = DriverStatus::Unknown;
-
+    pub fn new(device: HardwareDevice) -> Self {
         Self {
-            device_info,
-            driver_status,
+            device,
+            driver_status: DriverStatus::Unknown,
             is_selected: false,
         }
     }
 
     /// 获取设备名称
     pub fn get_device_name(&self) -> &str {
-        &self.device_info.name
+        &self.device.device_name
     }
 
-    /// 获取设备ID
+    /// 获取设备实例ID
     pub fn get_device_id(&self) -> &str {
-        self.device_info.primary_hardware_id().map_or("", |h| h.full_id.as_str())
+        &self.device.device_id
     }
 
     /// 获取设备类型
-    pub fn get_device_type(&self) -> String {
-        self.device_info.device_class.to_string()
+    pub fn get_device_type(&self) -> &str {
+        &self.device.device_class
     }
 
     /// 获取驱动状态
@@ -53,29 +61,127 @@ impl DeviceCard {
         self.is_selected = !self.is_selected;
     }
 
-    /// 更新设备信息
-    pub fn update_device_info(&mut self, new_info: DeviceInfo) {
-        self.device_info = new_info;
-        self.driver_status = DriverStatus::Unknown;
+    /// 设置选中状态
+    pub fn set_selected(&mut self, selected: bool) {
+        self.is_selected = selected;
+    }
+
+    /// 更新设备信息，驱动版本变化后之前的更新状态不再可信
+    pub fn update_device_info(&mut self, device: HardwareDevice) {
+        if device.driver_version != self.device.driver_version {
+            self.driver_status = DriverStatus::Unknown;
+        }
+        self.device = device;
+    }
+
+    /// 根据查找更新的结果设置驱动状态
+    pub fn apply_update_candidate(&mut self, candidate: &UpdateCandidate) {
+        self.driver_status = match candidate.matched_driver {
+            Some(ref driver) if candidate.needs_update => DriverStatus::Outdated {
+                latest_version: driver.driver_version.clone(),
+            },
+            _ => DriverStatus::UpToDate,
+        };
     }
 
     /// 检查是否有可用更新
     pub fn has_update_available(&self) -> bool {
-        matches!(self.driver_status, DriverStatus::Outdated)
+        matches!(self.driver_status, DriverStatus::Outdated { .. })
     }
 
     /// 获取驱动版本信息
-    pub fn get_driver_version(&self) -> Option<String> {
-        self.device_info.driver_version.clone()
+    pub fn get_driver_version(&self) -> &str {
+        &self.device.driver_version
     }
 
-    /// 获取设备描述
-    pub fn get_description(&self) -> String {
-        self.device_info.description.clone()
+    pub fn has_problem(&self) -> bool {
+        self.device.has_problem()
     }
 
-    /// 设置选中状态
-    pub fn set_selected(&mut self, selected: bool) {
-        self.is_selected = selected;
+    pub fn is_hidden(&self) -> bool {
+        self.device.is_hidden()
     }
-}
\ No newline at end of file
+
+    /// 状态列显示的文字，问题优先于更新状态
+    pub fn status_label(&self) -> String {
+        if self.is_hidden() {
+            t!("device-not-present")
+        } else if self.has_problem() {
+            t!("device-problem-code", code = self.device.problem_code)
+        } else {
+            match self.driver_status {
+                DriverStatus::Unknown => t!("device-not-checked"),
+                DriverStatus::UpToDate => t!("device-up-to-date"),
+                DriverStatus::Outdated { ref latest_version } => t!("device-update-to", version = latest_version),
+            }
+        }
+    }
+
+    /// 排序用的状态优先级，越小越需要关注
+    pub fn status_rank(&self) -> u8 {
+        if self.has_problem() {
+            0
+        } else if self.has_update_available() {
+            1
+        } else if self.is_hidden() {
+            3
+        } else {
+            2
+        }
+    }
+
+    /// 名称、厂商或任意ID包含搜索文字，不区分大小写
+    pub fn matches_query(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        let device = &self.device;
+        [&device.device_name, &device.manufacturer, &device.device_class, &device.device_id, &device.hardware_id]
+            .into_iter()
+            .chain(&device.hardware_ids)
+            .chain(&device.compatible_ids)
+            .any(|field| field.to_lowercase().contains(&query))
+    }
+
+    /// 显示设备的全部ID和驱动属性
+    pub fn show_details(&self, ui: &mut egui::Ui) {
+        let device = &self.device;
+        ui.heading(&device.device_name);
+        ui.separator();
+
+        egui::Grid::new(("device_detail", &device.device_id))
+            .num_columns(2)
+            .spacing([12.0, 4.0])
+            .show(ui, |ui| {
+                let mut row = |key: &str, value: &str| {
+                    ui.label(t!(key));
+                    ui.add(egui::Label::new(if value.is_empty() { "-" } else { value }).wrap(true));
+                    ui.end_row();
+                };
+                row("device-detail-class", &device.device_class);
+                row("device-detail-vendor", &device.manufacturer);
+                row("device-detail-status", &device.status.label());
+                row("device-detail-problem-code", &device.problem_code.to_string());
+                row("device-detail-present", &t!(if device.present { "common-yes" } else { "common-no" }));
+                row("device-detail-instance-id", &device.device_id);
+                row("device-detail-hardware-id", &device.hardware_id);
+                row("device-detail-driver-version", &device.driver_version);
+                row("device-detail-driver-date", &device.driver_date);
+                row("device-detail-driver-provider", &device.driver_provider);
+                row("device-detail-inf", &device.inf_name);
+                row("device-detail-update-status", &self.status_label());
+            });
+
+        for (title, ids) in [("device-detail-all-hardware-ids", &device.hardware_ids), ("device-detail-compatible-ids", &device.compatible_ids)] {
+            if ids.is_empty() {
+                continue;
+            }
+            ui.separator();
+            ui.label(t!(title));
+            for id in ids {
+                ui.monospace(id);
+            }
+        }
+    }
+}
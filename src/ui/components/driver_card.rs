@@ -1,48 +1,81 @@
 //! 驱动卡片组件
 //!
-//! 用于显示驱动信息的UI组件
+//! 用于显示驱动更新候选的UI组件
 
-use crate::types::driver_types::{DriverInfo, DriverStatus};
-use crate::utils::error::Result;
+use eframe::egui;
+use crate::config::UpdatePreferences;
+use crate::core::{CoreEvent, UpdateCandidate};
+use crate::installer::{InstallStep, StepStatus};
+use crate::i18n::format_size;
+use crate::matcher::{compare_driver_versions, DriverChannel};
+use crate::t;
+
+// 更新失败可能导致无法启动的设备类型
+const CRITICAL_CLASSES: [&str; 2] = ["存储设备", "系统设备"];
+
+/// 驱动在批量更新中的进度
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateItemState {
+    Idle,
+    Queued,
+    /// 下载进度 0.0 到 1.0
+    Downloading(f32),
+    Working(InstallStep),
+    Installed { reboot_required: bool },
+    Failed(String),
+}
+
+/// 卡片上的操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum DriverCardAction {
+    Ignore,
+    Unignore,
+    Pin,
+    Unpin,
+}
 
 pub struct DriverCard {
-    pub driver_info: DriverInfo,
-    pub status: DriverStatus,
+    pub candidate: UpdateCandidate,
     pub is_selected: bool,
     pub can_update: bool,
+    pub ignored: bool,
+    pub pinned: bool,
+    pub state: UpdateItemState,
 }
 
 impl DriverCard {
-    pub fn new(driver_info: DriverInfo) -> Self {
-        let status = driver_info.status.clone();
-        let can_update = matches!(status, DriverStatus::Outdated);
-
-        Self {
-            driver_info,
-            status,
+    pub fn new(candidate: UpdateCandidate, preferences: &UpdatePreferences) -> Self {
+        let mut card = Self {
+            candidate,
             is_selected: false,
-            can_update,
-        }
+            can_update: false,
+            ignored: false,
+            pinned: false,
+            state: UpdateItemState::Idle,
+        };
+        card.refresh(preferences);
+        card.is_selected = card.can_update;
+        card
+    }
+
+    /// 获取设备硬件ID
+    pub fn get_hardware_id(&self) -> &str {
+        &self.candidate.hardware_info.hardware_id
     }
 
     /// 获取驱动名称
     pub fn get_name(&self) -> &str {
-        &self.driver_info.name
+        &self.candidate.hardware_info.device_name
     }
 
-    /// 获取驱动版本
-    pub fn get_version(&self) -> String {
-        self.driver_info.current_version.to_string()
+    /// 获取新驱动版本
+    pub fn get_version(&self) -> &str {
+        self.candidate.matched_driver.as_ref().map_or("", |d| d.driver_version.as_str())
     }
 
     /// 获取驱动供应商
     pub fn get_vendor(&self) -> &str {
-        self.driver_info.provider.as_ref().map(|s| s.as_str()).unwrap_or("")
-    }
-
-    /// 获取驱动状态
-    pub fn get_status(&self) -> &DriverStatus {
-        &self.status
+        &self.candidate.hardware_info.manufacturer
     }
 
     /// 检查是否可更新
@@ -57,38 +90,189 @@ impl DriverCard {
 
     /// 选择/取消选择驱动
     pub fn toggle_selection(&mut self) {
-        self.is_selected = !self.is_selected;
+        self.set_selected(!self.is_selected);
     }
 
-    /// 设置选中状态
+    /// 设置选中状态，不可更新的驱动不能选中
     pub fn set_selected(&mut self, selected: bool) {
-        self.is_selected = selected;
+        self.is_selected = selected && self.can_update;
     }
 
-    /// 更新驱动信息
-    pub fn update_driver_info(&mut self, new_info: DriverInfo) {
-        self.driver_info = new_info;
-        self.status = self.driver_info.status.clone();
-        self.can_update = matches!(self.status, DriverStatus::Outdated);
+    /// 更新候选信息，保留选中状态和进度
+    pub fn update_candidate(&mut self, candidate: UpdateCandidate, preferences: &UpdatePreferences) {
+        self.candidate = candidate;
+        self.refresh(preferences);
     }
 
-    /// 获取驱动描述
-    pub fn get_description(&self) -> &str {
-        self.driver_info.release_notes.as_ref().map(|s| s.as_str()).unwrap_or("")
+    /// 获取驱动发布日期
+    pub fn get_release_date(&self) -> Option<&str> {
+        self.candidate.matched_driver.as_ref()
+            .map(|d| d.release_date.as_str())
+            .filter(|date| !date.is_empty())
     }
 
-    /// 获取驱动文件路径
-    pub fn get_file_path(&self) -> &str {
-        ""
+    /// 根据更新偏好刷新忽略、固定和可更新状态
+    pub fn refresh(&mut self, preferences: &UpdatePreferences) {
+        let hardware_id = self.get_hardware_id().to_string();
+        self.pinned = preferences.is_pinned(&hardware_id);
+        self.ignored = preferences.is_ignored(&hardware_id, self.get_version());
+        self.can_update = self.candidate.needs_update && self.candidate.matched_driver.is_some();
+        self.is_selected &= self.can_update;
     }
 
-    /// 获取驱动发布日期
-    pub fn get_release_date(&self) -> Option<&String> {
-        self.driver_info.release_date.as_ref()
+    /// 安装前需要用户注意的风险
+    pub fn risk_flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        let Some(ref driver) = self.candidate.matched_driver else {
+            return flags;
+        };
+        if driver.channel() == DriverChannel::Beta {
+            flags.push(t!("risk-beta"));
+        }
+        if driver.checksum.is_empty() {
+            flags.push(t!("risk-no-checksum"));
+        }
+        if driver.file_size == 0 {
+            flags.push(t!("risk-unknown-size"));
+        }
+        if CRITICAL_CLASSES.contains(&self.candidate.hardware_info.device_class.as_str()) {
+            flags.push(t!("risk-critical-device"));
+        }
+        let current = &self.candidate.current_version;
+        if compare_driver_versions(&driver.driver_version, current).is_none() {
+            flags.push(t!("risk-unknown-version-order"));
+        } else if driver.driver_version.split('.').next() != current.split('.').next() {
+            flags.push(t!("risk-major-version"));
+        }
+        flags
+    }
+
+    /// 根据核心事件更新进度，返回事件是否属于该驱动
+    pub fn handle_event(&mut self, event: &CoreEvent) -> bool {
+        if !event.hardware_id().is_some_and(|id| id.eq_ignore_ascii_case(self.get_hardware_id())) {
+            return false;
+        }
+        match event {
+            CoreEvent::DownloadProgress { progress, .. } => {
+                self.state = UpdateItemState::Downloading(progress.progress / 100.0);
+            }
+            CoreEvent::InstallStep { step, status, .. } => {
+                self.state = match (step, status) {
+                    (_, StepStatus::Failed { error }) => UpdateItemState::Failed(error.clone()),
+                    (InstallStep::Download, StepStatus::Started) => UpdateItemState::Downloading(0.0),
+                    (InstallStep::Install, StepStatus::Completed { .. }) => UpdateItemState::Installed { reboot_required: false },
+                    (InstallStep::VerifyAfter, StepStatus::Completed { .. }) => return true,
+                    (step, _) => UpdateItemState::Working(*step),
+                };
+            }
+            CoreEvent::RebootRequired { .. } => {
+                self.state = UpdateItemState::Installed { reboot_required: true };
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// 批量更新结束后仍在进行中的项没有完成
+    pub fn finish_batch(&mut self) {
+        if matches!(self.state, UpdateItemState::Queued | UpdateItemState::Downloading(_) | UpdateItemState::Working(_)) {
+            self.state = UpdateItemState::Failed(t!("update-item-unfinished"));
+        }
     }
 
-    /// 检查驱动是否已安装
-    pub fn is_installed(&self) -> bool {
-        !matches!(self.status, DriverStatus::NotInstalled)
+    pub fn show(&mut self, ui: &mut egui::Ui, enabled: bool) -> Option<DriverCardAction> {
+        let mut action = None;
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal(|ui| {
+                let mut selected = self.is_selected;
+                if ui.add_enabled(enabled && self.can_update, egui::Checkbox::new(&mut selected, "")).changed() {
+                    self.set_selected(selected);
+                }
+                ui.strong(self.get_name());
+                ui.label(format!("({})", self.get_vendor()));
+                if self.pinned {
+                    ui.label(t!("update-item-pinned"));
+                } else if self.ignored {
+                    ui.label(t!("update-item-ignored"));
+                }
+            });
+
+            let Some(driver) = self.candidate.matched_driver.clone() else {
+                ui.label(t!("update-item-no-update"));
+                return;
+            };
+
+            egui::Grid::new(("driver_card", self.get_hardware_id()))
+                .num_columns(4)
+                .spacing([16.0, 2.0])
+                .show(ui, |ui| {
+                    ui.label(t!("update-item-current-version"));
+                    ui.label(&self.candidate.current_version);
+                    ui.label(t!("update-item-new-version"));
+                    ui.label(&driver.driver_version);
+                    ui.end_row();
+
+                    ui.label(t!("update-item-release-date"));
+                    ui.label(self.get_release_date().map(str::to_string).unwrap_or_else(|| t!("common-unknown")));
+                    ui.label(t!("update-item-size"));
+                    ui.label(if driver.file_size > 0 { format_size(driver.file_size) } else { t!("common-unknown") });
+                    ui.end_row();
+
+                    ui.label(t!("update-item-source"));
+                    ui.label(driver.source());
+                    ui.label(t!("update-item-channel"));
+                    ui.label(driver.channel().label());
+                    ui.end_row();
+                });
+
+            let risks = self.risk_flags();
+            if !risks.is_empty() {
+                ui.colored_label(ui.visuals().warn_fg_color, t!("update-item-risks", risks = risks.join(&t!("list-separator"))));
+            }
+
+            match self.state {
+                UpdateItemState::Idle => {}
+                UpdateItemState::Queued => { ui.label(t!("update-item-queued")); }
+                UpdateItemState::Downloading(progress) => {
+                    ui.add(egui::ProgressBar::new(progress).show_percentage().text(t!("update-item-downloading", percent = format!("{:.0}", progress * 100.0))));
+                }
+                UpdateItemState::Working(step) => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(step.label());
+                    });
+                }
+                UpdateItemState::Installed { reboot_required } => {
+                    ui.label(t!(if reboot_required { "update-item-installed-reboot" } else { "update-item-installed" }));
+                }
+                UpdateItemState::Failed(ref error) => {
+                    ui.colored_label(ui.visuals().error_fg_color, t!("update-item-failed", error = error));
+                }
+            }
+
+            ui.add_enabled_ui(enabled, |ui| {
+                ui.horizontal(|ui| {
+                    if self.ignored {
+                        if ui.small_button(t!("update-item-unignore")).clicked() {
+                            action = Some(DriverCardAction::Unignore);
+                        }
+                    } else if !self.pinned && ui.small_button(t!("update-item-ignore")).clicked() {
+                        action = Some(DriverCardAction::Ignore);
+                    }
+                    if self.pinned {
+                        if ui.small_button(t!("update-item-unpin")).clicked() {
+                            action = Some(DriverCardAction::Unpin);
+                        }
+                    } else if ui.small_button(t!("update-item-pin")).clicked() {
+                        action = Some(DriverCardAction::Pin);
+                    }
+                    if let Some(ref url) = driver.release_notes_url {
+                        ui.hyperlink_to(t!("update-item-release-notes"), url);
+                    }
+                });
+            });
+        });
+        action
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,4 @@
+mod device_card;
+mod driver_card;
+pub use device_card::*;
+pub use driver_card::*;
@@ -1,19 +1,104 @@
 use eframe::egui;
-use std::sync::{Arc, Mutex};
-use crate::core::DriverUpdaterCore;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::{OwnedRwLockWriteGuard, RwLock};
+use crate::agent::AgentClient;
+use crate::config::{ConfigManager, PeerCacheConfig};
+use crate::driver::fetcher::{DownloadPriority, DownloadQueue, PeerCacheServer};
+use crate::driver::installer::{InstallationLogEntry, LogFilter};
+use crate::core::{run_scheduled_updates, AppCommand, CoreEvent, DriverUpdaterCore, EventFilter, RestorePlan, UpdateCandidate};
+use crate::hardware::HardwareScanResult;
+use crate::installer::{InstallationResult, SnapshotInfo, TransactionKind, TransactionSummary};
 use crate::os_info::SystemInfo;
+use crate::ui::{
+    apply_appearance, apply_fonts, sync_theme, DeviceListView, DownloadAction, DownloadsView, DriverUpdateAction, DriverUpdatesView,
+    HistoryAction, HistoryView, Notifier, Operation, SettingsAction, SettingsView, SystemTray, TaskRunner, TrayCommand,
+    TrayState,
+};
+use crate::utils::CancelHandle;
+use crate::t;
 
 
 pub struct HamsterDriveApp {
-    core: Arc<Mutex<Option<DriverUpdaterCore>>>,
+    /// 更新、恢复等只读取核心的操作持有读锁，查看历史和撤销更改不必等它们结束
+    core: Option<Arc<RwLock<DriverUpdaterCore>>>,
+    cancel: Option<CancelHandle>,
+    tasks: TaskRunner<TaskOutput>,
+    config: ConfigManager,
     current_view: View,
     system_info: Option<SystemInfo>,
-    scan_results: String,
-    update_candidates: String,
-    download_progress: String,
-    is_scanning: bool,
+    system_summary: Option<String>,
+    init_error: Option<String>,
+    scan_result: Option<HardwareScanResult>,
+    device_list: DeviceListView,
+    driver_updates: DriverUpdatesView,
+    update_status: String,
+    reboot_required: bool,
+    /// 核心的下载队列，核心就绪前为 None
+    download_queue: Option<DownloadQueue>,
+    /// 向局域网提供驱动包的服务，未启用时为 None
+    peer_cache_server: Option<PeerCacheServer>,
+    downloads: DownloadsView,
+    download_status: String,
+    history: HistoryView,
+    history_status: String,
+    settings: SettingsView,
+    settings_status: String,
+    /// 找不到或无法读取的字体
+    font_warning: Option<String>,
     scan_progress: f32,
     progress_text: String,
+    restore_path: String,
+    restore_plan: Option<RestorePlan>,
+    restore_selection: std::collections::HashSet<String>,
+    restore_status: String,
+    backup_snapshots: Vec<SnapshotInfo>,
+    pending_transactions: Vec<TransactionSummary>,
+    recovery_status: String,
+    /// 托盘创建失败时为 None，此时不会最小化到托盘
+    tray: Option<SystemTray>,
+    /// 主窗口已隐藏到托盘
+    hidden_to_tray: bool,
+    /// 从托盘菜单退出，关闭窗口时不再隐藏到托盘
+    exiting: bool,
+}
+
+/// 初始化完成的核心控制器
+struct ReadyCore {
+    core: DriverUpdaterCore,
+    system_info: Option<SystemInfo>,
+    system_summary: Option<String>,
+    pending_transactions: Vec<TransactionSummary>,
+    cancel: CancelHandle,
+    download_queue: DownloadQueue,
+}
+
+/// 后台任务送回界面的结果
+enum TaskOutput {
+    Initialized(Result<Box<ReadyCore>, String>),
+    Event(CoreEvent),
+    Scanned(Result<HardwareScanResult, String>),
+    UpdatesFound(Result<Vec<UpdateCandidate>, String>),
+    UpdatesInstalled(Result<Vec<InstallationResult>, String>),
+    /// 更新偏好变化后核心重新标记的候选
+    CandidatesRefreshed(Vec<UpdateCandidate>),
+    BackupFinished(Result<SnapshotInfo, String>),
+    SnapshotsListed(Result<Vec<SnapshotInfo>, String>),
+    /// from_snapshot 为 true 时同时更新备份包路径
+    RestorePlanOpened { plan: Result<RestorePlan, String>, from_snapshot: bool },
+    RestoreFinished(Result<Vec<InstallationResult>, String>),
+    RecoveryFinished { status: String, pending: Vec<TransactionSummary> },
+    HistoryLoaded(Result<Vec<InstallationLogEntry>, String>),
+    ChangeReverted(Result<InstallationResult, String>),
+    /// 设置已保存，deferred 为 true 时核心要等正在执行的操作结束后才使用新配置
+    SettingsApplied { saved: Result<(), String>, deferred: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RecoveryAction {
+    Resume,
+    Rollback,
+    Discard,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -21,98 +106,159 @@ enum View {
     SystemInfo,
     HardwareScan,
     DriverUpdates,
+    Downloads,
+    Restore,
+    History,
     Settings,
     About,
 }
 
 impl HamsterDriveApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        // 设置中文字体支持
-        let mut fonts = egui::FontDefinitions::default();
-        
-        // 添加NotoSansSC字体支持
-        fonts.font_data.insert(
-            "noto_sans_sc".to_owned(),
-            egui::FontData::from_static(include_bytes!("../../assets/font/NotoSansSC-Thin.otf"))
-        );
-        
-        // 配置字体族，将NotoSansSC作为中文字体选项
-        fonts.families.get_mut(&egui::FontFamily::Proportional)
-            .unwrap_or(&mut Vec::new())
-            .insert(0, "noto_sans_sc".to_owned());
-        fonts.families.get_mut(&egui::FontFamily::Monospace)
-            .unwrap_or(&mut Vec::new())
-            .insert(0, "noto_sans_sc".to_owned());
-        
-        cc.egui_ctx.set_fonts(fonts);
-        
         // 安装图像加载器以支持图片显示
         egui_extras::install_image_loaders(&cc.egui_ctx);
 
-        Self {
-            core: Arc::new(Mutex::new(None)),
+        let config = ConfigManager::load_or_default().unwrap_or_else(|e| {
+            eprintln!("{}", t!("config-load-failed", error = e));
+            ConfigManager::new()
+        });
+        crate::i18n::set_locale(config.app_config.locale());
+
+        // 字体从系统字体目录加载，需要中文字体才能正常显示界面
+        let font_warning = font_warning(apply_fonts(&cc.egui_ctx, &config.appearance_config.fonts));
+        apply_appearance(&cc.egui_ctx, &config.appearance_config, cc.integration_info.system_theme);
+
+        // 核心在后台初始化，完成前界面照常绘制
+        let mut tasks = TaskRunner::new(&cc.egui_ctx);
+        let initializing = initialize_core(config.clone(), tasks.notifier());
+        tasks.spawn(Operation::Initialize, async move {
+            TaskOutput::Initialized(initializing.await.map(Box::new).map_err(|e| e.to_string()))
+        });
+
+        let peer_cache_server = start_peer_cache_server(&config.download_config.peer_cache)
+            .unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                None
+            });
+
+        let tray = SystemTray::new()
+            .and_then(|mut tray| tray.show().map(|_| tray))
+            .map_err(|e| eprintln!("{}", t!("tray-create-failed", error = e)))
+            .ok();
+
+        let settings = SettingsView::new(&config);
+        let mut app = Self {
+            core: None,
+            cancel: None,
+            tasks,
+            config,
             current_view: View::SystemInfo,
             system_info: None,
-            scan_results: String::new(),
-            update_candidates: String::new(),
-            download_progress: String::new(),
-            is_scanning: false,
+            system_summary: None,
+            init_error: None,
+            scan_result: None,
+            device_list: DeviceListView::new(),
+            settings,
+            settings_status: String::new(),
+            font_warning,
+            driver_updates: DriverUpdatesView::new(),
+            update_status: String::new(),
+            reboot_required: false,
+            download_queue: None,
+            peer_cache_server,
+            downloads: DownloadsView::new(),
+            download_status: String::new(),
+            history: HistoryView::new(),
+            history_status: String::new(),
             scan_progress: 0.0,
             progress_text: String::new(),
+            restore_path: String::new(),
+            restore_plan: None,
+            restore_selection: std::collections::HashSet::new(),
+            restore_status: String::new(),
+            backup_snapshots: Vec::new(),
+            pending_transactions: Vec::new(),
+            recovery_status: String::new(),
+            tray,
+            hidden_to_tray: false,
+            exiting: false,
+        };
+        app.device_list.filter.show_hidden = app.config.scanner_config.include_hidden;
+        app
+    }
+
+    /// 在后台使用核心执行操作，核心未就绪或该操作已在运行时忽略
+    fn spawn_with_core<F, Fut>(&mut self, operation: Operation, task: F)
+    where
+        F: FnOnce(Arc<RwLock<DriverUpdaterCore>>) -> Fut,
+        Fut: Future<Output = TaskOutput> + Send + 'static,
+    {
+        if self.tasks.is_running(operation) {
+            return;
+        }
+        if let Some(core) = self.core.clone() {
+            self.tasks.spawn(operation, task(core));
         }
     }
 
-    fn initialize_core(&mut self) {
-        if self.core.lock().unwrap().is_none() {
-            // 使用内存路径而不是实际的数据库文件，避免文件锁定问题
-            let db_path = ":memory:";
-            let core_result = tokio::task::block_in_place(|| {
-                tokio::runtime::Handle::current().block_on(async {
-                    DriverUpdaterCore::new(db_path, "./downloads").await
-                })
-            });
-            
-            match core_result {
-                Ok(mut core) => {
-                    // 初始化core
-                    let init_result = tokio::task::block_in_place(|| {
-                        tokio::runtime::Handle::current().block_on(async {
-                            core.initialize().await
-                        })
-                    });
-                    
-                    if let Err(e) = init_result {
-                        eprintln!("初始化核心失败: {}", e);
-                    }
-                    
-                    // 更新系统信息到UI
-                    self.system_info = core.system_info.clone();
-                    
-                    // 最后设置core
-                    *self.core.lock().unwrap() = Some(core);
-                }
-                Err(e) => eprintln!("初始化核心失败: {}", e),
+    /// 启用了定时更新时在界面的核心上运行调度器，后台代理正在运行时由代理执行
+    fn start_scheduled_updates(&self) {
+        let Some(core) = self.core.clone().filter(|_| self.config.schedule_config.enabled) else {
+            return;
+        };
+        let config = self.config.clone();
+        tokio::spawn(async move {
+            if AgentClient::connect_default().await.is_ok() {
+                println!("{}", t!("schedule-agent-running"));
+                return;
+            }
+            if let Err(e) = run_scheduled_updates(core, config).await {
+                eprintln!("{}", t!("schedule-stopped", error = e));
             }
+        });
+    }
+
+    /// 是否可以开始该操作
+    fn can_start(&self, operation: Operation) -> bool {
+        self.core.is_some() && !self.tasks.is_running(operation)
+    }
+
+    /// 显示操作状态，运行中时显示进度圈和取消按钮
+    fn operation_status(&self, ui: &mut egui::Ui, operation: Operation) {
+        if !self.tasks.is_running(operation) {
+            ui.label(t!("status-ready"));
+            return;
         }
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.label(operation.label());
+            if let (true, Some(cancel)) = (operation.cancellable(), &self.cancel) {
+                if ui.small_button(t!("action-cancel")).clicked() {
+                    cancel.cancel();
+                }
+            }
+        });
     }
-    
 
     fn render_sidebar(&mut self, ui: &mut egui::Ui) {
         // 使用egui::Image::from_bytes API直接显示图片
         ui.add(egui::Image::from_bytes("HamsterDriveLogo", include_bytes!("../../assets/images/HamsterDrive64.png")).max_width(128.0));
         
         // 导航菜单
-        self.sidebar_button(ui, "系统信息", View::SystemInfo);
-        self.sidebar_button(ui, "硬件扫描", View::HardwareScan);
-        self.sidebar_button(ui, "驱动更新", View::DriverUpdates);
+        self.sidebar_button(ui, &t!("nav-system-info"), View::SystemInfo);
+        self.sidebar_button(ui, &t!("nav-hardware-scan"), View::HardwareScan);
+        self.sidebar_button(ui, &t!("nav-driver-updates"), View::DriverUpdates);
+        self.sidebar_button(ui, &t!("nav-downloads"), View::Downloads);
+        self.sidebar_button(ui, &t!("nav-restore"), View::Restore);
+        self.sidebar_button(ui, &t!("nav-history"), View::History);
         
         ui.separator();
         
         // 设置按钮放在分隔符下方
-        self.sidebar_button(ui, "设置", View::Settings);
+        self.sidebar_button(ui, &t!("nav-settings"), View::Settings);
         
         // 关于按钮放在设置按钮下方
-        self.sidebar_button(ui, "关于", View::About);
+        self.sidebar_button(ui, &t!("nav-about"), View::About);
     }
 
     fn sidebar_button(&mut self, ui: &mut egui::Ui, label: &str, view: View) {
@@ -124,73 +270,64 @@ impl HamsterDriveApp {
     }
 
     fn render_dashboard(&mut self, ui: &mut egui::Ui) {
-            
-        // 初始化核心
-        self.initialize_core();
-            
-        // 在进入UI闭包之前获取当前状态
-        let current_is_scanning = self.is_scanning;
-        let current_progress_text = self.progress_text.clone();
-        let current_scan_progress = self.scan_progress;
-                
         // 定义需要执行的操作
         let mut scan_clicked = false;
         let mut find_updates_clicked = false;
         let mut update_all_clicked = false;
-                
+        
+        if let Some(ref error) = self.init_error {
+            ui.colored_label(ui.visuals().error_fg_color, t!("init-failed", error = error));
+            ui.separator();
+        }
+        
         // 显示系统摘要、仪表盘和硬件摘要
-        if let Ok(core_guard) = self.core.lock() {
-            if let Some(ref core) = *core_guard {
-                if let Some(summary) = core.get_system_summary() {
-                            
-                    ui.heading("系统摘要");
-                    ui.label(&summary);
-                    ui.separator();
+        if let Some(ref summary) = self.system_summary {
+            ui.heading(t!("dashboard-system-summary"));
+            ui.label(summary);
+            ui.separator();
+        }
+        
+        ui.heading(t!("dashboard-title"));
+        
+        egui::Grid::new("dashboard_grid")
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .show(ui, |ui| {
+                ui.label(t!("dashboard-feature"));
+                ui.label(t!("dashboard-status"));
+                ui.end_row();
+                
+                if ui.add_enabled(self.can_start(Operation::Scan), egui::Button::new(t!("action-scan-hardware"))).clicked() {
+                    scan_clicked = true;
                 }
-                        
-                ui.heading("仪表盘");
-                        
-                egui::Grid::new("dashboard_grid")
-                    .num_columns(2)
-                    .spacing([40.0, 4.0])
-                    .show(ui, |ui| {
-                        ui.label("功能:");
-                        ui.label("状态:");
-                        ui.end_row();
-                                
-                        if ui.button("扫描硬件").clicked() {
-                            scan_clicked = true;
-                        }
-                        ui.label(if current_is_scanning { "正在扫描..." } else { "就绪" });
-                        ui.end_row();
-                                
-                        if ui.button("查找驱动更新").clicked() {
-                            find_updates_clicked = true;
-                        }
-                        ui.label("就绪");
-                        ui.end_row();
-                                
-                        if ui.button("更新所有驱动").clicked() {
-                            update_all_clicked = true;
-                        }
-                        ui.label("就绪");
-                        ui.end_row();
-                    });
-                        
-                // 显示进度
-                if current_is_scanning {
-                    ui.label(&current_progress_text);
-                    ui.add(egui::ProgressBar::new(current_scan_progress).show_percentage());
+                self.operation_status(ui, Operation::Scan);
+                ui.end_row();
+                
+                if ui.add_enabled(self.can_start(Operation::FindUpdates), egui::Button::new(t!("action-find-driver-updates"))).clicked() {
+                    find_updates_clicked = true;
                 }
-                        
-                if let Some(hardware_summary) = core.get_hardware_summary() {
-                    ui.separator();
-                    ui.heading("硬件摘要");
-                    ui.label(&hardware_summary);
+                self.operation_status(ui, Operation::FindUpdates);
+                ui.end_row();
+                
+                if ui.add_enabled(self.can_start(Operation::UpdateAll), egui::Button::new(t!("action-update-all"))).clicked() {
+                    update_all_clicked = true;
                 }
-            }
+                self.operation_status(ui, Operation::UpdateAll);
+                ui.end_row();
+            });
+        
+        // 显示进度
+        if self.tasks.is_running(Operation::Scan) {
+            ui.label(&self.progress_text);
+            ui.add(egui::ProgressBar::new(self.scan_progress).show_percentage());
         }
-                
+        
+        if let Some(ref scan_result) = self.scan_result {
+            ui.separator();
+            ui.heading(t!("dashboard-hardware-summary"));
+            ui.label(t!("dashboard-device-count", count = scan_result.devices.len()));
+        }
+        
         // 在UI更新后执行需要可变借用的方法
         if scan_clicked {
             self.start_hardware_scan();
@@ -203,240 +340,867 @@ impl HamsterDriveApp {
         }
     }
 
-
-
     fn render_hardware_scan(&mut self, ui: &mut egui::Ui) {
-        ui.heading("硬件扫描");
+        ui.heading(t!("nav-hardware-scan"));
         
-        if ui.button("开始扫描").clicked() {
-            self.start_hardware_scan();
-        }
+        ui.horizontal(|ui| {
+            if ui.add_enabled(self.can_start(Operation::Scan), egui::Button::new(t!("action-start-scan"))).clicked() {
+                self.start_hardware_scan();
+            }
+            self.operation_status(ui, Operation::Scan);
+        });
         
-        if self.is_scanning {
+        if self.tasks.is_running(Operation::Scan) {
             ui.label(&self.progress_text);
             ui.add(egui::ProgressBar::new(self.scan_progress).show_percentage());
-        } else {
-            ui.horizontal(|ui| {
-                if ui.button("刷新结果").clicked() {
-                    self.refresh_scan_results();
-                }
-                
-                if ui.button("导出结果").clicked() {
-                    // 导出功能待实现
-                }
-            });
+        } else if !self.progress_text.is_empty() {
+            ui.label(&self.progress_text);
         }
         
         ui.separator();
         
-        ui.label(&self.scan_results);
+        self.device_list.show(ui);
     }
 
     fn render_driver_updates(&mut self, ui: &mut egui::Ui) {
-        ui.heading("驱动更新");
+        ui.heading(t!("nav-driver-updates"));
         
         ui.horizontal(|ui| {
-            if ui.button("查找更新").clicked() {
+            if ui.add_enabled(self.can_start(Operation::FindUpdates), egui::Button::new(t!("action-find-updates"))).clicked() {
                 self.find_driver_updates();
             }
-            
-            if ui.button("更新所有").clicked() {
-                self.update_all_drivers();
-            }
+            self.operation_status(ui, Operation::FindUpdates);
         });
+        if !self.update_status.is_empty() {
+            ui.label(&self.update_status);
+        }
+        if self.tasks.is_running(Operation::UpdateAll) {
+            self.operation_status(ui, Operation::UpdateAll);
+        }
         
         ui.separator();
         
-        // 显示更新候选
-        ui.label(&self.update_candidates);
+        let busy = self.core.is_none()
+            || self.tasks.is_running(Operation::UpdateAll)
+            || self.tasks.is_running(Operation::Preferences);
+        match self.driver_updates.show(ui, busy) {
+            Some(DriverUpdateAction::UpdateSelected(hardware_ids)) => self.update_selected_drivers(hardware_ids),
+            Some(DriverUpdateAction::DownloadSelected(drivers)) => self.queue_downloads(drivers),
+            Some(action) => self.change_update_preferences(action),
+            None => {}
+        }
         
-        // 显示下载进度
-        if !self.download_progress.is_empty() {
-            ui.separator();
-            ui.heading("下载进度");
-            ui.label(&self.download_progress);
+        if self.reboot_required {
+            ui.colored_label(ui.visuals().warn_fg_color, t!("updates-reboot-required"));
         }
     }
 
-    fn render_settings(&mut self, ui: &mut egui::Ui) {
-        ui.heading("设置");
+    fn render_downloads(&mut self, ui: &mut egui::Ui) {
+        ui.heading(t!("nav-downloads"));
+        ui.separator();
         
-        egui::Grid::new("settings_grid")
-            .num_columns(2)
-            .spacing([20.0, 8.0])
-            .show(ui, |ui| {
-                let download_dir = tokio::task::block_in_place(|| {
-                    tokio::runtime::Handle::current().block_on(async {
-                        if let Ok(core_guard) = self.core.lock() {
-                            if let Some(ref core) = *core_guard {
-                                return core.driver_fetcher.lock().await.download_dir.clone();
-                            }
-                        }
-                        "./downloads".to_string()
-                    })
-                });
-                ui.label("下载目录:");
-                ui.label(&download_dir);
-                ui.end_row();
-                
-                ui.label("数据库路径:");
-                ui.label("drivers.db");
-                ui.end_row();
-                
-                ui.label("Aria2端口:");
-                ui.label("6800");
-                ui.end_row();
+        if !self.download_status.is_empty() {
+            ui.label(&self.download_status);
+        }
+        
+        // 下载队列属于核心，核心就绪后才能显示
+        let Some(queue) = self.download_queue.as_ref() else {
+            ui.label(t!("core-initializing"));
+            return;
+        };
+        let tasks = queue.get_all_tasks();
+        if let Some(action) = self.downloads.show(ui, &tasks) {
+            let result = match action {
+                DownloadAction::Pause(id) => queue.pause_task(&id),
+                DownloadAction::Resume(id) => queue.resume_task(&id),
+                DownloadAction::Retry(id) => queue.retry_task(&id),
+                DownloadAction::Cancel(id) => queue.cancel_task(&id),
+                DownloadAction::Remove(id) => queue.remove_task(&id),
+                DownloadAction::ClearFinished => queue.clear_finished(),
+            };
+            match result {
+                Ok(()) => self.download_status.clear(),
+                Err(e) => self.download_status = t!("downloads-action-failed", error = e),
+            }
+        }
+        
+        // 下载进度不产生事件，下载期间定时刷新
+        if queue.has_pending() {
+            ui.ctx().request_repaint_after(std::time::Duration::from_millis(500));
+        }
+    }
+
+    fn render_history(&mut self, ui: &mut egui::Ui) {
+        ui.heading(t!("nav-history"));
+        ui.separator();
+        
+        if self.core.is_none() {
+            ui.label(t!("op-initialize"));
+            return;
+        }
+        if !self.history_status.is_empty() {
+            ui.label(&self.history_status);
+        }
+        for operation in [Operation::History, Operation::RevertChange] {
+            if self.tasks.is_running(operation) {
+                self.operation_status(ui, operation);
+            }
+        }
+        
+        let busy = self.tasks.is_running(Operation::History) || self.tasks.is_running(Operation::RevertChange);
+        match self.history.show(ui, busy) {
+            Some(HistoryAction::Refresh) => self.refresh_history(),
+            Some(HistoryAction::Revert(log_id)) => self.revert_change(log_id),
+            None => {}
+        }
+    }
+
+    fn render_restore(&mut self, ui: &mut egui::Ui) {
+        ui.heading(t!("nav-restore"));
+        
+        let mut open_clicked = false;
+        let mut restore_clicked = false;
+        let mut backup_clicked = false;
+        let mut refresh_snapshots_clicked = false;
+        let mut open_snapshot: Option<String> = None;
+        
+        ui.horizontal(|ui| {
+            if ui.add_enabled(self.can_start(Operation::Backup), egui::Button::new(t!("restore-backup-now"))).clicked() {
+                backup_clicked = true;
+            }
+            if ui.add_enabled(self.can_start(Operation::ListSnapshots), egui::Button::new(t!("restore-refresh-snapshots"))).clicked() {
+                refresh_snapshots_clicked = true;
+            }
+            for operation in [Operation::Backup, Operation::ListSnapshots, Operation::OpenRestorePlan] {
+                if self.tasks.is_running(operation) {
+                    ui.spinner();
+                    ui.label(operation.label());
+                }
+            }
+        });
+        
+        for snapshot in &self.backup_snapshots {
+            ui.horizontal(|ui| {
+                ui.label(t!("restore-snapshot-item", created_at = snapshot.created_at, description = snapshot.description,
+                    count = snapshot.driver_count, id = snapshot.id));
+                if ui.button(t!("action-open")).clicked() {
+                    open_snapshot = Some(snapshot.id.clone());
+                }
             });
+        }
         
         ui.separator();
         
-        if ui.button("保存设置").clicked() {
-            // 保存设置功能待实现
+        ui.horizontal(|ui| {
+            ui.label(t!("restore-package-path"));
+            ui.text_edit_singleline(&mut self.restore_path);
+            if ui.add_enabled(self.can_start(Operation::OpenRestorePlan), egui::Button::new(t!("restore-open-package"))).clicked() {
+                open_clicked = true;
+            }
+        });
+        
+        ui.separator();
+        
+        if let Some(ref plan) = self.restore_plan {
+            ui.label(t!("restore-description", description = plan.description));
+            ui.label(t!("restore-created-at", created_at = plan.created_at));
+            ui.separator();
+            
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                for candidate in &plan.candidates {
+                    let entry = &candidate.entry;
+                    let mut selected = self.restore_selection.contains(&entry.id);
+                    
+                    ui.horizontal(|ui| {
+                        let checkbox = ui.add_enabled(
+                            candidate.is_restorable(),
+                            egui::Checkbox::new(&mut selected, format!("{} ({})", entry.device_name, entry.driver_version)),
+                        );
+                        if checkbox.changed() {
+                            if selected {
+                                self.restore_selection.insert(entry.id.clone());
+                            } else {
+                                self.restore_selection.remove(&entry.id);
+                            }
+                        }
+                        
+                        let status = match (&candidate.device, candidate.verification.valid) {
+                            (_, false) => t!("restore-verify-failed", problems = candidate.verification.problems.join("; ")),
+                            (None, true) => t!("restore-device-missing"),
+                            (Some(device), true) => t!("restore-matched-device", device = device.device_name),
+                        };
+                        ui.label(status);
+                    });
+                }
+            });
+            
+            ui.separator();
+            
+            ui.horizontal(|ui| {
+                let can_restore = self.can_start(Operation::Restore) && !self.restore_selection.is_empty();
+                if ui.add_enabled(can_restore, egui::Button::new(t!("restore-selected"))).clicked() {
+                    restore_clicked = true;
+                }
+                ui.label(t!("restore-selected-count", count = self.restore_selection.len()));
+                if self.tasks.is_running(Operation::Restore) {
+                    self.operation_status(ui, Operation::Restore);
+                }
+            });
+        }
+        
+        if !self.restore_status.is_empty() {
+            ui.separator();
+            ui.label(&self.restore_status);
         }
         
+        if backup_clicked {
+            self.backup_current_drivers();
+        }
+        if refresh_snapshots_clicked {
+            self.refresh_backup_snapshots();
+        }
+        if let Some(snapshot_id) = open_snapshot {
+            self.open_backup_snapshot(&snapshot_id);
+        }
+        if open_clicked {
+            self.open_backup_package();
+        }
+        if restore_clicked {
+            self.restore_selected_drivers();
+        }
+    }
+
+    fn render_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading(t!("nav-settings"));
+        ui.label(t!("settings-config-file", path = ConfigManager::default_config_path().display()));
+        self.operation_status(ui, Operation::ApplySettings);
+        if !self.settings_status.is_empty() {
+            ui.label(&self.settings_status);
+        }
+        if let Some(ref warning) = self.font_warning {
+            ui.colored_label(ui.visuals().warn_fg_color, warning);
+        }
+        ui.separator();
+
+        let busy = self.tasks.is_running(Operation::ApplySettings);
+        if let Some(SettingsAction::Apply(config)) = self.settings.show(ui, &self.config, busy) {
+            self.apply_settings(ui.ctx(), config);
+        }
     }
 
     fn render_about(&mut self, ui: &mut egui::Ui) {
-        ui.heading("关于");
-        ui.label(format!("HamsterDrivers - Windows驱动管理工具"));
-        ui.label(format!("版本: {}", env!("CARGO_PKG_VERSION")));
-        ui.label("作者: Gautown");
-        ui.label("许可证: MIT");
-        ui.label("这是一个功能强大的Windows驱动管理工具，旨在帮助用户自动扫描、识别、比较厂商服务器上的驱动版本，并下载安装最新驱动。");
+        ui.heading(t!("nav-about"));
+        ui.label(t!("about-title"));
+        ui.label(t!("about-version", version = env!("CARGO_PKG_VERSION")));
+        ui.label(t!("about-author", author = "Gautown"));
+        ui.label(t!("about-license", license = "MIT"));
+        ui.label(t!("about-description"));
     }
 
     fn start_hardware_scan(&mut self) {
-        self.is_scanning = true;
+        if !self.can_start(Operation::Scan) {
+            return;
+        }
         self.scan_progress = 0.0;
-        self.progress_text = "开始扫描...".to_string();
-        
-        // 直接执行扫描
-        let needs_refresh = {
-            if let Ok(mut core_guard) = self.core.lock() {
-                if let Some(ref mut core) = *core_guard {
-                    // 使用简单的回调函数
-                    let progress_callback = |_progress: crate::core::ScanProgress| {
-                        // 简单的回调，不修改UI状态
-                    };
-                    
-                    // 在当前线程中执行扫描
-                    match tokio::task::block_in_place(|| {
-                        tokio::runtime::Handle::current().block_on(async {
-                            core.scan_system(progress_callback).await
-                        })
-                    }) {
-                        Ok(result) => {
-                            println!("硬件扫描完成，发现 {} 个设备", result.devices.len());
-                            // 直接更新core中的扫描结果
-                            core.scan_result = Some(result);
-                            true // 需要刷新
-                        },
-                        Err(e) => {
-                            eprintln!("硬件扫描失败: {}", e);
-                            false
-                        }
-                    }
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
+        self.progress_text = t!("scan-starting");
+        
+        // 进度通过核心事件送回界面
+        self.spawn_with_core(Operation::Scan, |core| async move {
+            let mut core = core.write().await;
+            TaskOutput::Scanned(core.scan_system(|_| {}).await.map_err(|e| e.to_string()))
+        });
+    }
+
+    fn find_driver_updates(&mut self) {
+        self.spawn_with_core(Operation::FindUpdates, |core| async move {
+            let mut core = core.write().await;
+            TaskOutput::UpdatesFound(core.find_driver_updates().await.map_err(|e| e.to_string()))
+        });
+    }
+
+    fn update_all_drivers(&mut self) {
+        if !self.can_start(Operation::UpdateAll) {
+            return;
+        }
+        self.update_status.clear();
+        self.driver_updates.start_batch(&self.driver_updates.updatable_hardware_ids());
+        self.spawn_with_core(Operation::UpdateAll, |core| async move {
+            let core = core.read().await;
+            TaskOutput::UpdatesInstalled(core.update_all_drivers(DownloadPriority::User).await.map_err(|e| e.to_string()))
+        });
+    }
+
+    /// 把驱动加入核心的下载队列，由核心的工作池开始下载
+    fn queue_downloads(&mut self, drivers: Vec<crate::matcher::DriverInfo>) {
+        let Some(queue) = self.download_queue.as_ref() else {
+            return;
         };
+        let download_dir = std::path::PathBuf::from(&self.config.download_config.download_dir);
+        let mut queued = 0;
+        for driver in drivers {
+            let file_path = download_dir.join(driver.file_name()).to_string_lossy().to_string();
+            let url = driver.driver_url.clone();
+            match queue.add_task(driver, url, file_path) {
+                Ok(_) => queued += 1,
+                Err(e) => eprintln!("{}", t!("downloads-queue-failed", error = e)),
+            }
+        }
+        self.update_status = t!("downloads-queued", count = queued);
+    }
+
+    fn update_selected_drivers(&mut self, hardware_ids: Vec<String>) {
+        if !self.can_start(Operation::UpdateAll) {
+            return;
+        }
+        self.update_status.clear();
+        self.driver_updates.start_batch(&hardware_ids);
+        self.spawn_with_core(Operation::UpdateAll, |core| async move {
+            let core = core.read().await;
+            TaskOutput::UpdatesInstalled(core.update_selected_drivers(&hardware_ids, DownloadPriority::User).await.map_err(|e| e.to_string()))
+        });
+    }
+
+    /// 修改忽略和固定设置，保存配置并让核心重新标记候选
+    fn change_update_preferences(&mut self, action: DriverUpdateAction) {
+        if !self.can_start(Operation::Preferences) {
+            return;
+        }
+        let preferences = &mut self.config.update_preferences;
+        match action {
+            DriverUpdateAction::Ignore { hardware_id, version } => preferences.ignore(&hardware_id, &version),
+            DriverUpdateAction::Unignore(hardware_id) => preferences.unignore(&hardware_id),
+            DriverUpdateAction::Pin(hardware_id) => preferences.pin(&hardware_id),
+            DriverUpdateAction::Unpin(hardware_id) => preferences.unpin(&hardware_id),
+            DriverUpdateAction::UpdateSelected(_) | DriverUpdateAction::DownloadSelected(_) => return,
+        }
         
-        // 在锁外刷新扫描结果
-        if needs_refresh {
-            self.refresh_scan_results();
+        let config = self.config.clone();
+        self.spawn_with_core(Operation::Preferences, |core| async move {
+            let save = tokio::task::spawn_blocking({
+                let config = config.clone();
+                move || config.save_to_file(ConfigManager::default_config_path())
+            });
+            let mut core = core.write().await;
+            core.set_update_preferences(config.update_preferences);
+            match save.await {
+                Ok(Err(e)) => eprintln!("{}", t!("preferences-save-failed", error = e)),
+                Err(e) => eprintln!("{}", t!("preferences-save-failed", error = e)),
+                Ok(Ok(())) => {}
+            }
+            TaskOutput::CandidatesRefreshed(core.update_candidates.clone())
+        });
+    }
+
+    /// 保存配置，并让界面和核心使用新配置
+    fn apply_settings(&mut self, ctx: &egui::Context, config: ConfigManager) {
+        if self.tasks.is_running(Operation::ApplySettings) {
+            return;
+        }
+        self.settings_status.clear();
+        // 证书无法读取等问题在保存前提示，不保存无法使用的网络设置
+        if config.network_config != self.config.network_config {
+            if let Err(e) = crate::utils::set_network_config(&config.network_config) {
+                self.settings_status = t!("settings-save-failed", error = e);
+                return;
+            }
+        }
+        if config.scanner_config.include_hidden != self.config.scanner_config.include_hidden {
+            self.device_list.filter.show_hidden = config.scanner_config.include_hidden;
         }
+        // 下载队列和局域网缓存由核心按新配置更新，这里只重新启动提供驱动包的服务
+        let old = &self.config.download_config.peer_cache;
+        let new = &config.download_config.peer_cache;
+        if (new.serve, &new.listen_address) != (old.serve, &old.listen_address) {
+            self.peer_cache_server = None;
+            match start_peer_cache_server(new) {
+                Ok(server) => self.peer_cache_server = server,
+                Err(e) => self.settings_status = e,
+            }
+        }
+        // 语言和外观不需要等保存完成，下一帧即生效
+        crate::i18n::set_locale(config.app_config.locale());
+        if config.appearance_config.fonts != self.config.appearance_config.fonts {
+            self.font_warning = font_warning(apply_fonts(ctx, &config.appearance_config.fonts));
+        }
+        if config.appearance_config.ui_scale != self.config.appearance_config.ui_scale {
+            ctx.set_zoom_factor(config.appearance_config.ui_scale);
+        }
+        if let Some(Err(e)) = self.tray.as_mut().map(SystemTray::refresh) {
+            eprintln!("{}", t!("tray-update-failed", error = e));
+        }
+        self.config = config.clone();
+
+        // 并发数和时间段限制不必等核心，立即对下载队列生效
+        if let Some(queue) = &self.download_queue {
+            queue.apply_config(&config.download_config);
+        }
+
+        let core = self.core.clone();
+        let notifier = self.tasks.notifier();
+        self.tasks.spawn(Operation::ApplySettings, async move {
+            let save = tokio::task::spawn_blocking({
+                let config = config.clone();
+                move || config.save_to_file(ConfigManager::default_config_path())
+            });
+            let saved = match save.await {
+                Ok(result) => result.map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            // 核心正在执行更新等操作时不等待，操作结束后在后台应用
+            let deferred = match core {
+                Some(core) => match core.clone().try_write_owned() {
+                    Ok(core) => {
+                        apply_core_config(core, &config, &notifier);
+                        false
+                    }
+                    Err(_) => {
+                        tokio::spawn(async move { apply_core_config(core.write_owned().await, &config, &notifier) });
+                        true
+                    }
+                },
+                None => false,
+            };
+            TaskOutput::SettingsApplied { saved, deferred }
+        });
+    }
+
+    fn open_backup_package(&mut self) {
+        self.restore_selection.clear();
+        let package_path = self.restore_path.trim().to_string();
+        self.spawn_with_core(Operation::OpenRestorePlan, |core| async move {
+            let plan = core.read().await.open_backup_package(&package_path).map_err(|e| e.to_string());
+            TaskOutput::RestorePlanOpened { plan, from_snapshot: false }
+        });
+    }
+
+    fn backup_current_drivers(&mut self) {
+        self.spawn_with_core(Operation::Backup, |core| async move {
+            let core = core.read().await;
+            TaskOutput::BackupFinished(core.backup_drivers(&t!("restore-manual-backup")).await.map_err(|e| e.to_string()))
+        });
+    }
+
+    fn refresh_history(&mut self) {
+        self.spawn_with_core(Operation::History, |core| async move {
+            let history = core.read().await.installation_history(&LogFilter::default());
+            TaskOutput::HistoryLoaded(history.map_err(|e| e.to_string()))
+        });
+    }
+
+    fn revert_change(&mut self, log_id: String) {
+        if !self.can_start(Operation::RevertChange) {
+            return;
+        }
+        self.history_status.clear();
+        self.spawn_with_core(Operation::RevertChange, |core| async move {
+            let result = core.read().await.rollback_change(&log_id).await;
+            TaskOutput::ChangeReverted(result.map_err(|e| e.to_string()))
+        });
+    }
+
+    fn refresh_backup_snapshots(&mut self) {
+        self.spawn_with_core(Operation::ListSnapshots, |core| async move {
+            TaskOutput::SnapshotsListed(core.read().await.list_backup_snapshots().map_err(|e| e.to_string()))
+        });
+    }
+
+    fn open_backup_snapshot(&mut self, snapshot_id: &str) {
+        self.restore_selection.clear();
+        let snapshot_id = snapshot_id.to_string();
+        self.spawn_with_core(Operation::OpenRestorePlan, |core| async move {
+            let plan = core.read().await.open_backup_snapshot(&snapshot_id).map_err(|e| e.to_string());
+            TaskOutput::RestorePlanOpened { plan, from_snapshot: true }
+        });
+    }
+
+    fn restore_selected_drivers(&mut self) {
+        let package_path = match self.restore_plan {
+            Some(ref plan) => plan.package_path.clone(),
+            None => return,
+        };
+        let entry_ids: Vec<String> = self.restore_selection.drain().collect();
         
-        self.scan_progress = 100.0;
-        self.progress_text = "扫描完成".to_string();
-        self.is_scanning = false;
+        self.spawn_with_core(Operation::Restore, |core| async move {
+            let core = core.read().await;
+            TaskOutput::RestoreFinished(core.restore_drivers(&package_path, &entry_ids).await.map_err(|e| e.to_string()))
+        });
     }
 
-    fn find_driver_updates(&mut self) {
-        // 查找驱动更新
-        if let Ok(mut core_guard) = self.core.lock() {
-            if let Some(ref mut core) = *core_guard {
-                match tokio::task::block_in_place(|| {
-                    tokio::runtime::Handle::current().block_on(async {
-                        core.find_driver_updates().await
-                    })
-                }) {
-                    Ok(candidates) => {
-                        println!("找到 {} 个驱动更新候选", candidates.len());
-                        
-                        // 更新UI显示
-                        let mut result = String::new();
-                        result.push_str(&format!("找到 {} 个驱动更新:\n\n", candidates.len()));
-                        
-                        for (i, candidate) in candidates.iter().enumerate() {
-                            let hw_info = &candidate.hardware_info;
-                            if let Some(ref driver) = candidate.matched_driver {
-                                result.push_str(&format!(
-                                    "{}. {} ({})\n   当前版本: {}\n   新版本: {}\n   下载链接: {}\n\n",
-                                    i + 1,
-                                    hw_info.device_name,
-                                    hw_info.manufacturer,
-                                    candidate.current_version,
-                                    driver.driver_version,
-                                    driver.driver_url
-                                ));
-                            } else {
-                                result.push_str(&format!(
-                                    "{}. {} ({}) - 未找到更新\n\n",
-                                    i + 1,
-                                    hw_info.device_name,
-                                    hw_info.manufacturer
-                                ));
-                            }
+    fn render_recovery_window(&mut self, ctx: &egui::Context) {
+        if self.pending_transactions.is_empty() && self.recovery_status.is_empty() {
+            return;
+        }
+
+        let mut action: Option<(RecoveryAction, String)> = None;
+        let mut close = false;
+        egui::Window::new(t!("recovery-title"))
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label(t!("recovery-intro"));
+                ui.separator();
+                
+                for transaction in &self.pending_transactions {
+                    ui.group(|ui| {
+                        let kind = match transaction.kind {
+                            TransactionKind::Update => t!("recovery-kind-update"),
+                            TransactionKind::Install { .. } => t!("recovery-kind-install"),
+                            TransactionKind::Restore { .. } => t!("recovery-kind-restore"),
+                        };
+                        ui.label(t!("recovery-transaction", description = transaction.description, kind = kind,
+                            started_at = transaction.started_at));
+                        for device in transaction.pending_devices() {
+                            let state = match (device.in_progress, device.completed.last()) {
+                                (Some(step), _) => t!("recovery-interrupted-at", step = step.label()),
+                                (None, Some(step)) => t!("recovery-completed-step", step = step.label()),
+                                (None, None) => t!("recovery-not-started"),
+                            };
+                            ui.label(format!("  {} - {}", device.hardware_id, state));
                         }
                         
-                        self.update_candidates = result;
-                    },
-                    Err(e) => {
-                        eprintln!("查找驱动更新失败: {}", e);
-                        self.update_candidates = format!("查找驱动更新失败: {}", e);
+                        ui.horizontal(|ui| {
+                            if ui.button(t!("recovery-resume")).clicked() {
+                                action = Some((RecoveryAction::Resume, transaction.id.clone()));
+                            }
+                            if ui.add_enabled(transaction.rollback_id.is_some(), egui::Button::new(t!("recovery-rollback"))).clicked() {
+                                action = Some((RecoveryAction::Rollback, transaction.id.clone()));
+                            }
+                            if ui.button(t!("recovery-discard")).clicked() {
+                                action = Some((RecoveryAction::Discard, transaction.id.clone()));
+                            }
+                        });
+                    });
+                }
+                
+                if !self.recovery_status.is_empty() {
+                    ui.separator();
+                    ui.label(&self.recovery_status);
+                    if self.pending_transactions.is_empty() && ui.button(t!("action-close")).clicked() {
+                        close = true;
                     }
                 }
-            }
+            });
+
+        if let Some((action, transaction_id)) = action {
+            self.handle_recovery_action(action, &transaction_id);
+        }
+        if close {
+            self.recovery_status.clear();
         }
     }
 
-    fn update_all_drivers(&mut self) {
-        // 由于方法需要可变引用，我们暂时跳过
-        // 稍后通过其他方式实现
-        println!("更新所有驱动...");
-        self.download_progress = "驱动更新功能待实现".to_string();
-    }
-
-    fn refresh_scan_results(&mut self) {
-        if let Ok(core_guard) = self.core.lock() {
-            if let Some(ref core) = *core_guard {
-                if let Some(ref scan_result) = core.scan_result {
-                    let mut result = String::new();
-                    result.push_str(&format!("扫描到 {} 个设备:\n", scan_result.devices.len()));
-                    for (i, device) in scan_result.devices.iter().enumerate() {
-                        result.push_str(&format!(
-                            "{}. {} - {}\n   硬件ID: {}\n   驱动版本: {}\n",
-                            i + 1,
-                            device.device_name,
-                            device.manufacturer,
-                            device.hardware_id,
-                            device.driver_version
-                        ));
+    fn handle_recovery_action(&mut self, action: RecoveryAction, transaction_id: &str) {
+        let transaction_id = transaction_id.to_string();
+        self.spawn_with_core(Operation::Recovery, |core| async move {
+            // 继续事务时可能需要先扫描和查找更新，其他动作只读取核心
+            let result = match action {
+                RecoveryAction::Resume => core.write().await.resume_install_transaction(&transaction_id).await.map(|results| {
+                    let succeeded = results.iter().filter(|r| r.success).count();
+                    t!("recovery-resumed", succeeded = succeeded, failed = results.len() - succeeded)
+                }),
+                RecoveryAction::Rollback => core.read().await.rollback_install_transaction(&transaction_id).await.map(|report| {
+                    if report.is_success() {
+                        t!("recovery-rolled-back")
+                    } else {
+                        t!("recovery-rollback-partial")
+                    }
+                }),
+                RecoveryAction::Discard => core.read().await.discard_install_transaction(&transaction_id)
+                    .map(|_| t!("recovery-discarded")),
+            };
+            
+            TaskOutput::RecoveryFinished {
+                status: match result {
+                    Ok(status) => status,
+                    Err(e) => t!("recovery-failed", error = e),
+                },
+                pending: core.read().await.incomplete_install_transactions().unwrap_or_default(),
+            }
+        });
+    }
+
+    /// 处理后台任务送回的结果
+    fn apply_task_output(&mut self, output: TaskOutput) {
+        match output {
+            TaskOutput::Initialized(Ok(ready)) => {
+                let ready = *ready;
+                self.system_info = ready.system_info;
+                self.system_summary = ready.system_summary;
+                self.pending_transactions = ready.pending_transactions;
+                self.cancel = Some(ready.cancel);
+                self.download_queue = Some(ready.download_queue);
+                self.core = Some(Arc::new(RwLock::new(ready.core)));
+                self.start_scheduled_updates();
+                self.refresh_history();
+            }
+            TaskOutput::Initialized(Err(e)) => {
+                eprintln!("{}", t!("init-failed", error = e));
+                self.init_error = Some(e);
+            }
+            TaskOutput::Event(event) => self.handle_core_event(event),
+            TaskOutput::Scanned(result) => {
+                match result {
+                    Ok(result) => {
+                        println!("{}", t!("scan-finished", count = result.devices.len()));
+                        self.device_list.set_devices(&result.devices);
+                        self.scan_result = Some(result);
+                        self.progress_text = t!("scan-done");
+                    }
+                    Err(e) => {
+                        let message = t!("scan-failed", error = e);
+                        eprintln!("{}", message);
+                        self.progress_text = message;
                     }
-                    self.scan_results = result;
+                }
+            }
+            TaskOutput::UpdatesFound(Ok(candidates)) => {
+                println!("{}", t!("updates-found", count = candidates.len()));
+                self.device_list.apply_update_candidates(&candidates);
+                self.driver_updates.set_candidates(&candidates, &self.config.update_preferences);
+                self.update_status = if candidates.is_empty() {
+                    t!("updates-none-found")
                 } else {
-                    self.scan_results = "未进行扫描".to_string();
+                    t!("updates-found", count = candidates.len())
+                };
+            }
+            TaskOutput::UpdatesFound(Err(e)) => {
+                self.update_status = t!("updates-find-failed", error = e);
+                eprintln!("{}", self.update_status);
+            }
+            TaskOutput::UpdatesInstalled(result) => {
+                self.driver_updates.finish_batch();
+                self.update_status = match result {
+                    Ok(results) if results.is_empty() => t!("updates-nothing-to-update"),
+                    Ok(results) => summarize_results(&t!("updates-finished"), &results),
+                    Err(e) => t!("updates-failed", error = e),
+                };
+                self.refresh_history();
+            }
+            TaskOutput::CandidatesRefreshed(candidates) => {
+                self.device_list.apply_update_candidates(&candidates);
+                self.driver_updates.set_candidates(&candidates, &self.config.update_preferences);
+            }
+            TaskOutput::BackupFinished(result) => {
+                self.restore_status = match result {
+                    Ok(snapshot) => t!("restore-backup-finished", id = snapshot.id, count = snapshot.driver_count),
+                    Err(e) => t!("restore-backup-failed", error = e),
+                };
+                self.refresh_backup_snapshots();
+            }
+            TaskOutput::SnapshotsListed(result) => match result {
+                Ok(snapshots) => self.backup_snapshots = snapshots,
+                Err(e) => self.restore_status = t!("restore-list-failed", error = e),
+            },
+            TaskOutput::RestorePlanOpened { plan, from_snapshot } => match plan {
+                Ok(plan) => {
+                    if from_snapshot {
+                        self.restore_path = plan.package_path.clone();
+                    }
+                    self.restore_status = t!("restore-plan-count", count = plan.candidates.len());
+                    self.restore_plan = Some(plan);
+                }
+                Err(e) => {
+                    self.restore_plan = None;
+                    self.restore_status = t!("restore-open-failed", error = e);
                 }
+            },
+            TaskOutput::RestoreFinished(result) => {
+                self.restore_status = match result {
+                    Ok(results) => summarize_results(&t!("restore-finished"), &results),
+                    Err(e) => t!("restore-failed", error = e),
+                };
+                self.refresh_history();
+            }
+            TaskOutput::RecoveryFinished { status, pending } => {
+                self.recovery_status = status;
+                self.pending_transactions = pending;
+                self.refresh_history();
+            }
+            TaskOutput::HistoryLoaded(result) => match result {
+                Ok(entries) => self.history.set_entries(entries),
+                Err(e) => self.history_status = t!("history-load-failed", error = e),
+            },
+            TaskOutput::ChangeReverted(result) => {
+                self.history_status = match result {
+                    Ok(result) if result.success && result.reboot_required => t!("history-reverted-reboot", message = result.message),
+                    Ok(result) if result.success => t!("history-reverted", message = result.message),
+                    Ok(result) => t!("history-revert-failed", error = result.message),
+                    Err(e) => t!("history-revert-failed", error = e),
+                };
+                self.refresh_history();
+            }
+            TaskOutput::SettingsApplied { saved, deferred } => {
+                self.settings_status = match saved {
+                    Ok(()) if deferred => t!("settings-saved-deferred"),
+                    Ok(()) => t!("settings-saved"),
+                    Err(e) => t!("settings-save-failed", error = e),
+                };
             }
         }
     }
+
+    /// 按当前操作刷新托盘状态，并执行托盘菜单中点击的命令
+    fn sync_tray(&mut self, ctx: &egui::Context) {
+        let state = TrayState::from_activity(
+            self.tasks.is_running(Operation::Scan),
+            self.tasks.is_running(Operation::UpdateAll) || self.tasks.is_running(Operation::Restore),
+            self.driver_updates.updatable_hardware_ids().len(),
+            self.reboot_required,
+        );
+        let Some(tray) = self.tray.as_mut() else {
+            return;
+        };
+        let commands = tray.set_state(state).and_then(|_| tray.poll_commands()).unwrap_or_else(|e| {
+            eprintln!("{}", t!("tray-update-failed", error = e));
+            Vec::new()
+        });
+        for command in commands {
+            self.handle_tray_command(ctx, command);
+        }
+    }
+
+    fn handle_tray_command(&mut self, ctx: &egui::Context, command: TrayCommand) {
+        match command {
+            TrayCommand::ShowWindow => self.show_window(ctx),
+            TrayCommand::OpenSettings => {
+                self.current_view = View::Settings;
+                self.show_window(ctx);
+            }
+            TrayCommand::Core(AppCommand::ScanHardware) => self.start_hardware_scan(),
+            TrayCommand::Core(AppCommand::CheckUpdates) => self.find_driver_updates(),
+            TrayCommand::Core(AppCommand::InstallUpdates(_)) => self.update_all_drivers(),
+            TrayCommand::Core(AppCommand::Cancel) => {
+                if let Some(cancel) = &self.cancel {
+                    cancel.cancel();
+                }
+            }
+            TrayCommand::Core(AppCommand::Shutdown) => {
+                self.exiting = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+            TrayCommand::Core(_) => {}
+        }
+    }
+
+    /// 启用了最小化到托盘且托盘可用时，最小化或关闭主窗口改为隐藏
+    fn handle_minimize_to_tray(&mut self, ctx: &egui::Context) {
+        let tray_available = self.tray.as_ref().is_some_and(SystemTray::is_available);
+        if !self.config.app_config.minimize_to_tray || !tray_available || self.exiting {
+            return;
+        }
+        let (close_requested, minimized) = ctx.input(|i| (i.viewport().close_requested(), i.viewport().minimized));
+        if close_requested {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+        }
+        if (close_requested || minimized == Some(true)) && !self.hidden_to_tray {
+            self.hidden_to_tray = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+        }
+        // 窗口隐藏后没有输入事件，定时重绘以处理托盘菜单
+        if self.hidden_to_tray {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+    }
+
+    fn show_window(&mut self, ctx: &egui::Context) {
+        self.hidden_to_tray = false;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+    }
+
+    fn handle_core_event(&mut self, event: CoreEvent) {
+        match event {
+            CoreEvent::ScanStarted => {
+                self.scan_progress = 0.0;
+                self.progress_text = t!("scan-starting");
+            }
+            CoreEvent::ScanProgress { progress } => {
+                self.scan_progress = progress.progress / 100.0;
+                self.progress_text = progress.current_step;
+            }
+            CoreEvent::DownloadProgress { .. } | CoreEvent::InstallStep { .. } => {
+                self.driver_updates.handle_event(&event);
+            }
+            CoreEvent::RebootRequired { .. } => {
+                self.driver_updates.handle_event(&event);
+                self.reboot_required = true;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 在后台创建并初始化核心控制器，并把核心事件转发给界面
+async fn initialize_core(config: ConfigManager, notifier: Notifier<TaskOutput>) -> anyhow::Result<ReadyCore> {
+    // 使用内存路径而不是实际的数据库文件，避免文件锁定问题
+    let mut core = DriverUpdaterCore::new(":memory:", &config).await?;
+    if let Err(e) = core.initialize().await {
+        eprintln!("{}", t!("init-failed", error = e));
+    }
+    
+    // 检查上次运行中断的安装事务
+    let pending_transactions = core.incomplete_install_transactions().unwrap_or_else(|e| {
+        eprintln!("{}", t!("journal-read-failed", error = e));
+        Vec::new()
+    });
+    
+    let mut events = core.subscribe(EventFilter::all());
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            if !notifier.send(TaskOutput::Event(event)) {
+                break;
+            }
+        }
+    });
+    
+    Ok(ReadyCore {
+        system_info: core.system_info.clone(),
+        system_summary: core.get_system_summary(),
+        pending_transactions,
+        cancel: core.cancel_handle(),
+        download_queue: core.download_queue().clone(),
+        core,
+    })
+}
+
+/// 让核心使用新配置，并把按新配置重新标记的候选送回界面
+fn apply_core_config(mut core: OwnedRwLockWriteGuard<DriverUpdaterCore>, config: &ConfigManager, notifier: &Notifier<TaskOutput>) {
+    core.apply_config(config);
+    notifier.send(TaskOutput::CandidatesRefreshed(core.update_candidates.clone()));
+}
+
+/// 配置了向局域网提供驱动包时启动服务
+fn start_peer_cache_server(config: &PeerCacheConfig) -> Result<Option<PeerCacheServer>, String> {
+    PeerCacheServer::start_from_config(config)
+        .map_err(|e| t!("peer-cache-serve-failed", address = config.listen_address, error = e))
+}
+
+fn font_warning(missing: Vec<String>) -> Option<String> {
+    (!missing.is_empty()).then(|| t!("settings-missing-fonts", fonts = missing.join(&t!("list-separator"))))
+}
+
+fn summarize_results(title: &str, results: &[InstallationResult]) -> String {
+    let succeeded = results.iter().filter(|r| r.success).count();
+    let mut status = t!("results-summary", title = title, succeeded = succeeded, failed = results.len() - succeeded);
+    status.push('\n');
+    for result in results {
+        status.push_str(&format!("{}\n", result.message));
+    }
+    status
 }
 
 impl eframe::App for HamsterDriveApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        // 取出后台任务的结果，新结果到达时会请求重绘
+        for output in self.tasks.poll() {
+            self.apply_task_output(output);
+        }
+        // 跟随系统时系统切换深浅色后下一帧更新
+        sync_theme(ctx, self.config.appearance_config.theme, frame.info().system_theme);
+        self.sync_tray(ctx);
+        self.handle_minimize_to_tray(ctx);
+        
         // 创建左右分栏布局
         egui::SidePanel::left("sidebar")
             .min_width(150.0)
@@ -444,19 +1208,37 @@ impl eframe::App for HamsterDriveApp {
                 self.render_sidebar(ui);
             });
         
-
+        // 状态栏显示所有正在运行的操作
+        let running: Vec<Operation> = self.tasks.running().collect();
+        let downloading = self.download_queue.as_ref().is_some_and(|queue| queue.get_active_download_count() > 0);
+        if !running.is_empty() || downloading {
+            egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for operation in running {
+                        ui.spinner();
+                        ui.label(operation.label());
+                    }
+                    if downloading {
+                        ui.spinner();
+                        ui.label(t!("op-downloads"));
+                    }
+                });
+            });
+        }
         
         egui::CentralPanel::default().show(ctx, |ui| {
             match self.current_view {
                 View::SystemInfo => self.render_dashboard(ui),
                 View::HardwareScan => self.render_hardware_scan(ui),
                 View::DriverUpdates => self.render_driver_updates(ui),
+                View::Downloads => self.render_downloads(ui),
+                View::Restore => self.render_restore(ui),
+                View::History => self.render_history(ui),
                 View::Settings => self.render_settings(ui),
                 View::About => self.render_about(ui),
             }
         });
         
-        // 定期更新UI
-        ctx.request_repaint();
+        self.render_recovery_window(ctx);
     }
-}
\ No newline at end of file
+}
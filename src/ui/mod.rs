@@ -1,2 +1,12 @@
+mod components;
 mod gui;
-pub use gui::*;
\ No newline at end of file
+mod tasks;
+mod theme;
+mod tray;
+mod views;
+pub use components::*;
+pub use gui::*;
+pub use tasks::*;
+pub use theme::*;
+pub use tray::*;
+pub use views::*;
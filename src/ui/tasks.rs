@@ -0,0 +1,189 @@
+//! 界面后台任务
+//!
+//! 耗时的核心操作放到 tokio 任务中执行，结果通过通道送回界面线程并触发重绘，
+//! 界面每帧只取出已完成的结果，不会等待任何操作。
+
+use eframe::egui;
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::mpsc;
+use crate::t;
+
+/// 界面上可以同时显示状态的后台操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Initialize,
+    Scan,
+    FindUpdates,
+    UpdateAll,
+    Preferences,
+    ApplySettings,
+    Backup,
+    ListSnapshots,
+    OpenRestorePlan,
+    Restore,
+    Recovery,
+    History,
+    RevertChange,
+}
+
+impl Operation {
+    pub fn label(&self) -> String {
+        let key = match self {
+            Operation::Initialize => "op-initialize",
+            Operation::Scan => "op-scan",
+            Operation::FindUpdates => "op-find-updates",
+            Operation::UpdateAll => "op-update-all",
+            Operation::Preferences => "op-preferences",
+            Operation::ApplySettings => "op-apply-settings",
+            Operation::Backup => "op-backup",
+            Operation::ListSnapshots => "op-list-snapshots",
+            Operation::OpenRestorePlan => "op-open-restore-plan",
+            Operation::Restore => "op-restore",
+            Operation::Recovery => "op-recovery",
+            Operation::History => "op-history",
+            Operation::RevertChange => "op-revert-change",
+        };
+        t!(key)
+    }
+
+    /// 是否可以通过核心的 CancelHandle 取消
+    pub fn cancellable(&self) -> bool {
+        matches!(
+            self,
+            Operation::Scan | Operation::FindUpdates | Operation::UpdateAll | Operation::Restore | Operation::Recovery
+        )
+    }
+}
+
+enum Envelope<M> {
+    Finished(Operation, Option<M>),
+    Notify(M),
+}
+
+/// 从后台向界面发送消息，发送后请求重绘
+pub struct Notifier<M> {
+    sender: mpsc::Sender<Envelope<M>>,
+    ctx: egui::Context,
+}
+
+impl<M> Clone for Notifier<M> {
+    fn clone(&self) -> Self {
+        Self { sender: self.sender.clone(), ctx: self.ctx.clone() }
+    }
+}
+
+impl<M> Notifier<M> {
+    /// 界面已关闭时返回 false
+    pub fn send(&self, message: M) -> bool {
+        let sent = self.sender.send(Envelope::Notify(message)).is_ok();
+        self.ctx.request_repaint();
+        sent
+    }
+
+    fn finish(&self, operation: Operation, message: Option<M>) {
+        let _ = self.sender.send(Envelope::Finished(operation, message));
+        self.ctx.request_repaint();
+    }
+}
+
+/// 后台任务调度，同一种操作同时只运行一个
+pub struct TaskRunner<M> {
+    notifier: Notifier<M>,
+    receiver: mpsc::Receiver<Envelope<M>>,
+    running: HashSet<Operation>,
+}
+
+impl<M: Send + 'static> TaskRunner<M> {
+    pub fn new(ctx: &egui::Context) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            notifier: Notifier { sender, ctx: ctx.clone() },
+            receiver,
+            running: HashSet::new(),
+        }
+    }
+
+    pub fn notifier(&self) -> Notifier<M> {
+        self.notifier.clone()
+    }
+
+    /// 在后台运行操作，该操作已在运行时返回 false
+    pub fn spawn<F>(&mut self, operation: Operation, future: F) -> bool
+    where
+        F: Future<Output = M> + Send + 'static,
+    {
+        if !self.running.insert(operation) {
+            return false;
+        }
+        let notifier = self.notifier.clone();
+        tokio::spawn(async move {
+            // 任务 panic 时也要让界面知道操作已结束
+            match tokio::spawn(future).await {
+                Ok(message) => notifier.finish(operation, Some(message)),
+                Err(e) => {
+                    tracing::error!("后台任务异常退出: {:?} {}", operation, e);
+                    notifier.finish(operation, None);
+                }
+            }
+        });
+        true
+    }
+
+    pub fn is_running(&self, operation: Operation) -> bool {
+        self.running.contains(&operation)
+    }
+
+    /// 正在运行的操作
+    pub fn running(&self) -> impl Iterator<Item = Operation> + '_ {
+        self.running.iter().copied()
+    }
+
+    /// 取出所有已送达的消息，不会阻塞
+    pub fn poll(&mut self) -> Vec<M> {
+        let mut messages = Vec::new();
+        while let Ok(envelope) = self.receiver.try_recv() {
+            match envelope {
+                Envelope::Finished(operation, message) => {
+                    self.running.remove(&operation);
+                    messages.extend(message);
+                }
+                Envelope::Notify(message) => messages.push(message),
+            }
+        }
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_tasks_report_back_without_blocking() {
+        let mut runner = TaskRunner::new(&egui::Context::default());
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+        assert!(runner.spawn(Operation::Scan, async move {
+            let _ = release_rx.await;
+            "scanned"
+        }));
+        // 同一操作不会重复启动
+        assert!(!runner.spawn(Operation::Scan, async { "again" }));
+        assert!(runner.is_running(Operation::Scan));
+        assert!(runner.poll().is_empty());
+
+        runner.notifier().send("progress");
+        release_tx.send(()).unwrap();
+        let mut messages = Vec::new();
+        for _ in 0..100 {
+            messages.extend(runner.poll());
+            if !runner.is_running(Operation::Scan) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(messages, vec!["progress", "scanned"]);
+        assert!(!runner.is_running(Operation::Scan));
+    }
+}
@@ -0,0 +1,254 @@
+//! 界面主题和字体
+//!
+//! 字体从系统字体目录加载：正文字体后面依次接后备字体和平台自带的中文字体，
+//! 最后是 egui 内置字体；标题使用单独的粗体字族。
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use eframe::egui::{self, FontData, FontDefinitions, FontFamily, FontId, TextStyle};
+use crate::config::{AppearanceConfig, FontConfig, ThemePreference};
+
+/// 标题使用的字族名称
+pub const BOLD_FAMILY: &str = "bold";
+
+// 未配置正文字体时按顺序尝试，覆盖 Windows、macOS 和常见 Linux 发行版
+const DEFAULT_REGULAR: [&str; 5] = [
+    "msyh.ttc",
+    "PingFang.ttc",
+    "NotoSansCJK-Regular.ttc",
+    "NotoSansSC-Regular.otf",
+    "wqy-microhei.ttc",
+];
+const DEFAULT_BOLD: [&str; 4] = [
+    "msyhbd.ttc",
+    "PingFang.ttc",
+    "NotoSansCJK-Bold.ttc",
+    "NotoSansSC-Bold.otf",
+];
+// 正文字体缺少的符号
+const DEFAULT_FALLBACKS: [&str; 2] = ["seguisym.ttf", "simsun.ttc"];
+
+// 查找字体时进入子目录的最大层数，Linux 的字体按厂商分在多层目录中
+const MAX_FONT_DIR_DEPTH: usize = 3;
+
+/// 系统字体目录，不存在的目录已排除
+pub fn font_directories() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(windir) = std::env::var_os("WINDIR") {
+        dirs.push(Path::new(&windir).join("Fonts"));
+    }
+    if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+        dirs.push(Path::new(&local).join("Microsoft").join("Windows").join("Fonts"));
+    }
+    dirs.extend(["/System/Library/Fonts", "/Library/Fonts", "/usr/share/fonts", "/usr/local/share/fonts"].map(PathBuf::from));
+    if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(Path::new(&home).join(".local/share/fonts"));
+        dirs.push(Path::new(&home).join("Library/Fonts"));
+    }
+    dirs.retain(|dir| dir.is_dir());
+    dirs
+}
+
+/// 系统中可选的字体文件名，按名称排序
+pub fn available_fonts() -> Vec<String> {
+    let mut names = BTreeSet::new();
+    for dir in font_directories() {
+        visit_fonts(&dir, 0, &mut |path| {
+            if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+                names.insert(name.to_string());
+            }
+            false
+        });
+    }
+    names.into_iter().collect()
+}
+
+/// 按完整路径或文件名查找字体，文件名不区分大小写
+pub fn find_font(name: &str) -> Option<PathBuf> {
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let path = Path::new(name);
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    let mut found = None;
+    for dir in font_directories() {
+        visit_fonts(&dir, 0, &mut |candidate| {
+            let matched = candidate.file_name()
+                .and_then(|file| file.to_str())
+                .is_some_and(|file| file.eq_ignore_ascii_case(name));
+            if matched {
+                found = Some(candidate.to_path_buf());
+            }
+            matched
+        });
+        if found.is_some() {
+            break;
+        }
+    }
+    found
+}
+
+// 遍历字体文件，visit 返回 true 时停止
+fn visit_fonts(dir: &Path, depth: usize, visit: &mut dyn FnMut(&Path) -> bool) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth < MAX_FONT_DIR_DEPTH && visit_fonts(&path, depth + 1, visit) {
+                return true;
+            }
+            continue;
+        }
+        let is_font = path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ["ttf", "otf", "ttc"].iter().any(|font| ext.eq_ignore_ascii_case(font)));
+        if is_font && visit(&path) {
+            return true;
+        }
+    }
+    false
+}
+
+/// 字体配置解析出的文件，按使用顺序排列
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontChain {
+    pub regular: Vec<PathBuf>,
+    pub bold: Vec<PathBuf>,
+    /// 配置了但找不到的字体
+    pub missing: Vec<String>,
+}
+
+impl FontChain {
+    pub fn resolve(config: &FontConfig) -> Self {
+        Self::resolve_with(config, find_font)
+    }
+
+    fn resolve_with(config: &FontConfig, find: impl Fn(&str) -> Option<PathBuf>) -> Self {
+        let mut missing = Vec::new();
+        // 配置的字体找不到时记录下来，再按默认字体处理
+        let mut configured = |name: &str| {
+            let path = find(name);
+            if path.is_none() && !name.trim().is_empty() {
+                missing.push(name.to_string());
+            }
+            path
+        };
+        let regular = configured(&config.regular);
+        let bold = configured(&config.bold);
+        let fallbacks: Vec<PathBuf> = config.fallbacks.iter().filter_map(|name| configured(name)).collect();
+
+        let first_found = |names: &[&str]| names.iter().find_map(|name| find(name));
+        let regular = regular.or_else(|| first_found(&DEFAULT_REGULAR));
+        let bold = bold.or_else(|| first_found(&DEFAULT_BOLD));
+        let builtin = DEFAULT_FALLBACKS.iter().chain(&DEFAULT_REGULAR).filter_map(|name| find(name));
+
+        let mut chain = FontChain { missing, ..Default::default() };
+        for path in regular.into_iter().chain(fallbacks).chain(builtin) {
+            if !chain.regular.contains(&path) {
+                chain.regular.push(path);
+            }
+        }
+        // 粗体缺字时使用正文字体链
+        for path in bold.into_iter().chain(chain.regular.clone()) {
+            if !chain.bold.contains(&path) {
+                chain.bold.push(path);
+            }
+        }
+        chain
+    }
+
+    /// 读取字体文件，读取失败的字体记入 missing
+    pub fn font_definitions(&mut self) -> FontDefinitions {
+        let mut fonts = FontDefinitions::default();
+        let mut loaded = Vec::new();
+        for path in self.regular.iter().chain(&self.bold) {
+            let key = path.to_string_lossy().to_string();
+            if loaded.contains(&key) || self.missing.contains(&key) {
+                continue;
+            }
+            match std::fs::read(path) {
+                Ok(data) => {
+                    fonts.font_data.insert(key.clone(), FontData::from_owned(data));
+                    loaded.push(key);
+                }
+                Err(_) => self.missing.push(key),
+            }
+        }
+
+        let keys = |paths: &[PathBuf]| -> Vec<String> {
+            paths.iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .filter(|key| loaded.contains(key))
+                .collect()
+        };
+        let regular = keys(&self.regular);
+        let bold = keys(&self.bold);
+
+        let builtin_proportional = fonts.families.get(&FontFamily::Proportional).cloned().unwrap_or_default();
+        let builtin_monospace = fonts.families.get(&FontFamily::Monospace).cloned().unwrap_or_default();
+        // 等宽字体保留内置的等宽字体在最前面，中文再从正文字体中取
+        fonts.families.insert(FontFamily::Proportional, [regular.clone(), builtin_proportional.clone()].concat());
+        fonts.families.insert(FontFamily::Monospace, [builtin_monospace, regular].concat());
+        fonts.families.insert(FontFamily::Name(BOLD_FAMILY.into()), [bold, builtin_proportional].concat());
+        fonts
+    }
+}
+
+/// 加载字体并让标题使用粗体，返回找不到或无法读取的字体
+pub fn apply_fonts(ctx: &egui::Context, config: &FontConfig) -> Vec<String> {
+    let mut chain = FontChain::resolve(config);
+    let fonts = chain.font_definitions();
+    ctx.set_fonts(fonts);
+
+    let mut style = (*ctx.style()).clone();
+    let heading_size = style.text_styles.get(&TextStyle::Heading).map_or(18.0, |font| font.size);
+    style.text_styles.insert(TextStyle::Heading, FontId::new(heading_size, FontFamily::Name(BOLD_FAMILY.into())));
+    ctx.set_style(style);
+    chain.missing
+}
+
+/// 按偏好和系统主题切换浅色或深色外观，已是目标外观时不做修改
+pub fn sync_theme(ctx: &egui::Context, preference: ThemePreference, system_theme: Option<eframe::Theme>) {
+    let dark = match preference {
+        ThemePreference::System => system_theme != Some(eframe::Theme::Light),
+        ThemePreference::Light => false,
+        ThemePreference::Dark => true,
+    };
+    if ctx.style().visuals.dark_mode != dark {
+        ctx.set_visuals(if dark { egui::Visuals::dark() } else { egui::Visuals::light() });
+    }
+}
+
+/// 应用外观配置中的主题和缩放，字体单独用 apply_fonts 加载
+pub fn apply_appearance(ctx: &egui::Context, config: &AppearanceConfig, system_theme: Option<eframe::Theme>) {
+    sync_theme(ctx, config.theme, system_theme);
+    ctx.set_zoom_factor(config.ui_scale);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_font_chain() {
+        let installed = ["msyh.ttc", "msyhbd.ttc", "seguisym.ttf", "custom.ttf"];
+        let find = |name: &str| installed.contains(&name).then(|| PathBuf::from(name));
+        let config = FontConfig {
+            regular: "custom.ttf".to_string(),
+            bold: "missing-bold.ttf".to_string(),
+            fallbacks: vec!["nope.otf".to_string()],
+        };
+
+        let chain = FontChain::resolve_with(&config, find);
+        let names = |paths: &[PathBuf]| paths.iter().map(|path| path.to_string_lossy().to_string()).collect::<Vec<_>>();
+        assert_eq!(names(&chain.regular), ["custom.ttf", "seguisym.ttf", "msyh.ttc"]);
+        assert_eq!(names(&chain.bold), ["msyhbd.ttc", "custom.ttf", "seguisym.ttf", "msyh.ttc"]);
+        assert_eq!(chain.missing, ["missing-bold.ttf", "nope.otf"]);
+    }
+}
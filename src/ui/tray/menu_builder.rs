@@ -2,7 +2,8 @@
 //!
 //! 负责构建系统托盘右键菜单
 
-use crate::utils::error::{HamsterError, Result};
+use crate::utils::Result;
+use crate::t;
 
 pub struct MenuBuilder;
 
@@ -14,37 +15,62 @@ impl MenuBuilder {
     /// 构建默认托盘菜单
     pub fn build_default_menu(&self) -> Result<Vec<MenuItem>> {
         Ok(vec![
-            MenuItem::new("扫描硬件", MenuAction::ScanHardware),
-            MenuItem::new("检查更新", MenuAction::CheckUpdates),
-            MenuItem::new("打开主界面", MenuAction::OpenMainWindow),
-            MenuItem::new("设置", MenuAction::OpenSettings),
-            MenuItem::new("退出", MenuAction::Exit),
+            MenuItem::new(&t!("tray-scan"), MenuAction::ScanHardware),
+            MenuItem::new(&t!("tray-check-updates"), MenuAction::CheckUpdates),
+            MenuItem::new(&t!("tray-open"), MenuAction::OpenMainWindow),
+            MenuItem::new(&t!("tray-settings"), MenuAction::OpenSettings),
+            MenuItem::new(&t!("tray-exit"), MenuAction::Exit),
         ])
     }
 
     /// 构建带驱动更新选项的菜单
     pub fn build_update_menu(&self, update_count: usize) -> Result<Vec<MenuItem>> {
         Ok(vec![
-            MenuItem::new(&format!("发现 {} 个驱动更新", update_count), MenuAction::CheckUpdates),
-            MenuItem::new("安装所有更新", MenuAction::InstallAllUpdates),
-            MenuItem::new("忽略更新", MenuAction::IgnoreUpdates),
+            MenuItem::new(&t!("tray-updates-found", count = update_count), MenuAction::CheckUpdates),
+            MenuItem::new(&t!("tray-install-all"), MenuAction::InstallAllUpdates),
+            MenuItem::new(&t!("tray-ignore-updates"), MenuAction::IgnoreUpdates),
             MenuItem::separator(),
-            MenuItem::new("扫描硬件", MenuAction::ScanHardware),
-            MenuItem::new("打开主界面", MenuAction::OpenMainWindow),
-            MenuItem::new("设置", MenuAction::OpenSettings),
-            MenuItem::new("退出", MenuAction::Exit),
+            MenuItem::new(&t!("tray-scan"), MenuAction::ScanHardware),
+            MenuItem::new(&t!("tray-open"), MenuAction::OpenMainWindow),
+            MenuItem::new(&t!("tray-settings"), MenuAction::OpenSettings),
+            MenuItem::new(&t!("tray-exit"), MenuAction::Exit),
         ])
     }
 
     /// 构建扫描中状态的菜单
     pub fn build_scanning_menu(&self) -> Result<Vec<MenuItem>> {
         Ok(vec![
-            MenuItem::new("正在扫描...", MenuAction::None),
+            MenuItem::new(&t!("tray-scanning"), MenuAction::None).disable(),
             MenuItem::separator(),
-            MenuItem::new("取消扫描", MenuAction::CancelScan),
-            MenuItem::new("打开主界面", MenuAction::OpenMainWindow),
-            MenuItem::new("设置", MenuAction::OpenSettings),
-            MenuItem::new("退出", MenuAction::Exit),
+            MenuItem::new(&t!("tray-cancel-scan"), MenuAction::CancelScan),
+            MenuItem::new(&t!("tray-open"), MenuAction::OpenMainWindow),
+            MenuItem::new(&t!("tray-settings"), MenuAction::OpenSettings),
+            MenuItem::new(&t!("tray-exit"), MenuAction::Exit),
+        ])
+    }
+
+    /// 构建安装更新中状态的菜单
+    pub fn build_installing_menu(&self) -> Result<Vec<MenuItem>> {
+        Ok(vec![
+            MenuItem::new(&t!("tray-installing"), MenuAction::None).disable(),
+            MenuItem::separator(),
+            MenuItem::new(&t!("tray-cancel-install"), MenuAction::CancelScan),
+            MenuItem::new(&t!("tray-open"), MenuAction::OpenMainWindow),
+            MenuItem::new(&t!("tray-settings"), MenuAction::OpenSettings),
+            MenuItem::new(&t!("tray-exit"), MenuAction::Exit),
+        ])
+    }
+
+    /// 构建等待重启状态的菜单
+    pub fn build_reboot_menu(&self) -> Result<Vec<MenuItem>> {
+        Ok(vec![
+            MenuItem::new(&t!("tray-reboot-pending"), MenuAction::None).disable(),
+            MenuItem::separator(),
+            MenuItem::new(&t!("tray-scan"), MenuAction::ScanHardware),
+            MenuItem::new(&t!("tray-check-updates"), MenuAction::CheckUpdates),
+            MenuItem::new(&t!("tray-open"), MenuAction::OpenMainWindow),
+            MenuItem::new(&t!("tray-settings"), MenuAction::OpenSettings),
+            MenuItem::new(&t!("tray-exit"), MenuAction::Exit),
         ])
     }
 }
@@ -87,7 +113,7 @@ impl MenuItem {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MenuAction {
     ScanHardware,
     CheckUpdates,
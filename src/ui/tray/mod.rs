@@ -0,0 +1,6 @@
+mod menu_builder;
+mod system_tray;
+mod tray_model;
+pub use menu_builder::*;
+pub use system_tray::*;
+pub use tray_model::*;
@@ -1,48 +1,153 @@
 //! 系统托盘
 //!
-//! 负责系统托盘图标的创建和管理
+//! 负责系统托盘图标的创建和管理，图标、提示和菜单由 TrayModel 决定，
+//! 具体的平台实现通过 TrayBackend 接入。
 
-use crate::utils::error::{HamsterError, Result};
+use std::collections::VecDeque;
+use crate::utils::Result;
+use super::{MenuAction, MenuItem, TrayCommand, TrayIcon, TrayModel, TrayState};
+
+/// 平台托盘实现
+pub trait TrayBackend: Send {
+    /// 当前平台能否显示托盘图标，不能时主窗口不会最小化到托盘
+    fn is_available(&self) -> bool;
+    fn show(&mut self) -> Result<()>;
+    fn hide(&mut self) -> Result<()>;
+    fn set_icon(&mut self, icon: TrayIcon) -> Result<()>;
+    fn set_tooltip(&mut self, tooltip: &str) -> Result<()>;
+    fn set_menu(&mut self, items: &[MenuItem]) -> Result<()>;
+    /// 取出一个用户点击的菜单项，双击图标时返回 OpenMainWindow
+    fn poll_action(&mut self) -> Option<MenuAction>;
+}
+
+/// 不显示任何内容的托盘，在没有托盘实现的平台和测试中使用
+#[derive(Debug, Default)]
+pub struct HeadlessTray {
+    pub visible: bool,
+    pub icon: Option<TrayIcon>,
+    pub tooltip: String,
+    pub menu: Vec<MenuItem>,
+    clicks: VecDeque<MenuAction>,
+}
+
+impl HeadlessTray {
+    /// 模拟用户点击菜单项
+    pub fn click(&mut self, action: MenuAction) {
+        self.clicks.push_back(action);
+    }
+}
+
+impl TrayBackend for HeadlessTray {
+    fn is_available(&self) -> bool {
+        false
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+
+    fn hide(&mut self) -> Result<()> {
+        self.visible = false;
+        Ok(())
+    }
+
+    fn set_icon(&mut self, icon: TrayIcon) -> Result<()> {
+        self.icon = Some(icon);
+        Ok(())
+    }
+
+    fn set_tooltip(&mut self, tooltip: &str) -> Result<()> {
+        self.tooltip = tooltip.to_string();
+        Ok(())
+    }
+
+    fn set_menu(&mut self, items: &[MenuItem]) -> Result<()> {
+        self.menu = items.to_vec();
+        Ok(())
+    }
+
+    fn poll_action(&mut self) -> Option<MenuAction> {
+        self.clicks.pop_front()
+    }
+}
 
 pub struct SystemTray {
-    // 系统托盘实现的具体字段将根据所选的GUI框架而定
-    // 这里使用一个占位符
+    backend: Box<dyn TrayBackend>,
+    model: TrayModel,
     is_visible: bool,
 }
 
 impl SystemTray {
     pub fn new() -> Result<Self> {
-        Ok(Self {
+        Self::with_backend(Box::new(HeadlessTray::default()))
+    }
+
+    pub fn with_backend(backend: Box<dyn TrayBackend>) -> Result<Self> {
+        let mut tray = Self {
+            backend,
+            model: TrayModel::new(),
             is_visible: false,
-        })
+        };
+        tray.refresh()?;
+        Ok(tray)
+    }
+
+    /// 托盘图标能否显示，不能时不应隐藏主窗口
+    pub fn is_available(&self) -> bool {
+        self.backend.is_available()
     }
 
     /// 显示系统托盘图标
     pub fn show(&mut self) -> Result<()> {
+        self.backend.show()?;
         self.is_visible = true;
         Ok(())
     }
 
     /// 隐藏系统托盘图标
     pub fn hide(&mut self) -> Result<()> {
+        self.backend.hide()?;
         self.is_visible = false;
         Ok(())
     }
 
-    /// 设置托盘图标
-    pub fn set_icon(&self, _icon_path: &str) -> Result<()> {
-        // TODO: 实现图标设置逻辑
-        Ok(())
+    /// 检查托盘是否可见
+    pub fn is_visible(&self) -> bool {
+        self.is_visible
     }
 
-    /// 设置托盘提示文本
-    pub fn set_tooltip(&self, _tooltip: &str) -> Result<()> {
-        // TODO: 实现提示文本设置逻辑
+    pub fn state(&self) -> TrayState {
+        self.model.state()
+    }
+
+    /// 更新托盘状态，状态变化时刷新图标、提示和菜单
+    pub fn set_state(&mut self, state: TrayState) -> Result<()> {
+        if self.model.set_state(state) {
+            self.refresh()?;
+        }
         Ok(())
     }
 
-    /// 检查托盘是否可见
-    pub fn is_visible(&self) -> bool {
-        self.is_visible
+    /// 处理用户点击的菜单项，返回需要界面执行的命令
+    pub fn poll_commands(&mut self) -> Result<Vec<TrayCommand>> {
+        let mut commands = Vec::new();
+        while let Some(action) = self.backend.poll_action() {
+            let state = self.model.state();
+            commands.extend(self.model.handle(action));
+            // 忽略更新会改变托盘状态
+            if self.model.state() != state {
+                self.refresh()?;
+            }
+        }
+        Ok(commands)
     }
-}
\ No newline at end of file
+
+    /// 切换语言后重新生成提示和菜单文本
+    pub fn refresh(&mut self) -> Result<()> {
+        let state = self.model.state();
+        self.backend.set_icon(state.icon())?;
+        self.backend.set_tooltip(&state.tooltip())?;
+        self.backend.set_menu(&self.model.menu())
+    }
+}
@@ -0,0 +1,176 @@
+//! 托盘状态
+//!
+//! 根据界面的运行状态决定托盘的图标、提示和菜单，并把菜单操作转换为命令，
+//! 不依赖具体平台的托盘实现。
+
+use crate::core::AppCommand;
+use crate::driver::fetcher::DownloadPriority;
+use crate::t;
+use super::{MenuAction, MenuBuilder, MenuItem};
+
+/// 托盘显示的状态，多种情况同时存在时按安装、扫描、待重启、有更新的顺序取第一个
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrayState {
+    #[default]
+    Idle,
+    Scanning,
+    UpdatesAvailable(usize),
+    Installing,
+    RebootPending,
+}
+
+/// 托盘图标，由后端映射到具体的图标资源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayIcon {
+    Normal,
+    Busy,
+    Attention,
+    Warning,
+}
+
+impl TrayState {
+    pub fn from_activity(scanning: bool, installing: bool, update_count: usize, reboot_pending: bool) -> Self {
+        if installing {
+            TrayState::Installing
+        } else if scanning {
+            TrayState::Scanning
+        } else if reboot_pending {
+            TrayState::RebootPending
+        } else if update_count > 0 {
+            TrayState::UpdatesAvailable(update_count)
+        } else {
+            TrayState::Idle
+        }
+    }
+
+    pub fn icon(&self) -> TrayIcon {
+        match self {
+            TrayState::Idle => TrayIcon::Normal,
+            TrayState::Scanning | TrayState::Installing => TrayIcon::Busy,
+            TrayState::UpdatesAvailable(_) => TrayIcon::Attention,
+            TrayState::RebootPending => TrayIcon::Warning,
+        }
+    }
+
+    pub fn tooltip(&self) -> String {
+        let status = match self {
+            TrayState::Idle => t!("tray-status-idle"),
+            TrayState::Scanning => t!("tray-scanning"),
+            TrayState::UpdatesAvailable(count) => t!("tray-updates-found", count = count),
+            TrayState::Installing => t!("tray-installing"),
+            TrayState::RebootPending => t!("tray-reboot-pending"),
+        };
+        t!("tray-tooltip", app = t!("app-name"), status = status)
+    }
+}
+
+/// 托盘菜单操作对应的界面命令
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrayCommand {
+    /// 交给核心执行的命令，Shutdown 表示退出程序
+    Core(AppCommand),
+    ShowWindow,
+    OpenSettings,
+}
+
+impl MenuAction {
+    /// 菜单操作对应的核心命令，只影响界面的操作返回 None
+    pub fn command(&self) -> Option<AppCommand> {
+        match self {
+            MenuAction::ScanHardware => Some(AppCommand::ScanHardware),
+            MenuAction::CheckUpdates => Some(AppCommand::CheckUpdates),
+            MenuAction::InstallAllUpdates => Some(AppCommand::InstallUpdates(DownloadPriority::User)),
+            MenuAction::CancelScan => Some(AppCommand::Cancel),
+            MenuAction::Exit => Some(AppCommand::Shutdown),
+            MenuAction::IgnoreUpdates | MenuAction::OpenMainWindow | MenuAction::OpenSettings | MenuAction::None => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TrayModel {
+    state: TrayState,
+    /// 用户忽略的更新数量，数量变化前不再提示
+    ignored_updates: Option<usize>,
+}
+
+impl TrayModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(&self) -> TrayState {
+        self.state
+    }
+
+    /// 更新状态，托盘需要刷新时返回 true
+    pub fn set_state(&mut self, state: TrayState) -> bool {
+        let state = match state {
+            TrayState::UpdatesAvailable(count) if self.ignored_updates == Some(count) => TrayState::Idle,
+            TrayState::UpdatesAvailable(_) => {
+                self.ignored_updates = None;
+                state
+            }
+            state => state,
+        };
+        let changed = self.state != state;
+        self.state = state;
+        changed
+    }
+
+    pub fn menu(&self) -> Vec<MenuItem> {
+        let builder = MenuBuilder;
+        let menu = match self.state {
+            TrayState::Idle => builder.build_default_menu(),
+            TrayState::Scanning => builder.build_scanning_menu(),
+            TrayState::UpdatesAvailable(count) => builder.build_update_menu(count),
+            TrayState::Installing => builder.build_installing_menu(),
+            TrayState::RebootPending => builder.build_reboot_menu(),
+        };
+        menu.unwrap_or_default()
+    }
+
+    /// 处理菜单点击，忽略更新只改变托盘状态，不产生命令
+    pub fn handle(&mut self, action: MenuAction) -> Option<TrayCommand> {
+        match action {
+            MenuAction::OpenMainWindow => Some(TrayCommand::ShowWindow),
+            MenuAction::OpenSettings => Some(TrayCommand::OpenSettings),
+            MenuAction::IgnoreUpdates => {
+                if let TrayState::UpdatesAvailable(count) = self.state {
+                    self.ignored_updates = Some(count);
+                    self.state = TrayState::Idle;
+                }
+                None
+            }
+            action => action.command().map(TrayCommand::Core),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tray_state_and_actions() {
+        assert_eq!(TrayState::from_activity(true, true, 3, true), TrayState::Installing);
+        assert_eq!(TrayState::from_activity(false, false, 3, true), TrayState::RebootPending);
+        assert_eq!(TrayState::from_activity(false, false, 3, false).icon(), TrayIcon::Attention);
+
+        let mut model = TrayModel::new();
+        assert!(model.set_state(TrayState::UpdatesAvailable(3)));
+        assert!(model.menu().iter().any(|item| item.action == MenuAction::InstallAllUpdates));
+        assert_eq!(model.handle(MenuAction::InstallAllUpdates), Some(TrayCommand::Core(AppCommand::InstallUpdates(DownloadPriority::User))));
+        assert_eq!(model.handle(MenuAction::OpenMainWindow), Some(TrayCommand::ShowWindow));
+
+        // 忽略后同样数量的更新不再提示，数量变化后重新提示
+        assert_eq!(model.handle(MenuAction::IgnoreUpdates), None);
+        assert_eq!(model.state(), TrayState::Idle);
+        assert!(!model.set_state(TrayState::UpdatesAvailable(3)));
+        assert!(model.set_state(TrayState::UpdatesAvailable(4)));
+
+        assert!(model.set_state(TrayState::Scanning));
+        assert_eq!(model.handle(MenuAction::CancelScan), Some(TrayCommand::Core(AppCommand::Cancel)));
+        assert_eq!(model.handle(MenuAction::None), None);
+    }
+}
@@ -0,0 +1,365 @@
+//! 设备列表视图
+//!
+//! 以表格显示扫描到的设备，支持按类别分组、搜索、过滤、排序和多选，
+//! 点击设备名称在右侧显示详细信息。
+
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use crate::core::UpdateCandidate;
+use crate::hardware::HardwareDevice;
+use crate::ui::DeviceCard;
+use crate::t;
+
+/// 可排序的列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceColumn {
+    Name,
+    Class,
+    Vendor,
+    DriverVersion,
+    DriverDate,
+    Status,
+}
+
+impl DeviceColumn {
+    const ALL: [DeviceColumn; 6] = [
+        DeviceColumn::Name,
+        DeviceColumn::Class,
+        DeviceColumn::Vendor,
+        DeviceColumn::DriverVersion,
+        DeviceColumn::DriverDate,
+        DeviceColumn::Status,
+    ];
+
+    pub fn label(&self) -> String {
+        match self {
+            DeviceColumn::Name => t!("device-column-name"),
+            DeviceColumn::Class => t!("device-column-class"),
+            DeviceColumn::Vendor => t!("device-column-vendor"),
+            DeviceColumn::DriverVersion => t!("device-column-driver-version"),
+            DeviceColumn::DriverDate => t!("device-column-driver-date"),
+            DeviceColumn::Status => t!("device-column-status"),
+        }
+    }
+
+    fn compare(&self, a: &DeviceCard, b: &DeviceCard) -> Ordering {
+        let (a, b) = match self {
+            DeviceColumn::Status => return a.status_rank().cmp(&b.status_rank()),
+            DeviceColumn::Name => (&a.device.device_name, &b.device.device_name),
+            DeviceColumn::Class => (&a.device.device_class, &b.device.device_class),
+            DeviceColumn::Vendor => (&a.device.manufacturer, &b.device.manufacturer),
+            DeviceColumn::DriverVersion => (&a.device.driver_version, &b.device.driver_version),
+            DeviceColumn::DriverDate => (&a.device.driver_date, &b.device.driver_date),
+        };
+        a.to_lowercase().cmp(&b.to_lowercase())
+    }
+}
+
+/// 过滤条件
+#[derive(Debug, Clone, Default)]
+pub struct DeviceFilter {
+    pub query: String,
+    pub problems_only: bool,
+    pub outdated_only: bool,
+    pub show_hidden: bool,
+}
+
+impl DeviceFilter {
+    pub fn matches(&self, card: &DeviceCard) -> bool {
+        (self.show_hidden || !card.is_hidden())
+            && (!self.problems_only || card.has_problem())
+            && (!self.outdated_only || card.has_update_available())
+            && card.matches_query(&self.query)
+    }
+}
+
+pub struct DeviceListView {
+    cards: Vec<DeviceCard>,
+    pub filter: DeviceFilter,
+    pub sort_column: DeviceColumn,
+    pub sort_ascending: bool,
+    pub group_by_class: bool,
+    /// 显示详情的设备实例ID
+    detail: Option<String>,
+}
+
+impl Default for DeviceListView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeviceListView {
+    pub fn new() -> Self {
+        Self {
+            cards: Vec::new(),
+            filter: DeviceFilter::default(),
+            sort_column: DeviceColumn::Status,
+            sort_ascending: true,
+            group_by_class: true,
+            detail: None,
+        }
+    }
+
+    /// 替换设备列表，保留仍然存在的设备的选中和更新状态
+    pub fn set_devices(&mut self, devices: &[HardwareDevice]) {
+        let mut previous: BTreeMap<String, DeviceCard> = self.cards.drain(..)
+            .map(|card| (card.device.device_id.clone(), card))
+            .collect();
+        self.cards = devices.iter()
+            .map(|device| match previous.remove(&device.device_id) {
+                Some(mut card) => {
+                    card.update_device_info(device.clone());
+                    card
+                }
+                None => DeviceCard::new(device.clone()),
+            })
+            .collect();
+        if self.detail.as_ref().is_some_and(|id| !self.cards.iter().any(|c| &c.device.device_id == id)) {
+            self.detail = None;
+        }
+    }
+
+    /// 根据查找更新的结果标记设备的驱动状态
+    pub fn apply_update_candidates(&mut self, candidates: &[UpdateCandidate]) {
+        for card in &mut self.cards {
+            if let Some(candidate) = candidates.iter()
+                .find(|c| c.hardware_info.hardware_id.eq_ignore_ascii_case(&card.device.hardware_id))
+            {
+                card.apply_update_candidate(candidate);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    pub fn selected_devices(&self) -> impl Iterator<Item = &HardwareDevice> {
+        self.cards.iter().filter(|card| card.is_selected()).map(|card| &card.device)
+    }
+
+    /// 过滤并排序后的设备下标
+    pub fn visible_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.cards.len())
+            .filter(|&i| self.filter.matches(&self.cards[i]))
+            .collect();
+        indices.sort_by(|&a, &b| {
+            let ordering = self.sort_column.compare(&self.cards[a], &self.cards[b])
+                .then_with(|| DeviceColumn::Name.compare(&self.cards[a], &self.cards[b]));
+            if self.sort_ascending { ordering } else { ordering.reverse() }
+        });
+        indices
+    }
+
+    /// 按设备类型分组，组内保持排序
+    fn grouped_indices(&self, indices: Vec<usize>) -> BTreeMap<String, Vec<usize>> {
+        let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for i in indices {
+            groups.entry(self.cards[i].device.device_class.clone()).or_default().push(i);
+        }
+        groups
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        self.show_toolbar(ui);
+        ui.separator();
+
+        if self.cards.is_empty() {
+            ui.label(t!("devices-not-scanned"));
+            return;
+        }
+
+        if let Some(card) = self.detail.as_ref().and_then(|id| self.cards.iter().find(|c| &c.device.device_id == id)) {
+            let mut close = false;
+            egui::SidePanel::right("device_detail_panel")
+                .resizable(true)
+                .default_width(320.0)
+                .show_inside(ui, |ui| {
+                    if ui.small_button(t!("common-close")).clicked() {
+                        close = true;
+                    }
+                    egui::ScrollArea::vertical().show(ui, |ui| card.show_details(ui));
+                });
+            if close {
+                self.detail = None;
+            }
+        }
+
+        let indices = self.visible_indices();
+        ui.label(t!("devices-visible", visible = indices.len(), total = self.cards.len()));
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if self.group_by_class {
+                for (class, indices) in self.grouped_indices(indices) {
+                    egui::CollapsingHeader::new(format!("{} ({})", class, indices.len()))
+                        .id_source(("device_group", &class))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            ui.push_id(&class, |ui| self.show_table(ui, &indices));
+                        });
+                }
+            } else {
+                self.show_table(ui, &indices);
+            }
+        });
+    }
+
+    fn show_toolbar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(t!("common-search"));
+            ui.text_edit_singleline(&mut self.filter.query);
+            ui.checkbox(&mut self.filter.problems_only, t!("devices-problems-only"));
+            ui.checkbox(&mut self.filter.outdated_only, t!("devices-outdated-only"));
+            ui.checkbox(&mut self.filter.show_hidden, t!("devices-show-hidden"));
+            ui.checkbox(&mut self.group_by_class, t!("devices-group-by-class"));
+        });
+
+        let visible = self.visible_indices();
+        let selected = self.cards.iter().filter(|card| card.is_selected()).count();
+        ui.horizontal(|ui| {
+            if ui.button(t!("common-select-all")).clicked() {
+                for &i in &visible {
+                    self.cards[i].set_selected(true);
+                }
+            }
+            if ui.button(t!("devices-clear-selection")).clicked() {
+                self.cards.iter_mut().for_each(|card| card.set_selected(false));
+            }
+            if ui.add_enabled(selected > 0, egui::Button::new(t!("devices-copy-selected"))).clicked() {
+                let text = self.selected_devices()
+                    .map(|d| format!("{}\t{}\t{}\t{}\t{}", d.device_name, d.device_class, d.manufacturer, d.driver_version, d.hardware_id))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                ui.output_mut(|output| output.copied_text = text);
+            }
+            ui.label(t!("devices-selected", count = selected));
+        });
+    }
+
+    fn show_table(&mut self, ui: &mut egui::Ui, indices: &[usize]) {
+        let mut sort_clicked = None;
+        let mut detail_clicked = None;
+
+        TableBuilder::new(ui)
+            .striped(true)
+            .vscroll(false)
+            .column(Column::exact(24.0))
+            .column(Column::initial(240.0).at_least(120.0).resizable(true).clip(true))
+            .columns(Column::initial(110.0).at_least(60.0).resizable(true).clip(true), 4)
+            .column(Column::remainder().at_least(80.0))
+            .header(20.0, |mut header| {
+                header.col(|_| {});
+                for column in DeviceColumn::ALL {
+                    header.col(|ui| {
+                        let arrow = match (self.sort_column == column, self.sort_ascending) {
+                            (true, true) => " ▲",
+                            (true, false) => " ▼",
+                            (false, _) => "",
+                        };
+                        if ui.button(format!("{}{}", column.label(), arrow)).clicked() {
+                            sort_clicked = Some(column);
+                        }
+                    });
+                }
+            })
+            .body(|mut body| {
+                for &i in indices {
+                    let card = &mut self.cards[i];
+                    body.row(20.0, |mut row| {
+                        row.col(|ui| {
+                            let mut selected = card.is_selected();
+                            if ui.checkbox(&mut selected, "").changed() {
+                                card.set_selected(selected);
+                            }
+                        });
+                        row.col(|ui| {
+                            let is_detail = self.detail.as_deref() == Some(card.get_device_id());
+                            if ui.selectable_label(is_detail, card.get_device_name()).clicked() {
+                                detail_clicked = Some(card.get_device_id().to_string());
+                            }
+                        });
+                        row.col(|ui| { ui.label(card.get_device_type()); });
+                        row.col(|ui| { ui.label(&card.device.manufacturer); });
+                        row.col(|ui| { ui.label(card.get_driver_version()); });
+                        row.col(|ui| { ui.label(&card.device.driver_date); });
+                        row.col(|ui| {
+                            let status = card.status_label();
+                            if card.has_problem() {
+                                ui.colored_label(ui.visuals().error_fg_color, status);
+                            } else if card.has_update_available() {
+                                ui.colored_label(ui.visuals().warn_fg_color, status);
+                            } else {
+                                ui.label(status);
+                            }
+                        });
+                    });
+                }
+            });
+
+        if let Some(column) = sort_clicked {
+            if self.sort_column == column {
+                self.sort_ascending = !self.sort_ascending;
+            } else {
+                self.sort_column = column;
+                self.sort_ascending = true;
+            }
+        }
+        if let Some(device_id) = detail_clicked {
+            self.detail = Some(device_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::DeviceStatus;
+
+    fn device(name: &str, class: &str, status: DeviceStatus, present: bool) -> HardwareDevice {
+        HardwareDevice {
+            device_id: format!("ID\\{}", name),
+            device_name: name.to_string(),
+            hardware_id: format!("HW\\{}", name),
+            device_class: class.to_string(),
+            status,
+            present,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_filter_and_sort() {
+        let mut view = DeviceListView::new();
+        view.set_devices(&[
+            device("Intel Ethernet", "网络适配器", DeviceStatus::Ok, true),
+            device("Broken Camera", "摄像头", DeviceStatus::Error, true),
+            device("Old Printer", "打印机", DeviceStatus::Ok, false),
+            device("AMD Radeon", "显示适配器", DeviceStatus::Ok, true),
+        ]);
+        let names = |view: &DeviceListView| -> Vec<String> {
+            view.visible_indices().into_iter().map(|i| view.cards[i].device.device_name.clone()).collect()
+        };
+
+        // 默认按状态排序，问题设备在前，隐藏设备不显示
+        assert_eq!(names(&view), ["Broken Camera", "AMD Radeon", "Intel Ethernet"]);
+
+        view.filter.show_hidden = true;
+        view.sort_column = DeviceColumn::Name;
+        view.sort_ascending = false;
+        assert_eq!(names(&view), ["Old Printer", "Intel Ethernet", "Broken Camera", "AMD Radeon"]);
+
+        view.filter.query = "hw\\intel".to_string();
+        assert_eq!(names(&view), ["Intel Ethernet"]);
+
+        view.filter.query.clear();
+        view.filter.problems_only = true;
+        assert_eq!(names(&view), ["Broken Camera"]);
+
+        // 重新扫描后保留选中状态
+        view.cards[0].set_selected(true);
+        view.set_devices(&[device("Intel Ethernet", "网络适配器", DeviceStatus::Ok, true)]);
+        assert_eq!(view.selected_devices().count(), 1);
+    }
+}
@@ -0,0 +1,123 @@
+//! 下载管理视图
+//!
+//! 列出下载队列中的任务，显示进度、速度和剩余时间，并提供暂停、继续、重试和取消。
+
+use eframe::egui;
+use std::time::Duration;
+use crate::driver::fetcher::{DownloadPriority, DownloadStatus, DownloadTask};
+use crate::i18n::format_size;
+use crate::t;
+
+/// 需要对下载队列执行的操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadAction {
+    Pause(String),
+    Resume(String),
+    Retry(String),
+    Cancel(String),
+    Remove(String),
+    ClearFinished,
+}
+
+#[derive(Default)]
+pub struct DownloadsView;
+
+impl DownloadsView {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, tasks: &[DownloadTask]) -> Option<DownloadAction> {
+        let mut action = None;
+        let active = tasks.iter().filter(|task| task.status == DownloadStatus::Downloading).count();
+        let finished = tasks.iter().filter(|task| task.status.is_finished()).count();
+
+        ui.horizontal(|ui| {
+            ui.label(t!("downloads-summary", total = tasks.len(), active = active));
+            if ui.add_enabled(finished > 0, egui::Button::new(t!("downloads-clear-finished"))).clicked() {
+                action = Some(DownloadAction::ClearFinished);
+            }
+        });
+        ui.separator();
+
+        if tasks.is_empty() {
+            ui.label(t!("downloads-empty"));
+            return action;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for task in tasks {
+                if let Some(task_action) = show_task(ui, task) {
+                    action = Some(task_action);
+                }
+            }
+        });
+        action
+    }
+}
+
+fn show_task(ui: &mut egui::Ui, task: &DownloadTask) -> Option<DownloadAction> {
+    let mut action = None;
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.horizontal(|ui| {
+            ui.strong(&task.driver_info.driver_name);
+            ui.label(&task.driver_info.driver_version);
+            ui.label(format!("[{}]", task.status.label()));
+            if task.priority != DownloadPriority::User {
+                ui.label(format!("[{}]", task.priority.label()));
+            }
+        });
+
+        let size = if task.total > 0 {
+            format!("{} / {}", format_size(task.downloaded), format_size(task.total))
+        } else {
+            format_size(task.downloaded)
+        };
+        let mut text = format!("{:.1}%  {}", task.progress, size);
+        if task.status == DownloadStatus::Downloading {
+            if task.speed > 0 {
+                text.push_str(&format!("  {}/s", format_size(task.speed)));
+            }
+            if let Some(eta) = task.eta {
+                text.push_str(&format!("  {}", t!("downloads-remaining", time = format_eta(eta))));
+            }
+        }
+        ui.add(egui::ProgressBar::new((task.progress / 100.0) as f32).text(text));
+
+        if let Some(ref error) = task.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        ui.horizontal(|ui| {
+            let id = || task.id.clone();
+            if task.can_pause() && ui.small_button(t!("downloads-pause")).clicked() {
+                action = Some(DownloadAction::Pause(id()));
+            }
+            if task.can_resume() && ui.small_button(t!("downloads-resume")).clicked() {
+                action = Some(DownloadAction::Resume(id()));
+            }
+            if task.can_retry() && ui.small_button(t!("downloads-retry")).clicked() {
+                action = Some(DownloadAction::Retry(id()));
+            }
+            if task.can_cancel() && ui.small_button(t!("downloads-cancel")).clicked() {
+                action = Some(DownloadAction::Cancel(id()));
+            }
+            if task.status.is_finished() && ui.small_button(t!("downloads-remove")).clicked() {
+                action = Some(DownloadAction::Remove(id()));
+            }
+            ui.label(&task.file_path);
+        });
+    });
+    action
+}
+
+/// 剩余时间，如 "1小时5分"、"3分20秒"（英文为 "1 h 5 min"）
+fn format_eta(eta: Duration) -> String {
+    let secs = eta.as_secs();
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => t!("duration-seconds", seconds = s),
+        (0, m, s) => t!("duration-minutes-seconds", minutes = m, seconds = s),
+        (h, m, _) => t!("duration-hours-minutes", hours = h, minutes = m),
+    }
+}
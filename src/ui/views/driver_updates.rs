@@ -0,0 +1,208 @@
+//! 驱动更新视图
+//!
+//! 以卡片列出更新候选，支持批量选择、忽略和固定，并显示每个驱动的更新进度。
+
+use eframe::egui;
+use crate::config::UpdatePreferences;
+use crate::core::{CoreEvent, UpdateCandidate};
+use crate::matcher::DriverInfo;
+use crate::ui::{DriverCard, DriverCardAction, UpdateItemState};
+use crate::t;
+
+/// 需要界面执行的操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum DriverUpdateAction {
+    UpdateSelected(Vec<String>),
+    /// 加入下载队列，稍后再安装
+    DownloadSelected(Vec<DriverInfo>),
+    Ignore { hardware_id: String, version: String },
+    Unignore(String),
+    Pin(String),
+    Unpin(String),
+}
+
+#[derive(Default)]
+pub struct DriverUpdatesView {
+    cards: Vec<DriverCard>,
+    /// 同时显示已忽略、已固定和没有更新的设备
+    pub show_all: bool,
+}
+
+impl DriverUpdatesView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 替换候选列表，保留仍然存在的驱动的选中状态和进度
+    pub fn set_candidates(&mut self, candidates: &[UpdateCandidate], preferences: &UpdatePreferences) {
+        let mut previous: Vec<DriverCard> = std::mem::take(&mut self.cards);
+        self.cards = candidates.iter()
+            .map(|candidate| {
+                let hardware_id = &candidate.hardware_info.hardware_id;
+                match previous.iter().position(|card| card.get_hardware_id().eq_ignore_ascii_case(hardware_id)) {
+                    Some(index) => {
+                        let mut card = previous.swap_remove(index);
+                        card.update_candidate(candidate.clone(), preferences);
+                        card
+                    }
+                    None => DriverCard::new(candidate.clone(), preferences),
+                }
+            })
+            .collect();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    pub fn selected_hardware_ids(&self) -> Vec<String> {
+        self.cards.iter()
+            .filter(|card| card.is_selected())
+            .map(|card| card.get_hardware_id().to_string())
+            .collect()
+    }
+
+    pub fn selected_drivers(&self) -> Vec<DriverInfo> {
+        self.cards.iter()
+            .filter(|card| card.is_selected())
+            .filter_map(|card| card.candidate.matched_driver.clone())
+            .collect()
+    }
+
+    pub fn updatable_hardware_ids(&self) -> Vec<String> {
+        self.cards.iter()
+            .filter(|card| card.can_update())
+            .map(|card| card.get_hardware_id().to_string())
+            .collect()
+    }
+
+    /// 开始更新所选驱动
+    pub fn start_batch(&mut self, hardware_ids: &[String]) {
+        for card in &mut self.cards {
+            if hardware_ids.iter().any(|id| id.eq_ignore_ascii_case(card.get_hardware_id())) {
+                card.state = UpdateItemState::Queued;
+            }
+        }
+    }
+
+    pub fn finish_batch(&mut self) {
+        self.cards.iter_mut().for_each(DriverCard::finish_batch);
+    }
+
+    pub fn handle_event(&mut self, event: &CoreEvent) {
+        for card in &mut self.cards {
+            if card.handle_event(event) {
+                break;
+            }
+        }
+    }
+
+    fn is_listed(&self, card: &DriverCard) -> bool {
+        self.show_all || card.can_update()
+    }
+
+    /// busy 为 true 时正在更新，所有操作不可用
+    pub fn show(&mut self, ui: &mut egui::Ui, busy: bool) -> Option<DriverUpdateAction> {
+        let mut action = None;
+        let updatable = self.cards.iter().filter(|card| card.can_update()).count();
+        let selected = self.cards.iter().filter(|card| card.is_selected()).count();
+
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!busy, |ui| {
+                if ui.button(t!("common-select-all")).clicked() {
+                    self.cards.iter_mut().for_each(|card| card.set_selected(true));
+                }
+                if ui.button(t!("common-select-none")).clicked() {
+                    self.cards.iter_mut().for_each(|card| card.set_selected(false));
+                }
+                if ui.add_enabled(selected > 0, egui::Button::new(t!("updates-update-selected", count = selected))).clicked() {
+                    action = Some(DriverUpdateAction::UpdateSelected(self.selected_hardware_ids()));
+                }
+                if ui.add_enabled(selected > 0, egui::Button::new(t!("updates-download-selected"))).clicked() {
+                    action = Some(DriverUpdateAction::DownloadSelected(self.selected_drivers()));
+                }
+            });
+            ui.checkbox(&mut self.show_all, t!("updates-show-all"));
+        });
+        ui.label(t!("updates-available", count = updatable));
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for i in 0..self.cards.len() {
+                if !self.is_listed(&self.cards[i]) {
+                    continue;
+                }
+                let card = &mut self.cards[i];
+                let hardware_id = card.get_hardware_id().to_string();
+                action = match card.show(ui, !busy) {
+                    Some(DriverCardAction::Ignore) => Some(DriverUpdateAction::Ignore {
+                        version: card.get_version().to_string(),
+                        hardware_id,
+                    }),
+                    Some(DriverCardAction::Unignore) => Some(DriverUpdateAction::Unignore(hardware_id)),
+                    Some(DriverCardAction::Pin) => Some(DriverUpdateAction::Pin(hardware_id)),
+                    Some(DriverCardAction::Unpin) => Some(DriverUpdateAction::Unpin(hardware_id)),
+                    None => continue,
+                };
+            }
+        });
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::installer::{InstallStep, StepStatus};
+    use crate::matcher::HardwareInfo;
+
+    fn candidate(hardware_id: &str, current: &str, new: &str) -> UpdateCandidate {
+        UpdateCandidate {
+            hardware_info: HardwareInfo {
+                hardware_id: hardware_id.to_string(),
+                device_name: hardware_id.to_string(),
+                manufacturer: "NVIDIA".to_string(),
+                device_class: "显示适配器".to_string(),
+            },
+            matched_driver: Some(DriverInfo {
+                driver_id: format!("{}-{}", hardware_id, new),
+                hardware_id: hardware_id.to_string(),
+                driver_name: "Game Ready Driver".to_string(),
+                driver_version: new.to_string(),
+                driver_url: "https://www.nvidia.com/download/1".to_string(),
+                manufacturer: "NVIDIA".to_string(),
+                release_date: "2024-01-01".to_string(),
+                file_size: 0,
+                checksum: String::new(),
+                release_notes_url: None,
+            }),
+            needs_update: true,
+            current_version: current.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_selection_preferences_and_progress() {
+        let mut preferences = UpdatePreferences::default();
+        preferences.ignore("PCI\\B", "2.0");
+        let mut b = candidate("PCI\\B", "1.0", "2.0");
+        // 核心已按偏好把忽略的版本标记为无需更新
+        b.needs_update = false;
+
+        let mut view = DriverUpdatesView::new();
+        view.set_candidates(&[candidate("PCI\\A", "1.0", "1.1"), b], &preferences);
+        assert_eq!(view.selected_hardware_ids(), vec!["PCI\\A".to_string()]);
+        assert!(view.cards[1].ignored && !view.cards[1].can_update());
+        assert!(view.cards[0].risk_flags().contains(&t!("risk-no-checksum")));
+
+        view.start_batch(&view.selected_hardware_ids());
+        view.handle_event(&CoreEvent::InstallStep {
+            hardware_id: Some("pci\\a".to_string()),
+            step: InstallStep::Install,
+            status: StepStatus::Completed { detail: None },
+        });
+        view.finish_batch();
+        assert_eq!(view.cards[0].state, UpdateItemState::Installed { reboot_required: false });
+        assert_eq!(view.cards[1].state, UpdateItemState::Idle);
+    }
+}
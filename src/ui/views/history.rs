@@ -0,0 +1,295 @@
+//! 安装历史视图
+//!
+//! 以表格显示安装日志，可按操作、结果、时间和关键字过滤，
+//! 对成功的更改可以通过回滚点撤销。
+
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use chrono::Utc;
+use crate::driver::installer::{
+    action_label, status_label, InstallationAction, InstallationLogEntry, InstallationStatus, LogFilter,
+};
+use crate::i18n::format_datetime;
+use crate::t;
+
+const ACTIONS: [InstallationAction; 6] = [
+    InstallationAction::Install,
+    InstallationAction::Update,
+    InstallationAction::Uninstall,
+    InstallationAction::Backup,
+    InstallationAction::Restore,
+    InstallationAction::Rollback,
+];
+
+const STATUSES: [InstallationStatus; 4] = [
+    InstallationStatus::Success,
+    InstallationStatus::Failed,
+    InstallationStatus::Cancelled,
+    InstallationStatus::InProgress,
+];
+
+/// 可选的时间范围（天）和对应的消息键
+const PERIODS: [(Option<i64>, &str); 4] = [
+    (None, "history-period-all"),
+    (Some(1), "history-period-day"),
+    (Some(7), "history-period-week"),
+    (Some(30), "history-period-month"),
+];
+
+/// 需要界面执行的操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum HistoryAction {
+    Refresh,
+    /// 撤销该日志记录对应的更改
+    Revert(String),
+}
+
+/// 过滤条件
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    /// 匹配设备名称、硬件ID和说明，不区分大小写
+    pub query: String,
+    pub action: Option<InstallationAction>,
+    pub status: Option<InstallationStatus>,
+    pub days: Option<i64>,
+}
+
+impl HistoryFilter {
+    fn log_filter(&self) -> LogFilter {
+        LogFilter {
+            action: self.action,
+            status: self.status,
+            since: self.days.map(|days| Utc::now() - chrono::Duration::days(days)),
+            ..LogFilter::default()
+        }
+    }
+
+    pub fn matches(&self, entry: &InstallationLogEntry) -> bool {
+        let query = self.query.trim().to_lowercase();
+        self.log_filter().matches(entry)
+            && (query.is_empty()
+                || [&entry.change.device_name, &entry.hardware_id, &entry.message]
+                    .iter()
+                    .any(|text| text.to_lowercase().contains(&query)))
+    }
+}
+
+#[derive(Default)]
+pub struct HistoryView {
+    entries: Vec<InstallationLogEntry>,
+    pub filter: HistoryFilter,
+    /// 等待确认撤销的日志记录
+    confirm_revert: Option<String>,
+}
+
+impl HistoryView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 替换日志记录，记录按时间从新到旧排列
+    pub fn set_entries(&mut self, entries: Vec<InstallationLogEntry>) {
+        self.entries = entries;
+        self.confirm_revert = None;
+    }
+
+    pub fn visible_entries(&self) -> Vec<&InstallationLogEntry> {
+        self.entries.iter().filter(|entry| self.filter.matches(entry)).collect()
+    }
+
+    /// busy 为 true 时正在读取历史或撤销，撤销不可用
+    pub fn show(&mut self, ui: &mut egui::Ui, busy: bool) -> Option<HistoryAction> {
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            ui.label(t!("common-search"));
+            ui.text_edit_singleline(&mut self.filter.query);
+            option_combo(ui, "history_action", t!("history-all-actions"), &mut self.filter.action, &ACTIONS, action_label);
+            option_combo(ui, "history_status", t!("history-all-results"), &mut self.filter.status, &STATUSES, status_label);
+            let period = PERIODS.iter()
+                .find(|(days, _)| *days == self.filter.days)
+                .map_or("history-period-all", |(_, key)| key);
+            egui::ComboBox::from_id_source("history_period")
+                .selected_text(t!(period))
+                .show_ui(ui, |ui| {
+                    for (days, key) in PERIODS {
+                        ui.selectable_value(&mut self.filter.days, days, t!(key));
+                    }
+                });
+            if ui.add_enabled(!busy, egui::Button::new(t!("history-refresh"))).clicked() {
+                action = Some(HistoryAction::Refresh);
+            }
+        });
+
+        if let Some(ref log_id) = self.confirm_revert.clone() {
+            let device = self.entries.iter()
+                .find(|entry| entry.id == *log_id)
+                .map_or(log_id.as_str(), |entry| device_name(entry));
+            ui.horizontal(|ui| {
+                ui.colored_label(ui.visuals().warn_fg_color, t!("history-confirm-revert", device = device));
+                if ui.add_enabled(!busy, egui::Button::new(t!("history-confirm-revert-button"))).clicked() {
+                    action = Some(HistoryAction::Revert(log_id.clone()));
+                    self.confirm_revert = None;
+                }
+                if ui.button(t!("common-cancel")).clicked() {
+                    self.confirm_revert = None;
+                }
+            });
+        }
+        ui.separator();
+
+        let visible = self.visible_entries();
+        if visible.is_empty() {
+            ui.label(t!(if self.entries.is_empty() { "history-empty" } else { "history-no-match" }));
+            return action;
+        }
+
+        let mut revert_clicked = None;
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            TableBuilder::new(ui)
+                .striped(true)
+                .column(Column::auto().at_least(40.0))
+                .column(Column::initial(130.0).at_least(110.0))
+                .column(Column::initial(200.0).at_least(100.0).resizable(true).clip(true))
+                .columns(Column::auto().at_least(40.0), 2)
+                .column(Column::initial(150.0).at_least(80.0).resizable(true).clip(true))
+                .column(Column::auto().at_least(50.0))
+                .column(Column::remainder().at_least(120.0).clip(true))
+                .header(20.0, |mut header| {
+                    header.col(|_| {});
+                    for key in ["history-column-time", "history-column-device", "history-column-action", "history-column-result",
+                                "history-column-version", "history-column-duration", "history-column-message"] {
+                        header.col(|ui| { ui.strong(t!(key)); });
+                    }
+                })
+                .body(|mut body| {
+                    for entry in &visible {
+                        body.row(20.0, |mut row| {
+                            row.col(|ui| {
+                                if entry.can_revert(&self.entries)
+                                    && ui.add_enabled(!busy, egui::Button::new(t!("history-revert")).small())
+                                        .on_hover_text(t!("history-revert-hint"))
+                                        .clicked()
+                                {
+                                    revert_clicked = Some(entry.id.clone());
+                                }
+                            });
+                            row.col(|ui| {
+                                ui.label(format_datetime(&entry.timestamp));
+                            });
+                            row.col(|ui| {
+                                ui.label(device_name(entry)).on_hover_text(&entry.hardware_id);
+                            });
+                            row.col(|ui| { ui.label(action_label(&entry.action)); });
+                            row.col(|ui| {
+                                let text = status_label(&entry.status);
+                                match entry.status {
+                                    InstallationStatus::Failed => ui.colored_label(ui.visuals().error_fg_color, text),
+                                    _ => ui.label(text),
+                                };
+                            });
+                            row.col(|ui| { ui.label(version_change(entry)); });
+                            row.col(|ui| { ui.label(entry.duration.map(format_duration).unwrap_or_default()); });
+                            row.col(|ui| {
+                                ui.label(&entry.message).on_hover_text(&entry.message);
+                            });
+                        });
+                    }
+                });
+        });
+
+        if revert_clicked.is_some() {
+            self.confirm_revert = revert_clicked;
+        }
+        action
+    }
+}
+
+fn option_combo<T: Copy + PartialEq>(ui: &mut egui::Ui, id: &str, all: String, value: &mut Option<T>,
+                                     options: &[T], label: fn(&T) -> String) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(value.as_ref().map_or_else(|| all.clone(), label))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(value, None, all);
+            for option in options {
+                ui.selectable_value(value, Some(*option), label(option));
+            }
+        });
+}
+
+fn device_name(entry: &InstallationLogEntry) -> &str {
+    if entry.change.device_name.is_empty() {
+        &entry.hardware_id
+    } else {
+        &entry.change.device_name
+    }
+}
+
+/// 如 "1.0 → 2.0"，之前的版本未知时只显示新版本
+fn version_change(entry: &InstallationLogEntry) -> String {
+    match entry.change.previous_version {
+        Some(ref previous) => format!("{} → {}", previous, entry.driver_info.driver_version),
+        None => entry.driver_info.driver_version.clone(),
+    }
+}
+
+fn format_duration(millis: u64) -> String {
+    if millis < 60_000 {
+        t!("duration-seconds", seconds = format!("{:.1}", millis as f64 / 1000.0))
+    } else {
+        t!("duration-minutes-seconds", minutes = millis / 60_000, seconds = millis / 1000 % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::installer::ChangeDetails;
+    use crate::installer::DriverInfo;
+
+    fn entry(id: &str, device: &str, status: InstallationStatus) -> InstallationLogEntry {
+        InstallationLogEntry {
+            id: id.to_string(),
+            hardware_id: format!("PCI\\{}", id),
+            driver_info: DriverInfo {
+                file_path: String::new(),
+                file_name: "setup.exe".to_string(),
+                hardware_id: format!("PCI\\{}", id),
+                manufacturer: "Intel".to_string(),
+                driver_version: "2.0".to_string(),
+            },
+            action: InstallationAction::Update,
+            status,
+            timestamp: Utc::now() - chrono::Duration::days(3),
+            message: "安装完成".to_string(),
+            duration: Some(65_000),
+            change: ChangeDetails {
+                device_name: device.to_string(),
+                previous_version: Some("1.0".to_string()),
+                rollback_id: Some("rb".to_string()),
+                reverts: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_filters_and_labels() {
+        let mut view = HistoryView::new();
+        view.set_entries(vec![
+            entry("a", "Intel Wi-Fi", InstallationStatus::Success),
+            entry("b", "Realtek Audio", InstallationStatus::Failed),
+        ]);
+
+        view.filter.query = "wi-fi".to_string();
+        assert_eq!(view.visible_entries().len(), 1);
+        view.filter.query.clear();
+        view.filter.status = Some(InstallationStatus::Failed);
+        assert_eq!(view.visible_entries()[0].id, "b");
+        view.filter.status = None;
+        view.filter.days = Some(1);
+        assert!(view.visible_entries().is_empty());
+
+        assert_eq!(version_change(&view.entries[0]), "1.0 → 2.0");
+        assert_eq!(format_duration(65_000), "1分5秒");
+    }
+}
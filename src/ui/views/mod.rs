@@ -0,0 +1,10 @@
+mod device_list;
+mod downloads;
+mod driver_updates;
+mod history;
+mod settings;
+pub use device_list::*;
+pub use downloads::*;
+pub use driver_updates::*;
+pub use history::*;
+pub use settings::*;
@@ -0,0 +1,671 @@
+//! 设置视图
+//!
+//! 编辑配置的副本，验证通过后应用并保存到默认配置文件，未应用的修改可以还原。
+
+use eframe::egui;
+use crate::config::{
+    ChannelTarget, ConfigManager, MaintenanceWindow, NetworkConfig, NotificationChannel, NotificationKind, ScheduledAction, ThemePreference,
+    WebhookTemplate, LOG_LEVELS, UI_SCALE_MAX, UI_SCALE_MIN,
+};
+use crate::hardware::DEVICE_CLASSES;
+use crate::i18n::Locale;
+use crate::ui::available_fonts;
+use crate::t;
+
+const SCHEDULED_ACTIONS: [ScheduledAction; 3] = [
+    ScheduledAction::CheckOnly,
+    ScheduledAction::Download,
+    ScheduledAction::DownloadAndInstall,
+];
+
+/// 需要界面执行的操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingsAction {
+    /// 应用并保存验证通过的配置
+    Apply(ConfigManager),
+}
+
+pub struct SettingsView {
+    draft: ConfigManager,
+    /// 系统字体列表，第一次显示时读取
+    font_choices: Option<Vec<String>>,
+}
+
+impl SettingsView {
+    pub fn new(config: &ConfigManager) -> Self {
+        Self {
+            draft: config.clone(),
+            font_choices: None,
+        }
+    }
+
+    pub fn is_modified(&self, saved: &ConfigManager) -> bool {
+        self.draft != *saved
+    }
+
+    /// 丢弃未应用的修改
+    pub fn revert(&mut self, saved: &ConfigManager) {
+        self.draft = saved.clone();
+    }
+
+    /// 恢复默认值，忽略的版本和固定的设备保持不变
+    pub fn reset_to_defaults(&mut self) {
+        let preferences = self.draft.update_preferences.clone();
+        self.draft = ConfigManager::new();
+        self.draft.update_preferences.ignored = preferences.ignored;
+        self.draft.update_preferences.pinned = preferences.pinned;
+    }
+
+    /// 草稿未通过验证时的错误信息
+    pub fn validation_error(&self) -> Option<String> {
+        self.draft.validate().err().map(|e| e.to_string())
+    }
+
+    /// busy 为 true 时正在应用设置，应用按钮不可用
+    pub fn show(&mut self, ui: &mut egui::Ui, saved: &ConfigManager, busy: bool) -> Option<SettingsAction> {
+        // 忽略和固定在驱动更新页面修改，这里始终使用已保存的值
+        self.draft.update_preferences.ignored = saved.update_preferences.ignored.clone();
+        self.draft.update_preferences.pinned = saved.update_preferences.pinned.clone();
+
+        let mut action = None;
+        let modified = self.is_modified(saved);
+        let error = self.validation_error();
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(modified && error.is_none() && !busy, egui::Button::new(t!("settings-apply"))).clicked() {
+                action = Some(SettingsAction::Apply(self.draft.clone()));
+            }
+            if ui.add_enabled(modified, egui::Button::new(t!("settings-revert"))).clicked() {
+                self.revert(saved);
+            }
+            if ui.button(t!("settings-defaults")).clicked() {
+                self.reset_to_defaults();
+            }
+            if modified {
+                ui.label(t!("settings-unapplied"));
+            }
+        });
+        if let Some(ref error) = error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            self.show_interface(ui);
+            self.show_downloads(ui);
+            self.show_network(ui);
+            self.show_scanner(ui);
+            self.show_updates(ui);
+            self.show_schedule(ui);
+            self.show_notifications(ui);
+            self.show_backup(ui);
+            self.show_timeouts(ui);
+            self.show_logging(ui);
+        });
+        action
+    }
+
+    fn show_interface(&mut self, ui: &mut egui::Ui) {
+        let config = &mut self.draft.app_config;
+        section(ui, &t!("settings-section-interface"), |ui| {
+            ui.label(t!("settings-language"));
+            egui::ComboBox::from_id_source("settings_language")
+                .selected_text(config.locale().native_name())
+                .show_ui(ui, |ui| {
+                    for locale in Locale::ALL {
+                        ui.selectable_value(&mut config.language, locale.code().to_string(), locale.native_name());
+                    }
+                });
+            ui.end_row();
+
+            ui.label(t!("settings-minimize-to-tray"));
+            ui.checkbox(&mut config.minimize_to_tray, t!("settings-minimize-to-tray-hint"));
+            ui.end_row();
+        });
+
+        let appearance = &mut self.draft.appearance_config;
+        let fonts = self.font_choices.get_or_insert_with(available_fonts);
+        section(ui, &t!("settings-section-appearance"), |ui| {
+            ui.label(t!("settings-theme"));
+            egui::ComboBox::from_id_source("settings_theme")
+                .selected_text(appearance.theme.label())
+                .show_ui(ui, |ui| {
+                    for theme in ThemePreference::ALL {
+                        ui.selectable_value(&mut appearance.theme, theme, theme.label());
+                    }
+                });
+            ui.end_row();
+
+            ui.label(t!("settings-ui-scale"));
+            ui.add(egui::Slider::new(&mut appearance.ui_scale, UI_SCALE_MIN..=UI_SCALE_MAX)
+                .step_by(0.05)
+                .custom_formatter(|value, _| format!("{:.0}%", value * 100.0)));
+            ui.end_row();
+
+            ui.label(t!("settings-font-regular"));
+            font_combo(ui, "settings_font_regular", &mut appearance.fonts.regular, fonts);
+            ui.end_row();
+
+            ui.label(t!("settings-font-bold"));
+            font_combo(ui, "settings_font_bold", &mut appearance.fonts.bold, fonts);
+            ui.end_row();
+
+            ui.label(t!("settings-font-fallbacks"));
+            let mut fallbacks = appearance.fonts.fallbacks.join(",");
+            let response = ui.add(egui::TextEdit::singleline(&mut fallbacks).hint_text("seguiemj.ttf,simsun.ttc"))
+                .on_hover_text(t!("settings-font-fallbacks-hint"));
+            if response.changed() {
+                appearance.fonts.fallbacks = if fallbacks.trim().is_empty() {
+                    Vec::new()
+                } else {
+                    fallbacks.split(',').map(|font| font.trim().to_string()).collect()
+                };
+            }
+            ui.end_row();
+        });
+    }
+
+    fn show_downloads(&mut self, ui: &mut egui::Ui) {
+        let config = &mut self.draft.download_config;
+        section(ui, &t!("settings-section-downloads"), |ui| {
+            ui.label(t!("settings-download-dir"));
+            ui.text_edit_singleline(&mut config.download_dir);
+            ui.end_row();
+
+            ui.label(t!("settings-concurrency"));
+            ui.add(egui::DragValue::new(&mut config.max_concurrent_downloads).clamp_range(1..=16));
+            ui.end_row();
+
+            ui.label(t!("settings-per-host-concurrency"));
+            ui.add(egui::DragValue::new(&mut config.max_downloads_per_host).clamp_range(1..=16));
+            ui.end_row();
+
+            ui.label(t!("settings-download-rate"));
+            ui.add(egui::DragValue::new(&mut config.max_download_rate_kbps).suffix(" KB/s"))
+                .on_hover_text(t!("settings-rate-unlimited-hint"));
+            ui.end_row();
+
+            ui.label(t!("settings-task-rate"));
+            ui.add(egui::DragValue::new(&mut config.max_task_rate_kbps).suffix(" KB/s"))
+                .on_hover_text(t!("settings-rate-unlimited-hint"));
+            ui.end_row();
+
+            ui.label(t!("settings-large-download-threshold"));
+            ui.add(egui::DragValue::new(&mut config.large_download_threshold_mb).suffix(" MB"))
+                .on_hover_text(t!("settings-large-download-threshold-hint"));
+            ui.end_row();
+
+            ui.label(t!("settings-large-download-windows"));
+            window_list(ui, &mut config.large_download_windows, ("19:00", "07:00"));
+            ui.end_row();
+
+            ui.label(t!("settings-aria2"));
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut config.aria2_host);
+                ui.add(egui::DragValue::new(&mut config.aria2_port).prefix(t!("settings-port-prefix")));
+            });
+            ui.end_row();
+
+            ui.label(t!("settings-connect-timeout"));
+            ui.add(egui::DragValue::new(&mut config.timeout_secs).suffix(t!("unit-seconds-suffix")));
+            ui.end_row();
+
+            let peer_cache = &mut config.peer_cache;
+            ui.label(t!("settings-peer-cache"));
+            ui.checkbox(&mut peer_cache.enabled, t!("settings-peer-cache-enabled"))
+                .on_hover_text(t!("settings-peer-cache-hint"));
+            ui.end_row();
+
+            ui.label(t!("settings-peer-cache-serve"));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut peer_cache.serve, "");
+                ui.add_enabled(peer_cache.serve, egui::TextEdit::singleline(&mut peer_cache.listen_address).desired_width(140.0));
+            });
+            ui.end_row();
+
+            if peer_cache.enabled {
+                ui.label(t!("settings-peers"));
+                let mut peers = peer_cache.peers.join(", ");
+                if ui.add(egui::TextEdit::singleline(&mut peers).hint_text("http://192.168.1.20:47800"))
+                    .on_hover_text(t!("settings-peers-hint"))
+                    .changed()
+                {
+                    peer_cache.peers = peers.split(',').map(str::trim).filter(|peer| !peer.is_empty()).map(str::to_string).collect();
+                }
+                ui.end_row();
+
+                ui.label(t!("settings-shared-folder"));
+                optional_text(ui, &mut peer_cache.shared_folder, r"\\server\drivers", false);
+                ui.end_row();
+
+                ui.label(t!("settings-peer-timeout"));
+                ui.add(egui::DragValue::new(&mut peer_cache.peer_timeout_secs).clamp_range(1..=60).suffix(t!("unit-seconds-suffix")));
+                ui.end_row();
+            }
+        });
+    }
+
+    fn show_network(&mut self, ui: &mut egui::Ui) {
+        let config = &mut self.draft.network_config;
+        let repository = &mut self.draft.repository_config;
+        section(ui, &t!("settings-section-network"), |ui| {
+            ui.label(t!("settings-proxy"));
+            ui.horizontal(|ui| {
+                let mut use_proxy = config.proxy.is_some();
+                if ui.checkbox(&mut use_proxy, t!("settings-use-proxy")).changed() {
+                    config.proxy = use_proxy.then(String::new);
+                }
+                if let Some(ref mut proxy) = config.proxy {
+                    ui.add(egui::TextEdit::singleline(proxy).hint_text("http://127.0.0.1:7890"))
+                        .on_hover_text(t!("settings-proxy-hint"));
+                }
+            });
+            ui.end_row();
+
+            if config.proxy.is_some() {
+                ui.label(t!("settings-proxy-auth"));
+                ui.horizontal(|ui| {
+                    optional_text(ui, &mut config.proxy_username, &t!("settings-proxy-username"), false);
+                    optional_text(ui, &mut config.proxy_password, &t!("settings-proxy-password"), true);
+                });
+                ui.end_row();
+
+                ui.label(t!("settings-no-proxy"));
+                let mut no_proxy = config.no_proxy.join(", ");
+                if ui.add(egui::TextEdit::singleline(&mut no_proxy).hint_text("localhost, .corp.example.com"))
+                    .on_hover_text(t!("settings-no-proxy-hint"))
+                    .changed()
+                {
+                    config.no_proxy = no_proxy.split(',').map(str::trim).filter(|host| !host.is_empty()).map(str::to_string).collect();
+                }
+                ui.end_row();
+            }
+
+            ui.label(t!("settings-ca-certificates"));
+            ui.vertical(|ui| {
+                let mut removed = None;
+                for (index, path) in config.ca_certificates.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(path).hint_text(t!("settings-ca-certificate-hint")));
+                        if ui.small_button(t!("action-delete")).clicked() {
+                            removed = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = removed {
+                    config.ca_certificates.remove(index);
+                }
+                if ui.small_button(t!("settings-add-certificate")).clicked() {
+                    config.ca_certificates.push(String::new());
+                }
+            });
+            ui.end_row();
+
+            ui.label(t!("settings-network-timeouts"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut config.connect_timeout_secs).clamp_range(1..=300)
+                    .prefix(t!("settings-connect-prefix")).suffix(t!("unit-seconds-suffix")));
+                ui.add(egui::DragValue::new(&mut config.request_timeout_secs).clamp_range(1..=3600)
+                    .prefix(t!("settings-request-prefix")).suffix(t!("unit-seconds-suffix")));
+            });
+            ui.end_row();
+
+            ui.label(t!("settings-retries"));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut config.max_retries).clamp_range(0..=10));
+                ui.add(egui::DragValue::new(&mut config.retry_backoff_ms).clamp_range(0..=60_000)
+                    .prefix(t!("settings-retry-backoff-prefix")).suffix(" ms"));
+            });
+            ui.end_row();
+
+            ui.label("User-Agent:");
+            ui.add(egui::TextEdit::singleline(&mut config.user_agent).hint_text(NetworkConfig::default().user_agent()));
+            ui.end_row();
+
+            ui.label(t!("settings-repository"));
+            ui.horizontal(|ui| {
+                optional_text(ui, &mut repository.url, "http://drivers.example.com:8080", false);
+                optional_text(ui, &mut repository.api_key, &t!("settings-repository-api-key"), true);
+                ui.add_enabled(repository.url.is_some(), egui::Checkbox::new(&mut repository.vendor_fallback, t!("settings-vendor-fallback")));
+            }).response.on_hover_text(t!("settings-repository-hint"));
+            ui.end_row();
+        });
+    }
+
+    fn show_scanner(&mut self, ui: &mut egui::Ui) {
+        let config = &mut self.draft.scanner_config;
+        section(ui, &t!("settings-section-scanner"), |ui| {
+            ui.label(t!("settings-scan-methods"));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut config.use_setupapi, "SetupAPI");
+                ui.checkbox(&mut config.use_wmi, "WMI").on_hover_text(t!("settings-wmi-hint"));
+            });
+            ui.end_row();
+
+            ui.label(t!("settings-hidden-devices"));
+            ui.checkbox(&mut config.include_hidden, t!("settings-include-hidden"));
+            ui.end_row();
+
+            ui.label(t!("settings-device-classes"));
+            ui.horizontal_wrapped(|ui| {
+                let mut removed = None;
+                for (index, class) in config.device_classes.iter().enumerate() {
+                    if ui.small_button(format!("{} ×", class)).on_hover_text(t!("action-remove")).clicked() {
+                        removed = Some(index);
+                    }
+                }
+                if let Some(index) = removed {
+                    config.device_classes.remove(index);
+                }
+                egui::ComboBox::from_id_source("settings_device_class")
+                    .selected_text(if config.device_classes.is_empty() { t!("settings-all-classes") } else { t!("settings-add-class") })
+                    .show_ui(ui, |ui| {
+                        for class in DEVICE_CLASSES {
+                            if !config.device_classes.iter().any(|c| c == class) && ui.selectable_label(false, class).clicked() {
+                                config.device_classes.push(class.to_string());
+                            }
+                        }
+                    });
+            });
+            ui.end_row();
+        });
+    }
+
+    fn show_updates(&mut self, ui: &mut egui::Ui) {
+        let preferences = &mut self.draft.update_preferences;
+        section(ui, &t!("settings-section-channels"), |ui| {
+            ui.label(t!("settings-beta"));
+            ui.checkbox(&mut preferences.include_beta, t!("settings-include-beta"));
+            ui.end_row();
+
+            ui.label(t!("settings-ignored-pinned"));
+            ui.label(t!("settings-ignored-pinned-summary", ignored = preferences.ignored.len(), pinned = preferences.pinned.len()));
+            ui.end_row();
+        });
+    }
+
+    fn show_schedule(&mut self, ui: &mut egui::Ui) {
+        let config = &mut self.draft.schedule_config;
+        section(ui, &t!("settings-section-schedule"), |ui| {
+            ui.label(t!("settings-schedule-enabled"));
+            ui.checkbox(&mut config.enabled, t!("settings-schedule-enable"));
+            ui.end_row();
+
+            ui.label(t!("settings-schedule-action"));
+            egui::ComboBox::from_id_source("settings_schedule_action")
+                .selected_text(config.action.label())
+                .show_ui(ui, |ui| {
+                    for action in SCHEDULED_ACTIONS {
+                        ui.selectable_value(&mut config.action, action, action.label());
+                    }
+                });
+            ui.end_row();
+
+            ui.label(t!("settings-schedule-plan"));
+            ui.horizontal(|ui| {
+                let mut use_cron = config.cron.is_some();
+                if ui.checkbox(&mut use_cron, t!("settings-use-cron")).changed() {
+                    config.cron = use_cron.then(|| "0 3 * * *".to_string());
+                }
+                match config.cron {
+                    Some(ref mut cron) => {
+                        ui.add(egui::TextEdit::singleline(cron).hint_text(t!("settings-cron-hint")));
+                    }
+                    None => {
+                        ui.label(t!("settings-schedule-every"));
+                        ui.add(egui::DragValue::new(&mut config.interval_hours).clamp_range(1..=720).suffix(t!("unit-hours-suffix")));
+                    }
+                }
+            });
+            ui.end_row();
+
+            ui.label(t!("settings-jitter"));
+            ui.add(egui::DragValue::new(&mut config.jitter_minutes).clamp_range(0..=1440).suffix(t!("unit-minutes-suffix")));
+            ui.end_row();
+
+            ui.label(t!("settings-maintenance-windows"));
+            window_list(ui, &mut config.maintenance_windows, ("02:00", "05:00"));
+            ui.end_row();
+        });
+    }
+
+    fn show_notifications(&mut self, ui: &mut egui::Ui) {
+        let config = &mut self.draft.notification_config;
+        section(ui, &t!("settings-section-notifications"), |ui| {
+            ui.label(t!("settings-notifications-enabled"));
+            ui.checkbox(&mut config.enabled, t!("settings-notifications-hint"));
+            ui.end_row();
+
+            ui.label(t!("settings-notification-channels"));
+            ui.vertical(|ui| {
+                let mut removed = None;
+                for (index, channel) in config.channels.iter_mut().enumerate() {
+                    ui.group(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.strong(channel.target.label());
+                            channel_target_fields(ui, index, &mut channel.target);
+                            if ui.small_button(t!("action-delete")).clicked() {
+                                removed = Some(index);
+                            }
+                        });
+                        channel_event_checkboxes(ui, channel);
+                    });
+                }
+                if let Some(index) = removed {
+                    config.channels.remove(index);
+                }
+                ui.horizontal(|ui| {
+                    let targets = [
+                        ChannelTarget::Desktop,
+                        ChannelTarget::Webhook { url: String::new(), template: WebhookTemplate::Generic, headers: Default::default() },
+                        ChannelTarget::File { directory: String::new() },
+                    ];
+                    for target in targets {
+                        if ui.small_button(t!("settings-add-channel", channel = target.label())).clicked() {
+                            config.channels.push(NotificationChannel::new(target));
+                        }
+                    }
+                });
+            });
+            ui.end_row();
+        });
+    }
+
+    fn show_backup(&mut self, ui: &mut egui::Ui) {
+        let backup = &mut self.draft.backup_config;
+        let retention = &mut backup.retention;
+        section(ui, &t!("settings-section-backup"), |ui| {
+            for (label, value) in [
+                (t!("settings-keep-last"), &mut retention.keep_last),
+                (t!("settings-keep-daily"), &mut retention.keep_daily),
+                (t!("settings-keep-weekly"), &mut retention.keep_weekly),
+                (t!("settings-keep-monthly"), &mut retention.keep_monthly),
+            ] {
+                ui.label(label);
+                ui.add(egui::DragValue::new(value).clamp_range(0..=365).suffix(t!("unit-snapshots-suffix")));
+                ui.end_row();
+            }
+            ui.label(t!("settings-max-rollback-points"));
+            ui.add(egui::DragValue::new(&mut backup.max_rollback_points).clamp_range(1..=100));
+            ui.end_row();
+        });
+    }
+
+    fn show_timeouts(&mut self, ui: &mut egui::Ui) {
+        let timeouts = &mut self.draft.timeout_config;
+        section(ui, &t!("settings-section-timeouts"), |ui| {
+            for (label, value) in [
+                (t!("settings-timeout-scan"), &mut timeouts.scan_secs),
+                (t!("settings-timeout-match"), &mut timeouts.match_secs),
+                (t!("settings-timeout-download"), &mut timeouts.download_secs),
+                (t!("settings-timeout-install"), &mut timeouts.install_secs),
+            ] {
+                ui.label(label);
+                ui.add(egui::DragValue::new(value).suffix(t!("unit-seconds-suffix")));
+                ui.end_row();
+            }
+        });
+    }
+
+    fn show_logging(&mut self, ui: &mut egui::Ui) {
+        let config = &mut self.draft.app_config;
+        section(ui, &t!("settings-section-logging"), |ui| {
+            ui.label(t!("settings-log-level"));
+            egui::ComboBox::from_id_source("settings_log_level")
+                .selected_text(config.log_level.as_str())
+                .show_ui(ui, |ui| {
+                    for level in LOG_LEVELS {
+                        ui.selectable_value(&mut config.log_level, level.to_string(), level);
+                    }
+                })
+                .response
+                .on_hover_text(t!("settings-log-level-hint"));
+            ui.end_row();
+        });
+    }
+}
+
+/// 空值表示自动选择，也可以直接输入系统字体目录外的字体路径
+fn font_combo(ui: &mut egui::Ui, id: &str, value: &mut String, fonts: &[String]) {
+    ui.horizontal(|ui| {
+        let selected = if value.is_empty() { t!("settings-font-auto") } else { value.clone() };
+        egui::ComboBox::from_id_source(id)
+            .selected_text(selected)
+            .width(220.0)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(value, String::new(), t!("settings-font-auto"));
+                for font in fonts {
+                    ui.selectable_value(value, font.clone(), font);
+                }
+            });
+        ui.add(egui::TextEdit::singleline(value).desired_width(160.0).hint_text(t!("settings-font-path-hint")));
+    });
+}
+
+fn channel_target_fields(ui: &mut egui::Ui, index: usize, target: &mut ChannelTarget) {
+    match target {
+        ChannelTarget::Desktop => {}
+        ChannelTarget::Webhook { url, template, .. } => {
+            ui.add(egui::TextEdit::singleline(url).desired_width(260.0).hint_text("https://"));
+            egui::ComboBox::from_id_source(("settings_webhook_template", index))
+                .selected_text(template.label())
+                .show_ui(ui, |ui| {
+                    for option in WebhookTemplate::ALL {
+                        ui.selectable_value(template, option, option.label());
+                    }
+                });
+        }
+        ChannelTarget::File { directory } => {
+            ui.add(egui::TextEdit::singleline(directory).desired_width(260.0).hint_text(t!("settings-notify-directory-hint")));
+        }
+    }
+}
+
+/// 事件列表为空表示全部事件，至少保留一个勾选
+fn channel_event_checkboxes(ui: &mut egui::Ui, channel: &mut NotificationChannel) {
+    let mut selected = if channel.events.is_empty() { NotificationKind::ALL.to_vec() } else { channel.events.clone() };
+    ui.horizontal(|ui| {
+        for kind in NotificationKind::ALL {
+            let mut checked = selected.contains(&kind);
+            let last = checked && selected.len() == 1;
+            if ui.add_enabled(!last, egui::Checkbox::new(&mut checked, kind.label())).changed() {
+                if checked {
+                    selected.push(kind);
+                } else {
+                    selected.retain(|other| *other != kind);
+                }
+            }
+        }
+    });
+    channel.events = if selected.len() == NotificationKind::ALL.len() {
+        Vec::new()
+    } else {
+        NotificationKind::ALL.into_iter().filter(|kind| selected.contains(kind)).collect()
+    };
+}
+
+/// 默认展开的分组，内容按两列排列
+/// 可选文本的输入框，清空时设为 None
+fn optional_text(ui: &mut egui::Ui, value: &mut Option<String>, hint: &str, password: bool) {
+    let mut text = value.clone().unwrap_or_default();
+    let edit = egui::TextEdit::singleline(&mut text).hint_text(hint).password(password).desired_width(120.0);
+    if ui.add(edit).changed() {
+        *value = (!text.is_empty()).then_some(text);
+    }
+}
+
+/// 时间段列表的编辑器，新增的时间段默认为 start 到 end
+fn window_list(ui: &mut egui::Ui, windows: &mut Vec<MaintenanceWindow>, (start, end): (&str, &str)) {
+    ui.vertical(|ui| {
+        let mut removed = None;
+        for (index, window) in windows.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                let mut days = window.days.join(",");
+                if ui.add(egui::TextEdit::singleline(&mut days).desired_width(120.0).hint_text(t!("settings-every-day"))).changed() {
+                    window.days = if days.trim().is_empty() {
+                        Vec::new()
+                    } else {
+                        days.split(',').map(|day| day.trim().to_string()).collect()
+                    };
+                }
+                ui.add(egui::TextEdit::singleline(&mut window.start).desired_width(50.0));
+                ui.label(t!("settings-window-to"));
+                ui.add(egui::TextEdit::singleline(&mut window.end).desired_width(50.0));
+                if ui.small_button(t!("action-delete")).clicked() {
+                    removed = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed {
+            windows.remove(index);
+        }
+        if ui.small_button(t!("settings-add-window")).on_hover_text(t!("settings-window-hint")).clicked() {
+            windows.push(MaintenanceWindow {
+                days: Vec::new(),
+                start: start.to_string(),
+                end: end.to_string(),
+            });
+        }
+    });
+}
+
+fn section(ui: &mut egui::Ui, title: &str, add_contents: impl FnOnce(&mut egui::Ui)) {
+    egui::CollapsingHeader::new(title)
+        .default_open(true)
+        .show(ui, |ui| {
+            egui::Grid::new(title)
+                .num_columns(2)
+                .spacing([20.0, 8.0])
+                .show(ui, add_contents);
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modify_validate_and_revert() {
+        let saved = ConfigManager::new();
+        let mut view = SettingsView::new(&saved);
+        assert!(!view.is_modified(&saved));
+
+        view.draft.network_config.proxy = Some("127.0.0.1:7890".to_string());
+        assert!(view.is_modified(&saved));
+        assert!(view.validation_error().is_some());
+        view.draft.network_config.proxy = Some("http://127.0.0.1:7890".to_string());
+        view.draft.scanner_config.use_setupapi = false;
+        assert!(view.validation_error().is_some());
+        view.draft.scanner_config.use_wmi = true;
+        assert_eq!(view.validation_error(), None);
+
+        view.revert(&saved);
+        assert!(!view.is_modified(&saved));
+
+        view.draft.update_preferences.pin("PCI\\VEN_8086");
+        view.draft.backup_config.retention.keep_last = 0;
+        view.reset_to_defaults();
+        assert_eq!(view.draft.backup_config.retention.keep_last, 5);
+        assert_eq!(view.draft.update_preferences.pinned.len(), 1);
+    }
+}
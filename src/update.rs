@@ -3,7 +3,6 @@ use crate::scan::{DriverInfo};
 use std::path::{Path, PathBuf};
 use std::fs;
 use std::sync::Arc;
-use std::time::Duration;
 use tokio::sync::Mutex;
 use futures_util::StreamExt;
 
@@ -100,10 +99,7 @@ pub async fn download_driver_with_progress(
     version: &str,
     callback: Option<DownloadProgressCallback>
 ) -> Result<DownloadResult, HamsterError> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(300))
-        .build()
-        .map_err(|e| HamsterError::NetworkError(format!("创建HTTP客户端失败: {}", e)))?;
+    let client = crate::utils::download_http_client();
     
     let response = client.get(url).send().await
         .map_err(|e| HamsterError::NetworkError(format!("下载失败: {}", e)))?;
@@ -509,21 +505,9 @@ pub fn get_download_progress(downloaded: usize, total: usize) -> f64 {
     }
 }
 
-/// 格式化文件大小
+/// 格式化文件大小，单位和字节数的写法随界面语言变化
 pub fn format_file_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-    
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} bytes", bytes)
-    }
+    crate::i18n::format_size(bytes)
 }
 
 /// 自动安装驱动更新
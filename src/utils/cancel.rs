@@ -0,0 +1,175 @@
+//! 协作式取消
+//!
+//! 耗时操作定期检查 CancellationToken，或用 run_cancellable 与取消和超时竞争。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use crate::utils::error::{HamsterError, Result};
+use crate::t;
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+/// 取消令牌，克隆后共享同一状态
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::SeqCst);
+        self.state.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 已取消时返回 Cancelled 错误
+    pub fn check(&self, operation: &str) -> Result<()> {
+        if self.is_cancelled() {
+            Err(HamsterError::Cancelled(operation.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 等待直到被取消
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.state.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ActiveOperation {
+    token: CancellationToken,
+    depth: usize,
+}
+
+/// 当前操作的取消入口
+///
+/// 最外层操作开始时换一个新令牌，嵌套的子操作共用同一个令牌，
+/// 因此取消会作用于整个批量操作而不只是正在执行的那一步。
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    active: Arc<Mutex<ActiveOperation>>,
+}
+
+impl CancelHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始一个操作，守卫释放时操作结束
+    pub fn begin(&self) -> OperationGuard {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        if active.depth == 0 {
+            active.token = CancellationToken::new();
+        }
+        active.depth += 1;
+        OperationGuard { handle: self.clone(), token: active.token.clone() }
+    }
+
+    /// 取消当前操作，没有操作在执行时不做任何事
+    pub fn cancel(&self) {
+        let active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        if active.depth > 0 {
+            active.token.cancel();
+        }
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.active.lock().unwrap_or_else(|e| e.into_inner()).depth > 0
+    }
+}
+
+/// 正在执行的操作
+pub struct OperationGuard {
+    handle: CancelHandle,
+    token: CancellationToken,
+}
+
+impl OperationGuard {
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        let mut active = self.handle.active.lock().unwrap_or_else(|e| e.into_inner());
+        active.depth = active.depth.saturating_sub(1);
+    }
+}
+
+/// 在超时或取消前完成 future，timeout 为 None 时不限时
+pub async fn run_cancellable<T, F>(
+    token: &CancellationToken,
+    timeout: Option<Duration>,
+    operation: &str,
+    future: F,
+) -> Result<T>
+where
+    F: std::future::Future<Output = T>,
+{
+    let limited = async {
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, future).await
+                .map_err(|_| HamsterError::TimeoutError(t!("timeout-exceeded", operation = operation, seconds = timeout.as_secs()))),
+            None => Ok(future.await),
+        }
+    };
+    tokio::select! {
+        result = limited => result,
+        _ = token.cancelled() => Err(HamsterError::Cancelled(operation.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancel_and_timeout() {
+        let handle = CancelHandle::new();
+        let operation = handle.begin();
+        // 嵌套操作共用外层的令牌
+        let nested = handle.begin();
+        let slow = tokio::time::sleep(Duration::from_secs(30));
+
+        let canceller = handle.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+        let result = run_cancellable(nested.token(), None, "扫描", slow).await;
+        assert!(matches!(result, Err(HamsterError::Cancelled(_))));
+        drop(nested);
+        assert!(operation.token().check("批量更新").is_err());
+        drop(operation);
+        assert!(!handle.is_busy());
+
+        // 新操作使用新令牌，不受之前取消的影响
+        let operation = handle.begin();
+        let token = operation.token();
+        let result = run_cancellable(token, Some(Duration::from_millis(20)), "下载", std::future::pending::<()>()).await;
+        assert!(matches!(result, Err(HamsterError::TimeoutError(_))));
+        assert_eq!(run_cancellable(token, None, "安装", async { 7 }).await.unwrap(), 7);
+    }
+}
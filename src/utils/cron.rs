@@ -0,0 +1,189 @@
+//! Cron表达式解析
+//!
+//! 支持标准的五段格式：分 时 日 月 周，字段内可用 `*`、`a-b`、`*/n`、`a-b/n` 和逗号列表，
+//! 月份和星期也可以写英文缩写（jan、mon 等）。
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use crate::utils::error::{HamsterError, Result};
+use crate::t;
+
+const MONTH_NAMES: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+// 最多向后查找的天数，覆盖 2 月 29 日这类四年一次的表达式
+const MAX_SEARCH_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronExpression {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    // 日和周都被限制时，两者满足其一即可（与 cron 一致）
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronExpression {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(HamsterError::ParseError(t!("cron-field-count", expression = expression)));
+        }
+
+        let minutes = parse_field(fields[0], 0, 59, &[])?;
+        let hours = parse_field(fields[1], 0, 23, &[])?;
+        let days_of_month = parse_field(fields[2], 1, 31, &[])?;
+        let months = parse_field(fields[3], 1, 12, &MONTH_NAMES)?;
+        // 周日可以写成 0 或 7
+        let mut days_of_week = parse_field(fields[4], 0, 7, &WEEKDAY_NAMES)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes,
+            hours: hours as u32,
+            days_of_month: days_of_month as u32,
+            months: months as u16,
+            days_of_week: days_of_week as u8,
+            dom_restricted: fields[2] != "*",
+            dow_restricted: fields[4] != "*",
+        })
+    }
+
+    /// 严格晚于 after 的下一个触发时间（精确到分钟）
+    pub fn next_after_naive(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut date = start.date();
+
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.matches_date(date) {
+                let first_minute = if date == start.date() { start.hour() * 60 + start.minute() } else { 0 };
+                for minute_of_day in first_minute..24 * 60 {
+                    let (hour, minute) = (minute_of_day / 60, minute_of_day % 60);
+                    if self.hours & (1 << hour) != 0 && self.minutes & (1 << minute) != 0 {
+                        return date.and_hms_opt(hour, minute, 0);
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    /// 按本地时间计算下一个触发时间，夏令时跳过的时刻顺延到下一个匹配
+    pub fn next_after(&self, after: chrono::DateTime<Local>) -> Option<chrono::DateTime<Local>> {
+        let mut cursor = after.naive_local();
+        loop {
+            let next = self.next_after_naive(cursor)?;
+            if let Some(local) = Local.from_local_datetime(&next).earliest() {
+                if local > after {
+                    return Some(local);
+                }
+            }
+            cursor = next;
+        }
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+}
+
+impl std::str::FromStr for CronExpression {
+    type Err = HamsterError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse()
+                    .map_err(|_| HamsterError::ParseError(t!("cron-invalid-step", part = part)))?;
+                if step == 0 {
+                    return Err(HamsterError::ParseError(t!("cron-zero-step", part = part)));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start, min, names)?, parse_value(end, min, names)?)
+        } else {
+            let value = parse_value(range, min, names)?;
+            // "5/15" 表示从5开始每15一次
+            (value, if step > 1 { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(HamsterError::ParseError(t!("cron-out-of-range", min = min, max = max, part = part)));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(value: &str, min: u32, names: &[&str]) -> Result<u32> {
+    let lower = value.to_ascii_lowercase();
+    if let Some(index) = names.iter().position(|name| *name == lower) {
+        // 月份名称从1开始，星期名称从0开始
+        return Ok(index as u32 + min);
+    }
+    value.parse()
+        .map_err(|_| HamsterError::ParseError(t!("cron-invalid-value", value = value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_next_after() {
+        let daily = CronExpression::parse("30 3 * * *").unwrap();
+        assert_eq!(daily.next_after_naive(at("2024-05-01 03:29")), Some(at("2024-05-01 03:30")));
+        assert_eq!(daily.next_after_naive(at("2024-05-01 03:30")), Some(at("2024-05-02 03:30")));
+
+        let weekdays = CronExpression::parse("*/20 22-23 * * mon-fri").unwrap();
+        // 2024-05-03 是周五
+        assert_eq!(weekdays.next_after_naive(at("2024-05-03 23:45")), Some(at("2024-05-06 22:00")));
+
+        let either = CronExpression::parse("0 0 1 * sun").unwrap();
+        // 2024-05-05 是周日，早于 6 月 1 日
+        assert_eq!(either.next_after_naive(at("2024-05-02 00:00")), Some(at("2024-05-05 00:00")));
+
+        let leap = CronExpression::parse("0 12 29 feb *").unwrap();
+        assert_eq!(leap.next_after_naive(at("2024-03-01 00:00")), Some(at("2028-02-29 12:00")));
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for expression in ["* * * *", "60 * * * *", "*/0 * * * *", "5-1 * * * *", "0 0 * * funday"] {
+            assert!(CronExpression::parse(expression).is_err(), "{}", expression);
+        }
+        assert!(CronExpression::parse("0 0 * * 7").is_ok());
+    }
+}
@@ -2,81 +2,125 @@
 //!
 //! 本模块定义了项目中使用的所有错误类型
 
-use thiserror::Error;
+use std::fmt;
+use crate::t;
 
-
-/// 项目统一错误类型
-#[derive(Error, Debug)]
+/// 项目统一错误类型，显示时按错误代码取当前语言的信息
+#[derive(Debug)]
 pub enum HamsterError {
     /// 硬件扫描错误
-    #[error("硬件扫描错误: {0}")]
     ScanError(String),
 
     /// 驱动备份错误
-    #[error("驱动备份错误: {0}")]
     BackupError(String),
 
     /// 驱动恢复错误
-    #[error("驱动恢复错误: {0}")]
     RestoreError(String),
 
     /// 驱动更新错误
-    #[error("驱动更新错误: {0}")]
     UpdateError(String),
 
     /// 驱动安装错误
-    #[error("驱动安装错误: {0}")]
     InstallError(String),
 
     /// 驱动签名错误
-    #[error("驱动签名错误: {0}")]
     SignatureError(String),
 
     /// 网络错误
-    #[error("网络错误: {0}")]
     NetworkError(String),
 
     /// IO错误
-    #[error("IO错误: {0}")]
     IoError(String),
 
     /// 数据库错误
-    #[error("数据库错误: {0}")]
     DatabaseError(String),
 
     /// 配置错误
-    #[error("配置错误: {0}")]
     ConfigError(String),
 
     /// 解析错误
-    #[error("解析错误: {0}")]
     ParseError(String),
 
     /// 权限错误
-    #[error("权限错误: {0}")]
     PermissionError(String),
 
     /// 初始化错误
-    #[error("初始化错误: {0}")]
     InitError(String),
 
     /// 下载错误
-    #[error("下载错误: {0}")]
     DownloadError(String),
 
     /// 验证错误
-    #[error("验证错误: {0}")]
     ValidationError(String),
 
     /// 超时错误
-    #[error("超时错误: {0}")]
     TimeoutError(String),
 
+    /// 操作被取消
+    Cancelled(String),
+
     /// 未知错误
-    #[error("未知错误: {0}")]
     Unknown(String),
 }
 
+impl HamsterError {
+    /// 错误代码，对应消息目录中的 error-<代码>
+    pub fn code(&self) -> &'static str {
+        match self {
+            HamsterError::ScanError(_) => "scan",
+            HamsterError::BackupError(_) => "backup",
+            HamsterError::RestoreError(_) => "restore",
+            HamsterError::UpdateError(_) => "update",
+            HamsterError::InstallError(_) => "install",
+            HamsterError::SignatureError(_) => "signature",
+            HamsterError::NetworkError(_) => "network",
+            HamsterError::IoError(_) => "io",
+            HamsterError::DatabaseError(_) => "database",
+            HamsterError::ConfigError(_) => "config",
+            HamsterError::ParseError(_) => "parse",
+            HamsterError::PermissionError(_) => "permission",
+            HamsterError::InitError(_) => "init",
+            HamsterError::DownloadError(_) => "download",
+            HamsterError::ValidationError(_) => "validation",
+            HamsterError::TimeoutError(_) => "timeout",
+            HamsterError::Cancelled(_) => "cancelled",
+            HamsterError::Unknown(_) => "unknown",
+        }
+    }
+
+    /// 错误的具体说明
+    pub fn detail(&self) -> &str {
+        match self {
+            HamsterError::ScanError(detail)
+            | HamsterError::BackupError(detail)
+            | HamsterError::RestoreError(detail)
+            | HamsterError::UpdateError(detail)
+            | HamsterError::InstallError(detail)
+            | HamsterError::SignatureError(detail)
+            | HamsterError::NetworkError(detail)
+            | HamsterError::IoError(detail)
+            | HamsterError::DatabaseError(detail)
+            | HamsterError::ConfigError(detail)
+            | HamsterError::ParseError(detail)
+            | HamsterError::PermissionError(detail)
+            | HamsterError::InitError(detail)
+            | HamsterError::DownloadError(detail)
+            | HamsterError::ValidationError(detail)
+            | HamsterError::TimeoutError(detail)
+            | HamsterError::Cancelled(detail)
+            | HamsterError::Unknown(detail) => detail,
+        }
+    }
+}
+
+impl fmt::Display for HamsterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&t!(&format!("error-{}", self.code()), detail = self.detail()))
+    }
+}
+
+impl std::error::Error for HamsterError {}
+
 /// 项目统一Result类型
 pub type Result<T> = std::result::Result<T, HamsterError>;
 
@@ -0,0 +1,267 @@
+//! 共享的 HTTP 客户端
+//!
+//! 按网络配置创建 reqwest 客户端，统一代理、证书、超时、重试和 User-Agent。
+//! 配置修改后调用 set_network_config，之后获取的客户端使用新的配置。
+
+use std::sync::RwLock;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use crate::config::NetworkConfig;
+use crate::utils::{HamsterError, Result};
+
+// 服务器要求的重试等待时间超过该值时按退避时间等待
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+static SHARED: Lazy<RwLock<SharedClients>> = Lazy::new(|| {
+    RwLock::new(SharedClients::new(&NetworkConfig::default()).unwrap_or_else(|_| SharedClients::fallback()))
+});
+
+struct SharedClients {
+    config: NetworkConfig,
+    api: HttpClient,
+    downloads: HttpClient,
+    lan: HttpClient,
+}
+
+impl SharedClients {
+    fn new(config: &NetworkConfig) -> Result<Self> {
+        Ok(Self {
+            config: config.clone(),
+            api: HttpClient::new(config)?,
+            downloads: HttpClient::for_downloads(config)?,
+            lan: HttpClient::for_lan(config)?,
+        })
+    }
+
+    fn fallback() -> Self {
+        let client = HttpClient { client: reqwest::Client::new(), retry: RetryPolicy::default() };
+        Self { config: NetworkConfig::default(), api: client.clone(), downloads: client.clone(), lan: client }
+    }
+}
+
+/// 应用网络配置，配置无效时保留原来的客户端并返回错误
+pub fn set_network_config(config: &NetworkConfig) -> Result<()> {
+    let clients = SharedClients::new(config)?;
+    *SHARED.write().unwrap_or_else(|e| e.into_inner()) = clients;
+    Ok(())
+}
+
+/// 当前使用的网络配置，供不经过共享客户端的下载工具（如 aria2）使用
+pub fn network_config() -> NetworkConfig {
+    SHARED.read().unwrap_or_else(|e| e.into_inner()).config.clone()
+}
+
+/// 用于 API 请求和网页的客户端，请求有总超时时间
+pub fn http_client() -> HttpClient {
+    SHARED.read().unwrap_or_else(|e| e.into_inner()).api.clone()
+}
+
+/// 用于下载驱动文件的客户端，只限制连接和读取的超时，不限制整个下载的时间
+pub fn download_http_client() -> HttpClient {
+    SHARED.read().unwrap_or_else(|e| e.into_inner()).downloads.clone()
+}
+
+/// 请求失败时的重试策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    /// 第一次重试前的等待时间，之后每次翻倍
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_retries: 0, backoff: Duration::from_millis(500) }
+    }
+}
+
+impl RetryPolicy {
+    /// 第 attempt 次重试（从0开始）前的等待时间
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.backoff.saturating_mul(1 << attempt.min(10))
+    }
+}
+
+/// 按网络配置创建的客户端，克隆后共享连接池
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    retry: RetryPolicy,
+}
+
+impl HttpClient {
+    pub fn new(config: &NetworkConfig) -> Result<Self> {
+        Self::build(config, builder(config)?.timeout(config.request_timeout()))
+    }
+
+    /// 下载大文件用的客户端，读取超时代替总超时
+    pub fn for_downloads(config: &NetworkConfig) -> Result<Self> {
+        Self::build(config, builder(config)?.read_timeout(config.request_timeout()))
+    }
+
+    /// 直接连接局域网地址的下载客户端，忽略配置和系统的代理
+    pub fn for_lan(config: &NetworkConfig) -> Result<Self> {
+        let direct = NetworkConfig { proxy: None, ..config.clone() };
+        Self::build(config, builder(&direct)?.no_proxy().read_timeout(config.request_timeout()))
+    }
+
+    fn build(config: &NetworkConfig, builder: reqwest::ClientBuilder) -> Result<Self> {
+        let client = builder.build()
+            .map_err(|e| HamsterError::NetworkError(format!("创建HTTP客户端失败: {}", e)))?;
+        let retry = RetryPolicy {
+            max_retries: config.max_retries,
+            backoff: Duration::from_millis(config.retry_backoff_ms),
+        };
+        Ok(Self { client, retry })
+    }
+
+    pub fn get(&self, url: &str) -> HttpRequest {
+        self.request(self.client.get(url))
+    }
+
+    pub fn head(&self, url: &str) -> HttpRequest {
+        self.request(self.client.head(url))
+    }
+
+    pub fn post(&self, url: &str) -> HttpRequest {
+        self.request(self.client.post(url))
+    }
+
+    fn request(&self, builder: RequestBuilder) -> HttpRequest {
+        HttpRequest { builder, retry: self.retry }
+    }
+}
+
+/// 用于局域网中其他计算机的客户端，不经过任何代理
+pub fn lan_http_client() -> HttpClient {
+    SHARED.read().unwrap_or_else(|e| e.into_inner()).lan.clone()
+}
+
+/// 按重试策略发送的请求
+pub struct HttpRequest {
+    builder: RequestBuilder,
+    retry: RetryPolicy,
+}
+
+impl HttpRequest {
+    pub fn header(mut self, name: &str, value: impl AsRef<str>) -> Self {
+        self.builder = self.builder.header(name, value.as_ref());
+        self
+    }
+
+    pub fn json<T: Serialize + ?Sized>(mut self, json: &T) -> Self {
+        self.builder = self.builder.json(json);
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<reqwest::Body>) -> Self {
+        self.builder = self.builder.body(body);
+        self
+    }
+
+    pub fn bearer_auth(mut self, token: impl std::fmt::Display) -> Self {
+        self.builder = self.builder.bearer_auth(token);
+        self
+    }
+
+    /// 覆盖客户端的总超时时间
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.builder = self.builder.timeout(timeout);
+        self
+    }
+
+    /// 发送请求，连接失败、超时、429 和 5xx 时按策略重试，流式请求体无法重发时只发送一次
+    pub async fn send(self) -> reqwest::Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let Some(request) = self.builder.try_clone() else {
+                return self.builder.send().await;
+            };
+            let result = request.send().await;
+            if attempt >= self.retry.max_retries || !should_retry(&result) {
+                return result;
+            }
+            let delay = result.as_ref().ok()
+                .and_then(retry_after)
+                .filter(|delay| *delay <= MAX_RETRY_AFTER)
+                .unwrap_or_else(|| self.retry.delay(attempt));
+            tracing::debug!("请求失败，{} 毫秒后第 {} 次重试", delay.as_millis(), attempt + 1);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+fn builder(config: &NetworkConfig) -> Result<reqwest::ClientBuilder> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(config.user_agent())
+        .connect_timeout(config.connect_timeout());
+
+    if let Some(ref url) = config.proxy {
+        let mut proxy = reqwest::Proxy::all(url)
+            .map_err(|e| HamsterError::ConfigError(format!("代理地址无效 {}: {}", url, e)))?;
+        if let Some(ref username) = config.proxy_username {
+            proxy = proxy.basic_auth(username, config.proxy_password.as_deref().unwrap_or_default());
+        }
+        proxy = proxy.no_proxy(reqwest::NoProxy::from_string(&config.no_proxy.join(",")));
+        builder = builder.proxy(proxy);
+    }
+
+    for path in &config.ca_certificates {
+        let pem = std::fs::read(path)
+            .map_err(|e| HamsterError::ConfigError(format!("无法读取证书 {}: {}", path, e)))?;
+        let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| HamsterError::ConfigError(format!("证书格式无效 {}: {}", path, e)))?;
+        if certificates.is_empty() {
+            return Err(HamsterError::ConfigError(format!("证书文件中没有证书: {}", path)));
+        }
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    Ok(builder)
+}
+
+fn should_retry(result: &reqwest::Result<Response>) -> bool {
+    match result {
+        Ok(response) => {
+            let status = response.status();
+            status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+        }
+        Err(e) => e.is_connect() || e.is_timeout(),
+    }
+}
+
+// Retry-After 头要求的等待时间，可以是秒数或 HTTP 日期
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::{http_response, HttpStub};
+
+    #[tokio::test]
+    async fn test_retry_on_server_error() {
+        // 前两次返回 503，第三次成功
+        let server = HttpStub::serve(["503 Service Unavailable", "503 Service Unavailable", "200 OK"]
+            .map(|status| http_response(status, &[], "ok"))
+            .to_vec()).await;
+
+        let config = NetworkConfig { max_retries: 2, retry_backoff_ms: 10, ..NetworkConfig::default() };
+        let client = HttpClient::new(&config).unwrap();
+        let response = client.get(&format!("{}/", server.url())).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(server.requests().await.len(), 3);
+
+        assert!(HttpClient::new(&NetworkConfig { ca_certificates: vec!["/nonexistent.pem".to_string()], ..config }).is_err());
+    }
+}
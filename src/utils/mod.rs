@@ -1,13 +1,23 @@
+mod cancel;
+mod cron;
 mod error;
 mod file_utils;
+mod http_client;
 mod logging;
 mod process_utils;
+mod rate_limiter;
 mod system_utils;
 mod winsafe_utils;
+#[cfg(test)]
+pub(crate) mod test_support;
 
+pub use cancel::*;
+pub use cron::*;
 pub use error::*;
 pub use file_utils::*;
+pub use http_client::*;
 pub use logging::*;
 pub use process_utils::*;
+pub use rate_limiter::*;
 pub use system_utils::*;
 pub use winsafe_utils::*;
\ No newline at end of file
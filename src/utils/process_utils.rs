@@ -30,4 +30,139 @@ pub fn run_powershell_command(script: &str) -> Result<std::process::Output> {
         .map_err(|e| HamsterError::IoError(e.to_string()))?;
     
     Ok(output)
-}
\ No newline at end of file
+}
+
+/// 运行子进程并在超时或取消时终止它
+///
+/// 用于安装程序等可能卡住的外部进程，终止后返回 TimeoutError 或 Cancelled。
+/// 子进程启动的其他进程在同一进程组（Windows 上为作业对象）中，命令结束后一并终止。
+pub async fn run_command_with_watchdog(
+    mut command: tokio::process::Command,
+    timeout: std::time::Duration,
+    cancel: &crate::utils::CancellationToken,
+) -> Result<std::process::Output> {
+    let program = format!("{:?}", command.as_std().get_program());
+    command.kill_on_drop(true)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    #[cfg(unix)]
+    command.process_group(0);
+    let child = command.spawn()
+        .map_err(|e| HamsterError::IoError(format!("启动 {} 失败: {}", program, e)))?;
+    let _tree = ProcessTree::attach(&child);
+
+    // wait_with_output 被丢弃时 kill_on_drop 会终止子进程
+    tokio::select! {
+        output = child.wait_with_output() => Ok(output?),
+        _ = tokio::time::sleep(timeout) => Err(HamsterError::TimeoutError(
+            format!("{} 超过 {} 秒未结束，已终止", program, timeout.as_secs())
+        )),
+        _ = cancel.cancelled() => Err(HamsterError::Cancelled(format!("{} 已终止", program))),
+    }
+}
+
+/// 子进程及其启动的进程，drop 时全部终止
+struct ProcessTree {
+    #[cfg(unix)]
+    group: Option<i32>,
+    #[cfg(windows)]
+    job: Option<winapi::um::winnt::HANDLE>,
+}
+
+// 作业对象的句柄只用于关闭，可以跨线程持有
+#[cfg(windows)]
+unsafe impl Send for ProcessTree {}
+
+impl ProcessTree {
+    #[cfg(unix)]
+    fn attach(child: &tokio::process::Child) -> Self {
+        // 子进程以自身 pid 为进程组
+        Self { group: child.id().map(|pid| pid as i32) }
+    }
+
+    // 子进程在加入作业对象前启动的进程不会被终止
+    #[cfg(windows)]
+    fn attach(child: &tokio::process::Child) -> Self {
+        use winapi::um::handleapi::CloseHandle;
+        use winapi::um::jobapi2::{AssignProcessToJobObject, CreateJobObjectW, SetInformationJobObject};
+        use winapi::um::winnt::{
+            JobObjectExtendedLimitInformation, HANDLE, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+        };
+
+        let Some(process) = child.raw_handle() else {
+            return Self { job: None };
+        };
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null_mut(), std::ptr::null());
+            if job.is_null() {
+                tracing::warn!("创建作业对象失败: {}", std::io::Error::last_os_error());
+                return Self { job: None };
+            }
+            let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            let configured = SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &mut limits as *mut _ as *mut _,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            ) != 0;
+            if !configured || AssignProcessToJobObject(job, process as HANDLE) == 0 {
+                tracing::warn!("无法将子进程加入作业对象: {}", std::io::Error::last_os_error());
+                CloseHandle(job);
+                return Self { job: None };
+            }
+            Self { job: Some(job) }
+        }
+    }
+}
+
+impl Drop for ProcessTree {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(group) = self.group {
+            // 进程组中已没有进程时返回 ESRCH，忽略即可
+            unsafe {
+                libc::killpg(group, libc::SIGKILL);
+            }
+        }
+        #[cfg(windows)]
+        if let Some(job) = self.job {
+            // 关闭最后一个句柄时系统终止作业中的所有进程
+            unsafe {
+                winapi::um::handleapi::CloseHandle(job);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_watchdog_kills_hung_process() {
+        let mut command = tokio::process::Command::new("sleep");
+        command.arg("30");
+        let started = Instant::now();
+        let result = run_command_with_watchdog(command, Duration::from_millis(100), &crate::utils::CancellationToken::new()).await;
+        assert!(matches!(result, Err(HamsterError::TimeoutError(_))));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_watchdog_kills_grandchildren() {
+        let marker = std::env::temp_dir().join(format!("hamster_watchdog_{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+        // 后台的孙进程在父进程被终止后才会写入文件
+        let mut command = tokio::process::Command::new("sh");
+        command.arg("-c").arg(format!("(sleep 1; touch '{}') & sleep 30", marker.display()));
+        let result = run_command_with_watchdog(command, Duration::from_millis(200), &crate::utils::CancellationToken::new()).await;
+        assert!(matches!(result, Err(HamsterError::TimeoutError(_))));
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!marker.exists());
+    }
+}
@@ -0,0 +1,83 @@
+//! 下载限速
+//!
+//! 令牌桶限速器，每次读取数据后按字节数等待，多个下载共享同一个限速器时合计速度不超过上限。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// 允许的突发量，按上限速度计算的时长
+const BURST: Duration = Duration::from_millis(500);
+
+/// 限速器，速度上限为0时不限速
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// 字节/秒，与 bucket 创建的限速器共享
+    rate: Arc<AtomicU64>,
+    /// 按上限速度传完已申请的字节的时刻
+    ready_at: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(bytes_per_sec: u64) -> Self {
+        Self { rate: Arc::new(AtomicU64::new(bytes_per_sec)), ready_at: Mutex::new(None) }
+    }
+
+    /// 创建共享速度上限、单独计量的限速器，用于每个任务各自的上限
+    pub fn bucket(&self) -> Self {
+        Self { rate: self.rate.clone(), ready_at: Mutex::new(None) }
+    }
+
+    /// 修改速度上限，共享该上限的限速器立即生效
+    pub fn set_rate(&self, bytes_per_sec: u64) {
+        self.rate.store(bytes_per_sec, Ordering::Relaxed);
+    }
+
+    pub fn rate(&self) -> u64 {
+        self.rate.load(Ordering::Relaxed)
+    }
+
+    /// 申请传输 bytes 字节需要等待的时间
+    pub fn reserve(&self, bytes: u64) -> Duration {
+        let rate = self.rate();
+        let mut ready_at = self.ready_at.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        if rate == 0 {
+            *ready_at = None;
+            return Duration::ZERO;
+        }
+        let start = ready_at.map_or(now, |ready_at| ready_at.max(now));
+        let ready = start + Duration::from_secs_f64(bytes as f64 / rate as f64);
+        *ready_at = Some(ready);
+        ready.saturating_duration_since(now).saturating_sub(BURST)
+    }
+
+    /// 按速度上限等待传输 bytes 字节
+    pub async fn acquire(&self, bytes: u64) {
+        let wait = self.reserve(bytes);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve_paces_transfers() {
+        let limiter = RateLimiter::new(1000);
+        // 突发量之内不需要等待
+        assert_eq!(limiter.reserve(400), Duration::ZERO);
+        let wait = limiter.reserve(1000);
+        assert!(wait > Duration::from_millis(800) && wait <= Duration::from_millis(900), "{:?}", wait);
+
+        // 每个任务的限速器单独计量，但共享速度上限
+        let task = limiter.bucket();
+        assert_eq!(task.reserve(400), Duration::ZERO);
+        limiter.set_rate(0);
+        assert_eq!(task.reserve(1_000_000), Duration::ZERO);
+        assert_eq!(limiter.reserve(1_000_000), Duration::ZERO);
+    }
+}
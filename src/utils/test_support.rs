@@ -0,0 +1,121 @@
+//! 测试用的临时目录和本地 HTTP 服务
+//!
+//! 只在单元测试中编译。
+
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// 测试用的临时目录，创建时清空上次留下的内容，drop 时删除
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// 在系统临时目录下创建 hamster_<name>_<进程ID>
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("hamster_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.path.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// 回复后关闭连接的 HTTP/1.1 响应
+pub fn http_response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+    let mut response = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n", status, body.len());
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+    response
+}
+
+/// 按顺序回复预设响应的本地 HTTP 服务
+pub struct HttpStub {
+    url: String,
+    handle: JoinHandle<Vec<String>>,
+}
+
+impl HttpStub {
+    /// 每个连接依次得到 responses 中的一个响应，全部回复后停止监听
+    pub async fn serve(responses: Vec<String>) -> Self {
+        Self::start(responses, false).await
+    }
+
+    /// 回复完 responses 后继续接受连接但不再回复，用于测试取消和超时
+    pub async fn serve_then_stall(responses: Vec<String>) -> Self {
+        Self::start(responses, true).await
+    }
+
+    async fn start(responses: Vec<String>, stall: bool) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                requests.push(read_request(&mut stream).await);
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+            // 连接保持打开，客户端一直等不到响应
+            let mut stalled = Vec::new();
+            if stall {
+                while let Ok((stream, _)) = listener.accept().await {
+                    stalled.push(stream);
+                }
+            }
+            requests
+        });
+        Self { url, handle }
+    }
+
+    /// 服务地址，如 http://127.0.0.1:12345
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// 等待所有响应发出，返回收到的请求（请求头和请求体）
+    pub async fn requests(self) -> Vec<String> {
+        self.handle.await.unwrap()
+    }
+}
+
+// 读取到请求体结束或连接关闭
+async fn read_request(stream: &mut TcpStream) -> String {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        let read = stream.read(&mut buffer).await.unwrap_or(0);
+        request.extend_from_slice(&buffer[..read]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+            let length = head.lines()
+                .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length:").and_then(|value| value.trim().parse().ok()))
+                .unwrap_or(0);
+            if body.len() >= length {
+                break;
+            }
+        }
+        if read == 0 {
+            break;
+        }
+    }
+    String::from_utf8_lossy(&request).into_owned()
+}
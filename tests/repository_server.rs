@@ -0,0 +1,202 @@
+//! 在本机启动驱动仓库服务，通过客户端和驱动匹配器访问
+
+use std::path::{Path, PathBuf};
+use hamster_drivers::config::RepositoryConfig;
+use hamster_drivers::fetcher::DriverFetcher;
+use hamster_drivers::matcher::{DriverMatcher, HardwareInfo};
+use hamster_drivers::repository::{
+    Catalog, DownloadRequest, DownloadResponse, DriverQuery, RepositoryClient, RepositoryServer, ServerOptions, DOWNLOAD_PATH,
+};
+use hamster_drivers::utils::CancellationToken;
+use sha2::{Digest, Sha256};
+
+const WIFI_ID: &str = r"PCI\VEN_8086&DEV_2723&SUBSYS_00848086&REV_1A";
+
+struct TempCatalog(PathBuf);
+
+impl TempCatalog {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("hamster_repository_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        Self(root)
+    }
+
+    fn add(&self, name: &str, manifest: &str, package: &[u8]) {
+        std::fs::write(self.0.join(format!("{}.exe", name)), package).unwrap();
+        std::fs::write(self.0.join(format!("{}.toml", name)), format!("file = \"{}.exe\"\n{}", name, manifest)).unwrap();
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempCatalog {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn sample_catalog(name: &str) -> TempCatalog {
+    let catalog = TempCatalog::new(name);
+    catalog.add("intel-wifi-22", r#"
+name = "Intel Wi-Fi 6 AX200"
+version = "22.250.1.2"
+manufacturer = "Intel"
+hardware_ids = ['PCI\VEN_8086&DEV_2723']
+"#, b"old wifi driver");
+    catalog.add("intel-wifi-23", r#"
+name = "Intel Wi-Fi 6 AX200"
+version = "23.60.0.10"
+manufacturer = "Intel"
+release_date = "2024-05-01"
+hardware_ids = ['PCI\VEN_8086&DEV_2723']
+os_versions = ["10", "11"]
+architectures = ["x64"]
+"#, &b"new wifi driver".repeat(1000));
+    catalog.add("realtek-audio", r#"
+name = "Realtek Audio"
+version = "6.0.9600.1"
+hardware_ids = ['HDAUDIO\FUNC_01&VEN_10EC&DEV_0295']
+"#, b"audio driver");
+    catalog
+}
+
+#[tokio::test]
+async fn test_query_and_download() {
+    let catalog = sample_catalog("query");
+    let server = RepositoryServer::start(Catalog::load(catalog.path()).unwrap(), "127.0.0.1:0", ServerOptions::default()).unwrap();
+    let client = RepositoryClient::new(&server.url());
+
+    assert_eq!(client.health().await.unwrap().drivers, 3);
+
+    // 最新版本在前，大小和校验和由服务计算
+    let record = client.latest_driver(WIFI_ID).await.unwrap().unwrap();
+    let package = b"new wifi driver".repeat(1000);
+    assert_eq!(record.driver.driver_id, "intel-wifi-23");
+    assert_eq!(record.driver.hardware_id, WIFI_ID);
+    assert_eq!(record.latest_version, "23.60.0.10");
+    assert_eq!(record.driver.file_size, package.len() as u64);
+    assert_eq!(record.driver.checksum, format!("{:x}", Sha256::digest(&package)));
+
+    let downloaded = reqwest::get(&record.download_url).await.unwrap().bytes().await.unwrap();
+    assert_eq!(downloaded.as_ref(), package.as_slice());
+
+    // 硬件ID需要在 & 处断开才算匹配
+    assert!(client.latest_driver(r"PCI\VEN_8086&DEV_27231").await.unwrap().is_none());
+    assert!(client.compatibility(r"USB\VID_0000").await.unwrap().is_none());
+
+    let compatibility = client.compatibility(WIFI_ID).await.unwrap().unwrap();
+    assert_eq!(compatibility.os_versions, vec!["10", "11"]);
+
+    // 不支持 32 位系统的驱动被排除，已安装的版本不再返回
+    let query = DriverQuery {
+        hardware_id: WIFI_ID.to_string(),
+        architecture: Some("x86".to_string()),
+        ..DriverQuery::default()
+    };
+    let response = client.query(&query).await.unwrap();
+    assert_eq!(response.latest_version.as_deref(), Some("22.250.1.2"));
+    let response = client.query(&DriverQuery { current_version: Some("22.250.1.2".to_string()), architecture: None, ..query }).await.unwrap();
+    let versions: Vec<_> = response.available_drivers.iter().map(|driver| driver.driver_version.as_str()).collect();
+    assert_eq!(versions, vec!["23.60.0.10"]);
+
+    let download = client.download(&DownloadRequest { hardware_id: WIFI_ID.to_string(), version: Some("22.250.1.2".to_string()) })
+        .await.unwrap().unwrap();
+    assert!(download.download_url.ends_with("/api/v1/packages/intel-wifi-22"));
+    assert_eq!(download.checksum, format!("{:x}", Sha256::digest(b"old wifi driver")));
+
+    // 下载链接使用服务的地址，不受请求中 Host 的影响
+    let spoofed: DownloadResponse = reqwest::Client::new()
+        .post(format!("{}{}", server.url(), DOWNLOAD_PATH))
+        .header(reqwest::header::HOST, "attacker.example.com")
+        .json(&DownloadRequest { hardware_id: WIFI_ID.to_string(), version: None })
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert!(spoofed.download_url.starts_with(&server.url()));
+
+    server.stop();
+}
+
+#[tokio::test]
+async fn test_matcher_uses_repository() {
+    let catalog = sample_catalog("matcher");
+    let options = ServerOptions { public_url: Some("http://drivers.example.com/".to_string()), ..ServerOptions::default() };
+    let server = RepositoryServer::start(Catalog::load(catalog.path()).unwrap(), "127.0.0.1:0", options).unwrap();
+
+    let mut matcher = DriverMatcher::new("").await.unwrap();
+    matcher.set_repository(&RepositoryConfig { url: Some(server.url()), api_key: None, vendor_fallback: false });
+    let hardware = |hardware_id: &str| HardwareInfo {
+        hardware_id: hardware_id.to_string(),
+        device_name: String::new(),
+        manufacturer: String::new(),
+        device_class: String::new(),
+    };
+
+    let audio_id = r"HDAUDIO\FUNC_01&VEN_10EC&DEV_0295&SUBSYS_10280872";
+    matcher.prefetch_repository(&[audio_id.to_string()]).await.unwrap();
+    let result = matcher.match_driver(&hardware(audio_id)).await.unwrap();
+    let driver = result.matched_driver.unwrap();
+    assert_eq!(driver.driver_name, "Realtek Audio");
+    assert_eq!(driver.driver_url, "http://drivers.example.com/api/v1/packages/realtek-audio");
+
+    // 只使用仓库时仓库中没有的设备不再访问厂商网站
+    let result = matcher.match_driver(&hardware(r"USB\VID_1234&PID_5678")).await.unwrap();
+    assert!(result.matched_driver.is_none());
+
+    server.stop();
+}
+
+#[tokio::test]
+async fn test_api_key_batch_and_pagination() {
+    let catalog = sample_catalog("batch");
+    let options = ServerOptions { api_key: Some("secret".to_string()), ..ServerOptions::default() };
+    let server = RepositoryServer::start(Catalog::load(catalog.path()).unwrap(), "127.0.0.1:0", options).unwrap();
+
+    // 没有密钥时只能访问 health
+    let anonymous = RepositoryClient::new(&server.url());
+    assert!(anonymous.health().await.is_ok());
+    let error = anonymous.latest_driver(WIFI_ID).await.unwrap_err();
+    assert!(error.to_string().contains("401"), "{}", error);
+    let package_url = format!("{}/api/v1/packages/realtek-audio", server.url());
+    let package = reqwest::get(&package_url).await.unwrap();
+    assert_eq!(package.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // 下载器对配置的仓库中的驱动包带上密钥
+    let fetcher = DriverFetcher::new("localhost".to_string(), 6800, String::new());
+    fetcher.set_repository(&RepositoryConfig { url: Some(server.url()), api_key: Some("secret".to_string()), vendor_fallback: false });
+    let file = catalog.path().join("downloaded.exe");
+    let size = fetcher.download_resumable(&package_url, &file.display().to_string(), &CancellationToken::new(), &[], |_, _| {})
+        .await.unwrap();
+    assert_eq!((size, std::fs::read(&file).unwrap()), (12, b"audio driver".to_vec()));
+
+    let client = RepositoryClient::new(&server.url()).with_api_key(Some("secret".to_string()));
+    let results = client.batch(&[
+        DriverQuery::new(WIFI_ID),
+        DriverQuery::new(r"USB\VID_1234&PID_5678"),
+        DriverQuery { current_version: Some("23.60.0.10".to_string()), ..DriverQuery::new(WIFI_ID) },
+    ]).await.unwrap();
+    let versions: Vec<_> = results.iter().map(|result| result.driver.as_ref().map(|driver| driver.driver_version.as_str())).collect();
+    assert_eq!(versions, vec![Some("23.60.0.10"), None, None]);
+
+    // 每页一个时需要翻页才能拿到旧版本
+    let page = client.query(&DriverQuery { per_page: Some(1), ..DriverQuery::new(WIFI_ID) }).await.unwrap();
+    assert_eq!((page.available_drivers.len(), page.total, page.next_page), (1, 2, Some(2)));
+    let all = client.query_all(&DriverQuery::new(WIFI_ID)).await.unwrap();
+    assert_eq!(all.iter().map(|driver| driver.driver_id.as_str()).collect::<Vec<_>>(), vec!["intel-wifi-23", "intel-wifi-22"]);
+
+    server.stop();
+}
+
+#[test]
+fn test_catalog_rejects_checksum_mismatch() {
+    let catalog = TempCatalog::new("invalid");
+    catalog.add("broken", &format!(r#"
+name = "Broken"
+version = "1.0"
+hardware_ids = ['PCI\VEN_1234']
+sha256 = "{}"
+"#, "0".repeat(64)), b"package");
+    assert!(Catalog::load(catalog.path()).is_err());
+}